@@ -635,6 +635,7 @@ impl CliCommand<&'static str> for MutatePackage {
                 known_attributes: known_attributes.clone(),
                 skip_attribute_checks: self.move_options.skip_attribute_checks,
                 compiler_version: self.move_options.compiler_version,
+                language_version: self.move_options.language_version,
                 ..Default::default()
             },
             ..Default::default()
@@ -691,6 +692,7 @@ impl CliCommand<&'static str> for SpecTestPackage {
                 known_attributes: known_attributes.clone(),
                 skip_attribute_checks: self.move_options.skip_attribute_checks,
                 compiler_version: self.move_options.compiler_version,
+                language_version: self.move_options.language_version,
                 ..Default::default()
             },
             ..Default::default()
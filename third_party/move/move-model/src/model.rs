@@ -481,6 +481,8 @@ pub enum VerificationScope {
     Only(String),
     /// Verify only functions from the given module.
     OnlyModule(String),
+    /// Verify only functions from the given modules.
+    OnlyModules(Vec<String>),
     /// Verify no functions
     None,
 }
@@ -497,7 +499,9 @@ impl VerificationScope {
     pub fn is_exclusive(&self) -> bool {
         matches!(
             self,
-            VerificationScope::Only(_) | VerificationScope::OnlyModule(_)
+            VerificationScope::Only(_)
+                | VerificationScope::OnlyModule(_)
+                | VerificationScope::OnlyModules(_)
         )
     }
 
@@ -255,6 +255,19 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessor {
                     )
                 }
             },
+            VerificationScope::OnlyModules(names) => {
+                for name in names {
+                    if !env
+                        .get_modules()
+                        .any(|module| module.is_target() && module.matches_name(name))
+                    {
+                        env.error(
+                            &env.unknown_loc(),
+                            &format!("module target {} does not exist in target modules", name),
+                        )
+                    }
+                }
+            },
             _ => {},
         }
 
@@ -364,6 +377,9 @@ impl VerificationAnalysisProcessor {
             VerificationScope::All => true,
             VerificationScope::Only(name) => fun_env.matches_name(name),
             VerificationScope::OnlyModule(name) => fun_env.module_env.matches_name(name),
+            VerificationScope::OnlyModules(names) => names
+                .iter()
+                .any(|name| fun_env.module_env.matches_name(name)),
             VerificationScope::None => false,
         }
     }
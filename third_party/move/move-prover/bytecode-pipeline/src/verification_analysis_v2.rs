@@ -612,6 +612,12 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessorV2 {
                 VerificationScope::OnlyModule(module_name) => {
                     is_in_target_mod && fun_env.module_env.matches_name(module_name)
                 },
+                VerificationScope::OnlyModules(module_names) => {
+                    is_in_target_mod
+                        && module_names
+                            .iter()
+                            .any(|name| fun_env.module_env.matches_name(name))
+                },
                 VerificationScope::None => false,
             };
             if is_verified {
@@ -658,6 +664,19 @@ impl FunctionTargetProcessor for VerificationAnalysisProcessorV2 {
                     )
                 }
             },
+            VerificationScope::OnlyModules(names) => {
+                for name in names {
+                    if !global_env
+                        .get_modules()
+                        .any(|module| module.is_target() && module.matches_name(name))
+                    {
+                        global_env.error(
+                            &global_env.unknown_loc(),
+                            &format!("module target {} does not exist in target modules", name),
+                        )
+                    }
+                }
+            },
             _ => {},
         }
 
@@ -0,0 +1,102 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Extension of the archives with the output directory.
+pub const ARCHIVE_EXTENSION: &str = "tar.zst";
+
+/// Compression level of the archives (the default level of zstd).
+const COMPRESSION_LEVEL: i32 = 3;
+
+/// Returns the path of the archive created for the output directory (the directory path with the
+/// `.tar.zst` extension appended).
+#[must_use]
+pub fn archive_path(output_dir: &Path) -> PathBuf {
+    let mut path = output_dir.as_os_str().to_owned();
+    path.push(".");
+    path.push(ARCHIVE_EXTENSION);
+    PathBuf::from(path)
+}
+
+/// Checks if the path points to the archive (judging by its extension).
+#[must_use]
+pub fn is_archive(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.ends_with(&format!(".{ARCHIVE_EXTENSION}")))
+}
+
+/// Writes the whole output directory (mutants, reports and the manifest) to the single `.tar.zst` archive.
+/// Paths inside of the archive are relative to the output directory, so the archive can be extracted anywhere.
+///
+/// # Errors
+///
+/// Returns an error if the directory can't be read or the archive can't be written.
+pub fn create_archive(output_dir: &Path, archive: &Path) -> anyhow::Result<()> {
+    let file = fs::File::create(archive)?;
+    let encoder = zstd::Encoder::new(file, COMPRESSION_LEVEL)?;
+
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+    builder.append_dir_all(".", output_dir)?;
+
+    builder.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Extracts the archive created with `create_archive` to the given directory.
+///
+/// # Errors
+///
+/// Returns an error if the archive can't be read or extracted.
+pub fn extract_archive(archive: &Path, dir: &Path) -> anyhow::Result<()> {
+    let file = fs::File::open(archive)
+        .map_err(|e| anyhow::anyhow!("Cannot open archive {}: {e}", archive.display()))?;
+    let decoder = zstd::Decoder::new(file)?;
+
+    fs::create_dir_all(dir)?;
+    tar::Archive::new(decoder).unpack(dir)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_path_appends_extension() {
+        let path = archive_path(Path::new("out/mutants_output"));
+        assert_eq!(path, PathBuf::from("out/mutants_output.tar.zst"));
+        assert!(is_archive(&path));
+        assert!(!is_archive(Path::new("out/mutants_output")));
+        assert!(!is_archive(Path::new("out/report.zst")));
+    }
+
+    #[test]
+    fn archive_round_trip_keeps_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let output_dir = dir.path().join("mutants_output");
+        fs::create_dir_all(output_dir.join("multi_file_0123")).unwrap();
+        fs::write(output_dir.join("report.json"), "{}").unwrap();
+        fs::write(output_dir.join("multi_file_0123/Sum.move"), "a - b").unwrap();
+
+        let archive = archive_path(&output_dir);
+        create_archive(&output_dir, &archive).unwrap();
+
+        let extracted = dir.path().join("extracted");
+        extract_archive(&archive, &extracted).unwrap();
+        assert_eq!(
+            fs::read_to_string(extracted.join("report.json")).unwrap(),
+            "{}"
+        );
+        assert_eq!(
+            fs::read_to_string(extracted.join("multi_file_0123/Sum.move")).unwrap(),
+            "a - b"
+        );
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Experimental mutation backend mutating the instructions of the compiled modules directly, for the
+//! packages whose sources aren't available.
+//!
+//! Mutants replace a single instruction with another one leaving the same types on the stack (operator
+//! swaps, constant tweaks and negated branches), and are checked with the bytecode verifier. As there are no
+//! sources, the diffs in the report show the listings of the instructions of the mutated function and the
+//! ranges of the modifications are the code offsets of the mutated instructions.
+
+use crate::{
+    cli::ModuleFilter,
+    configuration::Configuration,
+    diff::{self, DiffStyle},
+    output,
+    report::{Mutation, MutationReport, Range, Report, ReportFormat, ReportStream},
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset},
+    CompiledModule,
+};
+use num_traits::PrimInt;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Extension of the compiled module files.
+const MODULE_EXTENSION: &str = "mv";
+
+/// Name of the operator replacing the binary operators.
+const BINARY_OPERATOR_REPLACEMENT: &str = "bytecode_binary_operator_replacement";
+/// Name of the operator replacing the loaded constants.
+const LITERAL_REPLACEMENT: &str = "bytecode_literal_replacement";
+/// Name of the operator negating the conditional branches.
+const BRANCH_NEGATION: &str = "bytecode_branch_negation";
+
+/// Groups of the instructions which can replace each other without changing the types on the stack.
+const REPLACEMENT_GROUPS: &[&[Bytecode]] = &[
+    &[
+        Bytecode::Add,
+        Bytecode::Sub,
+        Bytecode::Mul,
+        Bytecode::Div,
+        Bytecode::Mod,
+    ],
+    &[Bytecode::BitOr, Bytecode::BitAnd, Bytecode::Xor],
+    &[Bytecode::Shl, Bytecode::Shr],
+    &[Bytecode::Lt, Bytecode::Gt, Bytecode::Le, Bytecode::Ge],
+    &[Bytecode::Eq, Bytecode::Neq],
+    &[Bytecode::And, Bytecode::Or],
+];
+
+/// Mutant replacing a single instruction of the compiled module.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BytecodeMutant {
+    /// Index of the mutated function definition in the module.
+    function_index: usize,
+    /// Name of the mutated function.
+    function_name: String,
+    /// Offset of the mutated instruction in the code of the function.
+    offset: CodeOffset,
+    /// Name of the mutation operator.
+    operator: &'static str,
+    /// The original instruction.
+    original: Bytecode,
+    /// The instruction used instead.
+    mutated: Bytecode,
+}
+
+impl BytecodeMutant {
+    /// Returns the name of the mutated function.
+    #[must_use]
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Returns the name of the mutation operator.
+    #[must_use]
+    pub fn operator_name(&self) -> &str {
+        self.operator
+    }
+
+    /// Returns the copy of the module with the instruction replaced.
+    #[must_use]
+    pub fn apply(&self, module: &CompiledModule) -> CompiledModule {
+        let mut mutated = module.clone();
+        if let Some(code) = mutated.function_defs[self.function_index].code.as_mut() {
+            code.code[self.offset as usize] = self.mutated.clone();
+        }
+        mutated
+    }
+}
+
+/// Returns all mutants of the instructions of the module (native functions have no code to mutate).
+#[must_use]
+pub fn module_mutants(module: &CompiledModule) -> Vec<BytecodeMutant> {
+    let mut mutants = vec![];
+    for (function_index, definition) in module.function_defs.iter().enumerate() {
+        let Some(code) = &definition.code else {
+            continue;
+        };
+        let handle = module.function_handle_at(definition.function);
+        let function_name = module.identifier_at(handle.name).to_string();
+        for (offset, instruction) in code.code.iter().enumerate() {
+            for (operator, mutated) in instruction_mutants(instruction) {
+                mutants.push(BytecodeMutant {
+                    function_index,
+                    function_name: function_name.clone(),
+                    offset: offset as CodeOffset,
+                    operator,
+                    original: instruction.clone(),
+                    mutated,
+                });
+            }
+        }
+    }
+    mutants
+}
+
+/// Mutates the compiled modules given in the configuration (`bytecode_modules`) and writes the mutated
+/// modules and the reports to the output directory. Mutants rejected by the bytecode verifier are skipped.
+///
+/// # Errors
+///
+/// Returns an error if a module can't be read or deserialized, or the output can't be written.
+pub(crate) fn run_bytecode_mutator(configuration: &Configuration) -> anyhow::Result<()> {
+    let output_dir = output::setup_output_dir(configuration)?;
+    let diff_context = configuration
+        .project
+        .diff_context
+        .unwrap_or(diff::DEFAULT_DIFF_CONTEXT);
+
+    let mut report = Report::new();
+    for path in module_files(&configuration.project.bytecode_modules)? {
+        let module = CompiledModule::deserialize(&fs::read(&path)?).map_err(|e| {
+            anyhow::anyhow!("Can't deserialize the module {}: {e:?}", path.display())
+        })?;
+        let module_name = module.self_id().name().to_string();
+        if let ModuleFilter::Selected(modules) = &configuration.project.mutate_modules {
+            if !modules.contains(&module_name) {
+                continue;
+            }
+        }
+
+        let mutants = module_mutants(&module);
+        let sites = mutants
+            .iter()
+            .map(|mutant| (mutant.operator_name(), mutant.function_index, mutant.offset))
+            .collect::<BTreeSet<_>>();
+        for (operator, _, _) in sites {
+            report.add_operator_sites(operator, 1);
+        }
+
+        for mutant in mutants {
+            let mutated_module = mutant.apply(&module);
+            if let Err(e) = move_bytecode_verifier::verify_module(&mutated_module) {
+                info!(
+                    "Mutant of {module_name}::{} at offset {} is rejected by the bytecode verifier: {e:?}",
+                    mutant.function_name(), mutant.offset
+                );
+                report.increment_operator_skipped(mutant.operator_name());
+                continue;
+            }
+            let mut binary = vec![];
+            mutated_module.serialize(&mut binary)?;
+
+            let mut entry = MutationReport::with_diff_context(
+                Path::new(""),
+                &path,
+                &module_name,
+                mutant.function_name(),
+                &function_listing(&mutated_module, mutant.function_index),
+                &function_listing(&module, mutant.function_index),
+                diff_context,
+            );
+            let offset = mutant.offset as usize;
+            entry.add_modification(Mutation::new(
+                Range::new(offset, offset + 1),
+                mutant.operator.to_owned(),
+                format!("{:?}", mutant.original),
+                format!("{:?}", mutant.mutated),
+            ));
+            entry.set_valid(true);
+
+            // The file name is derived from the mutant ID, so it's stable between runs.
+            let mutant_path = output_dir.join(format!(
+                "{module_name}_{}_{}.{MODULE_EXTENSION}",
+                mutant.operator_name(),
+                entry.get_id()
+            ));
+            fs::write(&mutant_path, binary)?;
+            entry.set_mutant_path(&mutant_path);
+            report.add_entry(entry);
+        }
+    }
+
+    let report_formats = if configuration.project.report_formats.is_empty() {
+        ReportFormat::all()
+    } else {
+        configuration.project.report_formats.clone()
+    };
+    for format in report_formats {
+        let report_path = output_dir.join(format.file_name());
+        match format {
+            ReportFormat::Json | ReportFormat::Yaml | ReportFormat::Toml => report
+                .save_with_details(&report_path, format, configuration.project.report_details)?,
+            ReportFormat::Text => report.save_to_text_file(&report_path, DiffStyle {
+                word_level: configuration.project.word_diff,
+                color: false,
+            })?,
+            ReportFormat::JsonLines => {
+                let mut stream = ReportStream::create(&report_path)?
+                    .with_details(configuration.project.report_details);
+                for entry in report.get_mutants() {
+                    stream.write_entry(entry)?;
+                }
+                stream.finish(&report)?;
+            },
+        }
+    }
+
+    info!(
+        "{} bytecode mutants written to {}",
+        report.get_mutants().len(),
+        output_dir.display()
+    );
+    Ok(())
+}
+
+// Internal function returning the replacements of the instruction with the names of their operators.
+fn instruction_mutants(instruction: &Bytecode) -> Vec<(&'static str, Bytecode)> {
+    if let Some(group) = REPLACEMENT_GROUPS
+        .iter()
+        .find(|group| group.contains(instruction))
+    {
+        return group
+            .iter()
+            .filter(|other| *other != instruction)
+            .map(|other| (BINARY_OPERATOR_REPLACEMENT, other.clone()))
+            .collect();
+    }
+
+    let literals = |replacements: Vec<Bytecode>| {
+        replacements
+            .into_iter()
+            .map(|replacement| (LITERAL_REPLACEMENT, replacement))
+            .collect()
+    };
+    match instruction {
+        Bytecode::LdTrue => literals(vec![Bytecode::LdFalse]),
+        Bytecode::LdFalse => literals(vec![Bytecode::LdTrue]),
+        Bytecode::LdU8(value) => {
+            literals(integer_replacements(*value).map(Bytecode::LdU8).collect())
+        },
+        Bytecode::LdU16(value) => {
+            literals(integer_replacements(*value).map(Bytecode::LdU16).collect())
+        },
+        Bytecode::LdU32(value) => {
+            literals(integer_replacements(*value).map(Bytecode::LdU32).collect())
+        },
+        Bytecode::LdU64(value) => {
+            literals(integer_replacements(*value).map(Bytecode::LdU64).collect())
+        },
+        Bytecode::LdU128(value) => {
+            literals(integer_replacements(*value).map(Bytecode::LdU128).collect())
+        },
+        Bytecode::BrTrue(target) => vec![(BRANCH_NEGATION, Bytecode::BrFalse(*target))],
+        Bytecode::BrFalse(target) => vec![(BRANCH_NEGATION, Bytecode::BrTrue(*target))],
+        _ => vec![],
+    }
+}
+
+// Internal function returning the distinct replacements of the integer constant: zero and the value
+// increased and decreased by one (if they don't overflow).
+fn integer_replacements<T: PrimInt>(value: T) -> impl Iterator<Item = T> {
+    let mut replacements = vec![];
+    for replacement in [
+        Some(T::zero()),
+        value.checked_add(&T::one()),
+        value.checked_sub(&T::one()),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if replacement != value && !replacements.contains(&replacement) {
+            replacements.push(replacement);
+        }
+    }
+    replacements.into_iter()
+}
+
+// Internal function returning the listing of the instructions of the function (one per line, prefixed with
+// the code offset).
+fn function_listing(module: &CompiledModule, function_index: usize) -> String {
+    module.function_defs[function_index]
+        .code
+        .iter()
+        .flat_map(|code| code.code.iter().enumerate())
+        .map(|(offset, instruction)| format!("{offset}: {instruction:?}\n"))
+        .collect()
+}
+
+// Internal function collecting the compiled module files from the given files and directories (searched
+// recursively, in sorted order).
+fn module_files(paths: &[PathBuf]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for path in paths {
+        if path.is_dir() {
+            let mut entries = fs::read_dir(path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()?;
+            entries.sort();
+            let (dirs, modules): (Vec<_>, Vec<_>) = entries.into_iter().partition(|p| p.is_dir());
+            files.extend(
+                modules
+                    .into_iter()
+                    .filter(|file| file.extension().is_some_and(|ext| ext == MODULE_EXTENSION)),
+            );
+            files.extend(module_files(&dirs)?);
+        } else {
+            files.push(path.clone());
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::basic_test_module;
+
+    // Helper creating the module with the `foo` function computing `1 + 2`.
+    fn module() -> CompiledModule {
+        let mut module = basic_test_module();
+        module.function_defs[0].code.as_mut().unwrap().code = vec![
+            Bytecode::LdU64(1),
+            Bytecode::LdU64(2),
+            Bytecode::Add,
+            Bytecode::Pop,
+            Bytecode::Ret,
+        ];
+        module
+    }
+
+    #[test]
+    fn instructions_are_mutated() {
+        let module = module();
+        let mutants = module_mutants(&module);
+
+        let literals = mutants
+            .iter()
+            .filter(|m| m.operator_name() == LITERAL_REPLACEMENT)
+            .map(|m| m.mutated.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(literals, vec![
+            Bytecode::LdU64(0),
+            Bytecode::LdU64(2),
+            Bytecode::LdU64(0),
+            Bytecode::LdU64(3),
+            Bytecode::LdU64(1),
+        ]);
+        let operators = mutants
+            .iter()
+            .filter(|m| m.operator_name() == BINARY_OPERATOR_REPLACEMENT)
+            .count();
+        assert_eq!(operators, 4);
+        assert!(mutants.iter().all(|m| m.function_name() == "foo"));
+
+        let sub = mutants.iter().find(|m| m.mutated == Bytecode::Sub).unwrap();
+        let mutated = sub.apply(&module);
+        assert_eq!(
+            mutated.function_defs[0].code.as_ref().unwrap().code[2],
+            Bytecode::Sub
+        );
+        assert_eq!(
+            function_listing(&mutated, 0),
+            "0: LdU64(1)\n1: LdU64(2)\n2: Sub\n3: Pop\n4: Ret\n"
+        );
+    }
+
+    #[test]
+    fn branches_and_booleans_are_negated() {
+        assert_eq!(instruction_mutants(&Bytecode::BrTrue(3)), vec![(
+            BRANCH_NEGATION,
+            Bytecode::BrFalse(3)
+        )]);
+        assert_eq!(instruction_mutants(&Bytecode::LdFalse), vec![(
+            LITERAL_REPLACEMENT,
+            Bytecode::LdTrue
+        )]);
+        assert_eq!(integer_replacements(u8::MAX).collect::<Vec<_>>(), vec![
+            0,
+            u8::MAX - 1
+        ]);
+        assert!(instruction_mutants(&Bytecode::Ret).is_empty());
+    }
+
+    #[test]
+    fn module_files_are_collected_recursively() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("deps")).unwrap();
+        for file in ["b.mv", "a.mv", "notes.txt", "deps/c.mv"] {
+            fs::write(dir.path().join(file), "").unwrap();
+        }
+
+        let files = module_files(&[dir.path().to_path_buf()]).unwrap();
+        assert_eq!(files, vec![
+            dir.path().join("a.mv"),
+            dir.path().join("b.mv"),
+            dir.path().join("deps/c.mv"),
+        ]);
+    }
+}
@@ -2,7 +2,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
+use crate::report::{ReportDetails, ReportFormat};
+use clap::{Parser, ValueEnum};
 use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, str::FromStr};
 
@@ -21,9 +22,32 @@ pub struct CLIOptions {
     /// The path where to put the output files.
     #[clap(long, short, value_parser)]
     pub out_mutant_dir: Option<PathBuf>,
+    /// Layout of the output directory: `flat` (mutants next to each other, in the directories mirroring the
+    /// `sources` directory) or `module` (`<module>/<function>/<mutant-id>.move` hierarchy).
+    #[clap(long, value_enum, default_value = "flat")]
+    pub output_layout: OutputLayout,
+    /// Template of the mutant file names, e.g. `{file}_{operator}_{line}_{index}.move`. Placeholders
+    /// `{file}`, `{module}`, `{function}`, `{operator}`, `{line}`, `{id}` and `{index}` are substituted.
+    #[clap(long)]
+    pub name_template: Option<String>,
+    /// Write also the whole output directory (mutants, reports and the manifest) to the single
+    /// `<out-mutant-dir>.tar.zst` archive (e.g. to upload it as a CI artifact).
+    #[clap(long, default_value = "false")]
+    pub archive: bool,
+    /// Emit also a self-contained package for each mutant (`<out-mutant-dir>/packages/<mutant-id>`): a copy
+    /// of the package with the mutated files substituted, ready to be built, tested or verified.
+    #[clap(long, default_value = "false")]
+    pub emit_packages: bool,
     /// Indicates if mutants should be verified and made sure mutants can compile.
     #[clap(long, default_value = "false")]
     pub verify_mutants: bool,
+    /// Type check each mutant and record the result in the report (`valid` field).
+    #[clap(long, default_value = "false")]
+    pub type_check_mutants: bool,
+    /// Type check each mutant and keep only the valid ones, so the mutants which don't compile never reach
+    /// the verification.
+    #[clap(long, default_value = "false")]
+    pub only_valid: bool,
     /// Indicates if the output files should be overwritten.
     #[clap(long, short, default_value = "false")]
     pub no_overwrite: bool,
@@ -34,8 +58,164 @@ pub struct CLIOptions {
     #[clap(long)]
     pub downsampling_ratio_percentage: Option<usize>,
     /// Optional configuration file. If provided, it will override the default configuration.
+    /// Use `-` to read the configuration (TOML or JSON) from the standard input.
     #[clap(long, short, value_parser)]
     pub configuration_file: Option<PathBuf>,
+    /// Name of the configuration profile to use (e.g. quick, thorough, ci).
+    #[clap(long)]
+    pub profile: Option<String>,
+    /// Report formats to generate. Defaults to the JSON and text reports.
+    #[clap(long = "report-format", value_enum, value_delimiter = ',')]
+    pub report_formats: Vec<ReportFormat>,
+    /// Mutate only the externally reachable functions (`public entry` functions and scripts).
+    #[clap(long, default_value = "false")]
+    pub entry_functions_only: bool,
+    /// Mutate also the private functions called (directly or transitively) by the selected functions.
+    #[clap(long, default_value = "false")]
+    pub include_callees: bool,
+    /// Mutate also the test code (`#[test]` and `#[test_only]` functions and `#[test_only]` modules), which is
+    /// skipped by default, as its mutants can't be killed by the specifications.
+    #[clap(long, default_value = "false")]
+    pub include_test_code: bool,
+    /// Mutate also the spec functions and the global invariants (to check if the prover notices the changes
+    /// of the specifications).
+    #[clap(long, default_value = "false")]
+    pub mutate_specs: bool,
+    /// Mutate also the specification files (`*.spec.move`), which are left untouched by default (they are
+    /// still compiled with the package and copied to the mutant packages).
+    #[clap(long, default_value = "false")]
+    pub mutate_spec_files: bool,
+    /// Generate also up to the given number of mutants spanning two files, which combine a mutant of a function
+    /// with a mutant of its caller from another module (to model the faults crossing the module boundaries).
+    #[clap(long)]
+    pub multi_file_mutants: Option<usize>,
+    /// Number of context lines in the diffs of the mutants (3 by default).
+    #[clap(long)]
+    pub diff_context: Option<usize>,
+    /// Highlight the changed words of the diffs in the text report (`[-old-]{+new+}`).
+    #[clap(long, default_value = "false")]
+    pub word_diff: bool,
+    /// Level of detail of the mutants in the JSON reports: `full`, `compact` (without the diffs and the source
+    /// excerpts) or `external` (the diffs are written to the `diffs/<mutant-id>.diff` files referenced by path).
+    #[clap(long, value_enum, default_value = "full")]
+    pub report_details: ReportDetails,
+    /// Stop at the first file which can't be mutated, instead of recording the failure in the report and
+    /// continuing with the other files.
+    #[clap(long, default_value = "false")]
+    pub strict: bool,
+    /// Number of the mutants waiting to be written by the writer thread (64 by default). Zero writes the
+    /// mutants synchronously.
+    #[clap(long)]
+    pub write_buffer: Option<usize>,
+    /// Fix all randomness of the run (e.g. the downsampling of the mutants), so the same mutants are generated
+    /// for the same sources in every run (for reproducible CI runs).
+    #[clap(long, default_value = "false")]
+    pub deterministic: bool,
+    /// Experimental: mutate the compiled modules (`.mv` files or directories containing them) instead of the
+    /// sources, e.g. of the packages whose sources aren't available. The mutants are checked with the bytecode
+    /// verifier.
+    #[clap(long, value_parser)]
+    pub bytecode_modules: Vec<PathBuf>,
+    /// Print the effective configuration (after merging all configuration sources) and exit.
+    #[clap(long)]
+    #[serde(skip)]
+    pub print_config: bool,
+}
+
+impl CLIOptions {
+    /// Overrides options with the values explicitly set in `other`.
+    /// Values of `other` which are equal to the default ones are treated as not set and are ignored.
+    pub fn merge(&mut self, other: &CLIOptions) {
+        let defaults = CLIOptions::default();
+
+        if other.move_sources != defaults.move_sources {
+            self.move_sources = other.move_sources.clone();
+        }
+        if other.mutate_modules != defaults.mutate_modules {
+            self.mutate_modules = other.mutate_modules.clone();
+        }
+        if other.out_mutant_dir != defaults.out_mutant_dir {
+            self.out_mutant_dir = other.out_mutant_dir.clone();
+        }
+        if other.output_layout != defaults.output_layout {
+            self.output_layout = other.output_layout;
+        }
+        if other.name_template != defaults.name_template {
+            self.name_template = other.name_template.clone();
+        }
+        if other.archive != defaults.archive {
+            self.archive = other.archive;
+        }
+        if other.emit_packages != defaults.emit_packages {
+            self.emit_packages = other.emit_packages;
+        }
+        if other.verify_mutants != defaults.verify_mutants {
+            self.verify_mutants = other.verify_mutants;
+        }
+        if other.type_check_mutants != defaults.type_check_mutants {
+            self.type_check_mutants = other.type_check_mutants;
+        }
+        if other.only_valid != defaults.only_valid {
+            self.only_valid = other.only_valid;
+        }
+        if other.no_overwrite != defaults.no_overwrite {
+            self.no_overwrite = other.no_overwrite;
+        }
+        if other.downsample_filter != defaults.downsample_filter {
+            self.downsample_filter = other.downsample_filter.clone();
+        }
+        if other.downsampling_ratio_percentage != defaults.downsampling_ratio_percentage {
+            self.downsampling_ratio_percentage = other.downsampling_ratio_percentage;
+        }
+        if other.configuration_file != defaults.configuration_file {
+            self.configuration_file = other.configuration_file.clone();
+        }
+        if other.profile != defaults.profile {
+            self.profile = other.profile.clone();
+        }
+        if other.report_formats != defaults.report_formats {
+            self.report_formats = other.report_formats.clone();
+        }
+        if other.entry_functions_only != defaults.entry_functions_only {
+            self.entry_functions_only = other.entry_functions_only;
+        }
+        if other.include_callees != defaults.include_callees {
+            self.include_callees = other.include_callees;
+        }
+        if other.include_test_code != defaults.include_test_code {
+            self.include_test_code = other.include_test_code;
+        }
+        if other.mutate_specs != defaults.mutate_specs {
+            self.mutate_specs = other.mutate_specs;
+        }
+        if other.mutate_spec_files != defaults.mutate_spec_files {
+            self.mutate_spec_files = other.mutate_spec_files;
+        }
+        if other.multi_file_mutants != defaults.multi_file_mutants {
+            self.multi_file_mutants = other.multi_file_mutants;
+        }
+        if other.diff_context != defaults.diff_context {
+            self.diff_context = other.diff_context;
+        }
+        if other.word_diff != defaults.word_diff {
+            self.word_diff = other.word_diff;
+        }
+        if other.report_details != defaults.report_details {
+            self.report_details = other.report_details;
+        }
+        if other.strict != defaults.strict {
+            self.strict = other.strict;
+        }
+        if other.write_buffer != defaults.write_buffer {
+            self.write_buffer = other.write_buffer;
+        }
+        if other.deterministic != defaults.deterministic {
+            self.deterministic = other.deterministic;
+        }
+        if other.bytecode_modules != defaults.bytecode_modules {
+            self.bytecode_modules = other.bytecode_modules.clone();
+        }
+    }
 }
 
 impl Default for CLIOptions {
@@ -47,15 +227,50 @@ impl Default for CLIOptions {
             move_sources: vec![],
             mutate_modules: ModuleFilter::All,
             out_mutant_dir: Some(PathBuf::from(DEFAULT_OUTPUT_DIR)),
+            output_layout: OutputLayout::Flat,
+            name_template: None,
+            archive: false,
+            emit_packages: false,
             verify_mutants: false,
+            type_check_mutants: false,
+            only_valid: false,
             no_overwrite: false,
             downsample_filter: None,
             downsampling_ratio_percentage: None,
             configuration_file: None,
+            profile: None,
+            report_formats: vec![],
+            entry_functions_only: false,
+            include_callees: false,
+            include_test_code: false,
+            mutate_specs: false,
+            mutate_spec_files: false,
+            multi_file_mutants: None,
+            diff_context: None,
+            word_diff: false,
+            report_details: ReportDetails::Full,
+            strict: false,
+            write_buffer: None,
+            deterministic: false,
+            bytecode_modules: vec![],
+            print_config: false,
         }
     }
 }
 
+/// Layout of the output directory.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputLayout {
+    /// Mutants are stored next to each other (`<file>_<operator>_<line>_<mutant-id>.move`), in the directories
+    /// mirroring the `sources` directory of the package.
+    #[default]
+    Flat,
+    /// Mutants are stored in the per-module and per-function subdirectories
+    /// (`<module>/<function>/<mutant-id>.move`).
+    Module,
+}
+
 /// Filter allowing to select modules to be mutated.
 #[derive(Default, Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub enum ModuleFilter {
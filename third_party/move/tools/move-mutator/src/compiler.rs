@@ -8,7 +8,10 @@ use itertools::Itertools;
 use move_command_line_common::{address::NumericalAddress, parser::NumberFormat};
 use move_compiler::{attr_derivation, shared::Flags};
 use move_compiler_v2::run_checker;
-use move_model::model::GlobalEnv;
+use move_model::{
+    metadata::{CompilerVersion, LanguageVersion},
+    model::GlobalEnv,
+};
 use move_package::{
     compilation::compiled_package::make_source_and_deps_for_compiler,
     resolution::resolution_graph::ResolvedTable,
@@ -18,6 +21,21 @@ use move_package::{
 use move_symbol_pool::Symbol;
 use std::{collections::BTreeMap, fs, io, path::Path};
 
+/// Patterns (relative to the package root) of the entries which are never copied by `copy_package`:
+/// the build artifacts and the version control directories.
+pub const DEFAULT_COPY_IGNORE: &[&str] = &["build", "**/.git", "**/.hg", "**/.svn"];
+
+/// Suffix of the specification files, which keep the spec blocks of the modules separately from the code.
+pub const SPEC_FILE_SUFFIX: &str = ".spec.move";
+
+/// Checks if the file is a specification file (its name ends with `.spec.move`). Such files are compiled
+/// with the package, but they aren't mutated unless requested.
+#[must_use]
+pub fn is_spec_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().ends_with(SPEC_FILE_SUFFIX))
+}
+
 /// Generate the AST from the Move sources.
 ///
 /// Generation of the AST is done by the Move model package.
@@ -201,6 +219,8 @@ fn prepare_compiler_for_package(
             .collect(),
         skip_attribute_checks: config.compiler_config.skip_attribute_checks,
         known_attributes: known_attributes.clone(),
+        language_version: config.compiler_config.language_version,
+        experiments: config.compiler_config.experiments.clone(),
         ..Default::default()
     };
 
@@ -253,6 +273,8 @@ fn prepare_compiler_for_files(
             .collect(),
         skip_attribute_checks: config.compiler_config.skip_attribute_checks,
         known_attributes: known_attributes.clone(),
+        language_version: config.compiler_config.language_version,
+        experiments: config.compiler_config.experiments.clone(),
         ..Default::default()
     }
 }
@@ -283,43 +305,69 @@ pub fn verify_mutant(
     mutated_source: &str,
     original_file: &Path,
 ) -> Result<(), anyhow::Error> {
-    // Find the root for the package.
-    let root = SourcePackageLayout::try_find_root(&original_file.canonicalize()?)?;
+    verify_multi_file_mutant(config, &[(mutated_source, original_file)])
+}
 
-    debug!("Package path found: {root:?}");
+/// Verify the mutant spanning multiple files.
+/// This function works the same way as `verify_mutant`, but replaces all the given original files (which
+/// must belong to the same package) with their mutated sources.
+///
+/// # Arguments
+///
+/// * `config` - the build configuration.
+/// * `files` - the mutated sources with the paths to their original files.
+///
+/// # Errors
+///
+/// * If any error occurs during the verification, the string with the cause is returned.
+pub fn verify_multi_file_mutant(
+    config: &BuildConfig,
+    files: &[(&str, &Path)],
+) -> Result<(), anyhow::Error> {
+    let (_, first_file) = files
+        .first()
+        .ok_or_else(|| anyhow::anyhow!("The mutant doesn't change any file"))?;
 
-    // Get the relative path to the original file.
-    let relative_path = original_file.canonicalize()?;
-    let relative_path = relative_path.strip_prefix(&root)?;
+    // Find the root for the package.
+    let root = SourcePackageLayout::try_find_root(&first_file.canonicalize()?)?;
 
-    debug!("Relative path: {relative_path:?}");
+    debug!("Package path found: {root:?}");
 
     let tempdir = tempfile::tempdir()?;
 
     debug!("Temporary directory: {:?}", tempdir.path());
 
-    // Copy the whole package to the tempdir.
+    // Copy the whole package to the tempdir (without the build artifacts).
     // We need to copy the whole package because the Move compiler needs to find the Move.toml file and all the dependencies
     // as we don't know which files are needed for the compilation.
-    copy_dir_all(&root, tempdir.path())?;
+    copy_package(&root, tempdir.path(), &IgnoreRules::default())?;
+
+    for (mutated_source, original_file) in files {
+        // Get the relative path to the original file.
+        let relative_path = original_file.canonicalize()?;
+        let relative_path = relative_path.strip_prefix(&root)?;
 
-    // Write the mutated source to the tempdir in place of the original file.
-    std::fs::write(tempdir.path().join(relative_path), mutated_source)?;
+        debug!("Relative path: {relative_path:?}");
+
+        // Write the mutated source to the tempdir in place of the original file.
+        std::fs::write(tempdir.path().join(relative_path), mutated_source)?;
+
+        debug!(
+            "Mutated source written to {:?}",
+            tempdir.path().join(relative_path)
+        );
+    }
 
     // Rewrite the manifest file to use absolute paths
     rewrite_manifest_for_mutant(&root, tempdir.path())?;
 
-    debug!(
-        "Mutated source written to {:?}",
-        tempdir.path().join(relative_path)
-    );
-
     let mut compilation_msg = vec![];
 
     // Create a working config, making sure that the test mode is disabled.
     // We want just check if the compilation is successful.
     let mut working_config = config.clone();
     working_config.test_mode = false;
+    working_config.compiler_config.compiler_version = Some(compiler_version_for(&working_config));
 
     // Compile the package.
     //TODO: It might be better to use the different compiler stage to speed up the whole
@@ -336,6 +384,104 @@ pub fn verify_mutant(
     Ok(())
 }
 
+/// Type check the mutant.
+/// This function runs only the type checker of the compiler v2 (no bytecode is generated) with the given
+/// compiler options (the options used to generate the AST). Only the mutated file is written to a temporary
+/// directory, the other sources and dependencies are read from their original locations.
+///
+/// # Arguments
+///
+/// * `options` - the compiler options used to generate the AST.
+/// * `mutated_source` - the mutated source code as a string.
+/// * `original_file` - the path to the original file.
+///
+/// # Errors
+///
+/// * If the mutant doesn't type check or any error occurs during the checking, the error with the cause is
+///   returned.
+pub fn type_check_mutant(
+    options: &move_compiler_v2::Options,
+    mutated_source: &str,
+    original_file: &Path,
+) -> Result<(), anyhow::Error> {
+    type_check_multi_file_mutant(options, &[(mutated_source, original_file)])
+}
+
+/// Type check the mutant spanning multiple files.
+/// This function works the same way as `type_check_mutant`, but replaces all the given original files with
+/// their mutated sources.
+///
+/// # Arguments
+///
+/// * `options` - the compiler options used to generate the AST.
+/// * `files` - the mutated sources with the paths to their original files.
+///
+/// # Errors
+///
+/// * If the mutant doesn't type check or any error occurs during the checking, the error with the cause is
+///   returned.
+pub fn type_check_multi_file_mutant(
+    options: &move_compiler_v2::Options,
+    files: &[(&str, &Path)],
+) -> Result<(), anyhow::Error> {
+    let tempdir = tempfile::tempdir()?;
+    let mut options = options.clone();
+
+    for (index, (mutated_source, original_file)) in files.iter().enumerate() {
+        let original_file = original_file.canonicalize()?;
+        let file_name = original_file
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid mutated file {original_file:?}"))?;
+
+        // Files of different directories may have the same names.
+        let mutant_dir = tempdir.path().join(index.to_string());
+        fs::create_dir_all(&mutant_dir)?;
+        let mutant_file = mutant_dir.join(file_name);
+        fs::write(&mutant_file, mutated_source)?;
+
+        // The mutated file replaces the original one in the sources of the package.
+        let mut replaced = false;
+        for source in &mut options.sources {
+            if Path::new(source)
+                .canonicalize()
+                .is_ok_and(|path| path == original_file)
+            {
+                *source = mutant_file.to_string_lossy().to_string();
+                replaced = true;
+            }
+        }
+        anyhow::ensure!(
+            replaced,
+            "The mutated file {original_file:?} is not a source of the package"
+        );
+    }
+
+    let env = run_checker(options)?;
+    anyhow::ensure!(
+        !env.has_errors(),
+        "The mutant doesn't type check ({} errors)",
+        env.error_count()
+    );
+
+    Ok(())
+}
+
+/// Returns the compiler version to use for the verification of the mutants.
+///
+/// The AST is always generated with the compiler v2 pipeline, so the mutants may use Move 2 language
+/// features (e.g. enums or receiver style calls) which the compiler v1 doesn't support. If the compiler
+/// version is not specified explicitly, the compiler v2 is used for the language versions newer than v1.
+fn compiler_version_for(config: &BuildConfig) -> CompilerVersion {
+    let compiler_config = &config.compiler_config;
+    match compiler_config.compiler_version {
+        Some(version) => version,
+        None if compiler_config.language_version.unwrap_or_default() > LanguageVersion::V1 => {
+            CompilerVersion::V2_0
+        },
+        None => CompilerVersion::default(),
+    }
+}
+
 /// Rewrite the manifest file to use absolute paths.
 ///
 /// # Arguments
@@ -358,25 +504,21 @@ pub fn rewrite_manifest_for_mutant(root: &Path, tempdir: &Path) -> Result<(), an
     let mut manifest_string = fs::read_to_string(root.join(SourcePackageLayout::Manifest.path()))?;
     let manifest = manifest_parser::parse_move_manifest_string(manifest_string.clone())?;
     let manifest = manifest_parser::parse_source_manifest(manifest)?;
-    let curdir = std::env::current_dir()?;
-
-    // We need to switch to package dir as paths in manifest are relative to package dir.
-    std::env::set_current_dir(root)?;
 
+    // Paths in the manifest are relative to the package dir. They are resolved without switching the current
+    // directory, as it's shared by all threads of the process.
     manifest
         .dependencies
         .values()
         .chain(manifest.dev_dependencies.values())
         .for_each(|dep| {
-            let dep_canon = dep.local.canonicalize();
+            let dep_canon = root.join(&dep.local).canonicalize();
             if let Ok(dep_canon) = dep_canon {
                 manifest_string = manifest_string
                     .replace(dep.local.to_str().unwrap(), dep_canon.to_str().unwrap());
             }
         });
 
-    // Switch back to the original dir.
-    std::env::set_current_dir(curdir)?;
     fs::write(
         tempdir.join(SourcePackageLayout::Manifest.path()),
         manifest_string,
@@ -385,6 +527,7 @@ pub fn rewrite_manifest_for_mutant(root: &Path, tempdir: &Path) -> Result<(), an
 }
 
 /// Copies all files and directories from the source directory to the destination directory.
+/// Use `copy_package` to copy the package without its build artifacts and version control directories.
 ///
 /// # Arguments
 ///
@@ -413,12 +556,145 @@ pub fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> io::Result<
     Ok(())
 }
 
+/// Rules deciding which entries of the package are not copied by `copy_package`.
+///
+/// Each rule is a glob pattern matched against the path of the entry relative to the package root (e.g.
+/// `build`, `docs/**` or `**/*.bak`). Ignored directories are skipped with all their content.
+#[derive(Debug, Clone)]
+pub struct IgnoreRules {
+    patterns: Vec<glob::Pattern>,
+}
+
+impl IgnoreRules {
+    /// Creates the rules ignoring the default entries (see `DEFAULT_COPY_IGNORE`) and the entries
+    /// matching the given patterns.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any pattern is not a valid glob pattern.
+    pub fn new(patterns: &[String]) -> anyhow::Result<Self> {
+        let patterns = DEFAULT_COPY_IGNORE
+            .iter()
+            .copied()
+            .chain(patterns.iter().map(String::as_str))
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map_err(|e| anyhow::anyhow!("Invalid ignore pattern {pattern}: {e}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { patterns })
+    }
+
+    /// Checks if the entry with the given path (relative to the package root) is ignored.
+    #[must_use]
+    pub fn is_ignored(&self, relative_path: &Path) -> bool {
+        self.patterns
+            .iter()
+            .any(|pattern| pattern.matches_path(relative_path))
+    }
+}
+
+impl Default for IgnoreRules {
+    fn default() -> Self {
+        Self::new(&[]).expect("default ignore patterns are valid")
+    }
+}
+
+/// Copies the package directory to the destination directory, except for the entries ignored by the rules
+/// (e.g. the build artifacts and the version control directories, which can be much larger than the
+/// package itself).
+///
+/// # Errors
+///
+/// Returns an error if any entry can't be copied.
+pub fn copy_package(src: &Path, dst: &Path, rules: &IgnoreRules) -> anyhow::Result<()> {
+    copy_package_entries(src, Path::new(""), dst, rules)
+}
+
+// Internal function copying the entries of the package directory at the given path (relative to the root).
+fn copy_package_entries(
+    root: &Path,
+    relative_dir: &Path,
+    dst: &Path,
+    rules: &IgnoreRules,
+) -> anyhow::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(root.join(relative_dir))? {
+        let entry = entry?;
+        let relative_path = relative_dir.join(entry.file_name());
+        if rules.is_ignored(&relative_path) {
+            trace!("Skipping ignored entry {}", relative_path.display());
+            continue;
+        }
+
+        if entry.file_type()?.is_dir() {
+            copy_package_entries(root, &relative_path, &dst.join(entry.file_name()), rules)?;
+        } else {
+            fs::copy(entry.path(), dst.join(entry.file_name()))?;
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::fs;
     use tempfile::tempdir;
 
+    #[test]
+    fn copy_package_skips_ignored_entries() {
+        let temp_dir = tempdir().unwrap();
+        let src_dir = temp_dir.path().join("src");
+        let dst_dir = temp_dir.path().join("dst");
+
+        for dir in ["sources", "build/package", ".git", "sources/.git", "docs"] {
+            fs::create_dir_all(src_dir.join(dir)).unwrap();
+        }
+        for file in [
+            "Move.toml",
+            "sources/Coin.move",
+            "sources/Coin.spec.move",
+            "sources/Coin.move.bak",
+            "build/package/BuildInfo.yaml",
+            ".git/HEAD",
+            "sources/.git/HEAD",
+            "docs/Coin.md",
+        ] {
+            fs::write(src_dir.join(file), "").unwrap();
+        }
+
+        let rules = IgnoreRules::new(&["docs".to_owned(), "**/*.bak".to_owned()]).unwrap();
+        copy_package(&src_dir, &dst_dir, &rules).unwrap();
+
+        assert!(dst_dir.join("Move.toml").exists());
+        assert!(dst_dir.join("sources/Coin.move").exists());
+        // The specification files are copied with the code.
+        assert!(dst_dir.join("sources/Coin.spec.move").exists());
+        for ignored in [
+            "build",
+            ".git",
+            "sources/.git",
+            "docs",
+            "sources/Coin.move.bak",
+        ] {
+            assert!(!dst_dir.join(ignored).exists(), "{ignored}");
+        }
+    }
+
+    #[test]
+    fn spec_files_are_recognized() {
+        assert!(is_spec_file(Path::new("sources/Coin.spec.move")));
+        assert!(!is_spec_file(Path::new("sources/Coin.move")));
+        assert!(!is_spec_file(Path::new("sources/spec.move")));
+        assert!(!is_spec_file(Path::new("sources.spec.move/Coin.move")));
+    }
+
+    #[test]
+    fn invalid_ignore_patterns_are_rejected() {
+        assert!(IgnoreRules::new(&["[".to_owned()]).is_err());
+    }
+
     #[test]
     fn copy_dir_all_copies_all_files_and_directories() {
         let temp_dir = tempdir().unwrap();
@@ -433,6 +709,16 @@ mod tests {
         assert!(dst_dir.join("file.txt").exists());
     }
 
+    #[test]
+    fn compiler_v2_is_used_for_move_2() {
+        let mut config = BuildConfig::default();
+        config.compiler_config.language_version = Some(LanguageVersion::V2_0);
+        assert_eq!(compiler_version_for(&config), CompilerVersion::V2_0);
+
+        config.compiler_config.compiler_version = Some(CompilerVersion::V1);
+        assert_eq!(compiler_version_for(&config), CompilerVersion::V1);
+    }
+
     #[test]
     fn copy_dir_all_errors_if_source_does_not_exist() {
         let temp_dir = tempdir().unwrap();
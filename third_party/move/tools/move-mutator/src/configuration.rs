@@ -2,13 +2,37 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::cli::CLIOptions;
+use crate::{
+    cli::{CLIOptions, ModuleFilter, OutputLayout},
+    report::{ReportDetails, ReportFormat},
+};
+use clap::ValueEnum;
+use move_package::source_package::layout::SourcePackageLayout;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::BTreeMap,
+    io::Read,
     path::{Path, PathBuf},
     str::FromStr,
+    sync::OnceLock,
 };
 
+/// Name of the built-in profile tuned for fast local feedback.
+pub const PROFILE_QUICK: &str = "quick";
+/// Name of the built-in profile tuned for exhaustive runs.
+pub const PROFILE_THOROUGH: &str = "thorough";
+/// Name of the built-in profile tuned for continuous integration runs.
+pub const PROFILE_CI: &str = "ci";
+
+/// Prefix of the environment variables overriding the project options (e.g. `MOVE_MUTATOR_VERIFY_MUTANTS`).
+pub const ENV_PREFIX: &str = "MOVE_MUTATOR_";
+
+/// Path of the configuration file meaning that the configuration is read from the standard input.
+pub const STDIN_CONFIGURATION: &str = "-";
+
+/// Name of the table of the package manifest (`Move.toml`) holding the mutation configuration.
+pub const MANIFEST_MUTATION_TABLE: &str = "mutation";
+
 /// Configuration file type.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum FileType {
@@ -20,13 +44,22 @@ pub enum FileType {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Configuration {
     /// Main project options. It's the same as the CLI options.
+    #[serde(default)]
     pub project: CLIOptions,
     /// Path to the project.
     pub project_path: Option<PathBuf>,
     /// Configuration for the mutation operators (project-wide).
     pub mutation: Option<MutationConfig>,
     /// Configuration for the individual files. (optional).
+    #[serde(default)]
     pub individual: Vec<FileConfiguration>,
+    /// Named configuration profiles (e.g. `[profile.quick]`). Built-in profiles can be overridden here.
+    #[serde(default)]
+    pub profile: BTreeMap<String, Profile>,
+    /// Prover settings overridden for the mutants of the selected modules or for the selected mutants (used by
+    /// the specification testing tool), e.g. a longer timeout for a module known to be heavy.
+    #[serde(default)]
+    pub prover_override: Vec<ProverOverride>,
 }
 
 impl Configuration {
@@ -38,9 +71,80 @@ impl Configuration {
             project_path,
             mutation: None,
             individual: vec![],
+            profile: BTreeMap::new(),
+            prover_override: vec![],
         }
     }
 
+    /// Resolves the effective configuration by merging all configuration sources.
+    ///
+    /// The sources are applied in the following order, where each one overrides the previous ones:
+    /// defaults, `[mutation]` table of the package manifest, configuration file, selected profile,
+    /// environment variables (`MOVE_MUTATOR_*`) and finally options explicitly set on the command line.
+    /// The `[mutation]` table of the configuration file replaces the one of the package manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration file or the package manifest can't be read, an environment
+    /// variable has an invalid value or the selected profile doesn't exist.
+    pub fn resolve(
+        options: CLIOptions,
+        project_path: Option<PathBuf>,
+    ) -> anyhow::Result<Configuration> {
+        Configuration::resolve_with_env(options, project_path, |name| std::env::var(name).ok())
+    }
+
+    /// Resolves the effective configuration using the given environment variables lookup function.
+    fn resolve_with_env<F>(
+        options: CLIOptions,
+        project_path: Option<PathBuf>,
+        lookup: F,
+    ) -> anyhow::Result<Configuration>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let env_options = options_from_env(lookup)?;
+
+        let configuration_file = options
+            .configuration_file
+            .clone()
+            .or_else(|| env_options.configuration_file.clone());
+
+        let mut configuration = match &configuration_file {
+            Some(path) => Configuration::from_file(path)?,
+            None => Configuration::new(CLIOptions::default(), None),
+        };
+
+        if configuration.project_path.is_none() {
+            configuration.project_path = project_path;
+        }
+
+        if configuration.mutation.is_none() {
+            if let Some(project_path) = &configuration.project_path {
+                configuration.mutation = MutationConfig::from_package_manifest(project_path)?;
+            }
+        }
+
+        if let Some(mutation) = &configuration.mutation {
+            mutation.validate()?;
+        }
+
+        let profile = options
+            .profile
+            .clone()
+            .or_else(|| env_options.profile.clone())
+            .or_else(|| configuration.project.profile.clone());
+
+        if let Some(profile) = profile {
+            configuration.apply_profile(&profile)?;
+        }
+
+        configuration.project.merge(&env_options);
+        configuration.project.merge(&options);
+
+        Ok(configuration)
+    }
+
     /// Recognizes the file type based on the file extension.
     /// Currently supported file types are JSON and TOML.
     ///
@@ -69,6 +173,10 @@ impl Configuration {
     ///
     /// * `Configuration` - The configuration read from the file.
     pub fn from_file(config_path: &Path) -> anyhow::Result<Configuration> {
+        if config_path == Path::new(STDIN_CONFIGURATION) {
+            return Configuration::from_stdin();
+        }
+
         let file_type = Configuration::get_file_type(config_path)?;
         debug!("Reading configuration from file type: {:?}", file_type);
         match file_type {
@@ -106,6 +214,52 @@ impl Configuration {
         Ok(serde_json::from_str(&std::fs::read_to_string(json_file)?)?)
     }
 
+    /// Reads configuration (TOML or JSON) from the standard input.
+    ///
+    /// The standard input is read only once, so the configuration can be resolved multiple times during
+    /// the run (e.g. by the spec-test tool and then by the mutator).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the standard input can't be read or the configuration has an invalid format.
+    pub fn from_stdin() -> anyhow::Result<Configuration> {
+        static STDIN: OnceLock<String> = OnceLock::new();
+
+        debug!("Reading configuration from the standard input");
+        let source = match STDIN.get() {
+            Some(source) => source,
+            None => {
+                let mut source = String::new();
+                std::io::stdin().read_to_string(&mut source)?;
+                STDIN.get_or_init(|| source)
+            },
+        };
+        Configuration::from_source(source)
+    }
+
+    /// Reads configuration from the TOML or JSON source (JSON sources are recognized by the leading `{`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration has an invalid format.
+    pub fn from_source(source: &str) -> anyhow::Result<Configuration> {
+        if source.trim_start().starts_with('{') {
+            Ok(serde_json::from_str(source)?)
+        } else {
+            Ok(toml::from_str(source)?)
+        }
+    }
+
+    /// Creates the configuration from the serde value, so the tools embedding the mutator can pass
+    /// the configuration without writing it to a file. Missing options take their default values.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the value doesn't describe a valid configuration.
+    pub fn from_value(value: serde_json::Value) -> anyhow::Result<Configuration> {
+        Ok(serde_json::from_value(value)?)
+    }
+
     /// Returns the configuration for the given file path.
     #[must_use]
     pub fn get_file_configuration(&self, file_path: &Path) -> Option<&FileConfiguration> {
@@ -113,15 +267,369 @@ impl Configuration {
             .iter()
             .find(|file_conf| file_conf.file == file_path)
     }
+
+    /// Returns the profile with the given name.
+    /// Profiles defined in the configuration take precedence over the built-in ones.
+    #[must_use]
+    pub fn get_profile(&self, name: &str) -> Option<Profile> {
+        self.profile
+            .get(name)
+            .cloned()
+            .or_else(|| Profile::builtin(name))
+    }
+
+    /// Returns the prover overrides applying to the mutant with the given ID of the given module, in the order
+    /// of the configuration (so the later ones take precedence).
+    pub fn prover_overrides_for<'a>(
+        &'a self,
+        module: &'a str,
+        mutant_id: &'a str,
+    ) -> impl Iterator<Item = &'a ProverOverride> + 'a {
+        self.prover_override
+            .iter()
+            .filter(move |prover_override| prover_override.applies_to(module, mutant_id))
+    }
+
+    /// Returns the weight of the given mutation operator (1 if no weight is configured).
+    #[must_use]
+    pub fn operator_weight(&self, operator: &str) -> f64 {
+        self.mutation
+            .as_ref()
+            .and_then(|mutation| mutation.weights.get(operator))
+            .copied()
+            .unwrap_or(1.0)
+    }
+
+    /// Returns the monetary functions listed in the mutation configuration.
+    #[must_use]
+    pub fn monetary_functions(&self) -> &[String] {
+        self.mutation
+            .as_ref()
+            .map_or(&[], |mutation| mutation.monetary_functions.as_slice())
+    }
+
+    /// Applies the profile with the given name to the configuration.
+    /// Only the settings defined in the profile are changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such profile.
+    pub fn apply_profile(&mut self, name: &str) -> anyhow::Result<()> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| anyhow::anyhow!("Unknown configuration profile: {name}"))?;
+        debug!("Applying configuration profile {name}: {profile:?}");
+
+        if let Some(operators) = profile.operators {
+            self.mutation
+                .get_or_insert_with(MutationConfig::default)
+                .operators = operators;
+        }
+        if let Some(percentage) = profile.downsampling_ratio_percentage {
+            self.project.downsampling_ratio_percentage = Some(percentage);
+        }
+        if let Some(verify_mutants) = profile.verify_mutants {
+            self.project.verify_mutants = verify_mutants;
+        }
+        if let Some(report_formats) = profile.report_formats {
+            self.project.report_formats = report_formats;
+        }
+        self.project.profile = Some(name.to_owned());
+
+        Ok(())
+    }
+}
+
+/// Reads the project options from the environment variables.
+/// Options which are not set in the environment are left with their default values.
+fn options_from_env<F>(lookup: F) -> anyhow::Result<CLIOptions>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let var = |name: &str| lookup(&format!("{ENV_PREFIX}{name}")).filter(|v| !v.is_empty());
+    let parse_bool = |name: &str, value: String| {
+        bool::from_str(&value)
+            .map_err(|_| anyhow::anyhow!("Invalid value of {ENV_PREFIX}{name}: {value}"))
+    };
+
+    let mut options = CLIOptions::default();
+
+    if let Some(value) = var("MOVE_SOURCES") {
+        options.move_sources = value.split(',').map(PathBuf::from).collect();
+    }
+    if let Some(value) = var("MUTATE_MODULES") {
+        options.mutate_modules = ModuleFilter::from_str(&value).map_err(|e| anyhow::anyhow!(e))?;
+    }
+    if let Some(value) = var("OUT_MUTANT_DIR") {
+        options.out_mutant_dir = Some(PathBuf::from(value));
+    }
+    if let Some(value) = var("ARCHIVE") {
+        options.archive = parse_bool("ARCHIVE", value)?;
+    }
+    if let Some(value) = var("VERIFY_MUTANTS") {
+        options.verify_mutants = parse_bool("VERIFY_MUTANTS", value)?;
+    }
+    if let Some(value) = var("EMIT_PACKAGES") {
+        options.emit_packages = parse_bool("EMIT_PACKAGES", value)?;
+    }
+    if let Some(value) = var("TYPE_CHECK_MUTANTS") {
+        options.type_check_mutants = parse_bool("TYPE_CHECK_MUTANTS", value)?;
+    }
+    if let Some(value) = var("ONLY_VALID") {
+        options.only_valid = parse_bool("ONLY_VALID", value)?;
+    }
+    if let Some(value) = var("NO_OVERWRITE") {
+        options.no_overwrite = parse_bool("NO_OVERWRITE", value)?;
+    }
+    if let Some(value) = var("DOWNSAMPLING_RATIO_PERCENTAGE") {
+        options.downsampling_ratio_percentage = Some(value.parse().map_err(|_| {
+            anyhow::anyhow!("Invalid value of {ENV_PREFIX}DOWNSAMPLING_RATIO_PERCENTAGE: {value}")
+        })?);
+    }
+    if let Some(value) = var("ENTRY_FUNCTIONS_ONLY") {
+        options.entry_functions_only = parse_bool("ENTRY_FUNCTIONS_ONLY", value)?;
+    }
+    if let Some(value) = var("INCLUDE_CALLEES") {
+        options.include_callees = parse_bool("INCLUDE_CALLEES", value)?;
+    }
+    if let Some(value) = var("INCLUDE_TEST_CODE") {
+        options.include_test_code = parse_bool("INCLUDE_TEST_CODE", value)?;
+    }
+    if let Some(value) = var("MUTATE_SPECS") {
+        options.mutate_specs = parse_bool("MUTATE_SPECS", value)?;
+    }
+    if let Some(value) = var("MUTATE_SPEC_FILES") {
+        options.mutate_spec_files = parse_bool("MUTATE_SPEC_FILES", value)?;
+    }
+    if let Some(value) = var("MULTI_FILE_MUTANTS") {
+        options.multi_file_mutants = Some(value.parse().map_err(|_| {
+            anyhow::anyhow!("Invalid value of {ENV_PREFIX}MULTI_FILE_MUTANTS: {value}")
+        })?);
+    }
+    if let Some(value) = var("DIFF_CONTEXT") {
+        options.diff_context = Some(value.parse().map_err(|_| {
+            anyhow::anyhow!("Invalid value of {ENV_PREFIX}DIFF_CONTEXT: {value}")
+        })?);
+    }
+    if let Some(value) = var("WORD_DIFF") {
+        options.word_diff = parse_bool("WORD_DIFF", value)?;
+    }
+    if let Some(value) = var("REPORT_DETAILS") {
+        options.report_details = <ReportDetails as ValueEnum>::from_str(value.trim(), true)
+            .map_err(|_| anyhow::anyhow!("Invalid value of {ENV_PREFIX}REPORT_DETAILS: {value}"))?;
+    }
+    if let Some(value) = var("STRICT") {
+        options.strict = parse_bool("STRICT", value)?;
+    }
+    if let Some(value) = var("WRITE_BUFFER") {
+        options.write_buffer = Some(value.parse().map_err(|_| {
+            anyhow::anyhow!("Invalid value of {ENV_PREFIX}WRITE_BUFFER: {value}")
+        })?);
+    }
+    if let Some(value) = var("DETERMINISTIC") {
+        options.deterministic = parse_bool("DETERMINISTIC", value)?;
+    }
+    if let Some(value) = var("BYTECODE_MODULES") {
+        options.bytecode_modules = value.split(',').map(PathBuf::from).collect();
+    }
+    if let Some(value) = var("CONFIGURATION_FILE") {
+        options.configuration_file = Some(PathBuf::from(value));
+    }
+    if let Some(value) = var("PROFILE") {
+        options.profile = Some(value);
+    }
+    if let Some(value) = var("OUTPUT_LAYOUT") {
+        options.output_layout = <OutputLayout as ValueEnum>::from_str(value.trim(), true)
+            .map_err(|_| anyhow::anyhow!("Invalid value of {ENV_PREFIX}OUTPUT_LAYOUT: {value}"))?;
+    }
+    if let Some(value) = var("NAME_TEMPLATE") {
+        options.name_template = Some(value);
+    }
+    if let Some(value) = var("REPORT_FORMAT") {
+        options.report_formats = value
+            .split(',')
+            .map(|format| {
+                <ReportFormat as ValueEnum>::from_str(format.trim(), true).map_err(|_| {
+                    anyhow::anyhow!("Invalid value of {ENV_PREFIX}REPORT_FORMAT: {format}")
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+    }
+
+    Ok(options)
+}
+
+/// Named set of settings that can be selected with `--profile`.
+/// All fields are optional - settings which are not defined are left untouched.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// Names of the mutation operators to use.
+    pub operators: Option<Vec<String>>,
+    /// Remove averagely given percentage of mutants.
+    pub downsampling_ratio_percentage: Option<usize>,
+    /// Indicates if mutants should be verified and made sure mutants can compile.
+    pub verify_mutants: Option<bool>,
+    /// Timeout for a single prover run in seconds (used by the specification testing tool).
+    pub prover_timeout: Option<usize>,
+    /// Report formats to generate.
+    pub report_formats: Option<Vec<ReportFormat>>,
+}
+
+impl Profile {
+    /// Returns the built-in profile with the given name.
+    #[must_use]
+    pub fn builtin(name: &str) -> Option<Profile> {
+        match name {
+            PROFILE_QUICK => Some(Profile {
+                operators: None,
+                downsampling_ratio_percentage: Some(75),
+                verify_mutants: Some(false),
+                prover_timeout: Some(10),
+                report_formats: Some(vec![ReportFormat::Json]),
+            }),
+            PROFILE_THOROUGH => Some(Profile {
+                operators: None,
+                downsampling_ratio_percentage: Some(0),
+                verify_mutants: Some(true),
+                prover_timeout: Some(120),
+                report_formats: Some(ReportFormat::all()),
+            }),
+            PROFILE_CI => Some(Profile {
+                operators: None,
+                downsampling_ratio_percentage: None,
+                verify_mutants: Some(true),
+                prover_timeout: Some(40),
+                report_formats: Some(vec![ReportFormat::Json]),
+            }),
+            _ => None,
+        }
+    }
 }
 
 /// Configuration of the mutation operators.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct MutationConfig {
     /// Names of the mutation operators to use. If not provided, all operators will be used.
     pub operators: Vec<String>,
     /// Names of the mutation categories to be used.
     pub categories: Vec<String>,
+    /// Weights of the mutation operators used to compute the weighted mutation score (e.g. to make the
+    /// access control mutants more important than the literal tweaks). Operators without a weight weigh 1.
+    pub weights: BTreeMap<String, f64>,
+    /// Full names of the functions moving the funds (e.g. `0xcafe::bank::charge` or `bank::charge`), whose
+    /// amount arguments are mutated besides the ones of the framework functions.
+    pub monetary_functions: Vec<String>,
+    /// Names of the modules which are never mutated.
+    pub exclude_modules: Vec<String>,
+    /// Names of the functions which are never mutated (e.g. `helper` or `module::helper`).
+    pub exclude_functions: Vec<String>,
+    /// Minimum mutation score (in percent) required by the specification testing tool, which fails the run
+    /// when the score is lower.
+    pub min_score: Option<f64>,
+}
+
+impl MutationConfig {
+    /// Checks if the configuration is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any operator weight is negative or not finite, or the minimum score isn't
+    /// a percentage.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if let Some((operator, weight)) = self
+            .weights
+            .iter()
+            .find(|(_, weight)| !weight.is_finite() || **weight < 0.0)
+        {
+            anyhow::bail!(
+                "Invalid weight {weight} of the {operator} operator - weights must be non-negative numbers"
+            );
+        }
+        if let Some(min_score) = self
+            .min_score
+            .filter(|score| !(0.0..=100.0).contains(score))
+        {
+            anyhow::bail!("Invalid minimum score {min_score} - it must be a percentage (0-100)");
+        }
+        Ok(())
+    }
+
+    /// Reads the mutation configuration from the `[mutation]` table of the manifest (`Move.toml`) of the
+    /// package at the given path, so the configuration can be versioned together with the package.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest can't be read or the table has an invalid format.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<MutationConfig>` - The configuration, or `None` if there is no manifest or no table.
+    pub fn from_package_manifest(package_path: &Path) -> anyhow::Result<Option<MutationConfig>> {
+        let manifest_path = package_path.join(SourcePackageLayout::Manifest.path());
+        if !manifest_path.is_file() {
+            return Ok(None);
+        }
+
+        debug!("Reading mutation configuration from the manifest: {manifest_path:?}");
+        let manifest: toml::Value = toml::from_str(&std::fs::read_to_string(&manifest_path)?)?;
+        manifest
+            .get(MANIFEST_MUTATION_TABLE)
+            .cloned()
+            .map(|table| {
+                table.try_into().map_err(|e| {
+                    anyhow::anyhow!(
+                        "Invalid [{MANIFEST_MUTATION_TABLE}] table of {}: {e}",
+                        manifest_path.display()
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    /// Checks if the module is excluded from the mutation.
+    #[must_use]
+    pub fn is_module_excluded(&self, module: &str) -> bool {
+        self.exclude_modules.iter().any(|name| name == module)
+    }
+
+    /// Checks if the function of the module is excluded from the mutation (by its name or by its name
+    /// qualified with the module name).
+    #[must_use]
+    pub fn is_function_excluded(&self, module: &str, function: &str) -> bool {
+        self.exclude_functions
+            .iter()
+            .any(|name| name == function || *name == format!("{module}::{function}"))
+    }
+}
+
+/// Prover settings overridden for some of the mutants (used by the specification testing tool).
+/// Settings which are not defined are left untouched.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProverOverride {
+    /// Names of the modules whose mutants use the settings.
+    pub modules: Vec<String>,
+    /// IDs of the mutants using the settings.
+    pub mutants: Vec<String>,
+    /// Timeout for a single prover run in seconds.
+    pub prover_timeout: Option<usize>,
+    /// Random seed of the solver.
+    pub random_seed: Option<usize>,
+    /// Additional flags passed to Boogie (e.g. `-proverOpt:O:smt.arith.solver=2`).
+    pub boogie_flags: Vec<String>,
+    /// Environment variables set for Boogie and the solver.
+    pub env: BTreeMap<String, String>,
+}
+
+impl ProverOverride {
+    /// Checks if the settings apply to the mutant with the given ID of the given module.
+    #[must_use]
+    pub fn applies_to(&self, module: &str, mutant_id: &str) -> bool {
+        self.modules.iter().any(|name| name == module)
+            || self.mutants.iter().any(|id| id == mutant_id)
+    }
 }
 
 /// Configuration for the individual file.
@@ -201,6 +709,81 @@ mod tests {
         );
     }
 
+    #[test]
+    fn configuration_loads_operator_weights() {
+        let toml_content = r#"
+            individual = []
+            [project]
+            [mutation.weights]
+            named_address_swap = 3.0
+            literal_replacement = 0.5
+        "#;
+        let config: Configuration = toml::from_str(toml_content).unwrap();
+        assert!(config.mutation.as_ref().unwrap().operators.is_empty());
+        assert_eq!(config.operator_weight("named_address_swap"), 3.0);
+        assert_eq!(config.operator_weight("literal_replacement"), 0.5);
+        assert_eq!(config.operator_weight("unary_operator_replacement"), 1.0);
+        assert_eq!(
+            Configuration::new(CLIOptions::default(), None).operator_weight("literal_replacement"),
+            1.0
+        );
+    }
+
+    #[test]
+    fn configuration_loads_monetary_functions() {
+        let toml_content = r#"
+            individual = []
+            [project]
+            [mutation]
+            monetary_functions = ["bank::charge"]
+        "#;
+        let config: Configuration = toml::from_str(toml_content).unwrap();
+        assert_eq!(config.monetary_functions(), ["bank::charge".to_owned()]);
+        assert!(Configuration::new(CLIOptions::default(), None)
+            .monetary_functions()
+            .is_empty());
+    }
+
+    #[test]
+    fn configuration_loads_prover_overrides() {
+        let toml_content = r#"
+            individual = []
+            [project]
+            [[prover_override]]
+            modules = ["vault"]
+            prover_timeout = 120
+            [[prover_override]]
+            mutants = ["00000000000000aa"]
+            random_seed = 7
+            env = { BOOGIE_LOG = "1" }
+        "#;
+        let config: Configuration = toml::from_str(toml_content).unwrap();
+
+        let vault = config
+            .prover_overrides_for("vault", "00000000000000aa")
+            .collect::<Vec<_>>();
+        assert_eq!(vault.len(), 2);
+        assert_eq!(vault[0].prover_timeout, Some(120));
+        assert_eq!(vault[1].random_seed, Some(7));
+        assert_eq!(vault[1].env["BOOGIE_LOG"], "1");
+        assert_eq!(
+            config
+                .prover_overrides_for("bank", "00000000000000bb")
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn negative_operator_weights_are_rejected() {
+        let config: Configuration = toml::from_str(
+            "individual = []\n[project]\n[mutation.weights]\nliteral_replacement = -1.0\n",
+        )
+        .unwrap();
+        let error = config.mutation.unwrap().validate().unwrap_err();
+        assert!(error.to_string().contains("literal_replacement"));
+    }
+
     #[test]
     fn configuration_from_non_existent_toml_file_fails() {
         let result = Configuration::from_toml_file(Path::new("non_existent.toml"));
@@ -297,6 +880,30 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn configuration_from_source_recognizes_format() {
+        let json = Configuration::from_source(r#"{"project": {"verify_mutants": true}}"#).unwrap();
+        assert!(json.project.verify_mutants);
+
+        let toml = Configuration::from_source("[project]\nverify_mutants = true\n").unwrap();
+        assert!(toml.project.verify_mutants);
+
+        assert!(Configuration::from_source("[project\n").is_err());
+    }
+
+    #[test]
+    fn configuration_from_value_uses_defaults() {
+        let config = Configuration::from_value(serde_json::json!({
+            "project": { "downsampling_ratio_percentage": 50 }
+        }))
+        .unwrap();
+        assert_eq!(config.project.downsampling_ratio_percentage, Some(50));
+        assert!(config.individual.is_empty());
+        assert!(config.mutation.is_none());
+
+        assert!(Configuration::from_value(serde_json::json!({ "project": 1 })).is_err());
+    }
+
     #[test]
     fn recognizes_json_file_type_correctly() {
         assert_eq!(
@@ -333,9 +940,228 @@ mod tests {
             project_path: None,
             mutation: None,
             individual: vec![file_config],
+            profile: BTreeMap::new(),
+            prover_override: vec![],
         };
 
         let result = config.get_file_configuration(&PathBuf::from("/unknown/path"));
         assert!(result.is_none());
     }
+
+    #[test]
+    fn configuration_profile_from_toml_file_overrides_settings() {
+        let toml_content = r#"
+            individual = []
+            [project]
+            move_sources = ["/path/to/move/source"]
+            [profile.custom]
+            operators = ["binary_operator_replacement"]
+            downsampling_ratio_percentage = 30
+            verify_mutants = true
+            report_formats = ["text"]
+        "#;
+        fs::write("test_profile.toml", toml_content).unwrap();
+        let mut config = Configuration::from_toml_file(Path::new("test_profile.toml")).unwrap();
+        fs::remove_file("test_profile.toml").unwrap();
+
+        config.apply_profile("custom").unwrap();
+        assert_eq!(config.mutation.unwrap().operators, vec![
+            "binary_operator_replacement"
+        ]);
+        assert_eq!(config.project.downsampling_ratio_percentage, Some(30));
+        assert!(config.project.verify_mutants);
+        assert_eq!(config.project.report_formats, vec![ReportFormat::Text]);
+        assert_eq!(config.project.profile, Some("custom".to_owned()));
+    }
+
+    #[test]
+    fn builtin_profiles_are_available() {
+        let config = Configuration::new(CLIOptions::default(), None);
+        for name in [PROFILE_QUICK, PROFILE_THOROUGH, PROFILE_CI] {
+            assert!(config.get_profile(name).is_some());
+        }
+    }
+
+    #[test]
+    fn configuration_profile_overrides_builtin_profile() {
+        let mut config = Configuration::new(CLIOptions::default(), None);
+        config.profile.insert(PROFILE_QUICK.to_owned(), Profile {
+            downsampling_ratio_percentage: Some(10),
+            ..Default::default()
+        });
+        config.apply_profile(PROFILE_QUICK).unwrap();
+        assert_eq!(config.project.downsampling_ratio_percentage, Some(10));
+        assert!(config.project.report_formats.is_empty());
+    }
+
+    #[test]
+    fn apply_unknown_profile_fails() {
+        let mut config = Configuration::new(CLIOptions::default(), None);
+        assert!(config.apply_profile("unknown").is_err());
+    }
+
+    #[test]
+    fn resolve_without_configuration_file_uses_cli_options() {
+        let options = CLIOptions {
+            move_sources: vec![PathBuf::from("/path/to/move/source")],
+            verify_mutants: true,
+            ..Default::default()
+        };
+        let config =
+            Configuration::resolve_with_env(options, Some(PathBuf::from("/project")), |_| None)
+                .unwrap();
+        assert_eq!(config.project.move_sources, vec![PathBuf::from(
+            "/path/to/move/source"
+        )]);
+        assert!(config.project.verify_mutants);
+        assert_eq!(config.project_path, Some(PathBuf::from("/project")));
+    }
+
+    #[test]
+    fn resolve_cli_options_override_configuration_file() {
+        let toml_content = r#"
+            individual = []
+            [project]
+            move_sources = ["/path/to/move/source"]
+            downsampling_ratio_percentage = 10
+        "#;
+        fs::write("test_resolve_cli.toml", toml_content).unwrap();
+        let options = CLIOptions {
+            configuration_file: Some(PathBuf::from("test_resolve_cli.toml")),
+            downsampling_ratio_percentage: Some(30),
+            ..Default::default()
+        };
+        let config = Configuration::resolve_with_env(options, None, |_| None);
+        fs::remove_file("test_resolve_cli.toml").unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.project.move_sources, vec![PathBuf::from(
+            "/path/to/move/source"
+        )]);
+        assert_eq!(config.project.downsampling_ratio_percentage, Some(30));
+        assert!(!config.project.verify_mutants);
+    }
+
+    #[test]
+    fn resolve_env_overrides_configuration_file_and_profile_but_not_cli() {
+        let toml_content = r#"
+            individual = []
+            [project]
+            out_mutant_dir = "/from/file"
+            profile = "quick"
+        "#;
+        fs::write("test_resolve_env.toml", toml_content).unwrap();
+        let env = BTreeMap::from([
+            ("MOVE_MUTATOR_CONFIGURATION_FILE", "test_resolve_env.toml"),
+            ("MOVE_MUTATOR_OUT_MUTANT_DIR", "/from/env"),
+            ("MOVE_MUTATOR_DOWNSAMPLING_RATIO_PERCENTAGE", "20"),
+            ("MOVE_MUTATOR_REPORT_FORMAT", "json,text"),
+            ("MOVE_MUTATOR_OUTPUT_LAYOUT", "module"),
+            ("MOVE_MUTATOR_REPORT_DETAILS", "external"),
+            ("MOVE_MUTATOR_MUTATE_SPEC_FILES", "true"),
+        ]);
+        let options = CLIOptions {
+            downsampling_ratio_percentage: Some(50),
+            ..Default::default()
+        };
+        let config = Configuration::resolve_with_env(options, None, |name| {
+            env.get(name).map(|v| (*v).to_owned())
+        });
+        fs::remove_file("test_resolve_env.toml").unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(
+            config.project.out_mutant_dir,
+            Some(PathBuf::from("/from/env"))
+        );
+        assert_eq!(config.project.downsampling_ratio_percentage, Some(50));
+        assert_eq!(config.project.report_formats, ReportFormat::all());
+        assert_eq!(config.project.output_layout, OutputLayout::Module);
+        assert_eq!(config.project.report_details, ReportDetails::External);
+        assert!(config.project.mutate_spec_files);
+        assert_eq!(config.project.profile, Some(PROFILE_QUICK.to_owned()));
+    }
+
+    #[test]
+    fn resolve_reads_mutation_table_of_package_manifest() {
+        let package = tempfile::tempdir().unwrap();
+        let manifest = r#"
+            [package]
+            name = "package"
+            version = "0.0.0"
+
+            [mutation]
+            operators = ["literal_replacement"]
+            exclude_modules = ["Helpers"]
+            exclude_functions = ["Coin::debug"]
+            min_score = 80.0
+        "#;
+        fs::write(package.path().join("Move.toml"), manifest).unwrap();
+
+        let config = Configuration::resolve_with_env(
+            CLIOptions::default(),
+            Some(package.path().to_path_buf()),
+            |_| None,
+        )
+        .unwrap();
+        let mutation = config.mutation.unwrap();
+        assert_eq!(mutation.operators, vec!["literal_replacement"]);
+        assert_eq!(mutation.min_score, Some(80.0));
+        assert!(mutation.is_module_excluded("Helpers"));
+        assert!(!mutation.is_module_excluded("Coin"));
+        assert!(mutation.is_function_excluded("Coin", "debug"));
+        assert!(!mutation.is_function_excluded("Other", "debug"));
+
+        // The configuration file replaces the table of the manifest.
+        let config_file = package.path().join("mutator.toml");
+        fs::write(
+            &config_file,
+            "[mutation]\noperators = [\"binary_operator_replacement\"]\n",
+        )
+        .unwrap();
+        let options = CLIOptions {
+            configuration_file: Some(config_file),
+            ..Default::default()
+        };
+        let config =
+            Configuration::resolve_with_env(options, Some(package.path().to_path_buf()), |_| None)
+                .unwrap();
+        let mutation = config.mutation.unwrap();
+        assert_eq!(mutation.operators, vec!["binary_operator_replacement"]);
+        assert!(mutation.exclude_modules.is_empty());
+    }
+
+    #[test]
+    fn resolve_ignores_package_manifest_without_mutation_table() {
+        let package = tempfile::tempdir().unwrap();
+        fs::write(
+            package.path().join("Move.toml"),
+            "[package]\nname = \"package\"\n",
+        )
+        .unwrap();
+        let config = Configuration::resolve_with_env(
+            CLIOptions::default(),
+            Some(package.path().to_path_buf()),
+            |_| None,
+        )
+        .unwrap();
+        assert!(config.mutation.is_none());
+    }
+
+    #[test]
+    fn invalid_minimum_score_is_rejected() {
+        let mutation = MutationConfig {
+            min_score: Some(120.0),
+            ..Default::default()
+        };
+        assert!(mutation.validate().is_err());
+    }
+
+    #[test]
+    fn resolve_fails_for_invalid_env_value() {
+        let result = Configuration::resolve_with_env(CLIOptions::default(), None, |name| {
+            (name == "MOVE_MUTATOR_VERIFY_MUTANTS").then(|| "maybe".to_owned())
+        });
+        assert!(result.is_err());
+    }
 }
@@ -0,0 +1,273 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+/// Number of context lines in the generated diffs, unless configured otherwise.
+pub const DEFAULT_DIFF_CONTEXT: usize = 3;
+
+const ANSI_RESET: &str = "\x1b[0m";
+const ANSI_BOLD: &str = "\x1b[1m";
+const ANSI_RED: &str = "\x1b[31m";
+const ANSI_GREEN: &str = "\x1b[32m";
+const ANSI_CYAN: &str = "\x1b[36m";
+const ANSI_REVERSE: &str = "\x1b[7m";
+const ANSI_NO_REVERSE: &str = "\x1b[27m";
+
+/// Style in which the diffs are rendered for the human-readable outputs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DiffStyle {
+    /// Highlight the changed words within the changed lines.
+    pub word_level: bool,
+    /// Use the ANSI escape codes to color the diff.
+    pub color: bool,
+}
+
+/// Kind of the diff line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LineKind {
+    Header,
+    Hunk,
+    Context,
+    Removed,
+    Added,
+}
+
+/// Creates the diff (patch) between the original and the mutated source with the given number of
+/// context lines.
+#[must_use]
+pub fn create_diff(original_source: &str, mutated_source: &str, context: usize) -> String {
+    diffy::DiffOptions::new()
+        .set_context_len(context)
+        .create_patch(original_source, mutated_source)
+        .to_string()
+}
+
+/// Renders the diff in the given style.
+///
+/// With the word-level style, the changed part of each removed line and its added counterpart is
+/// marked as `[-old-]` and `{+new+}` (or highlighted if the diff is colored).
+#[must_use]
+pub fn render_diff(diff: &str, style: DiffStyle) -> String {
+    let lines = diff.lines().collect::<Vec<_>>();
+    let kinds = line_kinds(&lines);
+    let mut rendered = String::new();
+
+    let mut index = 0;
+    while index < lines.len() {
+        if kinds[index] != LineKind::Removed {
+            push_line(&mut rendered, lines[index], kinds[index], None, style.color);
+            index += 1;
+            continue;
+        }
+
+        // Removed lines are paired with the added lines following them to find the changed words.
+        let count_kind = |start: usize, kind| {
+            kinds[start..]
+                .iter()
+                .take_while(|line_kind| **line_kind == kind)
+                .count()
+        };
+        let removed_end = index + count_kind(index, LineKind::Removed);
+        let added_end = removed_end + count_kind(removed_end, LineKind::Added);
+        let (removed, added) = (&lines[index..removed_end], &lines[removed_end..added_end]);
+
+        for (i, line) in removed.iter().enumerate() {
+            let changed = added
+                .get(i)
+                .filter(|_| style.word_level)
+                .map(|other| changed_ranges(&line[1..], &other[1..]).0);
+            push_line(&mut rendered, line, LineKind::Removed, changed, style.color);
+        }
+        for (i, line) in added.iter().enumerate() {
+            let changed = removed
+                .get(i)
+                .filter(|_| style.word_level)
+                .map(|other| changed_ranges(&other[1..], &line[1..]).1);
+            push_line(&mut rendered, line, LineKind::Added, changed, style.color);
+        }
+
+        index = added_end;
+    }
+
+    rendered
+}
+
+// Internal function classifying the diff lines. Lines before the first hunk form the header.
+fn line_kinds(lines: &[&str]) -> Vec<LineKind> {
+    let mut in_hunk = false;
+    lines
+        .iter()
+        .map(|line| {
+            if line.starts_with("@@") {
+                in_hunk = true;
+                LineKind::Hunk
+            } else if !in_hunk {
+                LineKind::Header
+            } else if line.starts_with('-') {
+                LineKind::Removed
+            } else if line.starts_with('+') {
+                LineKind::Added
+            } else {
+                LineKind::Context
+            }
+        })
+        .collect()
+}
+
+// Internal function appending the rendered line. The `changed` range (relative to the line content
+// without the leading marker) is highlighted.
+fn push_line(
+    rendered: &mut String,
+    line: &str,
+    kind: LineKind,
+    changed: Option<(usize, usize)>,
+    color: bool,
+) {
+    let line_color = match kind {
+        LineKind::Header => Some(ANSI_BOLD),
+        LineKind::Hunk => Some(ANSI_CYAN),
+        LineKind::Removed => Some(ANSI_RED),
+        LineKind::Added => Some(ANSI_GREEN),
+        LineKind::Context => None,
+    };
+    let line_color = line_color.filter(|_| color);
+
+    if let Some(code) = line_color {
+        rendered.push_str(code);
+    }
+
+    match changed.filter(|(start, end)| start < end) {
+        Some((start, end)) => {
+            let (open, close) = match (color, kind) {
+                (true, _) => (ANSI_REVERSE, ANSI_NO_REVERSE),
+                (false, LineKind::Removed) => ("[-", "-]"),
+                (false, _) => ("{+", "+}"),
+            };
+            let (marker, content) = line.split_at(1);
+            rendered.push_str(marker);
+            rendered.push_str(&content[..start]);
+            rendered.push_str(open);
+            rendered.push_str(&content[start..end]);
+            rendered.push_str(close);
+            rendered.push_str(&content[end..]);
+        },
+        None => rendered.push_str(line),
+    }
+
+    if line_color.is_some() {
+        rendered.push_str(ANSI_RESET);
+    }
+    rendered.push('\n');
+}
+
+// Internal function returning the byte ranges of the changed words in the old and the new line
+// (the lines without their common prefix and suffix words).
+fn changed_ranges(old: &str, new: &str) -> ((usize, usize), (usize, usize)) {
+    let old_words = word_offsets(old);
+    let new_words = word_offsets(new);
+    let word = |line: &str, words: &[usize], i: usize| {
+        let end = words.get(i + 1).copied().unwrap_or(line.len());
+        line[words[i]..end].to_owned()
+    };
+
+    let max_common = old_words.len().min(new_words.len());
+    let prefix = (0..max_common)
+        .take_while(|&i| word(old, &old_words, i) == word(new, &new_words, i))
+        .count();
+    let suffix = (0..max_common - prefix)
+        .take_while(|&i| {
+            word(old, &old_words, old_words.len() - 1 - i)
+                == word(new, &new_words, new_words.len() - 1 - i)
+        })
+        .count();
+
+    let range = |line: &str, words: &[usize]| {
+        let start = words.get(prefix).copied().unwrap_or(line.len());
+        let end = words
+            .get(words.len() - suffix)
+            .copied()
+            .unwrap_or(line.len());
+        (start, end.max(start))
+    };
+    (range(old, &old_words), range(new, &new_words))
+}
+
+// Internal function returning the start offsets of the words of the line. Words are the runs of
+// identifier characters, the runs of whitespaces and the single other characters.
+fn word_offsets(line: &str) -> Vec<usize> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+
+    let mut offsets = vec![];
+    let mut previous = None;
+    for (offset, c) in line.char_indices() {
+        let current = class(c);
+        if previous != Some(current) || current == 2 {
+            offsets.push(offset);
+        }
+        previous = Some(current);
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "a\nb\nc\nlet x = y + 1;\nd\ne\nf\n";
+    const MUTATED: &str = "a\nb\nc\nlet x = y - 1;\nd\ne\nf\n";
+
+    #[test]
+    fn diff_context_is_configurable() {
+        let diff = create_diff(ORIGINAL, MUTATED, 1);
+        assert!(diff.contains("@@ -3,3 +3,3 @@"));
+        assert!(!diff.contains("\n b\n"));
+
+        let diff = create_diff(ORIGINAL, MUTATED, DEFAULT_DIFF_CONTEXT);
+        assert!(diff.contains("\n a\n"));
+    }
+
+    #[test]
+    fn plain_style_keeps_diff() {
+        let diff = create_diff(ORIGINAL, MUTATED, 0);
+        assert_eq!(render_diff(&diff, DiffStyle::default()), diff);
+    }
+
+    #[test]
+    fn word_level_style_marks_changed_words() {
+        let diff = create_diff(ORIGINAL, MUTATED, 0);
+        let rendered = render_diff(&diff, DiffStyle {
+            word_level: true,
+            color: false,
+        });
+        assert!(rendered.contains("-let x = y [-+-] 1;\n"));
+        assert!(rendered.contains("+let x = y {+-+} 1;\n"));
+    }
+
+    #[test]
+    fn colored_style_uses_ansi_codes() {
+        let diff = create_diff(ORIGINAL, MUTATED, 0);
+        let rendered = render_diff(&diff, DiffStyle {
+            word_level: true,
+            color: true,
+        });
+        assert!(rendered.contains(&format!(
+            "{ANSI_RED}-let x = y {ANSI_REVERSE}+{ANSI_NO_REVERSE} 1;{ANSI_RESET}"
+        )));
+        assert!(rendered.contains(&format!("{ANSI_GREEN}+let x = y ")));
+    }
+
+    #[test]
+    fn changed_ranges_cover_changed_words() {
+        assert_eq!(changed_ranges("a == b", "a != b"), ((2, 3), (2, 3)));
+        assert_eq!(changed_ranges("foo(x)", "foo(x, y)"), ((5, 5), (5, 8)));
+        assert_eq!(changed_ranges("same", "same"), ((4, 4), (4, 4)));
+    }
+}
@@ -0,0 +1,615 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    compiler::generate_ast,
+    configuration::Configuration,
+    diff,
+    mutant::Mutant as MutationSite,
+    mutate::{self, SCRIPT_MODULE_NAME},
+    operator::MutantInfo,
+    report::{FileFailure, Mutation, MutationReport},
+    rewrite::Rewrite,
+};
+use move_model::model::GlobalEnv;
+use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
+use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, SeedableRng};
+use rayon::prelude::*;
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// Generator of the mutants for the tools embedding the mutator (e.g. fuzzers or IDE plugins), which
+/// consume the mutants programmatically instead of reading them from the output directory.
+///
+/// The package is compiled once, when the generator is created. The mutants are generated when the iterator
+/// over them is created. They are neither verified nor saved - use `compiler::verify_mutant` to check if
+/// the mutant compiles.
+pub struct MutantGenerator {
+    configuration: Configuration,
+    env: GlobalEnv,
+    package_root: Option<PathBuf>,
+    sites: Vec<MutationSite>,
+    unsupported_sites: usize,
+    failures: Vec<FileFailure>,
+}
+
+impl MutantGenerator {
+    /// Creates the generator for the package at the configured project path (or the current directory
+    /// if the path is not set), using the default build configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package can't be compiled.
+    pub fn new(configuration: Configuration) -> anyhow::Result<Self> {
+        Self::with_build_config(configuration, &BuildConfig::default())
+    }
+
+    /// Creates the generator for the package at the configured project path (or the current directory
+    /// if the path is not set), using the given build configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package can't be compiled (or, in the strict mode, if any of its files
+    /// can't be traversed).
+    pub fn with_build_config(
+        configuration: Configuration,
+        build_config: &BuildConfig,
+    ) -> anyhow::Result<Self> {
+        let package_path = configuration
+            .project_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from("."));
+        let env = generate_ast(&configuration, build_config, &package_path)?;
+        // The mutant identifiers are computed from the paths relative to the package root.
+        let package_root = package_path
+            .canonicalize()
+            .ok()
+            .and_then(|path| SourcePackageLayout::try_find_root(&path).ok());
+
+        trace!("Generated AST.");
+
+        let traversal = mutate::mutate(&env, &configuration)?;
+        Ok(Self {
+            configuration,
+            env,
+            package_root,
+            sites: traversal.mutants,
+            unsupported_sites: traversal.unsupported_sites,
+            failures: traversal.failures,
+        })
+    }
+
+    /// Returns the configuration of the generator.
+    #[must_use]
+    pub fn configuration(&self) -> &Configuration {
+        &self.configuration
+    }
+
+    /// Returns the compiler options the package was compiled with (e.g. to type check the mutants with
+    /// `compiler::type_check_mutant`).
+    #[must_use]
+    pub fn compiler_options(&self) -> move_compiler_v2::Options {
+        self.env
+            .get_extension::<move_compiler_v2::Options>()
+            .map(|options| (*options).clone())
+            .unwrap_or_default()
+    }
+
+    /// Returns the model of the mutated package.
+    #[must_use]
+    pub fn env(&self) -> &GlobalEnv {
+        &self.env
+    }
+
+    /// Returns the iterator over the mutants of the package.
+    ///
+    /// Downsampling and the mutation operators filter from the configuration are applied here, so the
+    /// iterator yields the same mutants as the mutator tool (before their verification). The mutation
+    /// operators are applied to all sites in parallel, and the mutants are yielded in the order of the sites.
+    pub fn iter(&self) -> impl Iterator<Item = Mutant<'_>> + '_ {
+        // The model is not thread-safe, so the files of the sites are looked up before the operators run.
+        let sites = self
+            .sites
+            .iter()
+            .map(|site| {
+                let file_id = site.get_file_id();
+                let path = Path::new(self.env.get_file(file_id));
+                (site, path, self.env.get_file_source(file_id))
+            })
+            .collect::<Vec<_>>();
+        let mutated_sources = sites
+            .par_iter()
+            .map(|(site, path, source)| {
+                let package_root = self.package_root.as_deref();
+                apply_site(site, path, package_root, source, &self.configuration)
+            })
+            .collect::<Vec<_>>();
+
+        sites
+            .into_iter()
+            .zip(mutated_sources)
+            .flat_map(move |((site, path, source), infos)| {
+                trace!("Processing file: {path:?}");
+
+                // If there is no module name, it is a script.
+                let module_name = site
+                    .get_module_name()
+                    .unwrap_or_else(|| SCRIPT_MODULE_NAME.to_owned());
+                let module_address = site.get_module_address();
+                let is_script = site.is_script();
+                let function_name = site.get_function_name().unwrap_or_default();
+                let description = site.to_string();
+
+                infos.into_iter().map(move |info| Mutant {
+                    path,
+                    package_root: self.package_root.as_deref(),
+                    source,
+                    description: description.clone(),
+                    module_name: module_name.clone(),
+                    module_address: module_address.clone(),
+                    function_name: function_name.clone(),
+                    is_script,
+                    info,
+                })
+            })
+    }
+
+    /// Returns the number of the mutation sites (places in the code the mutation operators apply to),
+    /// keyed by the operator name. Operators excluded by the mutation operators filter are not counted.
+    #[must_use]
+    pub fn operator_sites(&self) -> BTreeMap<String, u32> {
+        let mut sites = BTreeMap::new();
+        for site in &self.sites {
+            let operator = site.operator_name();
+            if is_operator_enabled(&self.configuration, &operator) {
+                *sites.entry(operator).or_default() += 1;
+            }
+        }
+        sites
+    }
+
+    /// Returns the number of the places in the code skipped because of the syntax the mutation operators
+    /// can't rewrite (e.g. the expressions synthesized by the compiler for the `for` loops).
+    #[must_use]
+    pub fn unsupported_sites(&self) -> usize {
+        self.unsupported_sites
+    }
+
+    /// Returns the files which couldn't be traversed (and so have no mutants). They are recorded only
+    /// if the strict mode is disabled - otherwise creating the generator fails.
+    #[must_use]
+    pub fn failures(&self) -> &[FileFailure] {
+        &self.failures
+    }
+
+    /// Returns up to `limit` mutants spanning two files of the package.
+    ///
+    /// For each call crossing the source files, the mutants of the called function are paired with the
+    /// mutants of the calling function (the first with the first, the second with the second, and so on),
+    /// so each such mutant changes the callee and its use in another module at the same time.
+    #[must_use]
+    pub fn multi_file_mutants(&self, limit: usize) -> Vec<MultiFileMutant<'_>> {
+        let mut by_function = BTreeMap::<(&str, &str), Vec<Mutant<'_>>>::new();
+        let mutants = self.iter().collect::<Vec<_>>();
+        for mutant in &mutants {
+            by_function
+                .entry((mutant.module_name(), mutant.function_name()))
+                .or_default()
+                .push(mutant.clone());
+        }
+
+        let calls = mutate::cross_file_calls(&self.env);
+        let mut result = vec![];
+        for ((callee_module, callee), (caller_module, caller)) in calls {
+            let (Some(callee_mutants), Some(caller_mutants)) = (
+                by_function.get(&(callee_module.as_str(), callee.as_str())),
+                by_function.get(&(caller_module.as_str(), caller.as_str())),
+            ) else {
+                continue;
+            };
+
+            for (callee_mutant, caller_mutant) in callee_mutants.iter().zip(caller_mutants) {
+                if result.len() == limit {
+                    return result;
+                }
+                result.push(MultiFileMutant {
+                    mutants: vec![callee_mutant.clone(), caller_mutant.clone()],
+                });
+            }
+        }
+
+        result
+    }
+}
+
+/// A single mutant of the package, generated by the `MutantGenerator`.
+#[derive(Debug, Clone)]
+pub struct Mutant<'a> {
+    /// Path of the original file.
+    path: &'a Path,
+    /// Root directory of the mutated package (if it's known).
+    package_root: Option<&'a Path>,
+    /// Source of the original file.
+    source: &'a str,
+    /// Description of the mutant used in the logs.
+    description: String,
+    /// Name of the mutated module.
+    module_name: String,
+    /// Address of the mutated module (`None` for the scripts).
+    module_address: Option<String>,
+    /// Name of the mutated function.
+    function_name: String,
+    /// Whether the mutated function is a script.
+    is_script: bool,
+    /// The mutated source and the applied modification.
+    info: MutantInfo,
+}
+
+impl Mutant<'_> {
+    /// Returns the path of the mutated file.
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        self.path
+    }
+
+    /// Returns the name of the mutated module (`script` for the scripts).
+    #[must_use]
+    pub fn module_name(&self) -> &str {
+        &self.module_name
+    }
+
+    /// Returns the address of the mutated module (e.g. `0xcafe`), which tells apart the modules with the same
+    /// name. `None` for the scripts.
+    #[must_use]
+    pub fn module_address(&self) -> Option<&str> {
+        self.module_address.as_deref()
+    }
+
+    /// Returns the name of the mutated function (empty if the mutant is outside of any function).
+    #[must_use]
+    pub fn function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Returns true if the mutant is in a script (not in a module).
+    #[must_use]
+    pub fn is_script(&self) -> bool {
+        self.is_script
+    }
+
+    /// Returns the description of the mutation operator which generated the mutant.
+    #[must_use]
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    /// Returns the modification applied by the mutant.
+    #[must_use]
+    pub fn mutation(&self) -> &Mutation {
+        &self.info.mutation
+    }
+
+    /// Returns the name of the mutation operator which generated the mutant.
+    #[must_use]
+    pub fn operator_name(&self) -> &str {
+        self.info.mutation.get_operator_name()
+    }
+
+    /// Returns the line (starting from 1) of the mutated code.
+    #[must_use]
+    pub fn line(&self) -> usize {
+        let offset = self.info.mutation.get_changed_place().start();
+        self.source
+            .bytes()
+            .take(offset)
+            .filter(|b| *b == b'\n')
+            .count()
+            + 1
+    }
+
+    /// Returns the source of the original file.
+    #[must_use]
+    pub fn original_source(&self) -> &str {
+        self.source
+    }
+
+    /// Returns the source of the mutated file.
+    #[must_use]
+    pub fn mutated_source(&self) -> &str {
+        &self.info.mutated_source
+    }
+
+    /// Checks if the mutant doesn't change the source (e.g. the literal is replaced with the same value).
+    #[must_use]
+    pub fn is_no_op(&self) -> bool {
+        self.mutated_source() == self.original_source()
+    }
+
+    /// Applies the mutant to the given source (e.g. the file from the editor buffer) and returns the
+    /// mutated source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given source doesn't contain the mutated code at the mutated place.
+    pub fn apply(&self, source: &str) -> anyhow::Result<String> {
+        let place = self.info.mutation.get_changed_place();
+        let (start, end) = (place.start(), place.end());
+
+        let original = self.source.get(start..end);
+        if original.is_none() || source.get(start..end) != original {
+            anyhow::bail!("The source doesn't contain the mutated code at {start}..{end}");
+        }
+
+        // The mutation replaces the code at the mutated place, so the rest of the file stays the same.
+        Rewrite::of_span(self.source, &self.info.mutated_source, start, end)
+            .ok_or_else(|| {
+                anyhow::anyhow!("The mutant changes the code outside of {start}..{end}")
+            })?
+            .apply(source)
+    }
+
+    /// Returns the diff between the original and the mutated source with the given number of context lines.
+    #[must_use]
+    pub fn diff(&self, context: usize) -> String {
+        diff::create_diff(self.source, &self.info.mutated_source, context)
+    }
+
+    /// Returns the stable identifier of the mutant (the same as in the mutator reports).
+    #[must_use]
+    pub fn id(&self) -> String {
+        self.report(diff::DEFAULT_DIFF_CONTEXT).get_id().to_owned()
+    }
+
+    /// Creates the mutator report entry of the mutant, with the diff of the given number of context lines.
+    /// The mutant path is left empty, as the mutant is not saved.
+    #[must_use]
+    pub fn report(&self, diff_context: usize) -> MutationReport {
+        let mut entry = MutationReport::with_diff_context(
+            Path::new(""),
+            self.path,
+            &self.module_name,
+            &self.function_name,
+            &self.info.mutated_source,
+            self.source,
+            diff_context,
+        );
+        entry.add_modification(self.info.mutation.clone());
+        entry.set_script(self.is_script);
+        if let Some(address) = &self.module_address {
+            entry.set_module_address(address);
+        }
+        if let Some(package_root) = self.package_root {
+            entry.set_package_root(package_root);
+        }
+        entry
+    }
+}
+
+/// A mutant spanning multiple files of the package, generated by the `MutantGenerator`. It combines
+/// the single-file mutants of different files - the first one is the primary mutant.
+#[derive(Debug, Clone)]
+pub struct MultiFileMutant<'a> {
+    mutants: Vec<Mutant<'a>>,
+}
+
+impl<'a> MultiFileMutant<'a> {
+    /// Returns the single-file mutants combined in the mutant.
+    #[must_use]
+    pub fn mutants(&self) -> &[Mutant<'a>] {
+        &self.mutants
+    }
+
+    /// Returns the description of the mutant used in the logs.
+    #[must_use]
+    pub fn description(&self) -> String {
+        self.mutants
+            .iter()
+            .map(Mutant::description)
+            .collect::<Vec<_>>()
+            .join(" + ")
+    }
+
+    /// Returns the stable identifier of the mutant (the same as in the mutator reports).
+    #[must_use]
+    pub fn id(&self) -> String {
+        self.report(diff::DEFAULT_DIFF_CONTEXT).get_id().to_owned()
+    }
+
+    /// Creates the mutator report entry of the mutant, with the diffs of the given number of context lines.
+    /// The mutant paths are left empty, as the mutant is not saved.
+    #[must_use]
+    pub fn report(&self, diff_context: usize) -> MutationReport {
+        MutationReport::multi_file(
+            Path::new(""),
+            self.mutants
+                .iter()
+                .map(|mutant| mutant.report(diff_context))
+                .collect(),
+        )
+    }
+}
+
+/// Applies the mutation operator of the site to the source and returns the mutated sources.
+/// Downsampling and the mutation operators filter from the configuration are applied here.
+///
+/// In the deterministic mode, the downsampling is seeded with the hash of the site (with the path relative
+/// to the package root, if it's known), so the same mutants are kept in every run, regardless of the order
+/// in which the sites are processed and of the directory the package is in.
+///
+/// # Panics
+///
+/// The function will panic if `downsampling_ratio_percentage` is not in the range 0..=100.
+fn apply_site(
+    site: &MutationSite,
+    path: &Path,
+    package_root: Option<&Path>,
+    source: &str,
+    mutator_configuration: &Configuration,
+) -> Vec<MutantInfo> {
+    let mut mutated_sources = site.apply(source);
+
+    // If the downsample ratio is set, we need to downsample the mutants.
+    //TODO: currently we are downsampling the mutants after they are generated. This is not
+    // ideal as we are generating all mutants and then removing some of them.
+    if let Some(percentage) = mutator_configuration.project.downsampling_ratio_percentage {
+        let no_of_mutants_to_keep = mutated_sources
+            .len()
+            .saturating_sub((mutated_sources.len() * percentage).div_ceil(100));
+        assert!(
+            no_of_mutants_to_keep <= mutated_sources.len(),
+            "Invalid downsampling ratio"
+        );
+
+        // Delete randomly elements from the vector.
+        let chosen_elements: Vec<_> = if mutator_configuration.project.deterministic {
+            let mut rng = StdRng::seed_from_u64(site_seed(path, package_root, &mutated_sources));
+            mutated_sources
+                .choose_multiple(&mut rng, no_of_mutants_to_keep)
+                .cloned()
+                .collect()
+        } else {
+            mutated_sources
+                .choose_multiple(&mut thread_rng(), no_of_mutants_to_keep)
+                .cloned()
+                .collect()
+        };
+
+        mutated_sources = chosen_elements;
+    }
+
+    mutated_sources.retain(|mutated| {
+        is_operator_enabled(mutator_configuration, mutated.mutation.get_operator_name())
+    });
+
+    mutated_sources
+}
+
+// Internal function computing the seed of the downsampling of the site from its file (relative to the package
+// root, with the components separated by `/`) and its mutations, using the 64-bit FNV-1a hash (the `std`
+// hashers are not guaranteed to be stable between Rust releases).
+fn site_seed(path: &Path, package_root: Option<&Path>, mutants: &[MutantInfo]) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut update = |data: &str| {
+        for byte in data.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    let path = package_root
+        .and_then(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path);
+    update(
+        &path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    );
+    for mutant in mutants {
+        let mutation = &mutant.mutation;
+        update(mutation.get_operator_name());
+        update(&mutation.get_changed_place().start().to_string());
+        update(&mutation.get_changed_place().end().to_string());
+        update(mutation.get_new_value());
+    }
+
+    hash
+}
+
+// Internal function checking if the operator passes the mutation operators filter from the configuration
+// (all operators pass the empty filter).
+fn is_operator_enabled(mutator_configuration: &Configuration, operator: &str) -> bool {
+    mutator_configuration
+        .mutation
+        .as_ref()
+        .map_or(true, |conf| {
+            conf.operators.is_empty() || conf.operators.iter().any(|op| op == operator)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::Range;
+
+    fn mutant<'a>(source: &'a str, mutated_source: &str, start: usize, end: usize) -> Mutant<'a> {
+        Mutant {
+            path: Path::new("sources/Sum.move"),
+            package_root: None,
+            source,
+            description: String::new(),
+            module_name: "Sum".to_owned(),
+            module_address: Some("0xcafe".to_owned()),
+            function_name: "sum".to_owned(),
+            is_script: false,
+            info: MutantInfo::new(
+                mutated_source.to_owned(),
+                Mutation::new(
+                    Range::new(start, end),
+                    "binary_operator_replacement".to_owned(),
+                    "+".to_owned(),
+                    "-".to_owned(),
+                ),
+            ),
+        }
+    }
+
+    #[test]
+    fn mutant_applies_to_other_sources() {
+        let mutant = mutant("let x = y + 1;\n", "let x = y - 1;\n", 10, 11);
+        assert_eq!(mutant.line(), 1);
+        assert_eq!(
+            mutant.apply(mutant.original_source()).unwrap(),
+            mutant.mutated_source()
+        );
+        assert_eq!(
+            mutant.apply("let x = y + 1;\n// edited\n").unwrap(),
+            "let x = y - 1;\n// edited\n"
+        );
+        assert!(mutant.apply("let x = y * 1;\n").is_err());
+        assert!(mutant.apply("let").is_err());
+
+        // Mutants changing the code outside of the mutated place (e.g. the whitespace) are rejected.
+        let churned = self::mutant("let x = y + 1;\n", "let x = y - 1;\r\n", 10, 11);
+        assert!(churned.apply(churned.original_source()).is_err());
+    }
+
+    #[test]
+    fn mutant_id_matches_report() {
+        let mutant = mutant("a\nlet x = y + 1;\n", "a\nlet x = y - 1;\n", 12, 13);
+        assert_eq!(mutant.line(), 2);
+        let report = mutant.report(0);
+        assert_eq!(mutant.id(), report.get_id());
+        assert_eq!(report.get_diff(), mutant.diff(0));
+        assert_eq!(report.get_mutations(), &[mutant.mutation().clone()]);
+    }
+
+    #[test]
+    fn site_seed_depends_only_on_site() {
+        let first = mutant("let x = y + 1;\n", "let x = y - 1;\n", 10, 11).info;
+        let second = mutant("let x = y + 1 + 2;\n", "let x = y + 1 - 2;\n", 14, 15).info;
+        let path = Path::new("sources/Sum.move");
+
+        let seed = site_seed(path, None, &[first.clone()]);
+        assert_eq!(seed, site_seed(path, None, &[first.clone()]));
+        assert_ne!(seed, site_seed(path, None, &[second]));
+        assert_ne!(
+            seed,
+            site_seed(Path::new("sources/Other.move"), None, &[first.clone()])
+        );
+
+        // The seed doesn't depend on the directory the package is in.
+        let moved = Path::new("/work/pkg/sources/Sum.move");
+        assert_eq!(
+            seed,
+            site_seed(moved, Some(Path::new("/work/pkg")), &[first])
+        );
+    }
+}
@@ -6,27 +6,45 @@ extern crate pretty_env_logger;
 #[macro_use]
 extern crate log;
 
+pub mod archive;
+mod bytecode;
 pub mod cli;
 pub mod compiler;
 
 mod mutate;
 
 pub mod configuration;
+pub mod diff;
+pub mod generator;
+pub mod manifest;
 mod mutant;
 mod operator;
 mod operators;
 mod output;
 pub mod report;
+mod rewrite;
+mod writer;
 
+pub use crate::operator::OperatorDescription;
 use crate::{
-    compiler::{generate_ast, verify_mutant},
+    cli::OutputLayout,
+    compiler::{
+        type_check_multi_file_mutant, type_check_mutant, verify_multi_file_mutant, verify_mutant,
+    },
     configuration::Configuration,
-    report::Report,
+    diff::DiffStyle,
+    generator::{MultiFileMutant, Mutant, MutantGenerator},
+    manifest::Manifest,
+    report::{FileFailure, MutationReport, Report, ReportFormat, ReportLine, ReportStream},
+    writer::{MutantWriter, DEFAULT_WRITE_BUFFER},
 };
-use move_package::BuildConfig;
-use rand::{seq::SliceRandom, thread_rng};
+use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
+use rayon::prelude::*;
 use std::{fs, path::Path};
 
+/// Version of the mutator (recorded e.g. in the reports of the tools using it).
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Runs the Move mutator tool.
 /// Entry point for the Move mutator tool both for the CLI and the Rust API.
 ///
@@ -59,120 +77,450 @@ pub fn run_move_mutator(
         "Executed move-mutator with the following options: {options:?} \n config: {config:?} \n package path: {package_path:?}"
     );
 
-    // Load configuration from file or create a new one.
-    let mutator_configuration = match options.configuration_file {
-        Some(path) => Configuration::from_file(path.as_path())?,
-        None => Configuration::new(options, Some(package_path.to_owned())),
-    };
+    let print_config = options.print_config;
 
-    trace!("Mutator configuration: {mutator_configuration:?}");
+    // Merge defaults, configuration file, profile, environment variables and command line options.
+    let mutator_configuration = Configuration::resolve(options, Some(package_path.to_owned()))?;
 
-    let env = generate_ast(
-        &mutator_configuration,
-        config,
-        mutator_configuration
-            .project_path
-            .as_ref()
-            .unwrap_or(&package_path.to_owned())
-            .as_path(),
-    )?;
+    if print_config {
+        println!("{}", serde_json::to_string_pretty(&mutator_configuration)?);
+        return Ok(());
+    }
 
-    trace!("Generated AST.");
+    trace!("Mutator configuration: {mutator_configuration:?}");
+
+    // The compiled modules are mutated by the experimental bytecode backend instead of the sources.
+    if !mutator_configuration.project.bytecode_modules.is_empty() {
+        return bytecode::run_bytecode_mutator(&mutator_configuration);
+    }
 
-    let mutants = mutate::mutate(&env, &mutator_configuration)?;
-    let output_dir = output::setup_output_dir(&mutator_configuration)?;
+    // The project path is always set, as it's resolved from the package path.
+    let generator = MutantGenerator::with_build_config(mutator_configuration, config)?;
+    let mutator_configuration = generator.configuration();
+    let output_dir = output::setup_output_dir(mutator_configuration)?;
     let mut report: Report = Report::new();
+    for (operator, sites) in generator.operator_sites() {
+        report.add_operator_sites(&operator, sites);
+    }
+    let unsupported_sites = generator.unsupported_sites();
+    if unsupported_sites > 0 {
+        info!("Skipped {unsupported_sites} places in the code with the syntax the operators can't mutate");
+    }
+    report.set_unsupported_sites(u32::try_from(unsupported_sites)?);
+    for failure in generator.failures() {
+        report.add_failure(failure.clone());
+    }
+    let strict = mutator_configuration.project.strict;
+
+    // The mutated sources are generated and then the candidates are verified (which compiles each of them) in
+    // parallel. The results are collected in the generation order, so the output (mutant file names and
+    // the report) stays deterministic.
+    // Mutants identical to the original source can't be killed, so they are counted, but not verified,
+    // written or sent to the prover.
+    let mut no_op = 0;
+    let candidates = generator
+        .iter()
+        .filter(|candidate| {
+            if !candidate.is_no_op() {
+                return true;
+            }
+            debug!(
+                "{} doesn't change the source and is skipped",
+                candidate.description()
+            );
+            report.increment_operator_no_op(candidate.operator_name());
+            no_op += 1;
+            false
+        })
+        .collect::<Vec<_>>();
+    if no_op > 0 {
+        warn!("Skipped {no_op} mutants identical to the original source");
+    }
+    let verify_mutants = mutator_configuration.project.verify_mutants;
+    let only_valid = mutator_configuration.project.only_valid;
+    let type_check = mutator_configuration.project.type_check_mutants || only_valid;
+    let results = candidates
+        .par_iter()
+        // The compiler options can't be shared between the threads, so each thread gets its own copy.
+        .map_with(
+            generator.compiler_options(),
+            |compiler_options, candidate| {
+                let compiles = !verify_mutants || {
+                    let res = verify_mutant(config, candidate.mutated_source(), candidate.path());
+
+                    // In case the mutant is not a valid Move file, skip the mutant (do not save it).
+                    if res.is_err() {
+                        warn!(
+                            "{} is not valid and will not be generated. Error: {res:?}",
+                            candidate.description()
+                        );
+                    }
+                    res.is_ok()
+                };
+
+                let type_checks = type_check.then(|| {
+                    let res = type_check_mutant(
+                        compiler_options,
+                        candidate.mutated_source(),
+                        candidate.path(),
+                    );
+                    if let Err(e) = &res {
+                        info!("{} doesn't type check: {e}", candidate.description());
+                    }
+                    res.is_ok()
+                });
+
+                (compiles, type_checks)
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let diff_context = mutator_configuration
+        .project
+        .diff_context
+        .unwrap_or(diff::DEFAULT_DIFF_CONTEXT);
+    let layout = mutator_configuration.project.output_layout;
+    let name_template = mutator_configuration.project.name_template.as_deref();
+    if let Some(template) = name_template {
+        output::check_name_template(template)?;
+    }
+    let packages_root = if mutator_configuration.project.emit_packages {
+        let project_path = mutator_configuration.project_path.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Mutant packages can be emitted only for the mutants of a package")
+        })?;
+        Some(SourcePackageLayout::try_find_root(
+            &project_path.canonicalize()?,
+        )?)
+    } else {
+        None
+    };
+    let report_formats = if mutator_configuration.project.report_formats.is_empty() {
+        ReportFormat::all()
+    } else {
+        mutator_configuration.project.report_formats.clone()
+    };
+    // The entries are streamed to the JSON lines report as they are generated, and kept in memory only if
+    // another report format needs them.
+    let mut stream = if report_formats.contains(&ReportFormat::JsonLines) {
+        Some(
+            ReportStream::create(&output_dir.join(ReportFormat::JsonLines.file_name()))?
+                .with_details(mutator_configuration.project.report_details),
+        )
+    } else {
+        None
+    };
+    let keep_entries = report_formats
+        .iter()
+        .any(|format| *format != ReportFormat::JsonLines);
 
-    for mutant in &mutants {
-        let file_id = &mutant.get_file_id();
-        let source = env.get_file_source(*file_id);
-        let filename = env.get_file(*file_id);
-        let path = Path::new(filename);
-
-        trace!("Processing file: {path:?}");
-
-        let mut mutated_sources = mutant.apply(source);
-
-        // If the downsample ratio is set, we need to downsample the mutants.
-        //TODO: currently we are downsampling the mutants after they are generated. This is not
-        // ideal as we are generating all mutants and then removing some of them.
-        if let Some(percentage) = mutator_configuration.project.downsampling_ratio_percentage {
-            let no_of_mutants_to_keep = mutated_sources
-                .len()
-                .saturating_sub((mutated_sources.len() * percentage).div_ceil(100));
-            assert!(
-                no_of_mutants_to_keep <= mutated_sources.len(),
-                "Invalid downsampling ratio"
+    // The mutant files are written by a dedicated thread, so the entries are added to the report once the
+    // writes complete.
+    let writer = MutantWriter::new(
+        mutator_configuration
+            .project
+            .write_buffer
+            .unwrap_or(DEFAULT_WRITE_BUFFER),
+    );
+    let mut written = vec![];
+    for (candidate, (compiles, type_checks)) in candidates.into_iter().zip(results) {
+        if !compiles {
+            report.increment_operator_skipped(candidate.operator_name());
+            continue;
+        }
+        if only_valid && type_checks == Some(false) {
+            info!(
+                "{} is skipped as it doesn't type check",
+                candidate.description()
             );
+            report.increment_operator_skipped(candidate.operator_name());
+            continue;
+        }
+
+        let path = candidate.path();
+        let mutant_name = mutant_file_name(&candidate);
+        let mut entry = candidate.report(diff_context);
+        // Mutants which compile type check as well.
+        if let Some(valid) = type_checks.or(verify_mutants.then_some(true)) {
+            entry.set_valid(valid);
+        }
 
-            // Delete randomly elements from the vector.
-            let mut rng = thread_rng();
-            let chosen_elements: Vec<_> = mutated_sources
-                .choose_multiple(&mut rng, no_of_mutants_to_keep)
-                .cloned()
-                .collect();
+        // The file name is derived from the mutant ID, so it's stable between runs.
+        let mutant_path = match (layout, name_template) {
+            (layout, Some(template)) => {
+                let dir = match layout {
+                    OutputLayout::Flat => output::flat_layout_dir(&output_dir, path),
+                    OutputLayout::Module => Ok(output::module_layout_dir(
+                        &output_dir,
+                        candidate.module_name(),
+                        candidate.function_name(),
+                    )),
+                };
+                let file = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy())
+                    .unwrap_or_default();
+                let fields = output::NameFields {
+                    file: &file,
+                    module: candidate.module_name(),
+                    function: candidate.function_name(),
+                    operator: candidate.operator_name(),
+                    line: candidate.line(),
+                    id: entry.get_id(),
+                };
+                dir.and_then(|dir| output::setup_templated_mutant_path(&dir, template, &fields))
+            },
+            (OutputLayout::Flat, None) => {
+                let mutant_name = format!("{mutant_name}_{}", entry.get_id());
+                output::setup_mutant_path(&output_dir, path, &mutant_name)
+            },
+            (OutputLayout::Module, None) => output::setup_module_layout_path(
+                &output_dir,
+                candidate.module_name(),
+                candidate.function_name(),
+                entry.get_id(),
+            ),
+        };
+        let Ok(mutant_path) = mutant_path else {
+            // If we cannot set up the mutant path, we skip the mutant.
+            debug!("Cannot set up mutant path for {path:?}");
+            report.increment_operator_skipped(candidate.operator_name());
+            continue;
+        };
 
-            mutated_sources = chosen_elements;
+        if let Err(e) = writer.write(&mutant_path, candidate.mutated_source()) {
+            if strict {
+                return Err(e);
+            }
+            warn!(
+                "Cannot write {} to {}: {e}",
+                candidate.description(),
+                mutant_path.display()
+            );
+            report.add_failure(FileFailure::new(path, e.to_string()));
+            report.increment_operator_skipped(candidate.operator_name());
+            continue;
         }
+        written.push((candidate, entry, mutant_path));
+    }
 
-        for mutated in mutated_sources {
-            if let Some(mutation_conf) = &mutator_configuration.mutation {
-                if !mutation_conf.operators.is_empty()
-                    && !mutation_conf
-                        .operators
-                        .contains(&mutated.mutation.get_operator_name().to_owned())
-                {
-                    continue;
-                }
+    let write_failures = writer.finish()?;
+    for (candidate, mut entry, mutant_path) in written {
+        let path = candidate.path();
+        if let Some(e) = write_failures.get(&mutant_path) {
+            let message = format!(
+                "Cannot write {} to {}: {e}",
+                candidate.description(),
+                mutant_path.display()
+            );
+            if strict {
+                return Err(anyhow::anyhow!(message));
             }
+            warn!("{message}");
+            report.add_failure(FileFailure::new(path, e.clone()));
+            report.increment_operator_skipped(candidate.operator_name());
+            continue;
+        }
+
+        info!(
+            "{} written to {}",
+            candidate.description(),
+            mutant_path.display()
+        );
+
+        if let Some(package_path) = &packages_root {
+            let files = [(path, candidate.mutated_source())];
+            let package_dir =
+                output::emit_mutant_package(&output_dir, package_path, entry.get_id(), &files)?;
+            info!("Package of the mutant written to {}", package_dir.display());
+        }
+
+        entry.set_mutant_path(&mutant_path);
+        record_entry(&mut report, stream.as_mut(), keep_entries, entry)?;
+    }
 
-            if mutator_configuration.project.verify_mutants {
-                let res = verify_mutant(config, &mutated.mutated_source, path);
+    // Mutants spanning multiple files are verified sequentially, as there are only a few of them.
+    if let Some(limit) = mutator_configuration.project.multi_file_mutants {
+        let compiler_options = generator.compiler_options();
+        for mutant in generator.multi_file_mutants(limit) {
+            let files = mutant
+                .mutants()
+                .iter()
+                .map(|part| (part.mutated_source(), part.path()))
+                .collect::<Vec<_>>();
 
-                // In case the mutant is not a valid Move file, skip the mutant (do not save it).
-                if res.is_err() {
-                    warn!("Mutant {mutant} is not valid and will not be generated. Error: {res:?}");
+            if verify_mutants {
+                if let Err(e) = verify_multi_file_mutant(config, &files) {
+                    warn!(
+                        "{} is not valid and will not be generated. Error: {e:?}",
+                        mutant.description()
+                    );
                     continue;
                 }
             }
 
-            let Ok(mutant_path) = output::setup_mutant_path(&output_dir, path) else {
-                // If we cannot set up the mutant path, we skip the mutant.
-                debug!("Cannot set up mutant path for {path:?}");
+            let type_checks = type_check.then(|| {
+                let res = type_check_multi_file_mutant(&compiler_options, &files);
+                if let Err(e) = &res {
+                    info!("{} doesn't type check: {e}", mutant.description());
+                }
+                res.is_ok()
+            });
+            if only_valid && type_checks == Some(false) {
+                info!(
+                    "{} is skipped as it doesn't type check",
+                    mutant.description()
+                );
                 continue;
-            };
-
-            fs::write(&mutant_path, &mutated.mutated_source)?;
-
-            info!("{} written to {}", mutant, mutant_path.display());
+            }
 
-            let mod_name = if let Some(name) = mutant.get_module_name() {
-                name
-            } else {
-                "script".to_owned() // if there is no module name, it is a script
+            let mut entry = match save_multi_file_mutant(&mutant, &output_dir, layout, diff_context)
+            {
+                Ok(entry) => entry,
+                Err(e) => {
+                    // If we cannot save the mutant, we skip it.
+                    debug!("Cannot save {}: {e:?}", mutant.description());
+                    continue;
+                },
             };
+            if let Some(valid) = type_checks.or(verify_mutants.then_some(true)) {
+                entry.set_valid(valid);
+            }
 
-            let mut entry = report::MutationReport::new(
-                mutant_path.as_path(),
-                path,
-                mod_name.as_str(),
-                mutant
-                    .get_function_name()
-                    .map_or_else(String::new, |f| f.to_string())
-                    .as_str(),
-                &mutated.mutated_source,
-                source,
+            info!(
+                "{} written to {}",
+                mutant.description(),
+                entry.mutant_path().display()
             );
 
-            entry.add_modification(mutated.mutation);
-            report.add_entry(entry);
+            if let Some(package_path) = &packages_root {
+                let files = files
+                    .iter()
+                    .map(|(source, path)| (*path, *source))
+                    .collect::<Vec<_>>();
+                let package_dir =
+                    output::emit_mutant_package(&output_dir, package_path, entry.get_id(), &files)?;
+                info!("Package of the mutant written to {}", package_dir.display());
+            }
+            record_entry(&mut report, stream.as_mut(), keep_entries, entry)?;
         }
     }
 
+    if let Some(stream) = stream {
+        let summary = stream.finish(&report)?;
+        info!(
+            "{} mutants written to the JSON lines report",
+            summary.mutants
+        );
+    }
+
     trace!("Saving reports to: {output_dir:?}");
-    report.save_to_json_file(output_dir.join(Path::new("report.json")).as_path())?;
-    report.save_to_text_file(output_dir.join(Path::new("report.txt")).as_path())?;
+    for format in report_formats {
+        let report_path = output_dir.join(format.file_name());
+        match format {
+            ReportFormat::Json | ReportFormat::Yaml | ReportFormat::Toml => report
+                .save_with_details(
+                    &report_path,
+                    format,
+                    mutator_configuration.project.report_details,
+                )?,
+            ReportFormat::Text => report.save_to_text_file(&report_path, DiffStyle {
+                word_level: mutator_configuration.project.word_diff,
+                color: false,
+            })?,
+            // The JSON lines report is written while the mutants are generated.
+            ReportFormat::JsonLines => {},
+        }
+    }
+
+    // The manifest allows verifying the mutants on another machine or in another pipeline stage.
+    // If the entries weren't kept in memory, they are read back one by one from the JSON lines report.
+    let project_path = mutator_configuration.project_path.as_deref();
+    let manifest = if keep_entries {
+        Manifest::create(&output_dir, project_path, &report)?
+    } else {
+        let lines = Report::read_json_lines(&output_dir.join(ReportFormat::JsonLines.file_name()))?;
+        let entries = lines.filter_map(|line| match line {
+            Ok(ReportLine::Mutant(entry)) => Some(Ok(entry)),
+            Ok(ReportLine::Summary(_)) => None,
+            Err(e) => Some(Err(e.into())),
+        });
+        Manifest::from_entries(&output_dir, project_path, entries)?
+    };
+    manifest.save(&output_dir)?;
+
+    if mutator_configuration.project.archive {
+        let archive_path = archive::archive_path(&output_dir);
+        archive::create_archive(&output_dir, &archive_path)?;
+        info!("Output directory archived to {}", archive_path.display());
+    }
 
     trace!("Mutator tool is done here...");
     Ok(())
 }
+
+/// Returns the descriptions of all mutation operators supported by the mutator (sorted by name).
+#[must_use]
+pub fn operator_descriptions() -> &'static [OperatorDescription] {
+    operators::OPERATORS
+}
+
+/// Returns the description of the mutation operator with the given name, if there is such operator.
+#[must_use]
+pub fn operator_description(name: &str) -> Option<&'static OperatorDescription> {
+    operators::OPERATORS.iter().find(|op| op.name == name)
+}
+
+/// Adds the entry to the report and writes it to the JSON lines report, if it's streamed. The entry is kept in
+/// the report only if `keep` is set, otherwise it's just counted in the operator statistics.
+fn record_entry(
+    report: &mut Report,
+    stream: Option<&mut ReportStream>,
+    keep: bool,
+    entry: MutationReport,
+) -> anyhow::Result<()> {
+    if let Some(stream) = stream {
+        stream.write_entry(&entry)?;
+    }
+    if keep {
+        report.add_entry(entry);
+    } else {
+        report.count_entry(&entry);
+    }
+    Ok(())
+}
+
+/// Saves the mutated files of the mutant spanning multiple files to the mutant directory (named after
+/// the mutant ID) and returns the report entry of the mutant. In the `module` layout, the directory is
+/// placed in the subdirectory of the primary mutant module and function.
+fn save_multi_file_mutant(
+    mutant: &MultiFileMutant<'_>,
+    output_dir: &Path,
+    layout: OutputLayout,
+    diff_context: usize,
+) -> anyhow::Result<MutationReport> {
+    let id = mutant.id();
+    let mutant_dir = match (layout, mutant.mutants().first()) {
+        (OutputLayout::Module, Some(primary)) => output::setup_mutant_dir(
+            &output::module_layout_dir(output_dir, primary.module_name(), primary.function_name()),
+            &id,
+        )?,
+        _ => output::setup_mutant_dir(output_dir, &format!("multi_file_{id}"))?,
+    };
+
+    let mut files = vec![];
+    for part in mutant.mutants() {
+        let mutant_path = output::setup_mutant_dir_file(&mutant_dir, part.path())?;
+        fs::write(&mutant_path, part.mutated_source())?;
+
+        let mut entry = part.report(diff_context);
+        entry.set_mutant_path(&mutant_path);
+        files.push(entry);
+    }
+
+    Ok(MutationReport::multi_file(&mutant_dir, files))
+}
+
+/// Returns the operator and line part of the mutant file name (e.g. `binary_operator_replacement_12`).
+fn mutant_file_name(mutant: &Mutant<'_>) -> String {
+    format!("{}_{}", mutant.operator_name(), mutant.line())
+}
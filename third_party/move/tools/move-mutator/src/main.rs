@@ -4,9 +4,11 @@
 
 #![forbid(unsafe_code)]
 
-use clap::Parser;
-use move_mutator::cli::CLIOptions;
-use move_mutator::run_move_mutator;
+use clap::{Parser, Subcommand};
+use move_mutator::{
+    cli::CLIOptions, operator_description, operator_descriptions, run_move_mutator,
+    OperatorDescription,
+};
 use move_package::BuildConfig;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -23,11 +25,92 @@ pub struct Opts {
     /// The build configuration for the Move package.
     #[clap(flatten)]
     pub build_config: BuildConfig,
+    /// Command to run instead of mutating the package.
+    #[clap(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Shows the mutation operators supported by the mutator.
+    Operators {
+        #[clap(subcommand)]
+        command: OperatorsCommand,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum OperatorsCommand {
+    /// Lists all mutation operators.
+    List {
+        /// Print the descriptions as JSON.
+        #[clap(long)]
+        json: bool,
+    },
+    /// Describes the mutation operator with the given name.
+    Describe {
+        /// Name of the operator (e.g. `binary_operator_replacement`).
+        name: String,
+        /// Print the description as JSON.
+        #[clap(long)]
+        json: bool,
+    },
 }
 
 fn main() -> anyhow::Result<()> {
     let opts = Opts::parse();
+
+    if let Some(Command::Operators { command }) = opts.command {
+        return run_operators_command(command);
+    }
+
     let package_path = opts.package_path.unwrap_or(PathBuf::from("."));
 
     run_move_mutator(opts.cli_options, &opts.build_config, &package_path)
 }
+
+// Internal function printing the descriptions of the operators from the operator registry.
+fn run_operators_command(command: OperatorsCommand) -> anyhow::Result<()> {
+    match command {
+        OperatorsCommand::List { json: true } => {
+            println!("{}", serde_json::to_string_pretty(operator_descriptions())?);
+        },
+        OperatorsCommand::List { json: false } => {
+            for op in operator_descriptions() {
+                println!("{:<28} {}", op.name, op.description);
+            }
+        },
+        OperatorsCommand::Describe { name, json } => {
+            let Some(op) = operator_description(&name) else {
+                let known = operator_descriptions()
+                    .iter()
+                    .map(|op| op.name)
+                    .collect::<Vec<_>>();
+                anyhow::bail!(
+                    "Unknown operator {name}, the known operators are: {}",
+                    known.join(", ")
+                );
+            };
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(op)?);
+            } else {
+                print_description(op);
+            }
+        },
+    }
+
+    Ok(())
+}
+
+// Internal function printing the description of the operator in the human readable form.
+fn print_description(op: &OperatorDescription) {
+    println!("{}", op.name);
+    println!();
+    println!("{}", op.description);
+    println!();
+    println!("Targets: {}", op.targets.join(", "));
+    println!("Before:  {}", op.before);
+    println!("After:   {}", op.after);
+}
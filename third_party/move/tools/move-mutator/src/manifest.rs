@@ -0,0 +1,388 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::report::{MutationReport, Report, ReportFormat};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    borrow::Borrow,
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the manifest file stored in the mutants directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version of the manifest format written by this version of the tool.
+pub const MANIFEST_SCHEMA_VERSION: u32 = 1;
+
+/// The `Manifest` struct describes the mutants directory, so it can be handed off to another machine or
+/// pipeline stage (e.g. generation on one machine and verification on another one).
+/// It contains SHA-256 hashes of the report, the mutant files and the original source files, which allow
+/// detecting corrupted artifacts and sources changed since the generation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Version of the manifest format.
+    schema_version: u32,
+    /// Root of the mutated package at the time of the generation.
+    package_root: Option<PathBuf>,
+    /// Hash of the JSON report (if generated).
+    report: Option<String>,
+    /// Hashes of the original source files, relative to the package root (if inside the package).
+    sources: BTreeMap<PathBuf, String>,
+    /// Hashes of the mutant files, relative to the mutants directory.
+    mutants: BTreeMap<PathBuf, String>,
+}
+
+impl Manifest {
+    /// Creates the manifest describing the mutants directory with the given report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the described files can't be read.
+    pub fn create(
+        output_dir: &Path,
+        package_root: Option<&Path>,
+        report: &Report,
+    ) -> anyhow::Result<Self> {
+        Self::from_entries(
+            output_dir,
+            package_root,
+            report.get_mutants().iter().map(Ok),
+        )
+    }
+
+    /// Creates the manifest describing the mutants directory with the given report entries, which are
+    /// processed one by one (e.g. read from the JSON lines report), so they don't need to be kept in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the entries or the described files can't be read.
+    pub fn from_entries<M: Borrow<MutationReport>>(
+        output_dir: &Path,
+        package_root: Option<&Path>,
+        entries: impl IntoIterator<Item = anyhow::Result<M>>,
+    ) -> anyhow::Result<Self> {
+        let package_root = package_root.map(Path::canonicalize).transpose()?;
+
+        let report_path = output_dir.join(ReportFormat::Json.file_name());
+        let report_hash = if report_path.exists() {
+            Some(file_hash(&report_path)?)
+        } else {
+            None
+        };
+
+        let mut sources = BTreeMap::new();
+        let mut mutants = BTreeMap::new();
+        for mutant in entries {
+            let mutant = mutant?;
+            let mutant: &MutationReport = mutant.borrow();
+            // Mutants spanning multiple files are directories, so each of the changed files is described.
+            let files = if mutant.get_changed_files().is_empty() {
+                vec![(mutant.mutant_path(), mutant.original_file_path())]
+            } else {
+                mutant
+                    .get_changed_files()
+                    .iter()
+                    .map(|file| (file.mutant_path(), file.original_file_path()))
+                    .collect()
+            };
+
+            for (mutant_path, original) in files {
+                let original = original.canonicalize()?;
+                let relative = package_root
+                    .as_ref()
+                    .and_then(|root| original.strip_prefix(root).ok())
+                    .unwrap_or(&original)
+                    .to_path_buf();
+                if !sources.contains_key(&relative) {
+                    sources.insert(relative, file_hash(&original)?);
+                }
+
+                let relative = mutant_path.strip_prefix(output_dir).unwrap_or(mutant_path);
+                mutants.insert(relative.to_path_buf(), file_hash(mutant_path)?);
+            }
+        }
+
+        Ok(Self {
+            schema_version: MANIFEST_SCHEMA_VERSION,
+            package_root,
+            report: report_hash,
+            sources,
+            mutants,
+        })
+    }
+
+    /// Saves the manifest to the mutants directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, output_dir: &Path) -> anyhow::Result<()> {
+        let file = fs::File::create(output_dir.join(MANIFEST_FILE))?;
+        Ok(serde_json::to_writer_pretty(file, self)?)
+    }
+
+    /// Loads the manifest from the mutants directory.
+    /// Returns `None` if the directory doesn't contain the manifest (e.g. it was generated by an older version).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest can't be read or is written in an unsupported version.
+    pub fn load(output_dir: &Path) -> anyhow::Result<Option<Self>> {
+        let path = output_dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let manifest: Self = serde_json::from_reader(fs::File::open(&path)?)?;
+        if manifest.schema_version > MANIFEST_SCHEMA_VERSION {
+            anyhow::bail!(
+                "Unsupported manifest schema version {} in {} (the newest supported version is {MANIFEST_SCHEMA_VERSION})",
+                manifest.schema_version,
+                path.display()
+            );
+        }
+
+        Ok(Some(manifest))
+    }
+
+    /// Returns the root of the mutated package at the time of the generation.
+    #[must_use]
+    pub fn package_root(&self) -> Option<&Path> {
+        self.package_root.as_deref()
+    }
+
+    /// Checks that the report and the mutant files in the mutants directory match the manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error listing the missing or modified files.
+    pub fn verify_integrity(&self, output_dir: &Path) -> anyhow::Result<()> {
+        let mut mismatches = vec![];
+
+        if let Some(hash) = &self.report {
+            let report_path = PathBuf::from(ReportFormat::Json.file_name());
+            if !hash_matches(&output_dir.join(&report_path), hash) {
+                mismatches.push(report_path);
+            }
+        }
+
+        for (path, hash) in &self.mutants {
+            if !hash_matches(&output_dir.join(path), hash) {
+                mismatches.push(path.clone());
+            }
+        }
+
+        if mismatches.is_empty() {
+            Ok(())
+        } else {
+            anyhow::bail!(
+                "Mutants directory {} doesn't match its manifest. Missing or modified files: {}",
+                output_dir.display(),
+                mismatches
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        }
+    }
+
+    /// Returns the original source files which have changed (or are missing) in the given package since the
+    /// mutants were generated.
+    #[must_use]
+    pub fn stale_sources(&self, package_root: &Path) -> Vec<PathBuf> {
+        self.sources
+            .iter()
+            .filter(|(path, hash)| !hash_matches(&package_root.join(path), hash))
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+}
+
+/// Computes the SHA-256 hash of the file content.
+///
+/// # Errors
+///
+/// Returns an error if the file can't be read.
+pub fn file_hash(path: &Path) -> anyhow::Result<String> {
+    let content = fs::read(path)?;
+    Ok(format!("{:x}", Sha256::digest(content)))
+}
+
+/// Computes the SHA-256 digest of the package sources: of the manifest (`Move.toml`) and the Move files of the
+/// package, outside of the `build` directory and the hidden directories. Packages with the same sources have
+/// the same digest, wherever they are placed.
+///
+/// # Errors
+///
+/// Returns an error if the package directory or any of its sources can't be read.
+pub fn package_digest(package_root: &Path) -> anyhow::Result<String> {
+    let mut files = vec![];
+    collect_package_files(package_root, Path::new(""), &mut files)?;
+    files.sort();
+
+    let mut hasher = Sha256::new();
+    for file in files {
+        hasher.update(file.to_string_lossy().as_bytes());
+        hasher.update([0]);
+        hasher.update(file_hash(&package_root.join(&file))?.as_bytes());
+        hasher.update([0]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+// Internal function collecting the package sources in the given directory (relative to the package root).
+fn collect_package_files(
+    root: &Path,
+    relative_dir: &Path,
+    files: &mut Vec<PathBuf>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(root.join(relative_dir))? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let relative_path = relative_dir.join(&name);
+        if entry.file_type()?.is_dir() {
+            if name != "build" && !name.to_string_lossy().starts_with('.') {
+                collect_package_files(root, &relative_path, files)?;
+            }
+        } else if relative_path == Path::new("Move.toml")
+            || relative_path.extension().is_some_and(|ext| ext == "move")
+        {
+            files.push(relative_path);
+        }
+    }
+    Ok(())
+}
+
+// Internal function checking if the file exists and has the given hash.
+fn hash_matches(path: &Path, hash: &str) -> bool {
+    file_hash(path).is_ok_and(|h| h == hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::MutationReport;
+    use tempfile::tempdir;
+
+    // Helper creating a package with a single source file and a mutants directory with a single mutant.
+    fn setup() -> (tempfile::TempDir, PathBuf, PathBuf, Report) {
+        let dir = tempdir().unwrap();
+        let package = dir.path().join("package");
+        let output_dir = dir.path().join("mutants");
+        fs::create_dir_all(package.join("sources")).unwrap();
+        fs::create_dir_all(&output_dir).unwrap();
+
+        let original = package.join("sources/Sum.move");
+        fs::write(&original, "a + b").unwrap();
+        let mutant = output_dir.join("Sum_mut.move");
+        fs::write(&mutant, "a - b").unwrap();
+
+        let mut report = Report::new();
+        report.add_entry(MutationReport::new(
+            &mutant, &original, "Sum", "sum", "a - b", "a + b",
+        ));
+        report
+            .save_to_json_file(&output_dir.join(ReportFormat::Json.file_name()))
+            .unwrap();
+
+        (dir, package, output_dir, report)
+    }
+
+    #[test]
+    fn manifest_is_saved_and_loaded() {
+        let (_dir, package, output_dir, report) = setup();
+        let manifest = Manifest::create(&output_dir, Some(&package), &report).unwrap();
+        manifest.save(&output_dir).unwrap();
+
+        let loaded = Manifest::load(&output_dir).unwrap().unwrap();
+        assert_eq!(loaded, manifest);
+        assert!(loaded.sources.contains_key(Path::new("sources/Sum.move")));
+        assert!(loaded.mutants.contains_key(Path::new("Sum_mut.move")));
+        assert!(loaded.verify_integrity(&output_dir).is_ok());
+        assert!(loaded.stale_sources(&package).is_empty());
+    }
+
+    #[test]
+    fn manifest_describes_all_files_of_multi_file_mutants() {
+        let (_dir, package, output_dir, mut report) = setup();
+        let original = package.join("sources/Other.move");
+        fs::write(&original, "a").unwrap();
+        fs::create_dir_all(output_dir.join("multi/sub")).unwrap();
+        let first = output_dir.join("multi/Sum.move");
+        let second = output_dir.join("multi/sub/Other.move");
+        fs::write(&first, "a * b").unwrap();
+        fs::write(&second, "b").unwrap();
+
+        report.add_entry(MutationReport::multi_file(&output_dir.join("multi"), vec![
+            MutationReport::new(
+                &first,
+                &package.join("sources/Sum.move"),
+                "Sum",
+                "sum",
+                "a * b",
+                "a + b",
+            ),
+            MutationReport::new(&second, &original, "Other", "other", "b", "a"),
+        ]));
+
+        let manifest = Manifest::create(&output_dir, Some(&package), &report).unwrap();
+        assert!(manifest
+            .sources
+            .contains_key(Path::new("sources/Other.move")));
+        assert!(manifest.mutants.contains_key(Path::new("multi/Sum.move")));
+        assert!(manifest
+            .mutants
+            .contains_key(Path::new("multi/sub/Other.move")));
+        assert!(!manifest.mutants.contains_key(Path::new("multi")));
+        assert!(manifest.verify_integrity(&output_dir).is_ok());
+    }
+
+    #[test]
+    fn missing_manifest_is_not_an_error() {
+        let dir = tempdir().unwrap();
+        assert!(Manifest::load(dir.path()).unwrap().is_none());
+    }
+
+    #[test]
+    fn modified_mutants_are_detected() {
+        let (_dir, package, output_dir, report) = setup();
+        let manifest = Manifest::create(&output_dir, Some(&package), &report).unwrap();
+
+        fs::write(output_dir.join("Sum_mut.move"), "a * b").unwrap();
+        let err = manifest.verify_integrity(&output_dir).unwrap_err();
+        assert!(err.to_string().contains("Sum_mut.move"));
+    }
+
+    #[test]
+    fn changed_sources_are_detected() {
+        let (_dir, package, output_dir, report) = setup();
+        let manifest = Manifest::create(&output_dir, Some(&package), &report).unwrap();
+
+        fs::write(package.join("sources/Sum.move"), "a + b + 0").unwrap();
+        assert_eq!(manifest.stale_sources(&package), vec![PathBuf::from(
+            "sources/Sum.move"
+        )]);
+    }
+    #[test]
+    fn package_digest_depends_only_on_sources() {
+        let (_dir, package, _, _) = setup();
+        let digest = package_digest(&package).unwrap();
+
+        // Build artifacts and other files are not part of the sources.
+        fs::create_dir_all(package.join("build/Sum")).unwrap();
+        fs::write(package.join("build/Sum/Sum.move"), "a").unwrap();
+        fs::write(package.join("README.md"), "docs").unwrap();
+        assert_eq!(package_digest(&package).unwrap(), digest);
+
+        fs::write(package.join("Move.toml"), "[package]").unwrap();
+        let with_manifest = package_digest(&package).unwrap();
+        assert_ne!(with_manifest, digest);
+        fs::write(package.join("sources/Sum.move"), "a + b + 0").unwrap();
+        assert_ne!(package_digest(&package).unwrap(), with_manifest);
+    }
+}
@@ -12,7 +12,9 @@ use std::fmt;
 pub struct Mutant {
     operator: MutationOp,
     module_name: Option<String>,
+    module_address: Option<String>,
     function_name: Option<String>,
+    is_script: bool,
 }
 
 impl Mutant {
@@ -23,7 +25,9 @@ impl Mutant {
         Self {
             operator,
             module_name: None,
+            module_address: None,
             function_name: None,
+            is_script: false,
         }
     }
 
@@ -39,6 +43,11 @@ impl Mutant {
         self.operator.apply(source)
     }
 
+    /// Returns the name of the mutation operator of this mutant.
+    pub fn operator_name(&self) -> String {
+        self.operator.name()
+    }
+
     /// Returns the module name that this mutant is in.
     pub fn get_module_name(&self) -> Option<String> {
         self.module_name.clone()
@@ -49,6 +58,16 @@ impl Mutant {
         self.module_name = Some(module_name);
     }
 
+    /// Returns the address of the module that this mutant is in (`None` for the scripts).
+    pub fn get_module_address(&self) -> Option<String> {
+        self.module_address.clone()
+    }
+
+    /// Sets the address of the module that this mutant is in.
+    pub fn set_module_address(&mut self, module_address: Option<String>) {
+        self.module_address = module_address;
+    }
+
     /// Returns the function name that this mutant is in.
     pub fn get_function_name(&self) -> Option<String> {
         self.function_name.clone()
@@ -58,6 +77,16 @@ impl Mutant {
     pub fn set_function_name(&mut self, function_name: String) {
         self.function_name = Some(function_name);
     }
+
+    /// Returns true if this mutant is in a script.
+    pub fn is_script(&self) -> bool {
+        self.is_script
+    }
+
+    /// Sets whether this mutant is in a script.
+    pub fn set_script(&mut self, is_script: bool) {
+        self.is_script = is_script;
+    }
 }
 
 impl fmt::Display for Mutant {
@@ -71,10 +100,7 @@ mod tests {
     use super::*;
     use crate::operators::{binary::Binary, ExpLoc};
     use codespan::Files;
-    use move_model::{
-        ast::{ExpData, Operation, Value},
-        model::{Loc, NodeId},
-    };
+    use move_model::{ast::Operation, model::Loc};
 
     #[test]
     fn test_new() {
@@ -105,10 +131,8 @@ mod tests {
         let loc = Loc::new(fid, codespan::Span::new(0, 3));
         let loc2 = Loc::new(fid, codespan::Span::new(0, 1));
         let loc3 = Loc::new(fid, codespan::Span::new(2, 3));
-        let e1 = ExpData::Value(NodeId::new(1), Value::Bool(true));
-        let e2 = ExpData::Value(NodeId::new(2), Value::Bool(false));
-        let exp1 = ExpLoc::new(e1.into_exp(), loc2);
-        let exp2 = ExpLoc::new(e2.into_exp(), loc3);
+        let exp1 = ExpLoc::new(loc2);
+        let exp2 = ExpLoc::new(loc3);
 
         let operator =
             MutationOp::new(Box::new(Binary::new(Operation::Add, loc, vec![exp1, exp2])));
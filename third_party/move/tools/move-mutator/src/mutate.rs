@@ -3,35 +3,105 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    cli,
+    cli, compiler,
     configuration::{Configuration, IncludeFunctions},
     mutant::Mutant,
     operator::MutationOp,
     operators::{
-        binary::Binary, binary_swap::BinarySwap, break_continue::BreakContinue,
-        delete_stmt::DeleteStmt, ifelse::IfElse, literal::Literal, unary::Unary, ExpLoc,
+        address_swap::AddressSwap,
+        amount::{AmountReplacement, MONETARY_FUNCTIONS},
+        binary::Binary,
+        binary_swap::BinarySwap,
+        branch_swap::BranchSwap,
+        break_continue::BreakContinue,
+        cast::Cast,
+        delete_stmt::DeleteStmt,
+        event_removal::{EventRemoval, EVENT_FUNCTIONS},
+        ifelse::IfElse,
+        literal::Literal,
+        loop_bound::LoopBound,
+        match_arm_swap::{MatchArmLoc, MatchArmSwap},
+        match_guard_removal::MatchGuardRemoval,
+        negation::NegationInsertion,
+        reorder::ResourceOperationReorder,
+        signer_address::SignerAddress,
+        timestamp::{Timestamp, TIMESTAMP_FUNCTIONS},
+        unary::Unary,
+        variant_replacement::VariantReplacement,
+        visibility::VisibilityReplacement,
+        ExpLoc, MOVE_ADDR_ZERO,
     },
+    report::FileFailure,
 };
 use move_model::{
-    ast::{Exp, ExpData, Operation},
-    model::{FunctionEnv, GlobalEnv, ModuleEnv},
+    ast::{Address, Exp, ExpData, MatchArm, Operation, Pattern, Value},
+    model::{
+        FunId, FunctionEnv, GlobalEnv, Loc, ModuleEnv, NodeId, Parameter, QualifiedId, StructId,
+        Visibility,
+    },
+    symbol::Symbol,
+    ty::{PrimitiveType, ReferenceKind, Type},
 };
 use move_package::source_package::layout::SourcePackageLayout;
-use std::path::Path;
+use std::{collections::BTreeSet, path::Path};
+
+/// Name used as the function name of the mutants of the global invariants.
+pub const INVARIANT_FUNCTION_NAME: &str = "invariant";
+
+/// Name used as the module name of the mutants of the scripts (which have no module).
+pub const SCRIPT_MODULE_NAME: &str = "script";
+
+/// Full name of the function returning the address of the signer.
+const SIGNER_ADDRESS_OF: &str = "0x1::signer::address_of";
+
+/// Result of the traversal of the package.
+#[derive(Default)]
+pub struct Traversal {
+    /// Mutants found in the package.
+    pub mutants: Vec<Mutant>,
+    /// Number of the sites skipped because they are synthesized by the compiler from the syntax the
+    /// operators can't rewrite (see `is_unsupported_syntax`).
+    pub unsupported_sites: usize,
+    /// Files which couldn't be traversed.
+    pub failures: Vec<FileFailure>,
+}
 
 /// Traverses the AST, identifies places where mutation operators can be applied
 /// and returns a list of mutants.
-pub fn mutate(env: &GlobalEnv, conf: &Configuration) -> anyhow::Result<Vec<Mutant>> {
+///
+/// Modules which can't be traversed are recorded as the failures of their files and skipped, unless
+/// the strict mode is enabled - then the first such error is returned.
+pub fn mutate(env: &GlobalEnv, conf: &Configuration) -> anyhow::Result<Traversal> {
     trace!("Starting mutation process");
-    let mutants = env
-        .get_modules()
-        .map(|module| traverse_module_with_check(&module, conf))
-        .collect::<Result<Vec<_>, _>>()?
-        .concat();
+    let callees = if conf.project.include_callees {
+        selected_callees(env, conf)
+    } else {
+        BTreeSet::new()
+    };
 
-    trace!("Found {} possible mutations", mutants.len());
+    let mut traversal = Traversal::default();
+    for module in env.get_modules() {
+        match traverse_module_with_check(&module, conf, &callees, &mut traversal.unsupported_sites)
+        {
+            Ok(mutants) => traversal.mutants.extend(mutants),
+            Err(e) if !conf.project.strict => {
+                let file = Path::new(module.get_source_path());
+                warn!("Skipping {} as it can't be mutated: {e:#}", file.display());
+                traversal
+                    .failures
+                    .push(FileFailure::new(file, format!("{e:#}")));
+            },
+            Err(e) => return Err(e),
+        }
+    }
 
-    Ok(mutants)
+    trace!(
+        "Found {} possible mutations, skipped {} sites with unsupported syntax",
+        traversal.mutants.len(),
+        traversal.unsupported_sites
+    );
+
+    Ok(traversal)
 }
 
 /// Traverses a single module and returns a list of mutants - helper function which filter out modules
@@ -40,8 +110,19 @@ pub fn mutate(env: &GlobalEnv, conf: &Configuration) -> anyhow::Result<Vec<Mutan
 fn traverse_module_with_check(
     module: &ModuleEnv<'_>,
     conf: &Configuration,
+    callees: &BTreeSet<QualifiedId<FunId>>,
+    unsupported: &mut usize,
 ) -> anyhow::Result<Vec<Mutant>> {
-    let module_name = module.env.symbol_pool().string(module.get_name().name());
+    // The scripts are selected and excluded under the common name, as their pseudo modules are unnamed.
+    let module_name = if module.is_script_module() {
+        SCRIPT_MODULE_NAME.to_owned()
+    } else {
+        module
+            .env
+            .symbol_pool()
+            .string(module.get_name().name())
+            .to_string()
+    };
 
     // We need to check if module comes from our source tree or from the deps, as we don't want to traverse
     // all the dependencies. That's a bit tricky as global deps are easy to identify but local deps can be
@@ -73,34 +154,82 @@ fn traverse_module_with_check(
         }
     }
 
+    if module.is_test_only() && !conf.project.include_test_code {
+        trace!("Skipping test only module {module_name}");
+        return Ok(vec![]);
+    }
+
+    if conf
+        .mutation
+        .as_ref()
+        .is_some_and(|mutation| mutation.is_module_excluded(&module_name))
+    {
+        trace!("Skipping excluded module {module_name}");
+        return Ok(vec![]);
+    }
+
     // Now we need to check if the module is included in the configuration.
     if let cli::ModuleFilter::Selected(mods) = &conf.project.mutate_modules {
-        if !mods.contains(&module_name) {
+        if !mods
+            .iter()
+            .any(|selected| is_module_selected(module, &module_name, selected))
+        {
             trace!("Skipping module {module_name}");
             return Ok(vec![]);
         }
     }
 
-    traverse_module(module, conf)
+    traverse_module(module, conf, callees, unsupported)
 }
 
 /// Traverses a single module and returns a list of mutants.
 /// Checks all the functions and constants defined in the module.
 #[allow(clippy::unnecessary_to_owned)]
-fn traverse_module(module: &ModuleEnv<'_>, conf: &Configuration) -> anyhow::Result<Vec<Mutant>> {
-    let module_name = module.get_name().display(module.env);
+fn traverse_module(
+    module: &ModuleEnv<'_>,
+    conf: &Configuration,
+    callees: &BTreeSet<QualifiedId<FunId>>,
+    unsupported: &mut usize,
+) -> anyhow::Result<Vec<Mutant>> {
+    let is_script = module.is_script_module();
+    let module_name = if is_script {
+        SCRIPT_MODULE_NAME.to_owned()
+    } else {
+        module.get_name().display(module.env).to_string()
+    };
 
     trace!("Traversing module {}", &module_name);
     let mut mutants = module
         .get_functions()
-        .map(|func| traverse_function(&func, conf))
+        .map(|func| traverse_function(&func, conf, callees, unsupported))
         .collect::<Result<Vec<_>, _>>()?
         .concat();
 
-    // Set the module name for all the mutants.
-    mutants
-        .iter_mut()
-        .for_each(|m| m.set_module_name(module_name.to_string()));
+    // The specification files contain only the spec blocks, so their spec functions and invariants are
+    // mutated if the files are, regardless of the `mutate_specs` option.
+    if conf.project.mutate_specs || conf.project.mutate_spec_files {
+        mutants.extend(
+            traverse_specs(module, unsupported)
+                .into_iter()
+                .filter(|m| conf.project.mutate_specs || is_spec_file(module.env, m)),
+        );
+    }
+    if !conf.project.mutate_spec_files {
+        let count = mutants.len();
+        mutants.retain(|m| !is_spec_file(module.env, m));
+        if mutants.len() < count {
+            trace!("Skipping the mutants of the specification files of module {module_name}");
+        }
+    }
+
+    // Set the module identity for all the mutants, so the mutants of the modules sharing a file (or a name)
+    // are attributed to the right one.
+    let address = module_address(module);
+    mutants.iter_mut().for_each(|m| {
+        m.set_module_name(module_name.clone());
+        m.set_module_address(address.clone());
+        m.set_script(is_script);
+    });
 
     trace!(
         "Found {} possible mutations in module {}",
@@ -116,36 +245,54 @@ fn traverse_module(module: &ModuleEnv<'_>, conf: &Configuration) -> anyhow::Resu
 fn traverse_function(
     function: &FunctionEnv<'_>,
     conf: &Configuration,
+    callees: &BTreeSet<QualifiedId<FunId>>,
+    unsupported: &mut usize,
 ) -> anyhow::Result<Vec<Mutant>> {
     let attrs = function.get_attributes();
     for attr in attrs {
-        // Omit all functions with test attribute.
-        if attr
-            .name()
-            .display(function.module_env.symbol_pool())
-            .to_string()
-            .contains("test")
+        // Omit all functions with test attribute (unless the test code is mutated too).
+        if !conf.project.include_test_code
+            && attr
+                .name()
+                .display(function.module_env.symbol_pool())
+                .to_string()
+                .contains("test")
         {
             trace!("Skipping test function {}", &function.get_name_str());
             return Ok(vec![]);
         }
     }
 
-    let mut is_inside_spec = false;
-
     let function_name = function.get_name_str();
-    let filename = function.module_env.get_source_path();
+    let module_name = if function.module_env.is_script_module() {
+        SCRIPT_MODULE_NAME.to_owned()
+    } else {
+        function
+            .symbol_pool()
+            .string(function.module_env.get_name().name())
+            .to_string()
+    };
+    if conf
+        .mutation
+        .as_ref()
+        .is_some_and(|mutation| mutation.is_function_excluded(&module_name, &function_name))
+    {
+        trace!("Skipping excluded function {function_name}");
+        return Ok(vec![]);
+    }
 
-    // Check if function is included in individual configuration.
-    if let Some(ind) = conf.get_file_configuration(Path::new(filename)) {
-        if let IncludeFunctions::Selected(funcs) = &ind.include_functions {
-            if !funcs.contains(&function_name) {
-                trace!("Skipping function {}", &function_name);
-                return Ok(vec![]);
-            }
+    if !is_selected(function, conf) {
+        if callees.contains(&function.get_qualified_id()) {
+            trace!("Including function {function_name} as it's called by a selected function");
+        } else {
+            trace!("Skipping function {}", &function_name);
+            return Ok(vec![]);
         }
     }
 
+    let mut is_inside_spec = false;
+    let function_loc = function.get_loc();
+
     trace!("Traversing function {}", &function_name);
     let mut result = Vec::<Mutant>::new();
     if let Some(exp) = function.get_def() {
@@ -156,14 +303,34 @@ fn traverse_function(
                 is_inside_spec = !asc;
             }
 
+            // The code expanded from the inline functions is mutated in the definitions of the inline
+            // functions, so the mutants (and their results) are attributed to the inline functions once,
+            // instead of to each expansion site.
+            let loc = function.module_env.env.get_node_loc(exp_data.node_id());
+            if !function_loc.is_enclosing(&loc) {
+                trace!("Skipping the code expanded into {function_name} at {loc:?}");
+                return true;
+            }
+
             // Parse only during the descend phase and when we are not inside the spec block.
             if !asc && !is_inside_spec {
-                result.extend(parse_expression_and_find_mutants(function, exp_data));
+                if is_unsupported_syntax(function.module_env.env, exp_data) {
+                    trace!("Skipping the site with unsupported syntax at {loc:?}");
+                    *unsupported += 1;
+                    return true;
+                }
+                result.extend(parse_expression_and_find_mutants(
+                    &function.module_env,
+                    exp_data,
+                ));
+                result.extend(parse_signer_address(function, exp_data));
+                result.extend(parse_monetary_call(function, exp_data, conf));
             }
 
             true
         });
     };
+    result.extend(parse_visibility(function));
 
     result
         .iter_mut()
@@ -172,16 +339,235 @@ fn traverse_function(
     Ok(result)
 }
 
+/// Traverses the spec functions and the global invariants of the module and returns a list of mutants.
+/// Mutated specifications are verified against the unmodified code, so each mutant checks if the prover
+/// notices the change. Only the spec functions with their own bodies are traversed (the spec versions
+/// of the Move functions are mutated together with the functions).
+fn traverse_specs(module: &ModuleEnv<'_>, unsupported: &mut usize) -> Vec<Mutant> {
+    let mut find_mutants = |exp: &Exp, name: &str| {
+        let mut mutants = vec![];
+        exp.visit_pre_post(&mut |asc, exp_data| {
+            if !asc && is_unsupported_syntax(module.env, exp_data) {
+                *unsupported += 1;
+            } else if !asc {
+                mutants.extend(parse_expression_and_find_mutants(module, exp_data));
+            }
+            true
+        });
+        mutants
+            .iter_mut()
+            .for_each(|m| m.set_function_name(name.to_owned()));
+        mutants
+    };
+
+    let mut result = vec![];
+    for (_, decl) in module.get_spec_funs() {
+        if decl.is_move_fun || decl.is_native || decl.uninterpreted {
+            continue;
+        }
+        if let Some(body) = &decl.body {
+            let name = module.symbol_pool().string(decl.name);
+            trace!("Traversing spec function {name}");
+            result.extend(find_mutants(body, &name));
+        }
+    }
+
+    for invariant in module.env.get_global_invariants_for_module(module.get_id()) {
+        trace!("Traversing global invariant");
+        result.extend(find_mutants(&invariant.cond, INVARIANT_FUNCTION_NAME));
+    }
+
+    result
+}
+
+/// Checks if the mutant is in a specification file (`*.spec.move`), which keeps the spec blocks of a module
+/// separately from its code.
+fn is_spec_file(env: &GlobalEnv, mutant: &Mutant) -> bool {
+    compiler::is_spec_file(Path::new(env.get_file(mutant.get_file_id())))
+}
+
+/// Checks if the function is selected for mutation by the configuration (the entry functions filter and the
+/// functions included in the individual file configuration).
+fn is_selected(function: &FunctionEnv<'_>, conf: &Configuration) -> bool {
+    if conf.project.entry_functions_only && !is_externally_reachable(function) {
+        return false;
+    }
+
+    // Check if function is included in individual configuration.
+    let filename = function.module_env.get_source_path();
+    if let Some(ind) = conf.get_file_configuration(Path::new(filename)) {
+        if let IncludeFunctions::Selected(funcs) = &ind.include_functions {
+            return funcs.contains(&function.get_name_str());
+        }
+    }
+
+    true
+}
+
+/// Returns the private functions called (directly or transitively) by the selected functions, which are not
+/// selected themselves. Specification weaknesses often hide in such helpers, as they are rarely specified directly.
+/// The inline functions are included regardless of their visibility, as they are expanded into the callers.
+fn selected_callees(env: &GlobalEnv, conf: &Configuration) -> BTreeSet<QualifiedId<FunId>> {
+    let mut queue = env
+        .get_modules()
+        .flat_map(|module| {
+            module
+                .get_functions()
+                .filter(|function| is_selected(function, conf))
+                .map(|function| function.get_qualified_id())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let mut callees = BTreeSet::new();
+
+    while let Some(id) = queue.pop() {
+        let function = env.get_function(id);
+        let Some(def) = function.get_def() else {
+            continue;
+        };
+
+        for callee in def.called_funs() {
+            let callee_env = env.get_function(callee);
+            if (callee_env.visibility() == Visibility::Private || callee_env.is_inline())
+                && !is_selected(&callee_env, conf)
+                && callees.insert(callee)
+            {
+                queue.push(callee);
+            }
+        }
+    }
+
+    callees
+}
+
+/// Returns the calls crossing the source files of the package, as the pairs of the called and the calling
+/// function (both given by the module and the function name, as in the mutants). The pairs are ordered
+/// deterministically.
+pub(crate) fn cross_file_calls(env: &GlobalEnv) -> BTreeSet<((String, String), (String, String))> {
+    let name = |function: &FunctionEnv<'_>| {
+        (
+            function.module_env.get_name().display(env).to_string(),
+            function.get_name_str(),
+        )
+    };
+
+    let mut calls = BTreeSet::new();
+    for module in env.get_modules() {
+        for caller in module.get_functions() {
+            let Some(def) = caller.get_def() else {
+                continue;
+            };
+
+            for callee in def.called_funs() {
+                let callee = env.get_function(callee);
+                if callee.module_env.get_source_path() != module.get_source_path() {
+                    calls.insert((name(&callee), name(&caller)));
+                }
+            }
+        }
+    }
+
+    calls
+}
+
+/// Checks if the function is a part of the externally reachable surface of the package, which are
+/// `public entry` functions and script functions.
+fn is_externally_reachable(function: &FunctionEnv<'_>) -> bool {
+    function.module_env.is_script_module()
+        || (function.is_entry() && function.visibility() == Visibility::Public)
+}
+
+/// Returns the mutant negating the boolean condition, unless the condition is already negated (removing
+/// the negation is the job of the unary operator replacement).
+fn negate_condition(module: &ModuleEnv<'_>, cond: &Exp) -> Option<Mutant> {
+    if matches!(cond.as_ref(), ExpData::Call(_, Operation::Not, _)) {
+        return None;
+    }
+    Some(Mutant::new(MutationOp::new(Box::new(
+        NegationInsertion::new(module.env.get_node_loc(cond.node_id())),
+    ))))
+}
+
+/// Checks if the `if` expression is the one the `while` loop is desugared into by the compiler, which is
+/// `loop { if (cond) body else break }` with the `break` sharing the node of the `if` expression.
+fn is_while_loop(node_id: NodeId, else_exp: &Exp) -> bool {
+    matches!(else_exp.as_ref(), ExpData::LoopCont(id, false) if *id == node_id)
+}
+
+/// Checks if the expression is synthesized by the compiler from the syntax the mutation operators can't
+/// rewrite, like the counter, the bounds check and the flags of the desugared `for` loop, or the `break` of
+/// the desugared `while` loop. Such expressions share the location of the whole loop (or have the operands
+/// outside of their own location), so mutating them would replace the loop instead of the expression.
+fn is_unsupported_syntax(env: &GlobalEnv, exp: &ExpData) -> bool {
+    let loc = env.get_node_loc(exp.node_id());
+    match exp {
+        ExpData::Call(
+            _,
+            Operation::Add
+            | Operation::Sub
+            | Operation::Mul
+            | Operation::Div
+            | Operation::Mod
+            | Operation::And
+            | Operation::Or
+            | Operation::Eq
+            | Operation::Neq
+            | Operation::Ge
+            | Operation::Gt
+            | Operation::Le
+            | Operation::Lt
+            | Operation::BitAnd
+            | Operation::BitOr
+            | Operation::Shl
+            | Operation::Shr
+            | Operation::Xor,
+            exps,
+        ) => {
+            let [lhs, rhs] = exps.as_slice() else {
+                return true;
+            };
+            let (lhs, rhs) = (
+                env.get_node_loc(lhs.node_id()),
+                env.get_node_loc(rhs.node_id()),
+            );
+            if !loc.is_enclosing(&lhs)
+                || !loc.is_enclosing(&rhs)
+                || lhs.span().end() > rhs.span().start()
+            {
+                return true;
+            }
+        },
+        ExpData::Call(_, Operation::Not, _)
+        | ExpData::Value(_, _)
+        | ExpData::LoopCont(_, _)
+        | ExpData::IfElse(_, _, _, _) => {},
+        _ => return false,
+    }
+    if let ExpData::IfElse(node_id, _, _, else_exp) = exp {
+        if is_while_loop(*node_id, else_exp) {
+            return false;
+        }
+    }
+
+    let source = env.get_file_source(loc.file_id());
+    let text = source
+        .get(loc.span().start().to_usize()..loc.span().end().to_usize())
+        .unwrap_or_default();
+    ["for", "while", "loop"].iter().any(|keyword| {
+        text.strip_prefix(keyword)
+            .is_some_and(|rest| !rest.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+    })
+}
+
 /// This function does the actual parsing of the expression and checks if any of the mutation operators
 /// can be applied to it.
 /// When Move language is extended with new expressions, this function needs to be updated to support them.
 #[allow(clippy::too_many_lines)]
-fn parse_expression_and_find_mutants(function: &FunctionEnv<'_>, exp: &ExpData) -> Vec<Mutant> {
+fn parse_expression_and_find_mutants(module: &ModuleEnv<'_>, exp: &ExpData) -> Vec<Mutant> {
     let convert_exps_to_explocs = |exps: &[Exp]| -> Vec<ExpLoc> {
         exps.iter()
             .map(|e| ExpLoc {
-                exp: e.clone(),
-                loc: function.module_env.env.get_node_loc(e.node_id()),
+                loc: module.env.get_node_loc(e.node_id()),
             })
             .collect::<Vec<ExpLoc>>()
     };
@@ -191,8 +577,8 @@ fn parse_expression_and_find_mutants(function: &FunctionEnv<'_>, exp: &ExpData)
         ExpData::Call(node_id, op, exps) => match op {
             Operation::MoveTo | Operation::Abort => {
                 vec![Mutant::new(MutationOp::new(Box::new(DeleteStmt::new(
-                    exp.clone().into_exp(),
-                    function.module_env.env.get_node_loc(*node_id),
+                    op.clone(),
+                    module.env.get_node_loc(*node_id),
                 ))))]
             },
             Operation::Add
@@ -216,59 +602,158 @@ fn parse_expression_and_find_mutants(function: &FunctionEnv<'_>, exp: &ExpData)
                 let exps_loc = convert_exps_to_explocs(exps);
                 let mut result = vec![Mutant::new(MutationOp::new(Box::new(Binary::new(
                     op.clone(),
-                    function.module_env.env.get_node_loc(*node_id),
+                    module.env.get_node_loc(*node_id),
                     exps_loc.clone(),
                 ))))];
 
                 result.push(Mutant::new(MutationOp::new(Box::new(BinarySwap::new(
                     op.clone(),
-                    function.module_env.env.get_node_loc(*node_id),
+                    module.env.get_node_loc(*node_id),
                     exps_loc,
                 )))));
 
+                if matches!(op, Operation::And | Operation::Or) {
+                    result.extend(exps.iter().filter_map(|e| negate_condition(module, e)));
+                }
+
                 result
             },
+            Operation::Cast => {
+                let Some(operand) = exps.first() else {
+                    return vec![];
+                };
+                let env = module.env;
+                // The inner cast of the nested casts can be removed.
+                let inner_operand = match operand.as_ref() {
+                    ExpData::Call(_, Operation::Cast, inner) => {
+                        inner.first().map(|exp| env.get_node_loc(exp.node_id()))
+                    },
+                    _ => None,
+                };
+                vec![Mutant::new(MutationOp::new(Box::new(Cast::new(
+                    env.get_node_loc(*node_id),
+                    env.get_node_loc(operand.node_id()),
+                    env.get_node_type(operand.node_id()),
+                    env.get_node_type(*node_id),
+                    inner_operand,
+                ))))]
+            },
             Operation::Not => {
                 let exps_loc = convert_exps_to_explocs(exps);
                 vec![Mutant::new(MutationOp::new(Box::new(Unary::new(
                     op.clone(),
-                    function.module_env.env.get_node_loc(*node_id),
+                    module.env.get_node_loc(*node_id),
                     exps_loc,
                 ))))]
             },
+            Operation::MoveFunction(module_id, fun_id) => {
+                let env = module.env;
+                let callee = env.get_function(module_id.qualified(*fun_id));
+                let callee = callee.get_full_name_with_address();
+                if EVENT_FUNCTIONS.contains(&callee.as_str()) {
+                    vec![Mutant::new(MutationOp::new(Box::new(EventRemoval::new(
+                        env.get_node_loc(*node_id),
+                    ))))]
+                } else if TIMESTAMP_FUNCTIONS.contains(&callee.as_str()) {
+                    vec![Mutant::new(MutationOp::new(Box::new(Timestamp::new(
+                        env.get_node_loc(*node_id),
+                    ))))]
+                } else {
+                    vec![]
+                }
+            },
+            Operation::Pack(module_id, struct_id, Some(variant)) => {
+                let env = module.env;
+                let siblings = compatible_variants(env, module_id.qualified(*struct_id), *variant);
+                if siblings.is_empty() {
+                    vec![]
+                } else {
+                    vec![Mutant::new(MutationOp::new(Box::new(
+                        VariantReplacement::new(
+                            env.get_node_loc(*node_id),
+                            env.symbol_pool().string(*variant).to_string(),
+                            siblings,
+                        ),
+                    )))]
+                }
+            },
             _ => vec![],
         },
-        ExpData::IfElse(_, cond, if_exp, else_exp) => {
+        ExpData::IfElse(node_id, cond, _, else_exp) if is_while_loop(*node_id, else_exp) => {
+            // The condition of the loop is mutated by the loop bound replacement (the `true` condition would
+            // never stop the loop and swapping the branches would swap the body with the loop exit). The
+            // condition of the `for` loop is synthesized by the compiler and covers the whole loop.
+            let env = module.env;
+            if env.get_node_loc(cond.node_id()) == env.get_node_loc(*node_id) {
+                vec![]
+            } else {
+                negate_condition(module, cond).into_iter().collect()
+            }
+        },
+        ExpData::IfElse(node_id, cond, if_exp, else_exp) => {
             let cond_loc = ExpLoc {
-                exp: cond.clone(),
-                loc: function.module_env.env.get_node_loc(cond.node_id()),
+                loc: module.env.get_node_loc(cond.node_id()),
             };
             let if_exp_loc = ExpLoc {
-                exp: if_exp.clone(),
-                loc: function.module_env.env.get_node_loc(if_exp.node_id()),
+                loc: module.env.get_node_loc(if_exp.node_id()),
             };
             let else_exp_loc = ExpLoc {
-                exp: else_exp.clone(),
-                loc: function.module_env.env.get_node_loc(else_exp.node_id()),
+                loc: module.env.get_node_loc(else_exp.node_id()),
             };
-            vec![Mutant::new(MutationOp::new(Box::new(IfElse::new(
+            let mut mutants = vec![Mutant::new(MutationOp::new(Box::new(IfElse::new(
                 cond_loc,
                 if_exp_loc,
                 else_exp_loc,
-            ))))]
+            ))))];
+            mutants.push(Mutant::new(MutationOp::new(Box::new(BranchSwap::new(
+                module.env.get_node_loc(*node_id),
+                module.env.get_node_loc(if_exp.node_id()),
+                module.env.get_node_loc(else_exp.node_id()),
+            )))));
+            mutants.extend(negate_condition(module, cond));
+            mutants
         },
         ExpData::Value(node_id, value) => {
-            let mutants = vec![Mutant::new(MutationOp::new(Box::new(Literal::new(
+            let mut mutants = vec![Mutant::new(MutationOp::new(Box::new(Literal::new(
                 value.clone(),
-                function.module_env.env.get_node_type(*node_id),
-                function.module_env.env.get_node_loc(*node_id),
+                module.env.get_node_type(*node_id),
+                module.env.get_node_loc(*node_id),
             ))))];
+            if let Value::Address(address) = value {
+                mutants.extend(parse_address(module, *node_id, address));
+            }
             mutants
         },
         ExpData::LoopCont(node_id, _) => vec![Mutant::new(MutationOp::new(Box::new(
-            BreakContinue::new(function.module_env.env.get_node_loc(*node_id)),
+            BreakContinue::new(module.env.get_node_loc(*node_id)),
         )))],
 
+        ExpData::Loop(node_id, body) => match body.as_ref() {
+            ExpData::IfElse(id, cond, _, else_exp)
+                if id == node_id && is_while_loop(*id, else_exp) =>
+            {
+                let env = module.env;
+                let bound = match cond.as_ref() {
+                    ExpData::Call(
+                        _,
+                        Operation::Lt | Operation::Le | Operation::Gt | Operation::Ge,
+                        exps,
+                    ) => exps.get(1).map(|exp| env.get_node_loc(exp.node_id())),
+                    _ => None,
+                };
+                vec![Mutant::new(MutationOp::new(Box::new(LoopBound::new(
+                    env.get_node_loc(*node_id),
+                    env.get_node_loc(cond.node_id()),
+                    bound,
+                ))))]
+            },
+            _ => vec![],
+        },
+
+        ExpData::Match(node_id, _, arms) => parse_match_arms(module, *node_id, arms),
+
+        ExpData::Sequence(_, exps) => parse_sequence(module, exps),
+
         ExpData::Return(_, _)
         | ExpData::Mutate(_, _, _)
         | ExpData::Assign(_, _, _)
@@ -276,12 +761,420 @@ fn parse_expression_and_find_mutants(function: &FunctionEnv<'_>, exp: &ExpData)
         | ExpData::Invoke(_, _, _)
         | ExpData::Lambda(_, _, _)
         | ExpData::LocalVar(_, _)
-        | ExpData::Loop(_, _)
         | ExpData::Temporary(_, _)
         | ExpData::SpecBlock(_, _)
-        | ExpData::Sequence(_, _)
         | ExpData::Quant(_, _, _, _, _, _)
-        | ExpData::Match(_, _, _)
         | ExpData::Invalid(_) => vec![],
     }
 }
+
+// Internal function creating the mutants replacing the address of the signer (`signer::address_of(account)`)
+// with the other addresses in scope of the function: the address parameters, the address constants of the
+// module, the zero address and the addresses of the other signer parameters.
+fn parse_signer_address(function: &FunctionEnv<'_>, exp: &ExpData) -> Vec<Mutant> {
+    let ExpData::Call(node_id, Operation::MoveFunction(module_id, fun_id), args) = exp else {
+        return vec![];
+    };
+    let env = function.module_env.env;
+    let Some(account) = args.first() else {
+        return vec![];
+    };
+    if env
+        .get_function(module_id.qualified(*fun_id))
+        .get_full_name_with_address()
+        != SIGNER_ADDRESS_OF
+    {
+        return vec![];
+    }
+
+    let pool = env.symbol_pool();
+    let account_vars = account.free_vars();
+    let signer = Type::Primitive(PrimitiveType::Signer);
+    let mut addresses = vec![];
+    let mut signers = vec![];
+    for Parameter(name, ty, _) in function.get_parameters() {
+        let param = pool.string(name).to_string();
+        match ty {
+            Type::Primitive(PrimitiveType::Address) => addresses.push(param),
+            Type::Reference(_, ty) if *ty == signer && !account_vars.contains(&name) => {
+                signers.push(param);
+            },
+            ty if ty == signer && !account_vars.contains(&name) => {
+                signers.push(format!("&{param}"))
+            },
+            _ => {},
+        }
+    }
+    addresses.extend(
+        function
+            .module_env
+            .get_named_constants()
+            .filter(|constant| constant.get_type() == Type::Primitive(PrimitiveType::Address))
+            .map(|constant| pool.string(constant.get_name()).to_string()),
+    );
+    addresses.push(format!("@{MOVE_ADDR_ZERO}"));
+
+    let loc = env.get_node_loc(*node_id);
+    let account = argument_loc(env, &loc, account);
+    vec![Mutant::new(MutationOp::new(Box::new(SignerAddress::new(
+        loc, account, addresses, signers,
+    ))))]
+}
+
+/// Returns the mutants lowering the visibility of the function, if the package still compiles with the
+/// lower visibility: the function stays `public(friend)` only if all its callers from the other modules are
+/// the friends of the module, and it becomes private only if it has no callers outside of the module. The
+/// modules with the `public(package)` functions can't declare friends, so their `public` functions are
+/// lowered to `public(package)` instead, if all the callers are in the package.
+fn parse_visibility(function: &FunctionEnv<'_>) -> Vec<Mutant> {
+    let module = &function.module_env;
+    if module.is_script_module()
+        || function.is_native()
+        || function.visibility() == Visibility::Private
+    {
+        return vec![];
+    }
+    // Without the call graph it's unknown which visibilities the callers need.
+    let Some(callers) = function.get_calling_functions() else {
+        return vec![];
+    };
+    let external_callers = callers
+        .iter()
+        .filter(|caller| caller.module_id != module.get_id())
+        .map(|caller| module.env.get_module(caller.module_id))
+        .collect::<Vec<_>>();
+
+    let mut visibilities = vec![];
+    if function.visibility() == Visibility::Public {
+        let has_package_visibility = module
+            .get_functions()
+            .any(|fun| fun.has_package_visibility());
+        if has_package_visibility {
+            if external_callers.iter().all(|caller| {
+                caller.is_primary_target() && caller.self_address() == module.self_address()
+            }) {
+                visibilities.push("public(package)".to_owned());
+            }
+        } else if external_callers
+            .iter()
+            .all(|caller| module.has_friend(&caller.get_id()))
+        {
+            visibilities.push("public(friend)".to_owned());
+        }
+    }
+    if external_callers.is_empty() {
+        visibilities.push(String::new());
+    }
+    if visibilities.is_empty() {
+        return vec![];
+    }
+
+    let loc = function.get_loc();
+    let name_loc = function.get_id_loc();
+    if name_loc.span().start() < loc.span().start() {
+        return vec![];
+    }
+    let header = Loc::new(
+        loc.file_id(),
+        codespan::Span::new(loc.span().start(), name_loc.span().start()),
+    );
+    vec![Mutant::new(MutationOp::new(Box::new(
+        VisibilityReplacement::new(header, visibilities),
+    )))]
+}
+
+/// Returns the location of the argument of the call. The receiver of the receiver style call
+/// (`account.address_of()`) is borrowed by the compiler, and the borrow shares the location of the whole
+/// call, so the location of the borrowed receiver is returned instead.
+fn argument_loc(env: &GlobalEnv, call_loc: &Loc, arg: &Exp) -> Loc {
+    let loc = env.get_node_loc(arg.node_id());
+    match arg.as_ref() {
+        ExpData::Call(_, Operation::Borrow(_) | Operation::Freeze(_), inner)
+            if loc == *call_loc =>
+        {
+            inner
+                .first()
+                .map_or(loc, |receiver| argument_loc(env, call_loc, receiver))
+        },
+        _ => loc,
+    }
+}
+
+// Internal function creating the mutants of the amounts passed to the functions moving the funds (the
+// framework ones and the ones listed in the configuration). The amounts are the integer arguments of the call.
+fn parse_monetary_call(
+    function: &FunctionEnv<'_>,
+    exp: &ExpData,
+    conf: &Configuration,
+) -> Vec<Mutant> {
+    let ExpData::Call(node_id, Operation::MoveFunction(module_id, fun_id), args) = exp else {
+        return vec![];
+    };
+    let env = function.module_env.env;
+    let callee = env.get_function(module_id.qualified(*fun_id));
+    let names = [
+        callee.get_full_name_with_address(),
+        callee.get_full_name_str(),
+    ];
+    let is_monetary = MONETARY_FUNCTIONS.contains(&names[0].as_str())
+        || conf
+            .monetary_functions()
+            .iter()
+            .any(|name| names.contains(name));
+    if !is_monetary {
+        return vec![];
+    }
+
+    let amounts = args
+        .iter()
+        .filter(|arg| {
+            matches!(
+                env.get_node_type(arg.node_id()),
+                Type::Primitive(
+                    PrimitiveType::U8
+                        | PrimitiveType::U16
+                        | PrimitiveType::U32
+                        | PrimitiveType::U64
+                        | PrimitiveType::U128
+                        | PrimitiveType::U256
+                )
+            )
+        })
+        .map(|arg| env.get_node_loc(arg.node_id()))
+        .collect::<Vec<_>>();
+    if amounts.is_empty() {
+        return vec![];
+    }
+
+    vec![Mutant::new(MutationOp::new(Box::new(
+        AmountReplacement::new(env.get_node_loc(*node_id), amounts),
+    )))]
+}
+
+// Internal function creating the mutants reordering the adjacent statements of the sequence operating on the
+// global storage. Only the statements independent of each other are reordered, the last expression stays in
+// its place unless its value is the unit.
+fn parse_sequence(module: &ModuleEnv<'_>, exps: &[Exp]) -> Vec<Mutant> {
+    let env = module.env;
+    let statements = match exps.split_last() {
+        Some((last, rest)) if !env.get_node_type(last.node_id()).is_unit() => rest,
+        _ => exps,
+    };
+    // The unit values (e.g. the ones after the trailing semicolons) aren't written as statements.
+    let is_unit_value =
+        |exp: &Exp| matches!(exp.as_ref(), ExpData::Sequence(_, exps) if exps.is_empty());
+    statements
+        .windows(2)
+        .filter(|pair| {
+            !is_unit_value(&pair[0])
+                && !is_unit_value(&pair[1])
+                && (uses_global_storage(&pair[0]) || uses_global_storage(&pair[1]))
+                && are_independent(&pair[0], &pair[1])
+        })
+        .map(|pair| {
+            Mutant::new(MutationOp::new(Box::new(ResourceOperationReorder::new(
+                env.get_node_loc(pair[0].node_id()),
+                env.get_node_loc(pair[1].node_id()),
+            ))))
+        })
+        .collect()
+}
+
+// Internal function checking if the statement operates on the global storage.
+fn uses_global_storage(exp: &Exp) -> bool {
+    exp.any(&mut |e| {
+        matches!(
+            e,
+            ExpData::Call(
+                _,
+                Operation::MoveTo
+                    | Operation::MoveFrom
+                    | Operation::BorrowGlobal(_)
+                    | Operation::Exists(_),
+                _
+            )
+        )
+    })
+}
+
+// Internal function checking if the adjacent statements can be swapped: none of them changes the control
+// flow (or aborts unconditionally) and none of them writes a local variable used by the other one.
+fn are_independent(first: &Exp, second: &Exp) -> bool {
+    let changes_control_flow = |exp: &Exp| {
+        matches!(exp.as_ref(), ExpData::Call(_, Operation::Abort, _))
+            || exp.any(&mut |e| {
+                matches!(
+                    e,
+                    ExpData::Return(..) | ExpData::LoopCont(..) | ExpData::SpecBlock(..)
+                )
+            })
+    };
+    if changes_control_flow(first) || changes_control_flow(second) {
+        return false;
+    }
+
+    let (first_written, second_written) = (written_vars(first), written_vars(second));
+    let (first_used, second_used) = (first.free_vars(), second.free_vars());
+    first_written
+        .iter()
+        .all(|var| !second_used.contains(var) && !second_written.contains(var))
+        && second_written.iter().all(|var| !first_used.contains(var))
+}
+
+// Internal function collecting the local variables written by the statement: the assigned ones, the mutated
+// ones and the mutably borrowed ones.
+fn written_vars(exp: &Exp) -> BTreeSet<Symbol> {
+    let mut vars = BTreeSet::new();
+    exp.visit_pre_order(&mut |e| {
+        match e {
+            ExpData::Assign(_, pattern, _) => {
+                vars.extend(pattern.vars().into_iter().map(|(_, var)| var));
+            },
+            ExpData::Mutate(_, target, _) => vars.extend(target.free_vars()),
+            ExpData::Call(_, Operation::Borrow(ReferenceKind::Mutable), exps) => {
+                vars.extend(exps.iter().flat_map(|exp| exp.free_vars()));
+            },
+            _ => {},
+        }
+        true
+    });
+    vars
+}
+
+// Internal function creating the mutants for the arms of the match expression: swapping of the arms and
+// removal of the arm guards.
+fn parse_match_arms(module: &ModuleEnv<'_>, node_id: NodeId, arms: &[MatchArm]) -> Vec<Mutant> {
+    let env = module.env;
+    let arm_locs = arms
+        .iter()
+        .map(|arm| MatchArmLoc {
+            body: env.get_node_loc(arm.body.node_id()),
+            variant: match &arm.pattern {
+                Pattern::Struct(_, _, Some(variant), _) => {
+                    Some(env.symbol_pool().string(*variant).to_string())
+                },
+                _ => None,
+            },
+        })
+        .collect::<Vec<_>>();
+
+    let mut mutants = vec![];
+    if arm_locs.len() > 1 {
+        mutants.push(Mutant::new(MutationOp::new(Box::new(MatchArmSwap::new(
+            env.get_node_loc(node_id),
+            arm_locs,
+        )))));
+    }
+
+    mutants.extend(arms.iter().filter_map(|arm| {
+        arm.condition.as_ref().map(|condition| {
+            Mutant::new(MutationOp::new(Box::new(MatchGuardRemoval::new(
+                env.get_node_loc(arm.pattern.node_id()),
+                env.get_node_loc(condition.node_id()),
+            ))))
+        })
+    }));
+
+    mutants
+}
+
+// Internal function creating the mutant swapping the named address with the other named addresses of the package.
+fn parse_address(module: &ModuleEnv<'_>, node_id: NodeId, address: &Address) -> Option<Mutant> {
+    let (aliases, others): (Vec<_>, Vec<_>) = named_addresses(module.env)
+        .into_iter()
+        .partition(|(_, value)| value == address);
+
+    // Use a single name for each distinct address.
+    let mut replacements: Vec<(String, Address)> = vec![];
+    for (name, value) in others {
+        if replacements.iter().all(|(_, v)| *v != value) {
+            replacements.push((name, value));
+        }
+    }
+
+    if aliases.is_empty() || replacements.is_empty() {
+        return None;
+    }
+
+    Some(Mutant::new(MutationOp::new(Box::new(AddressSwap::new(
+        module.env.get_node_loc(node_id),
+        aliases.into_iter().map(|(name, _)| name).collect(),
+        replacements.into_iter().map(|(name, _)| name).collect(),
+    )))))
+}
+
+// Internal function returning the address of the module as shown in the reports (e.g. `0xcafe`), `None`
+// for the scripts.
+fn module_address(module: &ModuleEnv<'_>) -> Option<String> {
+    (!module.is_script_module()).then(|| module.env.display(module.get_name().addr()).to_string())
+}
+
+// Internal function checking if the entry of the module filter selects the module. The entry is either the
+// name of the module (selecting all modules with that name) or the name qualified with the address, given
+// as a number or as a named address (e.g. `0xcafe::Sum` or `TestAccount::Sum`), selecting just one of
+// the modules with the same name.
+fn is_module_selected(module: &ModuleEnv<'_>, module_name: &str, selected: &str) -> bool {
+    let Some((address, name)) = selected.rsplit_once("::") else {
+        return selected == module_name;
+    };
+    let Some(module_address) = module_address(module).filter(|_| name == module_name) else {
+        return false;
+    };
+
+    let selected_address = if let Some(digits) = address.strip_prefix("0x") {
+        // Addresses are shown without the leading zeros.
+        let digits = digits.trim_start_matches('0').to_lowercase();
+        format!("0x{}", if digits.is_empty() { "0" } else { &digits })
+    } else {
+        let env = module.env;
+        match env.resolve_address_alias(env.symbol_pool().make(address)) {
+            Some(value) => env.display(&Address::Numerical(value)).to_string(),
+            None => return false,
+        }
+    };
+    selected_address == module_address
+}
+
+// Internal function returning the named addresses of the package (sorted by name) with their values
+// resolved from the build configuration.
+fn named_addresses(env: &GlobalEnv) -> Vec<(String, Address)> {
+    let Some(options) = env.get_extension::<move_compiler_v2::Options>() else {
+        return vec![];
+    };
+
+    let mut addresses = options
+        .named_address_mapping
+        .iter()
+        .filter_map(|mapping| mapping.split_once('='))
+        .filter_map(|(name, _)| {
+            env.resolve_address_alias(env.symbol_pool().make(name))
+                .map(|value| (name.to_owned(), Address::Numerical(value)))
+        })
+        .collect::<Vec<_>>();
+    addresses.sort();
+    addresses.dedup();
+    addresses
+}
+
+// Internal function returning the names of the sibling variants with the same payload (field names and types)
+// as the given variant of the enum.
+fn compatible_variants(
+    env: &GlobalEnv,
+    enum_id: QualifiedId<StructId>,
+    variant: Symbol,
+) -> Vec<String> {
+    let struct_env = env.get_struct(enum_id);
+    let payload = |variant: Symbol| {
+        struct_env
+            .get_fields_of_variant(variant)
+            .map(|field| (field.get_name(), field.get_type()))
+            .collect::<Vec<_>>()
+    };
+
+    let expected = payload(variant);
+    struct_env
+        .get_variants()
+        .filter(|sibling| *sibling != variant && payload(*sibling) == expected)
+        .map(|sibling| env.symbol_pool().string(sibling).to_string())
+        .collect()
+}
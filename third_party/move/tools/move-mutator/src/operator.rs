@@ -2,8 +2,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::report::Mutation;
+use crate::{
+    report::Mutation,
+    rewrite::{self, Rewrite},
+};
 use codespan::FileId;
+use serde::Serialize;
 use std::{
     fmt,
     fmt::{Debug, Display},
@@ -28,11 +32,27 @@ impl MutantInfo {
     }
 }
 
+/// Description of the mutation operator, used to document the operators (e.g. by the `operators` command
+/// of the mutator tool), so the documentation is kept next to the operator implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct OperatorDescription {
+    /// Name of the operator (as used in the configuration and in the reports).
+    pub name: &'static str,
+    /// The Move model AST nodes targeted by the operator.
+    pub targets: &'static [&'static str],
+    /// What the operator does.
+    pub description: &'static str,
+    /// Example of the code before the mutation.
+    pub before: &'static str,
+    /// Example of the code after the mutation.
+    pub after: &'static str,
+}
+
 /// Trait for mutation operators.
 /// Mutation operators are used to apply mutations to the source code. To keep adding new mutation operators simple,
 /// we use a trait that all mutation operators implement.
 #[allow(clippy::module_name_repetitions)]
-pub trait MutationOperator: Display + Debug + MutationOperatorClone {
+pub trait MutationOperator: Display + Debug + MutationOperatorClone + Send + Sync {
     /// Applies the mutation operator to the given source code.
     /// Returns differently mutated source code listings in a vector.
     ///
@@ -90,7 +110,38 @@ impl MutationOperator for MutationOp {
     fn apply(&self, source: &str) -> Vec<MutantInfo> {
         debug!("Applying mutation operator: {self}");
 
-        self.operator.apply(source)
+        // Only the mutated place may differ in the mutant, so its diff is a single hunk and its lines map to
+        // the lines of the original.
+        self.operator
+            .apply(source)
+            .into_iter()
+            .filter(|info| {
+                let place = info.mutation.get_changed_place();
+                if !rewrite::is_valid_span(source, place.start(), place.end()) {
+                    warn!(
+                        "{self} mutated the place {}..{} splitting a character or a line ending, the mutant is skipped",
+                        place.start(),
+                        place.end()
+                    );
+                    return false;
+                }
+                if rewrite::splits_comment_or_string(source, place.start(), place.end()) {
+                    warn!(
+                        "{self} mutated the place {}..{} inside a comment or a string literal (the locations may not match the source), the mutant is skipped",
+                        place.start(),
+                        place.end()
+                    );
+                    return false;
+                }
+                let preserved =
+                    Rewrite::of_span(source, &info.mutated_source, place.start(), place.end())
+                        .is_some();
+                if !preserved {
+                    warn!("{self} changed the code outside of the mutated place, the mutant is skipped");
+                }
+                preserved
+            })
+            .collect()
     }
 
     fn get_file_id(&self) -> FileId {
@@ -111,7 +162,7 @@ impl Display for MutationOp {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::operators::binary::Binary;
+    use crate::operators::{binary::Binary, ExpLoc};
     use codespan::Files;
     use move_model::{ast::Operation, model::Loc};
 
@@ -123,4 +174,30 @@ mod tests {
         let operator = MutationOp::new(Box::new(Binary::new(Operation::Add, loc, vec![])));
         assert_eq!(operator.get_file_id(), fid);
     }
+
+    // Returns the sources of the mutants replacing the binary operator between the operand spans.
+    fn binary_mutants(source: &str, left: (u32, u32), right: (u32, u32)) -> Vec<String> {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let exps = [left, right]
+            .into_iter()
+            .map(|(start, end)| ExpLoc::new(Loc::new(fid, codespan::Span::new(start, end))))
+            .collect();
+        let loc = Loc::new(fid, codespan::Span::new(left.0, right.1));
+        MutationOp::new(Box::new(Binary::new(Operation::Add, loc, exps)))
+            .apply(source)
+            .into_iter()
+            .map(|mutant| mutant.mutated_source)
+            .collect()
+    }
+
+    #[test]
+    fn mutants_inside_comments_are_skipped() {
+        // The comments between the operands are kept.
+        let mutants = binary_mutants("x /* + */ + y", (0, 1), (12, 13));
+        assert_eq!(mutants[0], "x /* + */ - y");
+        // The stale locations point into the comment.
+        assert!(binary_mutants("// a + b\nx + y", (3, 4), (7, 8)).is_empty());
+        assert!(binary_mutants("b\"a + b\" + y", (2, 3), (6, 7)).is_empty());
+    }
 }
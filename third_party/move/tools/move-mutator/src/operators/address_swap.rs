@@ -0,0 +1,142 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::{fmt, fmt::Debug};
+
+pub const OPERATOR_NAME: &str = "named_address_swap";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Value(Address)"],
+    description: "Replaces the named address with the other named addresses of the package resolving to a distinct value, which tests the access control specifications.",
+    before: "assert!(signer::address_of(account) == @admin, 1);",
+    after: "assert!(signer::address_of(account) == @treasury, 1);",
+};
+
+/// Named address swap mutation operator.
+/// Replaces the named address (e.g. `@admin`) with the other named addresses of the package resolving to
+/// a distinct value (e.g. `@treasury`), which tests the access control specifications.
+#[derive(Debug, Clone)]
+pub struct AddressSwap {
+    loc: Loc,
+    aliases: Vec<String>,
+    replacements: Vec<String>,
+}
+
+impl AddressSwap {
+    /// Creates a new instance of the named address swap mutation operator.
+    ///
+    /// `aliases` are the named addresses resolving to the value of the mutated address and `replacements`
+    /// are the named addresses resolving to the other values.
+    #[must_use]
+    pub fn new(loc: Loc, aliases: Vec<String>, replacements: Vec<String>) -> Self {
+        Self {
+            loc,
+            aliases,
+            replacements,
+        }
+    }
+}
+
+impl MutationOperator for AddressSwap {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let start = self.loc.span().start().to_usize();
+        let end = self.loc.span().end().to_usize();
+        let cur_op = &source[start..end];
+
+        // Only named addresses are swapped - numerical addresses and constants resolved to the address
+        // are left to the literal replacement operator.
+        let name = cur_op.trim_start_matches('@');
+        if !self.aliases.iter().any(|alias| alias == name) {
+            return vec![];
+        }
+        let start = end - name.len();
+
+        self.replacements
+            .iter()
+            .map(|replacement| {
+                let mutated_source = rewrite::replace(source, start, end, replacement);
+                MutantInfo::new(
+                    mutated_source,
+                    Mutation::new(
+                        Range::new(start, end),
+                        OPERATOR_NAME.to_string(),
+                        name.to_string(),
+                        replacement.clone(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for AddressSwap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "AddressSwapOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_address_swap() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(7, 13));
+        let operator = AddressSwap::new(loc, vec!["admin".to_owned()], vec![
+            "std".to_owned(),
+            "treasury".to_owned(),
+        ]);
+        let source = "addr = @admin;";
+        let expected = ["addr = @std;", "addr = @treasury;"];
+        let result = operator.apply(source);
+        assert_eq!(result.len(), expected.len());
+        for (i, r) in result.iter().enumerate() {
+            assert_eq!(r.mutated_source, expected[i]);
+        }
+    }
+
+    #[test]
+    fn test_constants_are_not_swapped() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(7, 12));
+        let operator = AddressSwap::new(loc, vec!["admin".to_owned()], vec!["std".to_owned()]);
+        assert!(operator.apply("addr = OWNER;").is_empty());
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = AddressSwap::new(loc, vec![], vec![]);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -0,0 +1,165 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "amount_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(MoveFunction(monetary function))"],
+    description: "Replaces the amounts passed to the functions moving the funds (the framework transfer, mint, burn and withdraw functions and the `monetary_functions` of the configuration) with zero and the amount increased by one, and swaps the adjacent amounts (e.g. the fee and the principal).",
+    before: "coin::transfer<USD>(from, to, amount);",
+    after: "coin::transfer<USD>(from, to, 0);",
+};
+
+/// Full names of the framework functions moving the funds. More functions can be added with the
+/// `monetary_functions` field of the mutation configuration.
+pub const MONETARY_FUNCTIONS: &[&str] = &[
+    "0x1::aptos_account::transfer",
+    "0x1::aptos_account::transfer_coins",
+    "0x1::coin::burn_from",
+    "0x1::coin::extract",
+    "0x1::coin::mint",
+    "0x1::coin::transfer",
+    "0x1::coin::withdraw",
+    "0x1::fungible_asset::extract",
+    "0x1::fungible_asset::mint",
+    "0x1::fungible_asset::withdraw",
+    "0x1::primary_fungible_store::burn",
+    "0x1::primary_fungible_store::mint",
+    "0x1::primary_fungible_store::transfer",
+    "0x1::primary_fungible_store::withdraw",
+];
+
+/// Amount mutation operator.
+/// Perturbs the amounts of the funds moved by the monetary functions, to check if the specifications
+/// constrain the moved amounts (and don't only check that something was moved).
+#[derive(Debug, Clone)]
+pub struct AmountReplacement {
+    loc: Loc,
+    amounts: Vec<Loc>,
+}
+
+impl AmountReplacement {
+    /// Creates a new instance of the amount mutation operator for the call at `loc` with the integer
+    /// arguments at the `amounts` locations (in the order of the arguments).
+    #[must_use]
+    pub fn new(loc: Loc, amounts: Vec<Loc>) -> Self {
+        Self { loc, amounts }
+    }
+}
+
+impl MutationOperator for AmountReplacement {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let spans = self
+            .amounts
+            .iter()
+            .map(|loc| (loc.span().start().to_usize(), loc.span().end().to_usize()))
+            .collect::<Vec<_>>();
+
+        let mut mutants = vec![];
+        for &(start, end) in &spans {
+            let amount = &source[start..end];
+            for op in ["0".to_owned(), format!("({amount} + 1)")] {
+                mutants.push(mutant(source, start, end, &op));
+            }
+        }
+
+        // Swapping the adjacent amounts confuses e.g. the fee with the principal.
+        for pair in spans.windows(2) {
+            let ((start, first_end), (second_start, end)) = (pair[0], pair[1]);
+            let (first, second) = (&source[start..first_end], &source[second_start..end]);
+            if first_end <= second_start && first != second {
+                let op = format!("{second}{}{first}", &source[first_end..second_start]);
+                mutants.push(mutant(source, start, end, &op));
+            }
+        }
+
+        mutants
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for AmountReplacement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "AmountReplacementOperator(amounts: {}, location: file id: {:?}, index start: {}, index stop: {})",
+            self.amounts.len(),
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+// Internal function creating the mutant replacing the given range of the source.
+fn mutant(source: &str, start: usize, end: usize, mutated: &str) -> MutantInfo {
+    let mutated_source = rewrite::replace(source, start, end, mutated);
+    MutantInfo::new(
+        mutated_source,
+        Mutation::new(
+            Range::new(start, end),
+            OPERATOR_NAME.to_string(),
+            source[start..end].to_string(),
+            mutated.to_string(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::loc;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_amounts() {
+        let mut files = Files::new();
+        let source = "charge(from, principal, fee)";
+        let fid = files.add("test", source);
+        let operator = AmountReplacement::new(loc(fid, source, source), vec![
+            loc(fid, source, "principal"),
+            loc(fid, source, "fee"),
+        ]);
+
+        let result = operator
+            .apply(source)
+            .into_iter()
+            .map(|m| m.mutated_source)
+            .collect::<Vec<_>>();
+        assert_eq!(result, [
+            "charge(from, 0, fee)",
+            "charge(from, (principal + 1), fee)",
+            "charge(from, principal, 0)",
+            "charge(from, principal, (fee + 1))",
+            "charge(from, fee, principal)",
+        ]);
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = AmountReplacement::new(loc, vec![]);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::ExpLoc,
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
 use move_model::{ast::Operation, model::Loc};
@@ -13,6 +14,21 @@ use std::fmt;
 
 pub const OPERATOR_NAME: &str = "binary_operator_replacement";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &[
+        "Call(Add | Sub | Mul | Div | Mod)",
+        "Call(BitAnd | BitOr | Xor)",
+        "Call(Shl | Shr)",
+        "Call(And | Or)",
+        "Call(Eq | Neq | Lt | Gt | Le | Ge)",
+    ],
+    description: "Replaces the binary operator with the other operators of the same group (arithmetic, bitwise, shift, logical or comparison operators).",
+    before: "a + b",
+    after: "a - b",
+};
+
 /// The binary mutation operator.
 #[derive(Debug, Clone)]
 pub struct Binary {
@@ -45,16 +61,13 @@ impl MutationOperator for Binary {
         // extracting the operator of a different binary expression.
         let left = &self.exps[0].loc;
         let right = &self.exps[1].loc;
-        let start = left.span().end().to_usize();
-        // Adjust start to omit whitespaces before the operator
-        let start = source[start..]
-            .find(|c: char| !c.is_whitespace())
-            .map_or(start, |i| start + i);
-        let end = right.span().start().to_usize();
-        // Adjust end to omit whitespaces after the operator
-        let end = source[..end]
-            .rfind(|c: char| !c.is_whitespace())
-            .map_or(end, |i| i + 1);
+        // The operator is between the operands, without the whitespaces and the comments around it (the
+        // boundaries of the trimmed span never split the multi-byte characters of the comments).
+        let (start, end) = rewrite::trim_comments(
+            source,
+            left.span().end().to_usize(),
+            right.span().start().to_usize(),
+        );
         let cur_op = &source[start..end];
 
         // Group of exchangeable binary operators - we only want to replace the operator with a different one
@@ -88,8 +101,7 @@ impl MutationOperator for Binary {
         ops.into_iter()
             .filter(|v| cur_op != *v)
             .map(|op| {
-                let mut mutated_source = source.to_string();
-                mutated_source.replace_range(start..end, op);
+                let mutated_source = rewrite::replace(source, start, end, op);
                 MutantInfo::new(
                     mutated_source,
                     Mutation::new(
@@ -129,10 +141,6 @@ impl fmt::Display for Binary {
 mod tests {
     use super::*;
     use codespan::Files;
-    use move_model::{
-        ast::{ExpData, Value},
-        model::NodeId,
-    };
 
     #[test]
     fn test_apply_binary_operator() {
@@ -141,10 +149,8 @@ mod tests {
         let loc = Loc::new(fid, codespan::Span::new(0, 3));
         let loc2 = Loc::new(fid, codespan::Span::new(0, 1));
         let loc3 = Loc::new(fid, codespan::Span::new(2, 3));
-        let e1 = ExpData::Value(NodeId::new(1), Value::Bool(true));
-        let e2 = ExpData::Value(NodeId::new(2), Value::Bool(false));
-        let exp1 = ExpLoc::new(e1.into_exp(), loc2);
-        let exp2 = ExpLoc::new(e2.into_exp(), loc3);
+        let exp1 = ExpLoc::new(loc2);
+        let exp2 = ExpLoc::new(loc3);
 
         let operator = Binary::new(Operation::Add, loc, vec![exp1, exp2]);
         let source = "5+2";
@@ -156,6 +162,47 @@ mod tests {
         }
     }
 
+    fn apply(operation: Operation, source: &str) -> Vec<String> {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, source.len() as u32));
+        let left = Loc::new(fid, codespan::Span::new(0, 1));
+        let right = Loc::new(
+            fid,
+            codespan::Span::new(source.len() as u32 - 1, source.len() as u32),
+        );
+        let exps = vec![ExpLoc::new(left), ExpLoc::new(right)];
+
+        Binary::new(operation, loc, exps)
+            .apply(source)
+            .into_iter()
+            .map(|mutant| mutant.mutated_source)
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_shift_operator() {
+        assert_eq!(apply(Operation::Shl, "x << y"), ["x >> y"]);
+        assert_eq!(apply(Operation::Shr, "x >> y"), ["x << y"]);
+    }
+
+    #[test]
+    fn test_apply_bitwise_operator() {
+        assert_eq!(apply(Operation::BitAnd, "x & y"), ["x | y", "x ^ y"]);
+        assert_eq!(apply(Operation::BitOr, "x | y"), ["x & y", "x ^ y"]);
+        assert_eq!(apply(Operation::Xor, "x ^ y"), ["x | y", "x & y"]);
+    }
+
+    #[test]
+    fn test_apply_next_to_multi_byte_characters() {
+        assert_eq!(apply(Operation::Add, "x\u{3000}+\u{3000}y"), [
+            "x\u{3000}-\u{3000}y",
+            "x\u{3000}*\u{3000}y",
+            "x\u{3000}/\u{3000}y",
+            "x\u{3000}%\u{3000}y"
+        ]);
+    }
+
     #[test]
     fn test_get_file_id() {
         let mut files = Files::new();
@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::ExpLoc,
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
 use move_model::{ast::Operation, model::Loc};
@@ -13,6 +14,17 @@ use std::fmt;
 
 pub const OPERATOR_NAME: &str = "binary_operator_swap";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &[
+        "Call(Sub | Div | Mod | BitAnd | BitOr | Xor | Shl | Shr | And | Or | Lt | Gt | Le | Ge)",
+    ],
+    description: "Swaps the operands of the binary operator, unless the operator is commutative.",
+    before: "a - b",
+    after: "b - a",
+};
+
 /// The binary swap mutation operator.
 #[derive(Debug, Clone)]
 pub struct BinarySwap {
@@ -55,16 +67,13 @@ impl MutationOperator for BinarySwap {
         // extracting the operator of a different binary expression.
         let left = &self.exps[0].loc;
         let right = &self.exps[1].loc;
-        let start = left.span().end().to_usize();
-        // Adjust start to omit whitespaces before the operator
-        let start = source[start..]
-            .find(|c: char| !c.is_whitespace())
-            .map_or(start, |i| start + i);
-        let end = right.span().start().to_usize();
-        // Adjust end to omit whitespaces after the operator
-        let end = source[..end]
-            .rfind(|c: char| !c.is_whitespace())
-            .map_or(end, |i| i + 1);
+        // The operator is between the operands, without the whitespaces around it (the boundaries of the
+        // trimmed span never split the multi-byte characters of the comments).
+        let (start, end) = rewrite::trim_span(
+            source,
+            left.span().end().to_usize(),
+            right.span().start().to_usize(),
+        );
         let binop_str = &source[start..end];
 
         let start = left.span().start().to_usize();
@@ -74,12 +83,11 @@ impl MutationOperator for BinarySwap {
         let left_str = &source[left.span().start().to_usize()..left.span().end().to_usize()];
         let right_str = &source[right.span().start().to_usize()..right.span().end().to_usize()];
 
-        let mut mutated_source = source.to_string();
         let mut op = right_str.to_owned();
         op.push_str(binop_str);
         op.push_str(left_str);
 
-        mutated_source.replace_range(start..end, op.as_str());
+        let mutated_source = rewrite::replace(source, start, end, op.as_str());
 
         vec![MutantInfo::new(
             mutated_source,
@@ -0,0 +1,160 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "if_else_branch_swap";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["IfElse (with the `else` branch)"],
+    description: "Swaps the `then` and `else` branches of the `if` expression, so each branch is taken when the condition selects the other one.",
+    before: "if (amount > limit) { limit } else { amount }",
+    after: "if (amount > limit) { amount } else { limit }",
+};
+
+/// If/else branch swap mutation operator.
+/// Swaps the branches of the `if` expressions written with the `else` branch. The `if` expressions
+/// without the `else` branch (and the ones desugared from the loops) have no branches to swap.
+#[derive(Debug, Clone)]
+pub struct BranchSwap {
+    loc: Loc,
+    then_branch: Loc,
+    else_branch: Loc,
+}
+
+impl BranchSwap {
+    /// Creates a new instance of the branch swap mutation operator.
+    #[must_use]
+    pub fn new(loc: Loc, then_branch: Loc, else_branch: Loc) -> Self {
+        Self {
+            loc,
+            then_branch,
+            else_branch,
+        }
+    }
+}
+
+impl MutationOperator for BranchSwap {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let (start, then_end) = (
+            self.then_branch.span().start().to_usize(),
+            self.then_branch.span().end().to_usize(),
+        );
+        let (else_start, end) = (
+            self.else_branch.span().start().to_usize(),
+            self.else_branch.span().end().to_usize(),
+        );
+        // The synthesized `else` branches overlap the `if` expression or aren't preceded by the keyword.
+        if then_end > else_start || !has_else_keyword(&source[then_end..else_start]) {
+            return vec![];
+        }
+
+        let then_body = &source[start..then_end];
+        let else_body = &source[else_start..end];
+        // Swapping the same branches doesn't change anything.
+        if then_body == else_body {
+            return vec![];
+        }
+
+        let new_value = format!("{else_body}{}{then_body}", &source[then_end..else_start]);
+        let mutated_source = rewrite::replace(source, start, end, &new_value);
+        vec![MutantInfo::new(
+            mutated_source,
+            Mutation::new(
+                Range::new(start, end),
+                OPERATOR_NAME.to_string(),
+                source[start..end].to_string(),
+                new_value,
+            ),
+        )]
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for BranchSwap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "BranchSwapOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+// Internal function checking if the source between the branches contains the `else` keyword.
+fn has_else_keyword(between: &str) -> bool {
+    between
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .any(|word| word == "else")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::loc;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_swaps_branches() {
+        let mut files = Files::new();
+        let source = "if (a > b) { a } else { b + 1 }";
+        let fid = files.add("test", source);
+        let operator = BranchSwap::new(
+            loc(fid, source, source),
+            loc(fid, source, "{ a }"),
+            loc(fid, source, "{ b + 1 }"),
+        );
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "if (a > b) { b + 1 } else { a }");
+    }
+
+    #[test]
+    fn test_apply_skips_missing_else() {
+        let mut files = Files::new();
+        let source = "if (a > b) abort 1; x";
+        let fid = files.add("test", source);
+        // The `else` branch of the `if` without `else` isn't written in the source.
+        let operator = BranchSwap::new(
+            loc(fid, source, "if (a > b) abort 1"),
+            loc(fid, source, "abort 1"),
+            loc(fid, source, "x"),
+        );
+        assert!(operator.apply(source).is_empty());
+
+        let operator = BranchSwap::new(
+            loc(fid, source, "if (a > b) abort 1"),
+            loc(fid, source, "abort 1"),
+            loc(fid, source, "if (a > b) abort 1"),
+        );
+        assert!(operator.apply(source).is_empty());
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = BranchSwap::new(loc.clone(), loc.clone(), loc);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
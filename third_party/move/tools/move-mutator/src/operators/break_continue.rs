@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::{MOVE_BREAK, MOVE_CONTINUE, MOVE_EMPTY_STMT},
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
 use move_model::model::Loc;
@@ -13,6 +14,15 @@ use std::{fmt, fmt::Debug};
 
 pub const OPERATOR_NAME: &str = "break_continue_replacement";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["LoopCont"],
+    description: "Replaces the `break` statement with `continue` or an empty block (and the `continue` statement with `break` or an empty block).",
+    before: "if (i == 10) break;",
+    after: "if (i == 10) continue;",
+};
+
 /// Break and continue mutation operator.
 /// Replaces break and continue statements with each other or deletes them.
 #[derive(Debug, Clone)]
@@ -47,8 +57,7 @@ impl MutationOperator for BreakContinue {
 
         ops.into_iter()
             .map(|op| {
-                let mut mutated_source = source.to_string();
-                mutated_source.replace_range(start..end, op);
+                let mutated_source = rewrite::replace(source, start, end, op);
                 MutantInfo::new(
                     mutated_source,
                     Mutation::new(
@@ -0,0 +1,297 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::{
+    model::Loc,
+    ty::{PrimitiveType, Type},
+};
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "cast_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(Cast)"],
+    description: "Narrows the integer cast through a smaller integer type (keeping the type of the result) and removes the inner cast of the nested casts.",
+    before: "(amount as u64)",
+    after: "((amount as u8) as u64)",
+};
+
+/// Integer types the casts can be narrowed to (from the narrowest one).
+const INTEGER_TYPES: [PrimitiveType; 6] = [
+    PrimitiveType::U8,
+    PrimitiveType::U16,
+    PrimitiveType::U32,
+    PrimitiveType::U64,
+    PrimitiveType::U128,
+    PrimitiveType::U256,
+];
+
+/// Cast mutation operator.
+/// Changes the bounds of the integer casts, to check if the specifications notice the truncation of the
+/// values (the cast aborts if the value doesn't fit). The result of the mutated cast keeps its type, so
+/// the mutants still compile.
+#[derive(Debug, Clone)]
+pub struct Cast {
+    loc: Loc,
+    operand: Loc,
+    operand_type: Type,
+    target_type: Type,
+    inner_operand: Option<Loc>,
+}
+
+impl Cast {
+    /// Creates a new instance of the cast mutation operator.
+    ///
+    /// `inner_operand` is the location of the operand of the inner cast, if the operand of this cast is
+    /// a cast as well (e.g. `x` in `((x as u8) as u64)`).
+    #[must_use]
+    pub fn new(
+        loc: Loc,
+        operand: Loc,
+        operand_type: Type,
+        target_type: Type,
+        inner_operand: Option<Loc>,
+    ) -> Self {
+        Self {
+            loc,
+            operand,
+            operand_type,
+            target_type,
+            inner_operand,
+        }
+    }
+}
+
+impl MutationOperator for Cast {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let mut mutants = vec![];
+
+        let operand_end = self.operand.span().end().to_usize();
+        let Some((start, as_start, type_end)) =
+            cast_parts(source, self.operand.span().start().to_usize(), operand_end)
+        else {
+            warn!("CastOperator: Cannot find the type of the cast at {operand_end}");
+            return mutants;
+        };
+        let current = &source[start..type_end];
+        let operand = source[start..as_start].trim_end();
+        let target = source[as_start..type_end].trim_start();
+
+        // Narrowing through the types not smaller than the operand or the target can't change the result.
+        let bound = [&self.operand_type, &self.target_type]
+            .into_iter()
+            .filter_map(integer_bits)
+            .min();
+        if let Some(bound) = bound {
+            for ty in INTEGER_TYPES
+                .iter()
+                .take_while(|ty| ty.get_num_bits() < Some(bound))
+            {
+                let mutated = format!("({operand} as {ty}) {target}");
+                mutants.push(mutant(source, start, type_end, current, &mutated));
+            }
+        }
+
+        // The operand of the removed inner cast is cast by this cast anyway, so the mutant compiles.
+        if let Some(inner) = &self.inner_operand {
+            let inner_end = inner.span().end().to_usize();
+            if let Some((inner_start, inner_as, inner_type_end)) =
+                cast_parts(source, inner.span().start().to_usize(), inner_end)
+            {
+                let inner_operand = source[inner_start..inner_as].trim_end();
+                mutants.push(mutant(
+                    source,
+                    inner_start,
+                    inner_type_end,
+                    &source[inner_start..inner_type_end],
+                    inner_operand,
+                ));
+            }
+        }
+
+        mutants
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for Cast {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CastOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+// Internal function returning the number of bits of the integer type (`None` for other types).
+fn integer_bits(ty: &Type) -> Option<usize> {
+    match ty {
+        Type::Primitive(ty) if INTEGER_TYPES.contains(ty) => ty.get_num_bits(),
+        _ => None,
+    }
+}
+
+// Internal function locating the parts of the cast `operand as type` in the source: the start of the operand
+// (including its parentheses, which may be outside of the operand location), the start of the `as` keyword
+// and the end of the type.
+fn cast_parts(
+    source: &str,
+    operand_start: usize,
+    operand_end: usize,
+) -> Option<(usize, usize, usize)> {
+    let rest = &source[operand_end..];
+    let as_offset = rest.find(|c: char| c.is_alphabetic())?;
+    if !rest[as_offset..].starts_with("as") {
+        return None;
+    }
+
+    let type_offset = as_offset + 2 + rest[as_offset + 2..].find(|c: char| !c.is_whitespace())?;
+    let type_len = rest[type_offset..]
+        .find(|c: char| !c.is_alphanumeric())
+        .unwrap_or(rest.len() - type_offset);
+    if type_len == 0 {
+        return None;
+    }
+
+    // Parentheses closed between the operand and the `as` keyword belong to the operand.
+    let mut start = operand_start;
+    for _ in 0..rest[..as_offset].matches(')').count() {
+        start = source[..start].rfind('(')?;
+    }
+
+    Some((
+        start,
+        operand_end + as_offset,
+        operand_end + type_offset + type_len,
+    ))
+}
+
+// Internal function creating the mutant replacing the given range of the source.
+fn mutant(source: &str, start: usize, end: usize, current: &str, mutated: &str) -> MutantInfo {
+    let mutated_source = rewrite::replace(source, start, end, mutated);
+    MutantInfo::new(
+        mutated_source,
+        Mutation::new(
+            Range::new(start, end),
+            OPERATOR_NAME.to_string(),
+            current.to_string(),
+            mutated.to_string(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::loc;
+    use codespan::Files;
+
+    fn u(ty: PrimitiveType) -> Type {
+        Type::Primitive(ty)
+    }
+
+    #[test]
+    fn test_apply_narrows_cast() {
+        let mut files = Files::new();
+        let source = "(amount as u64)";
+        let fid = files.add("test", source);
+        let operator = Cast::new(
+            loc(fid, source, source),
+            loc(fid, source, "amount"),
+            u(PrimitiveType::U128),
+            u(PrimitiveType::U64),
+            None,
+        );
+
+        let result = operator
+            .apply(source)
+            .into_iter()
+            .map(|m| m.mutated_source)
+            .collect::<Vec<_>>();
+        assert_eq!(result, [
+            "((amount as u8) as u64)",
+            "((amount as u16) as u64)",
+            "((amount as u32) as u64)",
+        ]);
+    }
+
+    #[test]
+    fn test_apply_keeps_parentheses_of_operand() {
+        let mut files = Files::new();
+        let source = "((a + b) as u16)";
+        let fid = files.add("test", source);
+        let operator = Cast::new(
+            loc(fid, source, source),
+            loc(fid, source, "a + b"),
+            u(PrimitiveType::U8),
+            u(PrimitiveType::U16),
+            None,
+        );
+
+        // The operand type is the narrowest one, so the cast can't be narrowed.
+        assert!(operator.apply(source).is_empty());
+
+        let operator = Cast::new(
+            loc(fid, source, source),
+            loc(fid, source, "a + b"),
+            u(PrimitiveType::U64),
+            u(PrimitiveType::U16),
+            None,
+        );
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "(((a + b) as u8) as u16)");
+    }
+
+    #[test]
+    fn test_apply_removes_inner_cast() {
+        let mut files = Files::new();
+        let source = "((x as u8) as u64)";
+        let fid = files.add("test", source);
+        let operator = Cast::new(
+            loc(fid, source, source),
+            loc(fid, source, "(x as u8)"),
+            u(PrimitiveType::U8),
+            u(PrimitiveType::U64),
+            Some(loc(fid, source, "x")),
+        );
+
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "((x) as u64)");
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = Cast::new(
+            loc.clone(),
+            loc,
+            u(PrimitiveType::U8),
+            u(PrimitiveType::U8),
+            None,
+        );
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -3,29 +3,39 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::MOVE_EMPTY_STMT,
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
-use move_model::{ast::Exp, model::Loc};
+use move_model::{ast::Operation, model::Loc};
 use std::{fmt, fmt::Debug};
 
 pub const OPERATOR_NAME: &str = "delete_statement";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(MoveTo | Abort)"],
+    description: "Deletes the statement (replaces it with an empty block), still allowing the code to compile.",
+    before: "move_to(account, Counter { value: 0 });",
+    after: "{};",
+};
+
 /// Statement delete operator.
 /// Deletes statements which can be potentially deleted, still allowing the code to compile
 /// properly.
 #[derive(Debug, Clone)]
 pub struct DeleteStmt {
-    operation: Exp,
+    operation: Operation,
     loc: Loc,
 }
 
 impl DeleteStmt {
     /// Creates a new instance of the delete mutation operator.
     #[must_use]
-    pub fn new(operation: Exp, loc: Loc) -> Self {
+    pub fn new(operation: Operation, loc: Loc) -> Self {
         Self { operation, loc }
     }
 }
@@ -42,8 +52,7 @@ impl MutationOperator for DeleteStmt {
 
         ops.into_iter()
             .map(|op| {
-                let mut mutated_source = source.to_string();
-                mutated_source.replace_range(start..end, op);
+                let mutated_source = rewrite::replace(source, start, end, op);
                 MutantInfo::new(
                     mutated_source,
                     Mutation::new(
@@ -0,0 +1,111 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    operators::MOVE_EMPTY_STMT,
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "event_emission_removal";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(MoveFunction(0x1::event::emit | 0x1::event::emit_event))"],
+    description: "Deletes the emission of the event (replaces the call with an empty block), checking if the specifications or the tests assert the emitted events.",
+    before: "event::emit(Deposit { amount });",
+    after: "{};",
+};
+
+/// Full names of the functions emitting the events (the module events and the legacy event handles).
+pub const EVENT_FUNCTIONS: &[&str] = &["0x1::event::emit", "0x1::event::emit_event"];
+
+/// Event emission removal mutation operator.
+/// Deletes the calls emitting the events. The emission functions return the unit, so the mutants still
+/// compile.
+#[derive(Debug, Clone)]
+pub struct EventRemoval {
+    loc: Loc,
+}
+
+impl EventRemoval {
+    /// Creates a new instance of the event emission removal mutation operator.
+    #[must_use]
+    pub fn new(loc: Loc) -> Self {
+        Self { loc }
+    }
+}
+
+impl MutationOperator for EventRemoval {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let (start, end) = (
+            self.loc.span().start().to_usize(),
+            self.loc.span().end().to_usize(),
+        );
+        let cur_op = &source[start..end];
+
+        let mutated_source = rewrite::replace(source, start, end, MOVE_EMPTY_STMT);
+        vec![MutantInfo::new(
+            mutated_source,
+            Mutation::new(
+                Range::new(start, end),
+                OPERATOR_NAME.to_string(),
+                cur_op.to_string(),
+                MOVE_EMPTY_STMT.to_string(),
+            ),
+        )]
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for EventRemoval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "EventRemovalOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_event_removal() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 28));
+        let operator = EventRemoval::new(loc);
+        let source = "event::emit(Paid { amount }); x";
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "{}; x");
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = EventRemoval::new(loc);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -3,15 +3,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::ExpLoc,
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
 use std::{fmt, fmt::Debug};
 
 pub const OPERATOR_NAME: &str = "if_else_replacement";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["IfElse"],
+    description: "Replaces the condition of the `if` expression with `true` or `false`.",
+    before: "if (amount > limit) abort 1;",
+    after: "if (false) abort 1;",
+};
+
 /// `IfElse` mutation operator.
 /// Replaces conditional expressions in if/else statements with literals.
 /// Currently only condition field is used.
@@ -42,16 +52,12 @@ impl MutationOperator for IfElse {
         let cur_op = &source[start..end];
 
         // Change if/else expression to true/false.
-        let ops: Vec<String> = vec![
-            "true".to_owned(),
-            "false".to_owned(),
-            format!("!({})", cur_op),
-        ];
+        // The negation of the condition is inserted by the negation insertion operator.
+        let ops: Vec<String> = vec!["true".to_owned(), "false".to_owned()];
 
         ops.into_iter()
             .map(|op| {
-                let mut mutated_source = source.to_string();
-                mutated_source.replace_range(start..end, op.as_str());
+                let mutated_source = rewrite::replace(source, start, end, op.as_str());
                 MutantInfo::new(
                     mutated_source,
                     Mutation::new(
@@ -90,21 +96,17 @@ impl fmt::Display for IfElse {
 mod tests {
     use super::*;
     use codespan::Files;
-    use move_model::{
-        ast::{ExpData, Value},
-        model::Loc,
-    };
+    use move_model::model::Loc;
 
     #[test]
     fn test_apply_ifelse() {
         let mut files = Files::new();
         let fid = files.add("test", "test");
         let loc = Loc::new(fid, codespan::Span::new(4, 5));
-        let expr = ExpData::Value(move_model::model::NodeId::new(1), Value::Bool(true));
-        let exp = ExpLoc::new(expr.into_exp(), loc);
+        let exp = ExpLoc::new(loc);
         let operator = IfElse::new(exp.clone(), exp.clone(), exp);
         let source = "if (a) { }";
-        let expected = ["if (true) { }", "if (false) { }", "if (!(a)) { }"];
+        let expected = ["if (true) { }", "if (false) { }"];
         let result = operator.apply(source);
         assert_eq!(result.len(), expected.len());
         for (i, r) in result.iter().enumerate() {
@@ -117,8 +119,7 @@ mod tests {
         let mut files = Files::new();
         let fid = files.add("test", "test");
         let loc = Loc::new(fid, codespan::Span::new(0, 0));
-        let expr = ExpData::Value(move_model::model::NodeId::new(1), Value::Bool(true));
-        let exp = ExpLoc::new(expr.into_exp(), loc);
+        let exp = ExpLoc::new(loc);
         let operator = IfElse::new(exp.clone(), exp.clone(), exp);
         assert_eq!(operator.get_file_id(), fid);
     }
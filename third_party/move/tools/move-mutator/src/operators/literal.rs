@@ -3,16 +3,17 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::{
         MOVE_ADDR_MAX, MOVE_ADDR_ZERO, MOVE_FALSE, MOVE_MAX_INFERRED_NUM, MOVE_MAX_U256, MOVE_TRUE,
         MOVE_ZERO_U256,
     },
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
 use move_model::{
-    ast::Value,
+    ast::{Address, Value},
     model::Loc,
     ty::{PrimitiveType, Type},
 };
@@ -21,6 +22,15 @@ use std::{fmt, fmt::Debug};
 
 pub const OPERATOR_NAME: &str = "literal_replacement";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Value(Number | Bool | Address)"],
+    description: "Replaces the literal with the boundary values of its type and with the neighbouring values (`0`, maximum, value + 1 and value - 1 for numbers, `true` and `false` for booleans, `0x0` and the maximum address for addresses).",
+    before: "let fee = 5;",
+    after: "let fee = 6;",
+};
+
 /// Literal replacement mutation operator.
 /// Replaces literal statements with other ones but withing the same type.
 #[derive(Debug, Clone)]
@@ -42,6 +52,18 @@ impl Literal {
     }
 }
 
+impl Literal {
+    // Internal function checking if the literal is the given address (named addresses are already resolved).
+    fn is_address(&self, addr: &str) -> bool {
+        match &self.operation {
+            Value::Address(Address::Numerical(value)) => {
+                format!("0x{}", value.short_str_lossless()).eq_ignore_ascii_case(addr)
+            },
+            _ => false,
+        }
+    }
+}
+
 impl MutationOperator for Literal {
     fn apply(&self, source: &str) -> Vec<MutantInfo> {
         let start = self.loc.span().start().to_usize();
@@ -58,7 +80,18 @@ impl MutationOperator for Literal {
         // the number of mutants significantly.
         let ops: Vec<String> = match &self.optype {
             Type::Primitive(PrimitiveType::Address) => {
-                vec![MOVE_ADDR_ZERO.to_owned(), MOVE_ADDR_MAX.to_owned()]
+                // Address literals need the `@` prefix, unless it's placed outside the literal location.
+                // This also covers named addresses and constants, which are resolved to their values.
+                let prefix = if source[..start].ends_with('@') {
+                    ""
+                } else {
+                    "@"
+                };
+                [MOVE_ADDR_ZERO, MOVE_ADDR_MAX]
+                    .into_iter()
+                    .filter(|addr| !self.is_address(addr))
+                    .map(|addr| format!("{prefix}{addr}"))
+                    .collect()
             },
             Type::Primitive(PrimitiveType::Bool) => {
                 vec![MOVE_TRUE.to_owned(), MOVE_FALSE.to_owned()]
@@ -143,8 +176,7 @@ impl MutationOperator for Literal {
         ops.into_iter()
             .filter(|v| cur_op != *v)
             .map(|op| {
-                let mut mutated_source = source.to_string();
-                mutated_source.replace_range(start..end, op.as_str());
+                let mutated_source = rewrite::replace(source, start, end, op.as_str());
                 MutantInfo::new(
                     mutated_source,
                     Mutation::new(
@@ -184,6 +216,7 @@ impl fmt::Display for Literal {
 mod tests {
     use super::*;
     use codespan::Files;
+    use move_command_line_common::address::NumericalAddress;
 
     #[test]
     fn test_apply_u8() {
@@ -326,6 +359,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_apply_address() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let address = |s| {
+            Value::Address(Address::Numerical(
+                NumericalAddress::parse_str(s).unwrap().into_inner(),
+            ))
+        };
+
+        // Named addresses keep the `@` prefix and the replacement equal to the resolved value is skipped.
+        let loc = Loc::new(fid, codespan::Span::new(0, 12));
+        let operator = Literal::new(address("0x0"), Type::Primitive(PrimitiveType::Address), loc);
+        let result = operator.apply("@TestAccount");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, format!("@{MOVE_ADDR_MAX}"));
+
+        // The prefix is not duplicated if it's placed outside the literal location.
+        let loc = Loc::new(fid, codespan::Span::new(1, 4));
+        let operator = Literal::new(address("0x1"), Type::Primitive(PrimitiveType::Address), loc);
+        let result = operator.apply("@0x1");
+        assert_eq!(result[0].mutated_source, format!("@{MOVE_ADDR_ZERO}"));
+    }
+
     #[test]
     fn test_get_file_id() {
         let mut files = Files::new();
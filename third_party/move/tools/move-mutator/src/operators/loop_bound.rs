@@ -0,0 +1,249 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "loop_bound_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Loop (while)", "Loop (for)"],
+    description: "Shifts the bound of the `while` condition and of the `for` range by one and replaces the loop condition with an always false one, so the loop never runs.",
+    before: "while (i < n) { ... }",
+    after: "while (i < (n - 1)) { ... }",
+};
+
+/// Loop bound mutation operator.
+/// Introduces the off-by-one errors into the loop bounds and skips the loops entirely, to expose the
+/// loops without the loop invariants in the specifications.
+///
+/// The `for` loops are desugared into the `while` loops by the compiler (with the synthesized condition),
+/// so their ranges are located in the source of the loop header.
+#[derive(Debug, Clone)]
+pub struct LoopBound {
+    loc: Loc,
+    cond: Loc,
+    bound: Option<Loc>,
+}
+
+impl LoopBound {
+    /// Creates a new instance of the loop bound mutation operator.
+    ///
+    /// `cond` is the location of the loop condition and `bound` is the location of the bound the loop
+    /// variable is compared with (if the condition is a comparison).
+    #[must_use]
+    pub fn new(loc: Loc, cond: Loc, bound: Option<Loc>) -> Self {
+        Self { loc, cond, bound }
+    }
+
+    // Internal function creating the mutants of the `while` loop.
+    fn apply_while(&self, source: &str) -> Vec<MutantInfo> {
+        let (loop_start, loop_end) = span(&self.loc);
+        let (start, end) = span(&self.cond);
+        // The condition synthesized by the compiler isn't written in the source of the loop.
+        if start <= loop_start || end >= loop_end {
+            return vec![];
+        }
+
+        let mut mutants = vec![mutant(source, start, end, "false")];
+        if let Some(bound) = &self.bound {
+            let (start, end) = span(bound);
+            let bound = &source[start..end];
+            for op in ["+", "-"] {
+                mutants.push(mutant(source, start, end, &format!("({bound} {op} 1)")));
+            }
+        }
+        mutants
+    }
+
+    // Internal function creating the mutants of the range of the `for` loop.
+    fn apply_for(&self, source: &str) -> Vec<MutantInfo> {
+        let (loop_start, loop_end) = span(&self.loc);
+        let Some((lower, upper)) = for_range(&source[loop_start..loop_end]) else {
+            warn!("LoopBoundOperator: Cannot find the range of the loop at {loop_start}");
+            return vec![];
+        };
+        let (start, end) = (loop_start + lower.0, loop_start + upper.1);
+        let lower = &source[loop_start + lower.0..loop_start + lower.1];
+        let upper = &source[loop_start + upper.0..loop_start + upper.1];
+
+        [
+            format!("{lower}..{lower}"),
+            format!("({lower} + 1)..{upper}"),
+            format!("{lower}..({upper} + 1)"),
+            format!("{lower}..({upper} - 1)"),
+        ]
+        .iter()
+        .map(|range| mutant(source, start, end, range))
+        .collect()
+    }
+}
+
+impl MutationOperator for LoopBound {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let (start, end) = span(&self.loc);
+        let code = &source[start..end];
+        if code.starts_with("for") {
+            self.apply_for(source)
+        } else if code.starts_with("while") {
+            self.apply_while(source)
+        } else {
+            vec![]
+        }
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for LoopBound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "LoopBoundOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+// Internal function returning the start and the end of the location.
+fn span(loc: &Loc) -> (usize, usize) {
+    (loc.span().start().to_usize(), loc.span().end().to_usize())
+}
+
+// Internal function locating the lower and the upper bound of the range in the header of the `for` loop
+// (`for (i in lower..upper) body`). The bounds are unary expressions, so they end at the first whitespace
+// or the closing parenthesis outside of the nested parentheses.
+fn for_range(code: &str) -> Option<((usize, usize), (usize, usize))> {
+    let open = code.find('(')? + 1;
+    let iter_start = open + code[open..].find(|c: char| !c.is_whitespace())?;
+    let iter_end =
+        iter_start + code[iter_start..].find(|c: char| !c.is_alphanumeric() && c != '_')?;
+    let rest = code[iter_end..].trim_start();
+    if !rest.starts_with("in") || !rest[2..].starts_with(char::is_whitespace) {
+        return None;
+    }
+    let in_end = code.len() - rest.len() + 2;
+    let lower_start = in_end + code[in_end..].find(|c: char| !c.is_whitespace())?;
+
+    let mut depth = 0usize;
+    let mut lower_end = None;
+    let mut upper_start = None;
+    let mut chars = code[lower_start..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        let pos = lower_start + i;
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' if depth == 0 => return Some(((lower_start, lower_end?), (upper_start?, pos))),
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '.' if depth == 0 && lower_end.is_none() && code[pos..].starts_with("..") => {
+                lower_end = Some(lower_start + code[lower_start..pos].trim_end().len());
+                chars.next();
+                let rest = &code[pos + 2..];
+                upper_start = Some(pos + 2 + rest.find(|c: char| !c.is_whitespace())?);
+            },
+            c if c.is_whitespace() && depth == 0 && upper_start.is_some_and(|s| pos > s) => {
+                return Some(((lower_start, lower_end?), (upper_start?, pos)));
+            },
+            _ => {},
+        }
+    }
+    None
+}
+
+// Internal function creating the mutant replacing the given range of the source.
+fn mutant(source: &str, start: usize, end: usize, mutated: &str) -> MutantInfo {
+    let mutated_source = rewrite::replace(source, start, end, mutated);
+    MutantInfo::new(
+        mutated_source,
+        Mutation::new(
+            Range::new(start, end),
+            OPERATOR_NAME.to_string(),
+            source[start..end].to_string(),
+            mutated.to_string(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::loc;
+    use codespan::Files;
+
+    fn mutated(operator: &LoopBound, source: &str) -> Vec<String> {
+        operator
+            .apply(source)
+            .into_iter()
+            .map(|m| m.mutated_source)
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_while_loop() {
+        let mut files = Files::new();
+        let source = "while (i < n) { i = i + 1 }";
+        let fid = files.add("test", source);
+        let operator = LoopBound::new(
+            loc(fid, source, source),
+            loc(fid, source, "i < n"),
+            Some(loc(fid, source, "n")),
+        );
+        assert_eq!(mutated(&operator, source), [
+            "while (false) { i = i + 1 }",
+            "while (i < (n + 1)) { i = i + 1 }",
+            "while (i < (n - 1)) { i = i + 1 }",
+        ]);
+
+        // The condition covering the whole loop is synthesized by the compiler.
+        let operator = LoopBound::new(loc(fid, source, source), loc(fid, source, source), None);
+        assert!(operator.apply(source).is_empty());
+    }
+
+    #[test]
+    fn test_apply_for_loop() {
+        let mut files = Files::new();
+        let source = "for (i in 0..vector::length(&v)) { sum = sum + i }";
+        let fid = files.add("test", source);
+        let operator = LoopBound::new(loc(fid, source, source), loc(fid, source, source), None);
+        assert_eq!(mutated(&operator, source), [
+            "for (i in 0..0) { sum = sum + i }",
+            "for (i in (0 + 1)..vector::length(&v)) { sum = sum + i }",
+            "for (i in 0..(vector::length(&v) + 1)) { sum = sum + i }",
+            "for (i in 0..(vector::length(&v) - 1)) { sum = sum + i }",
+        ]);
+    }
+
+    #[test]
+    fn test_for_range_with_invariant() {
+        let code = "for (i in lo .. hi spec { invariant i <= hi; }) {}";
+        let ((lower_start, lower_end), (upper_start, upper_end)) = for_range(code).unwrap();
+        assert_eq!(&code[lower_start..lower_end], "lo");
+        assert_eq!(&code[upper_start..upper_end], "hi");
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = LoopBound::new(loc.clone(), loc, None);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::{fmt, fmt::Debug};
+
+pub const OPERATOR_NAME: &str = "match_arm_swap";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Match"],
+    description: "Swaps the bodies of the adjacent arms of the match expression, so the arm matching one variant computes the result of its sibling.",
+    before: "match (self) { Shape::Circle { size } => size * size * 3, Shape::Square { size } => size * size }",
+    after: "match (self) { Shape::Circle { size } => size * size, Shape::Square { size } => size * size * 3 }",
+};
+
+/// Single arm of the match expression.
+#[derive(Debug, Clone)]
+pub struct MatchArmLoc {
+    /// The location of the arm body.
+    pub body: Loc,
+    /// The name of the variant matched by the arm (if the arm matches a variant).
+    pub variant: Option<String>,
+}
+
+/// Match arm swap mutation operator.
+/// Swaps the bodies of the adjacent arms of the match expression, so the arm matching one variant
+/// computes the result of its sibling.
+#[derive(Debug, Clone)]
+pub struct MatchArmSwap {
+    loc: Loc,
+    arms: Vec<MatchArmLoc>,
+}
+
+impl MatchArmSwap {
+    /// Creates a new instance of the match arm swap mutation operator.
+    #[must_use]
+    pub fn new(loc: Loc, arms: Vec<MatchArmLoc>) -> Self {
+        Self { loc, arms }
+    }
+}
+
+impl MutationOperator for MatchArmSwap {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        self.arms
+            .windows(2)
+            .filter_map(|pair| {
+                let (first, second) = (&pair[0], &pair[1]);
+                let (start, first_end) = (
+                    first.body.span().start().to_usize(),
+                    first.body.span().end().to_usize(),
+                );
+                let (second_start, end) = (
+                    second.body.span().start().to_usize(),
+                    second.body.span().end().to_usize(),
+                );
+                let first_body = &source[start..first_end];
+                let second_body = &source[second_start..end];
+                // Overlapping bodies can't be swapped and swapping the same bodies doesn't change anything.
+                if first_end > second_start || first_body == second_body {
+                    return None;
+                }
+
+                let new_value = format!(
+                    "{second_body}{}{first_body}",
+                    &source[first_end..second_start]
+                );
+                let mutated_source = rewrite::replace(source, start, end, &new_value);
+
+                let mut mutation = Mutation::new(
+                    Range::new(start, end),
+                    OPERATOR_NAME.to_string(),
+                    source[start..end].to_string(),
+                    new_value,
+                );
+                if let (Some(from), Some(to)) = (&first.variant, &second.variant) {
+                    mutation = mutation.with_variant_change(from, to);
+                }
+                Some(MutantInfo::new(mutated_source, mutation))
+            })
+            .collect()
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for MatchArmSwap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MatchArmSwapOperator(arms: {}, location: file id: {:?}, index start: {}, index stop: {})",
+            self.arms.len(),
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    fn arm(fid: FileId, start: u32, end: u32, variant: &str) -> MatchArmLoc {
+        MatchArmLoc {
+            body: Loc::new(fid, codespan::Span::new(start, end)),
+            variant: Some(variant.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_apply_match_arm_swap() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let source = "match (c) { C::A => 1, C::B => 2, C::C => 2 }";
+        let loc = Loc::new(fid, codespan::Span::new(0, source.len() as u32));
+        let operator = MatchArmSwap::new(loc, vec![
+            arm(fid, 20, 21, "A"),
+            arm(fid, 31, 32, "B"),
+            arm(fid, 42, 43, "C"),
+        ]);
+
+        // Arms `B` and `C` have the same bodies, so only one mutant is generated.
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].mutated_source,
+            "match (c) { C::A => 2, C::B => 1, C::C => 2 }"
+        );
+
+        let change = result[0].mutation.get_variant_change().unwrap();
+        assert_eq!((change.from.as_str(), change.to.as_str()), ("A", "B"));
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = MatchArmSwap::new(loc, vec![]);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -0,0 +1,116 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::{fmt, fmt::Debug};
+
+pub const OPERATOR_NAME: &str = "match_guard_removal";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Match (arms with a guard)"],
+    description: "Drops the guard of the match arm, so the arm matches all values of its pattern.",
+    before: "Shape::Square { side } if (side > 0) => side * side,",
+    after: "Shape::Square { side } => side * side,",
+};
+
+/// Match guard removal mutation operator.
+/// Drops the guard of the match arm (e.g. `Shape::Square { side } if (side > 0) => ...`), so the arm
+/// matches all values of its pattern.
+#[derive(Debug, Clone)]
+pub struct MatchGuardRemoval {
+    pattern: Loc,
+    condition: Loc,
+}
+
+impl MatchGuardRemoval {
+    /// Creates a new instance of the match guard removal mutation operator.
+    #[must_use]
+    pub fn new(pattern: Loc, condition: Loc) -> Self {
+        Self { pattern, condition }
+    }
+}
+
+impl MutationOperator for MatchGuardRemoval {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        // The guard spans from the end of the pattern to the arrow following the condition.
+        let start = self.pattern.span().end().to_usize();
+        let condition_end = self.condition.span().end().to_usize();
+        let Some(end) = source[condition_end..]
+            .find("=>")
+            .map(|offset| condition_end + offset)
+        else {
+            return vec![];
+        };
+
+        let cur_op = &source[start..end];
+        let mutated_source = rewrite::replace(source, start, end, " ");
+        vec![MutantInfo::new(
+            mutated_source,
+            Mutation::new(
+                Range::new(start, end),
+                OPERATOR_NAME.to_string(),
+                cur_op.trim().to_string(),
+                String::new(),
+            ),
+        )]
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.pattern.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for MatchGuardRemoval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "MatchGuardRemovalOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.pattern.file_id(),
+            self.pattern.span().end(),
+            self.condition.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_match_guard_removal() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let source = "C::A { x } if (x > 0) => x,";
+        let pattern = Loc::new(fid, codespan::Span::new(0, 10));
+        let condition = Loc::new(fid, codespan::Span::new(15, 20));
+        let operator = MatchGuardRemoval::new(pattern, condition);
+
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "C::A { x } => x,");
+        assert_eq!(result[0].mutation.get_old_value(), "if (x > 0)");
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = MatchGuardRemoval::new(loc.clone(), loc);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -2,16 +2,56 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use move_model::{ast::Exp, model::Loc};
+use crate::operator::OperatorDescription;
+use move_model::model::Loc;
 
+pub(crate) mod address_swap;
+pub(crate) mod amount;
 pub(crate) mod binary;
 pub(crate) mod binary_swap;
+pub(crate) mod branch_swap;
 
 pub(crate) mod break_continue;
+pub(crate) mod cast;
 pub(crate) mod delete_stmt;
+pub(crate) mod event_removal;
 pub(crate) mod ifelse;
 pub(crate) mod literal;
+pub(crate) mod loop_bound;
+pub(crate) mod match_arm_swap;
+pub(crate) mod match_guard_removal;
+pub(crate) mod negation;
+pub(crate) mod reorder;
+pub(crate) mod signer_address;
+pub(crate) mod timestamp;
 pub(crate) mod unary;
+pub(crate) mod variant_replacement;
+pub(crate) mod visibility;
+
+/// Descriptions of all mutation operators (sorted by name).
+pub(crate) const OPERATORS: &[OperatorDescription] = &[
+    amount::DESCRIPTION,
+    binary::DESCRIPTION,
+    binary_swap::DESCRIPTION,
+    break_continue::DESCRIPTION,
+    cast::DESCRIPTION,
+    delete_stmt::DESCRIPTION,
+    variant_replacement::DESCRIPTION,
+    event_removal::DESCRIPTION,
+    branch_swap::DESCRIPTION,
+    ifelse::DESCRIPTION,
+    literal::DESCRIPTION,
+    loop_bound::DESCRIPTION,
+    match_arm_swap::DESCRIPTION,
+    match_guard_removal::DESCRIPTION,
+    address_swap::DESCRIPTION,
+    negation::DESCRIPTION,
+    reorder::DESCRIPTION,
+    signer_address::DESCRIPTION,
+    timestamp::DESCRIPTION,
+    unary::DESCRIPTION,
+    visibility::DESCRIPTION,
+];
 
 // Section with Move constants.
 pub(crate) const MOVE_EMPTY_STMT: &str = "{}";
@@ -28,16 +68,44 @@ pub(crate) const MOVE_ADDR_ZERO: &str = "0x0";
 pub(crate) const MOVE_ADDR_MAX: &str =
     "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF";
 
+/// Location of the operand expression of the mutated operation. Only the location is kept (not the model
+/// expression, which can't be sent between the threads), so the operators can be applied in parallel.
 #[derive(Debug, Clone)]
 pub struct ExpLoc {
-    pub exp: Exp,
     pub loc: Loc,
 }
 
 impl ExpLoc {
-    /// Creates a new expression with location.
+    /// Creates a new expression location.
     #[cfg(test)]
-    pub fn new(exp: Exp, loc: Loc) -> Self {
-        Self { exp, loc }
+    pub fn new(loc: Loc) -> Self {
+        Self { loc }
+    }
+}
+
+/// Returns the location of the first occurrence of `part` in the source, for the operator tests.
+#[cfg(test)]
+pub(crate) fn loc(fid: codespan::FileId, source: &str, part: &str) -> Loc {
+    let start = source.find(part).unwrap() as u32;
+    Loc::new(fid, codespan::Span::new(start, start + part.len() as u32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn operators_are_sorted_and_described() {
+        let names = OPERATORS.iter().map(|op| op.name).collect::<Vec<_>>();
+        let mut sorted = names.clone();
+        sorted.sort_unstable();
+        sorted.dedup();
+        assert_eq!(names, sorted);
+
+        for op in OPERATORS {
+            assert!(!op.targets.is_empty(), "{} has no targets", op.name);
+            assert!(!op.description.is_empty(), "{} has no description", op.name);
+            assert_ne!(op.before, op.after, "{} example changes nothing", op.name);
+        }
     }
 }
@@ -0,0 +1,111 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "negation_insertion";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["IfElse (condition)", "Call(And | Or) (operands)"],
+    description: "Inserts the logical negation into the boolean condition (the condition of the `if` expression, `while` loop or `assert!`, and the operands of `&&` and `||`).",
+    before: "if (is_frozen && amount > 0) abort 1;",
+    after: "if (!(is_frozen) && amount > 0) abort 1;",
+};
+
+/// Negation insertion mutation operator.
+/// Negates the boolean condition, so the mutants are reported separately from the mutants replacing the
+/// operators (the removal of the negation is covered by the unary operator replacement).
+#[derive(Debug, Clone)]
+pub struct NegationInsertion {
+    loc: Loc,
+}
+
+impl NegationInsertion {
+    /// Creates a new instance of the negation insertion operator for the boolean condition at the given
+    /// location.
+    #[must_use]
+    pub fn new(loc: Loc) -> Self {
+        Self { loc }
+    }
+}
+
+impl MutationOperator for NegationInsertion {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let start = self.loc.span().start().to_usize();
+        let end = self.loc.span().end().to_usize();
+        let cur_op = &source[start..end];
+        if cur_op.trim().is_empty() {
+            return vec![];
+        }
+
+        let op = format!("!({cur_op})");
+        let mutated_source = rewrite::replace(source, start, end, &op);
+        vec![MutantInfo::new(
+            mutated_source,
+            Mutation::new(
+                Range::new(start, end),
+                OPERATOR_NAME.to_string(),
+                cur_op.to_string(),
+                op,
+            ),
+        )]
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for NegationInsertion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "NegationInsertionOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_negation_insertion() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(4, 9));
+        let operator = NegationInsertion::new(loc);
+        let source = "if (a > b) { }";
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "if (!(a > b)) { }");
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = NegationInsertion::new(loc);
+        assert_eq!(operator.get_file_id(), fid);
+        assert!(operator.apply("").is_empty());
+    }
+}
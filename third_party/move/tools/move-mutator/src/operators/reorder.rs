@@ -0,0 +1,140 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "resource_operation_reorder";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Sequence (MoveTo | MoveFrom | BorrowGlobal | Exists)"],
+    description: "Swaps the adjacent statements operating on the global storage, if they don't share any local variable written by one of them.",
+    before: "assert!(!exists<Vault>(addr), 1); move_to(account, Vault { amount });",
+    after: "move_to(account, Vault { amount }); assert!(!exists<Vault>(addr), 1);",
+};
+
+/// Resource operation reorder mutation operator.
+/// Swaps the adjacent independent statements of the sequence operating on the global storage, to expose
+/// the specifications relying on the order of the operations without stating it. The statements are
+/// checked to be independent by the caller, so the swapped statements read the same local variables.
+#[derive(Debug, Clone)]
+pub struct ResourceOperationReorder {
+    first: Loc,
+    second: Loc,
+}
+
+impl ResourceOperationReorder {
+    /// Creates a new instance of the reorder mutation operator swapping the statements at the given
+    /// locations (the first one precedes the second one).
+    #[must_use]
+    pub fn new(first: Loc, second: Loc) -> Self {
+        Self { first, second }
+    }
+}
+
+impl MutationOperator for ResourceOperationReorder {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let (start, first_end) = (
+            self.first.span().start().to_usize(),
+            self.first.span().end().to_usize(),
+        );
+        let (second_start, end) = (
+            self.second.span().start().to_usize(),
+            self.second.span().end().to_usize(),
+        );
+        let first = &source[start..first_end];
+        let second = &source[second_start..end];
+        // Overlapping statements can't be swapped and swapping the same statements doesn't change anything.
+        if first_end > second_start
+            || first.trim().is_empty()
+            || second.trim().is_empty()
+            || first == second
+        {
+            return vec![];
+        }
+
+        let new_value = format!("{second}{}{first}", &source[first_end..second_start]);
+        let mutated_source = rewrite::replace(source, start, end, &new_value);
+        vec![MutantInfo::new(
+            mutated_source,
+            Mutation::new(
+                Range::new(start, end),
+                OPERATOR_NAME.to_string(),
+                source[start..end].to_string(),
+                new_value,
+            ),
+        )]
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.first.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for ResourceOperationReorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ResourceOperationReorderOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.first.file_id(),
+            self.first.span().start(),
+            self.second.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::loc;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_swaps_statements() {
+        let mut files = Files::new();
+        let source = "move_to(a, V { x });\n    move_to(a, R { x });";
+        let fid = files.add("test", source);
+        let operator = ResourceOperationReorder::new(
+            loc(fid, source, "move_to(a, V { x })"),
+            loc(fid, source, "move_to(a, R { x })"),
+        );
+        let result = operator.apply(source);
+        assert_eq!(result.len(), 1);
+        assert_eq!(
+            result[0].mutated_source,
+            "move_to(a, R { x });\n    move_to(a, V { x });"
+        );
+    }
+
+    #[test]
+    fn test_apply_skips_overlapping_statements() {
+        let mut files = Files::new();
+        let source = "move_to(a, V { x })";
+        let fid = files.add("test", source);
+        let operator =
+            ResourceOperationReorder::new(loc(fid, source, source), loc(fid, source, "V { x }"));
+        assert!(operator.apply(source).is_empty());
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = ResourceOperationReorder::new(loc.clone(), loc);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
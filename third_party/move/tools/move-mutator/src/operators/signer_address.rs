@@ -0,0 +1,150 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "signer_address_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(MoveFunction(0x1::signer::address_of))"],
+    description: "Replaces the address of the signer with the other addresses in scope (the address parameters of the function, the address constants of the module and the zero address) and with the addresses of the other signers.",
+    before: "assert!(signer::address_of(module_owner) == MODULE_OWNER, ENOT_MODULE_OWNER);",
+    after: "assert!(mint_addr == MODULE_OWNER, ENOT_MODULE_OWNER);",
+};
+
+/// Signer address mutation operator.
+/// Replaces the results of `signer::address_of` with the addresses the signer shouldn't be confused with,
+/// to check if the specifications of the access control notice that the wrong account is authorized.
+#[derive(Debug, Clone)]
+pub struct SignerAddress {
+    loc: Loc,
+    account: Loc,
+    addresses: Vec<String>,
+    signers: Vec<String>,
+}
+
+impl SignerAddress {
+    /// Creates a new instance of the signer address mutation operator.
+    ///
+    /// `account` is the location of the signer argument of the call, `addresses` are the expressions of the
+    /// other addresses in scope and `signers` are the expressions of the other signers (passed to the call
+    /// instead of the argument).
+    #[must_use]
+    pub fn new(loc: Loc, account: Loc, addresses: Vec<String>, signers: Vec<String>) -> Self {
+        Self {
+            loc,
+            account,
+            addresses,
+            signers,
+        }
+    }
+}
+
+impl MutationOperator for SignerAddress {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let start = self.loc.span().start().to_usize();
+        let end = self.loc.span().end().to_usize();
+        let account_start = self.account.span().start().to_usize();
+        let account_end = self.account.span().end().to_usize();
+        let cur_op = &source[start..end];
+        if account_start < start || account_end > end {
+            return vec![];
+        }
+
+        let other_signers = self.signers.iter().map(|signer| {
+            format!(
+                "{}{signer}{}",
+                &source[start..account_start],
+                &source[account_end..end]
+            )
+        });
+
+        self.addresses
+            .iter()
+            .cloned()
+            .chain(other_signers)
+            .map(|op| {
+                let mutated_source = rewrite::replace(source, start, end, &op);
+                MutantInfo::new(
+                    mutated_source,
+                    Mutation::new(
+                        Range::new(start, end),
+                        OPERATOR_NAME.to_string(),
+                        cur_op.to_string(),
+                        op,
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for SignerAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SignerAddressOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operators::loc;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_signer_address() {
+        let mut files = Files::new();
+        let source = "let addr = signer::address_of(owner);";
+        let fid = files.add("test", source);
+        let operator = SignerAddress::new(
+            loc(fid, source, "signer::address_of(owner)"),
+            loc(fid, source, "owner"),
+            vec!["to".to_owned(), "@0x0".to_owned()],
+            vec!["&other".to_owned()],
+        );
+
+        let result = operator
+            .apply(source)
+            .into_iter()
+            .map(|m| m.mutated_source)
+            .collect::<Vec<_>>();
+        assert_eq!(result, [
+            "let addr = to;",
+            "let addr = @0x0;",
+            "let addr = signer::address_of(&other);",
+        ]);
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = SignerAddress::new(loc.clone(), loc, vec![], vec![]);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
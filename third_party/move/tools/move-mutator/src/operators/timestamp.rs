@@ -0,0 +1,123 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "timestamp_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &[
+        "Call(MoveFunction(0x1::timestamp::now_seconds | 0x1::timestamp::now_microseconds))",
+    ],
+    description: "Replaces the current time with the earliest (`0`) and the latest (`u64::MAX`) possible time, probing the time-locked logic.",
+    before: "timestamp::now_seconds() >= lock.unlock_at",
+    after: "0 >= lock.unlock_at",
+};
+
+/// Full names of the functions returning the current time.
+pub const TIMESTAMP_FUNCTIONS: &[&str] = &[
+    "0x1::timestamp::now_seconds",
+    "0x1::timestamp::now_microseconds",
+];
+
+/// Timestamp mutation operator.
+/// Replaces the calls returning the current time (both return `u64`) with the boundary constants, so the
+/// specifications of the time-dependent code are checked at the edges of the time range.
+#[derive(Debug, Clone)]
+pub struct Timestamp {
+    loc: Loc,
+}
+
+impl Timestamp {
+    /// Creates a new instance of the timestamp mutation operator.
+    #[must_use]
+    pub fn new(loc: Loc) -> Self {
+        Self { loc }
+    }
+}
+
+impl MutationOperator for Timestamp {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let (start, end) = (
+            self.loc.span().start().to_usize(),
+            self.loc.span().end().to_usize(),
+        );
+        let cur_op = &source[start..end];
+
+        ["0".to_owned(), u64::MAX.to_string()]
+            .into_iter()
+            .map(|op| {
+                let mutated_source = rewrite::replace(source, start, end, &op);
+                MutantInfo::new(
+                    mutated_source,
+                    Mutation::new(
+                        Range::new(start, end),
+                        OPERATOR_NAME.to_string(),
+                        cur_op.to_string(),
+                        op,
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "TimestampOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_timestamp() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 24));
+        let operator = Timestamp::new(loc);
+        let source = "timestamp::now_seconds() >= t";
+        let result = operator
+            .apply(source)
+            .into_iter()
+            .map(|m| m.mutated_source)
+            .collect::<Vec<_>>();
+        assert_eq!(result, ["0 >= t", "18446744073709551615 >= t"]);
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = Timestamp::new(loc);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
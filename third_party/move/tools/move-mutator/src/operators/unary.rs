@@ -3,9 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    operator::{MutantInfo, MutationOperator},
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
     operators::ExpLoc,
     report::{Mutation, Range},
+    rewrite,
 };
 use codespan::FileId;
 use move_model::{ast::Operation, model::Loc};
@@ -13,6 +14,15 @@ use std::fmt;
 
 pub const OPERATOR_NAME: &str = "unary_operator_replacement";
 
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(Not)"],
+    description: "Removes the unary operator.",
+    before: "if (!is_frozen) transfer(amount);",
+    after: "if ( is_frozen) transfer(amount);",
+};
+
 /// Represents a unary operator mutation.
 #[derive(Debug, Clone)]
 pub struct Unary {
@@ -53,8 +63,7 @@ impl MutationOperator for Unary {
         vec![" "]
             .into_iter()
             .map(|op| {
-                let mut mutated_source = source.to_string();
-                mutated_source.replace_range(start..end, op);
+                let mutated_source = rewrite::replace(source, start, end, op);
                 MutantInfo::new(
                     mutated_source,
                     Mutation::new(
@@ -94,18 +103,13 @@ impl fmt::Display for Unary {
 mod tests {
     use super::*;
     use codespan::Files;
-    use move_model::{
-        ast::{ExpData, Value},
-        model::NodeId,
-    };
 
     #[test]
     fn test_apply_unary_operator() {
         let mut files = Files::new();
         let fid = files.add("test", "test");
         let loc = Loc::new(fid, codespan::Span::new(0, 1));
-        let e1 = ExpData::Value(NodeId::new(1), Value::Bool(true));
-        let exp = ExpLoc::new(e1.into_exp(), loc.clone());
+        let exp = ExpLoc::new(loc.clone());
 
         let operator = Unary::new(Operation::Not, loc, vec![exp]);
         let source = "!";
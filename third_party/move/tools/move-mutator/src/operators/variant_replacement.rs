@@ -0,0 +1,164 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::{fmt, fmt::Debug};
+
+pub const OPERATOR_NAME: &str = "enum_variant_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Call(Pack) of an enum variant"],
+    description: "Replaces the constructed variant of the enum with its sibling variants of compatible payload (the same field names and types).",
+    before: "Shape::Square { side }",
+    after: "Shape::Cube { side }",
+};
+
+/// Enum variant replacement mutation operator.
+/// Replaces the constructed variant of the Move 2 enum with its sibling variants of compatible payload
+/// (the same field names and types), e.g. `Shape::Square { side }` with `Shape::Cube { side }`.
+#[derive(Debug, Clone)]
+pub struct VariantReplacement {
+    loc: Loc,
+    variant: String,
+    siblings: Vec<String>,
+}
+
+impl VariantReplacement {
+    /// Creates a new instance of the variant replacement mutation operator.
+    #[must_use]
+    pub fn new(loc: Loc, variant: String, siblings: Vec<String>) -> Self {
+        Self {
+            loc,
+            variant,
+            siblings,
+        }
+    }
+}
+
+impl MutationOperator for VariantReplacement {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let start = self.loc.span().start().to_usize();
+        let end = self.loc.span().end().to_usize();
+        let cur_op = &source[start..end];
+
+        // The variant name is the last segment of the path, placed before the fields (if any).
+        let head_end = cur_op.find(['{', '(']).unwrap_or(cur_op.len());
+        let Some(offset) = find_identifier(&cur_op[..head_end], &self.variant) else {
+            return vec![];
+        };
+        let start = start + offset;
+        let end = start + self.variant.len();
+
+        self.siblings
+            .iter()
+            .map(|sibling| {
+                let mutated_source = rewrite::replace(source, start, end, sibling);
+                MutantInfo::new(
+                    mutated_source,
+                    Mutation::new(
+                        Range::new(start, end),
+                        OPERATOR_NAME.to_string(),
+                        self.variant.clone(),
+                        sibling.clone(),
+                    )
+                    .with_variant_change(&self.variant, sibling),
+                )
+            })
+            .collect()
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for VariantReplacement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "VariantReplacementOperator({}, location: file id: {:?}, index start: {}, index stop: {})",
+            self.variant,
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+// Internal function finding the offset of the last occurrence of the identifier (as a whole word) in the text.
+fn find_identifier(text: &str, ident: &str) -> Option<usize> {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    text.rmatch_indices(ident)
+        .find(|(offset, _)| {
+            let before = text[..*offset].chars().next_back();
+            let after = text[offset + ident.len()..].chars().next();
+            !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+        })
+        .map(|(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    #[test]
+    fn test_apply_variant_replacement() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(8, 38));
+        let operator = VariantReplacement::new(loc, "Square".to_owned(), vec![
+            "Cube".to_owned(),
+            "Circle".to_owned(),
+        ]);
+        let source = "let s = Shape::Square { side: Square };";
+        let expected = [
+            "let s = Shape::Cube { side: Square };",
+            "let s = Shape::Circle { side: Square };",
+        ];
+        let result = operator.apply(source);
+        assert_eq!(result.len(), expected.len());
+        for (i, r) in result.iter().enumerate() {
+            assert_eq!(r.mutated_source, expected[i]);
+        }
+
+        let change = result[0].mutation.get_variant_change().unwrap();
+        assert_eq!(
+            (change.from.as_str(), change.to.as_str()),
+            ("Square", "Cube")
+        );
+    }
+
+    #[test]
+    fn test_apply_unit_variant_replacement() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 10));
+        let operator = VariantReplacement::new(loc, "Red".to_owned(), vec!["Blue".to_owned()]);
+        let result = operator.apply("Color::Red");
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].mutated_source, "Color::Blue");
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = VariantReplacement::new(loc, "Red".to_owned(), vec![]);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
@@ -0,0 +1,211 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    operator::{MutantInfo, MutationOperator, OperatorDescription},
+    report::{Mutation, Range},
+    rewrite,
+};
+use codespan::FileId;
+use move_model::model::Loc;
+use std::fmt;
+
+pub const OPERATOR_NAME: &str = "visibility_replacement";
+
+/// Description of the operator (see `OperatorDescription`).
+pub const DESCRIPTION: OperatorDescription = OperatorDescription {
+    name: OPERATOR_NAME,
+    targets: &["Function (public | public(friend) | public(package) visibility)"],
+    description: "Lowers the visibility of the function (`public` to `public(friend)` and to private, `public(friend)` to private) if the callers of the function still can call it, to check if the specifications or the tests use the externally visible API.",
+    before: "public fun balance_of(owner: address): u64 {",
+    after: "public(friend) fun balance_of(owner: address): u64 {",
+};
+
+/// Visibility mutation operator.
+/// Replaces the visibility modifier of the function with the lower visibilities (the empty replacement
+/// makes the function private).
+#[derive(Debug, Clone)]
+pub struct VisibilityReplacement {
+    loc: Loc,
+    visibilities: Vec<String>,
+}
+
+impl VisibilityReplacement {
+    /// Creates a new instance of the visibility mutation operator.
+    ///
+    /// `loc` is the location of the function header before the function name (the modifiers and the `fun`
+    /// keyword) and `visibilities` are the modifiers replacing the current one (the empty modifier makes the
+    /// function private). The callers of the function must be allowed to call it with each of them.
+    #[must_use]
+    pub fn new(loc: Loc, visibilities: Vec<String>) -> Self {
+        Self { loc, visibilities }
+    }
+}
+
+impl MutationOperator for VisibilityReplacement {
+    fn apply(&self, source: &str) -> Vec<MutantInfo> {
+        let start = self.loc.span().start().to_usize();
+        let end = self.loc.span().end().to_usize();
+        let Some((start, end)) = find_modifier(source, start, end) else {
+            return vec![];
+        };
+        let cur_op = &source[start..end];
+
+        // Removing the modifier removes the whitespace after it too (`fun` is always after it).
+        let modifier_end = end;
+        let end = end + source[end..].len() - source[end..].trim_start().len();
+
+        self.visibilities
+            .iter()
+            .filter(|op| op.as_str() != cur_op)
+            .map(|op| {
+                let (end, new_op) = if op.is_empty() {
+                    (end, String::new())
+                } else {
+                    (modifier_end, op.clone())
+                };
+                let mutated_source = rewrite::replace(source, start, end, &new_op);
+                MutantInfo::new(
+                    mutated_source,
+                    Mutation::new(
+                        Range::new(start, end),
+                        OPERATOR_NAME.to_string(),
+                        source[start..end].to_string(),
+                        new_op,
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn get_file_id(&self) -> FileId {
+        self.loc.file_id()
+    }
+
+    fn name(&self) -> String {
+        OPERATOR_NAME.to_string()
+    }
+}
+
+impl fmt::Display for VisibilityReplacement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "VisibilityReplacementOperator(location: file id: {:?}, index start: {}, index stop: {})",
+            self.loc.file_id(),
+            self.loc.span().start(),
+            self.loc.span().end()
+        )
+    }
+}
+
+// Internal function returning the span of the visibility modifier (`public`, `public(friend)`,
+// `public(package)`, `friend` or `package`) in the `start..end` span of the function header. The words in the
+// comments and the attributes before the modifiers are skipped, the header ends with the `fun` keyword.
+fn find_modifier(source: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let header = source.get(start..end)?;
+    let comments = rewrite::comment_and_string_spans(source);
+    let is_word_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let mut offset = 0;
+    while offset < header.len() {
+        let rest = &header[offset..];
+        let Some(word_start) = rest.find(is_word_char) else {
+            break;
+        };
+        let word_start = offset + word_start;
+        let word_end = header[word_start..]
+            .find(|c| !is_word_char(c))
+            .map_or(header.len(), |len| word_start + len);
+        offset = word_end;
+
+        let absolute = start + word_start;
+        if comments
+            .iter()
+            .any(|(from, to)| *from <= absolute && absolute < *to)
+        {
+            continue;
+        }
+
+        match &header[word_start..word_end] {
+            "public" => {
+                // The scope of the visibility (e.g. `public(friend)`) belongs to the modifier.
+                let after = header[word_end..].trim_start();
+                let scope_end = after.starts_with('(').then(|| after.find(')')).flatten();
+                let modifier_end =
+                    scope_end.map_or(word_end, |close| header.len() - after.len() + close + 1);
+                return Some((absolute, start + modifier_end));
+            },
+            "friend" | "package" => return Some((absolute, start + word_end)),
+            "fun" => break,
+            _ => {},
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan::Files;
+
+    fn apply(source: &str, header: &str, visibilities: &[&str]) -> Vec<String> {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let start = source.find(header).unwrap();
+        let loc = Loc::new(
+            fid,
+            codespan::Span::new(start as u32, (start + header.len()) as u32),
+        );
+        let operator =
+            VisibilityReplacement::new(loc, visibilities.iter().map(ToString::to_string).collect());
+        operator
+            .apply(source)
+            .into_iter()
+            .map(|info| info.mutated_source)
+            .collect()
+    }
+
+    #[test]
+    fn test_apply_public() {
+        let source = "module 0x1::m { public fun f() {} }";
+        let result = apply(source, "public fun", &["public(friend)", ""]);
+        assert_eq!(result, [
+            "module 0x1::m { public(friend) fun f() {} }",
+            "module 0x1::m { fun f() {} }",
+        ]);
+    }
+
+    #[test]
+    fn test_apply_scoped_and_entry() {
+        let source = "module 0x1::m { public ( friend ) entry fun f() {} }";
+        let result = apply(source, "public ( friend ) entry fun", &[""]);
+        assert_eq!(result, ["module 0x1::m { entry fun f() {} }"]);
+
+        let source = "module 0x1::m { package fun f() {} }";
+        let result = apply(source, "package fun", &[""]);
+        assert_eq!(result, ["module 0x1::m { fun f() {} }"]);
+    }
+
+    #[test]
+    fn test_apply_skips_attributes_and_comments() {
+        let source = "module 0x1::m { #[view] /* public */ public fun f() {} }";
+        let result = apply(source, "#[view] /* public */ public fun", &[""]);
+        assert_eq!(result, [
+            "module 0x1::m { #[view] /* public */ fun f() {} }"
+        ]);
+
+        let source = "module 0x1::m { inline fun f() {} }";
+        assert!(apply(source, "inline fun", &[""]).is_empty());
+    }
+
+    #[test]
+    fn test_get_file_id() {
+        let mut files = Files::new();
+        let fid = files.add("test", "test");
+        let loc = Loc::new(fid, codespan::Span::new(0, 0));
+        let operator = VisibilityReplacement::new(loc, vec![]);
+        assert_eq!(operator.get_file_id(), fid);
+    }
+}
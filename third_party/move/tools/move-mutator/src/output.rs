@@ -2,7 +2,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{cli, configuration::Configuration};
+use crate::{cli, compiler, configuration::Configuration};
 use move_package::source_package::layout::SourcePackageLayout;
 use std::{
     ffi::OsString,
@@ -10,6 +10,10 @@ use std::{
     path::{Path, PathBuf},
 };
 
+/// Name of the directory (inside the output directory) with the packages of the mutants (the `emit_packages`
+/// option).
+pub(crate) const PACKAGES_DIR: &str = "packages";
+
 /// Sets up the path for the mutant.
 ///
 /// It creates the directory structure for the mutant and returns the path to the mutant.
@@ -19,13 +23,16 @@ use std::{
 /// Example:
 /// The file to be mutated is located in "/a/b/c/sources/X/Y/file.move" (`file_path`).
 /// This function constructs the following output path for file.move:
-/// "`output_dir/X/Y/file_index.move`"
+/// "`output_dir/X/Y/file_name.move`"
 /// It finds the package root for the file, which is "/a/b/c", then it append the relative path to the output directory.
 ///
 /// If the file is not inside any package, it creates the directory structure in the output directory like:
 /// The file to be mutated is located in "/a/b/c/file.move" (`file_path`).
 /// This function constructs the following output path for file.move:
-/// "`output_dir/file_index.move`"
+/// "`output_dir/file_name.move`"
+///
+/// The `name` should be derived from the mutant itself (e.g. its operator, line and ID), so the file names
+/// don't depend on the order in which mutants are saved. If the file already exists, an index is appended.
 ///
 /// Paths can be absolute or relative - it doesn't matter. The function will handle it by
 /// canonicalizing the path. The returned path is always relative to the package directory root.
@@ -34,33 +41,19 @@ use std::{
 ///
 /// * `output_dir` - The directory where the mutant will be output.
 /// * `filename` - The path to the original file.
-/// * `index` - The index of the mutant.
+/// * `name` - The name of the mutant appended to the original file name.
 ///
 /// # Returns
 ///
 /// * `PathBuf` - The path to the mutant.
-pub(crate) fn setup_mutant_path(output_dir: &Path, file_path: &Path) -> anyhow::Result<PathBuf> {
+pub(crate) fn setup_mutant_path(
+    output_dir: &Path,
+    file_path: &Path,
+    name: &str,
+) -> anyhow::Result<PathBuf> {
     trace!("Trying to set up mutant path for {file_path:?}");
 
-    let file_path_canonicalized = file_path.canonicalize()?;
-
-    // Try to find package root for the file. If the file is not inside any package, assume that it is a single file.
-    let root = SourcePackageLayout::try_find_root(&file_path_canonicalized);
-    let root_path = if root.is_err() {
-        debug!("No package root for {file_path_canonicalized:?}. Assuming mutating a single file.");
-        file_path_canonicalized.clone()
-    } else {
-        // In case of file is inside the package it must follow the Move structure. So we can assume that
-        // there will be a sources directory inside the package root. We can omit it.
-        root?.join("sources")
-    };
-
-    // Stripping whole prefix before file to get it relative path inside the package.
-    let relative_path = file_path_canonicalized.strip_prefix(&root_path)?;
-
-    // Construct the directory structure for that specified file in the output directory. If file was inside the package,
-    // parent() will return its relative folder path inside the package. If file was outside any package, parent() will return None.
-    let output_struct = output_dir.join(relative_path.parent().unwrap_or(Path::new("")));
+    let output_struct = flat_layout_dir(output_dir, file_path)?;
 
     // Create the directory structure for that specified file in the output directory. Ignore errors if the directory already exists.
     if let Err(e) = fs::create_dir_all(&output_struct) {
@@ -79,7 +72,11 @@ pub(crate) fn setup_mutant_path(output_dir: &Path, file_path: &Path) -> anyhow::
     let filename = filename.to_os_string();
     for i in 0u32..u32::MAX {
         let mut mutant_path = filename.clone();
-        mutant_path.push(OsString::from(format!("_mut{i}.move")));
+        if i == 0 {
+            mutant_path.push(OsString::from(format!("_{name}.move")));
+        } else {
+            mutant_path.push(OsString::from(format!("_{name}_{i}.move")));
+        }
 
         let mutant_path = output_struct.join(mutant_path);
         if !mutant_path.exists() {
@@ -93,6 +90,336 @@ pub(crate) fn setup_mutant_path(output_dir: &Path, file_path: &Path) -> anyhow::
     ))
 }
 
+/// Returns the directory of the mutants of the given file in the `flat` output layout (the output directory
+/// with the relative path of the file inside the `sources` directory of its package).
+pub(crate) fn flat_layout_dir(output_dir: &Path, file_path: &Path) -> anyhow::Result<PathBuf> {
+    let relative_path = relative_source_path(file_path)?;
+
+    // Construct the directory structure for that specified file in the output directory. If file was inside the package,
+    // parent() will return its relative folder path inside the package. If file was outside any package, parent() will return None.
+    Ok(output_dir.join(relative_path.parent().unwrap_or(Path::new(""))))
+}
+
+/// Values of the placeholders of the mutant name template (the `name_template` option).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct NameFields<'a> {
+    /// Name of the original file (without the extension).
+    pub file: &'a str,
+    /// Name of the mutated module.
+    pub module: &'a str,
+    /// Name of the mutated function (empty if the mutant is outside of any function).
+    pub function: &'a str,
+    /// Name of the mutation operator.
+    pub operator: &'a str,
+    /// Line of the mutation.
+    pub line: usize,
+    /// ID of the mutant.
+    pub id: &'a str,
+}
+
+/// Renders the mutant name template, substituting the `{file}`, `{module}`, `{function}`, `{operator}`,
+/// `{line}`, `{id}` and `{index}` placeholders.
+///
+/// # Arguments
+///
+/// * `template` - The mutant name template (e.g. `{file}_{operator}_{line}_{index}.move`).
+/// * `fields` - The values of the placeholders describing the mutant.
+/// * `index` - The index distinguishing the mutants which would get the same name otherwise.
+///
+/// # Returns
+///
+/// * `anyhow::Result<String>` - The file name of the mutant or an error if the template is invalid.
+pub(crate) fn render_name_template(
+    template: &str,
+    fields: &NameFields<'_>,
+    index: u32,
+) -> anyhow::Result<String> {
+    let mut name = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        name.push_str(&rest[..start]);
+        let end = rest[start..].find('}').ok_or_else(|| {
+            anyhow::anyhow!("Unclosed placeholder in the mutant name template {template}")
+        })? + start;
+
+        match &rest[start + 1..end] {
+            "file" => name.push_str(fields.file),
+            "module" => name.push_str(fields.module),
+            "function" => name.push_str(fields.function),
+            "operator" => name.push_str(fields.operator),
+            "line" => name.push_str(&fields.line.to_string()),
+            "id" => name.push_str(fields.id),
+            "index" => name.push_str(&index.to_string()),
+            placeholder => anyhow::bail!(
+                "Unknown placeholder {{{placeholder}}} in the mutant name template {template}"
+            ),
+        }
+        rest = &rest[end + 1..];
+    }
+    name.push_str(rest);
+    Ok(name)
+}
+
+/// Checks that the mutant name template is valid: all placeholders are known and the rendered name is a
+/// Move file name (not a path).
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub(crate) fn check_name_template(template: &str) -> anyhow::Result<()> {
+    let fields = NameFields {
+        file: "file",
+        module: "module",
+        function: "function",
+        operator: "operator",
+        line: 1,
+        id: "id",
+    };
+    let name = render_name_template(template, &fields, 0)?;
+    if !name.ends_with(".move") || name == ".move" {
+        anyhow::bail!("Mutant name template {template} must produce a `.move` file name");
+    }
+    if name.contains(['/', '\\']) {
+        anyhow::bail!("Mutant name template {template} can't contain path separators");
+    }
+    Ok(())
+}
+
+/// Sets up the path for the mutant named after the mutant name template.
+///
+/// The `{index}` placeholder is 0 for the first mutant with the given name and it's increased until the
+/// file name is unique. If the template doesn't contain the `{index}` placeholder and the file already
+/// exists, the index is appended to the name (as for the default names).
+///
+/// # Arguments
+///
+/// * `dir` - The directory where the mutant will be output (see `flat_layout_dir` and `module_layout_dir`).
+/// * `template` - The mutant name template.
+/// * `fields` - The values of the placeholders describing the mutant.
+///
+/// # Returns
+///
+/// * `PathBuf` - The path to the mutant.
+pub(crate) fn setup_templated_mutant_path(
+    dir: &Path,
+    template: &str,
+    fields: &NameFields<'_>,
+) -> anyhow::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let indexed = template.contains("{index}");
+    for i in 0u32..u32::MAX {
+        let name = render_name_template(template, fields, i)?;
+        let mutant_path = if indexed || i == 0 {
+            dir.join(name)
+        } else {
+            let stem = name.strip_suffix(".move").unwrap_or(&name);
+            dir.join(format!("{stem}_{i}.move"))
+        };
+
+        if !mutant_path.exists() {
+            return Ok(mutant_path);
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "There is more than {} mutants in {dir:?}",
+        u32::MAX
+    ))
+}
+
+/// Sets up the path for the mutant in the `module` output layout.
+///
+/// The mutant is stored in the subdirectory of its module and function and named after its ID:
+/// "`output_dir/module/function/mutant_id.move`". Mutants outside of any function are stored directly
+/// in the module directory. If the file already exists, an index is appended.
+///
+/// # Arguments
+///
+/// * `output_dir` - The directory where the mutant will be output.
+/// * `module_name` - The name of the mutated module.
+/// * `function_name` - The name of the mutated function (empty if the mutant is outside of any function).
+/// * `id` - The ID of the mutant.
+///
+/// # Returns
+///
+/// * `PathBuf` - The path to the mutant.
+pub(crate) fn setup_module_layout_path(
+    output_dir: &Path,
+    module_name: &str,
+    function_name: &str,
+    id: &str,
+) -> anyhow::Result<PathBuf> {
+    let dir = module_layout_dir(output_dir, module_name, function_name);
+    fs::create_dir_all(&dir)?;
+
+    for i in 0u32..u32::MAX {
+        let mutant_path = if i == 0 {
+            dir.join(format!("{id}.move"))
+        } else {
+            dir.join(format!("{id}_{i}.move"))
+        };
+
+        if !mutant_path.exists() {
+            return Ok(mutant_path);
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "There is more than {} mutants in {dir:?}",
+        u32::MAX
+    ))
+}
+
+/// Returns the directory of the mutants of the given function in the `module` output layout
+/// ("`output_dir/module/function`", or "`output_dir/module`" if the function name is empty).
+pub(crate) fn module_layout_dir(
+    output_dir: &Path,
+    module_name: &str,
+    function_name: &str,
+) -> PathBuf {
+    let dir = output_dir.join(module_name);
+    if function_name.is_empty() {
+        dir
+    } else {
+        dir.join(function_name)
+    }
+}
+
+/// Sets up the directory for the mutant spanning multiple files.
+///
+/// The directory is created in the output directory and named after the mutant (an index is appended if
+/// it already exists). The mutated files are stored inside it at their original paths relative to the
+/// `sources` directory of the package (see `setup_mutant_dir_file`).
+///
+/// # Arguments
+///
+/// * `output_dir` - The directory where the mutant will be output.
+/// * `name` - The name of the mutant directory.
+///
+/// # Returns
+///
+/// * `PathBuf` - The path to the mutant directory.
+pub(crate) fn setup_mutant_dir(output_dir: &Path, name: &str) -> anyhow::Result<PathBuf> {
+    for i in 0u32..u32::MAX {
+        let mutant_dir = if i == 0 {
+            output_dir.join(name)
+        } else {
+            output_dir.join(format!("{name}_{i}"))
+        };
+
+        if !mutant_dir.exists() {
+            fs::create_dir_all(&mutant_dir)?;
+            return Ok(mutant_dir);
+        }
+    }
+
+    Err(anyhow::anyhow!(
+        "There is more than {} mutants in {output_dir:?}",
+        u32::MAX
+    ))
+}
+
+/// Sets up the path of the mutated file inside the directory of the mutant spanning multiple files.
+/// The file keeps its name and its relative path inside the package (without the `sources` directory),
+/// and the parent directories are created.
+///
+/// # Arguments
+///
+/// * `mutant_dir` - The directory of the mutant.
+/// * `file_path` - The path to the original file.
+///
+/// # Returns
+///
+/// * `PathBuf` - The path to the mutated file.
+pub(crate) fn setup_mutant_dir_file(
+    mutant_dir: &Path,
+    file_path: &Path,
+) -> anyhow::Result<PathBuf> {
+    let relative_path = relative_source_path(file_path)?;
+    let mutant_path = if relative_path.as_os_str().is_empty() {
+        let filename = file_path
+            .file_name()
+            .ok_or(anyhow::anyhow!("Cannot get file name of {file_path:?}"))?;
+        mutant_dir.join(filename)
+    } else {
+        mutant_dir.join(relative_path)
+    };
+    if let Some(parent) = mutant_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    Ok(mutant_path)
+}
+
+/// Emits the self-contained package of the mutant: the package is copied to the
+/// "`output_dir/packages/mutant_id`" directory (without the `build` directory and the output directory,
+/// if it's inside the package), the mutated files are substituted and the local dependencies in the
+/// manifest are rewritten to absolute paths, so the package can be built from anywhere.
+///
+/// # Arguments
+///
+/// * `output_dir` - The directory where the mutants are output.
+/// * `package_path` - The path to the root of the mutated package.
+/// * `id` - The ID of the mutant.
+/// * `files` - The original files changed by the mutant with their mutated sources.
+///
+/// # Returns
+///
+/// * `PathBuf` - The path to the package of the mutant.
+pub(crate) fn emit_mutant_package(
+    output_dir: &Path,
+    package_path: &Path,
+    id: &str,
+    files: &[(&Path, &str)],
+) -> anyhow::Result<PathBuf> {
+    let package_path = package_path.canonicalize()?;
+    // The output directory is not copied into the packages of the mutants if it's inside the package.
+    let ignored = output_dir
+        .canonicalize()?
+        .strip_prefix(&package_path)
+        .map(|relative| vec![glob::Pattern::escape(&relative.to_string_lossy())])
+        .unwrap_or_default();
+
+    let package_dir = setup_mutant_dir(&output_dir.join(PACKAGES_DIR), id)?;
+    compiler::copy_package(
+        &package_path,
+        &package_dir,
+        &compiler::IgnoreRules::new(&ignored)?,
+    )?;
+
+    for (file, source) in files {
+        let file = file.canonicalize()?;
+        let relative_path = file.strip_prefix(&package_path).map_err(|_| {
+            anyhow::anyhow!("Mutated file {file:?} is not inside the package {package_path:?}")
+        })?;
+        fs::write(package_dir.join(relative_path), source)?;
+    }
+
+    compiler::rewrite_manifest_for_mutant(&package_path, &package_dir)?;
+    Ok(package_dir)
+}
+
+// Internal function returning the path of the file relative to the `sources` directory of its package (empty
+// if the file is not inside any package).
+fn relative_source_path(file_path: &Path) -> anyhow::Result<PathBuf> {
+    let file_path_canonicalized = file_path.canonicalize()?;
+
+    // Try to find package root for the file. If the file is not inside any package, assume that it is a single file.
+    let root = SourcePackageLayout::try_find_root(&file_path_canonicalized);
+    let root_path = if root.is_err() {
+        debug!("No package root for {file_path_canonicalized:?}. Assuming mutating a single file.");
+        file_path_canonicalized.clone()
+    } else {
+        // In case of file is inside the package it must follow the Move structure. So we can assume that
+        // there will be a sources directory inside the package root. We can omit it.
+        root?.join("sources")
+    };
+
+    // Stripping whole prefix before file to get it relative path inside the package.
+    let relative_path = file_path_canonicalized.strip_prefix(&root_path)?;
+    Ok(relative_path.to_path_buf())
+}
+
 /// Sets up the output directory for the mutants.
 ///
 /// # Arguments
@@ -141,13 +468,13 @@ mod tests {
         let output_dir = Path::new("mutants_output");
         let filename = Path::new("💖");
         fs::File::create(filename).unwrap();
-        let result = setup_mutant_path(output_dir, filename);
+        let result = setup_mutant_path(output_dir, filename, "op_1_abcd");
         fs::remove_file(filename).unwrap();
         fs::remove_dir_all(output_dir).unwrap();
         assert!(result.is_ok());
         assert_eq!(
             result.unwrap(),
-            PathBuf::from("mutants_output/💖_mut0.move")
+            PathBuf::from("mutants_output/💖_op_1_abcd.move")
         );
     }
 
@@ -156,13 +483,13 @@ mod tests {
         let output_dir = Path::new("mutants_output_no_extension");
         let filename = Path::new("file1");
         fs::File::create(filename).unwrap();
-        let result = setup_mutant_path(output_dir, filename);
+        let result = setup_mutant_path(output_dir, filename, "op_1_abcd");
         fs::remove_file(filename).unwrap();
         fs::remove_dir_all(output_dir).unwrap();
         assert!(result.is_ok());
         assert_eq!(
             result.unwrap(),
-            PathBuf::from("mutants_output_no_extension/file1_mut0.move")
+            PathBuf::from("mutants_output_no_extension/file1_op_1_abcd.move")
         );
     }
 
@@ -171,13 +498,13 @@ mod tests {
         let output_dir = Path::new("mutants_output_correct");
         let filename = Path::new("test_correct");
         fs::File::create(filename).unwrap();
-        let result = setup_mutant_path(output_dir, filename);
+        let result = setup_mutant_path(output_dir, filename, "op_1_abcd");
         fs::remove_file(filename).unwrap();
         fs::remove_dir_all(output_dir).unwrap();
         assert!(result.is_ok());
         assert_eq!(
             result.unwrap(),
-            PathBuf::from("mutants_output_correct/test_correct_mut0.move")
+            PathBuf::from("mutants_output_correct/test_correct_op_1_abcd.move")
         );
     }
 
@@ -186,20 +513,70 @@ mod tests {
         let output_dir = Path::new("");
         let filename = Path::new("test_empty");
         fs::File::create(filename).unwrap();
-        let result = setup_mutant_path(output_dir, filename);
+        let result = setup_mutant_path(output_dir, filename, "op_1_abcd");
         fs::remove_file(filename).unwrap();
         assert!(result.is_ok());
-        assert_eq!(result.unwrap(), PathBuf::from("test_empty_mut0.move"));
+        assert_eq!(result.unwrap(), PathBuf::from("test_empty_op_1_abcd.move"));
+    }
+
+    #[test]
+    fn setup_mutant_path_appends_index_if_file_exists() {
+        let output_dir = tempdir().unwrap();
+        let filename = output_dir.path().join("test_exists.move");
+        fs::File::create(&filename).unwrap();
+        let first = setup_mutant_path(output_dir.path(), &filename, "op_1_abcd").unwrap();
+        fs::File::create(&first).unwrap();
+        let second = setup_mutant_path(output_dir.path(), &filename, "op_1_abcd").unwrap();
+        assert_eq!(first, output_dir.path().join("test_exists_op_1_abcd.move"));
+        assert_eq!(
+            second,
+            output_dir.path().join("test_exists_op_1_abcd_1.move")
+        );
     }
 
     #[test]
     fn setup_mutant_path_handles_empty_filename() {
         let output_dir = Path::new("mutants_output_empty_filename");
         let filename = Path::new("");
-        let result = setup_mutant_path(output_dir, filename);
+        let result = setup_mutant_path(output_dir, filename, "op_1_abcd");
         assert!(result.is_err());
     }
 
+    #[test]
+    fn setup_module_layout_path_uses_module_and_function_dirs() {
+        let output_dir = tempdir().unwrap();
+        let first = setup_module_layout_path(output_dir.path(), "Sum", "sum", "abcd").unwrap();
+        assert_eq!(first, output_dir.path().join("Sum/sum/abcd.move"));
+        assert!(output_dir.path().join("Sum/sum").is_dir());
+
+        fs::File::create(&first).unwrap();
+        let second = setup_module_layout_path(output_dir.path(), "Sum", "sum", "abcd").unwrap();
+        assert_eq!(second, output_dir.path().join("Sum/sum/abcd_1.move"));
+
+        let no_function = setup_module_layout_path(output_dir.path(), "Sum", "", "abcd").unwrap();
+        assert_eq!(no_function, output_dir.path().join("Sum/abcd.move"));
+    }
+
+    #[test]
+    fn setup_mutant_dir_keeps_file_names() {
+        let output_dir = tempdir().unwrap();
+        let package = output_dir.path().join("package");
+        fs::create_dir_all(package.join("sources/nested")).unwrap();
+        fs::write(package.join("Move.toml"), "[package]\nname = \"package\"\n").unwrap();
+        let filename = package.join("sources/nested/Sum.move");
+        fs::File::create(&filename).unwrap();
+
+        let first = setup_mutant_dir(output_dir.path(), "multi_file_abcd").unwrap();
+        let second = setup_mutant_dir(output_dir.path(), "multi_file_abcd").unwrap();
+        assert_eq!(first, output_dir.path().join("multi_file_abcd"));
+        assert_eq!(second, output_dir.path().join("multi_file_abcd_1"));
+        assert!(first.is_dir());
+
+        let mutant_path = setup_mutant_dir_file(&first, &filename).unwrap();
+        assert_eq!(mutant_path, first.join("nested/Sum.move"));
+        assert!(first.join("nested").is_dir());
+    }
+
     #[test]
     fn setup_output_dir_creates_directory_if_not_exists() {
         let temp_dir = tempdir().unwrap();
@@ -242,4 +619,57 @@ mod tests {
         let config = Configuration::new(options, None);
         assert!(setup_output_dir(&config).is_err());
     }
+
+    fn name_fields() -> NameFields<'static> {
+        NameFields {
+            file: "Sum",
+            module: "Sum",
+            function: "sum",
+            operator: "binary_operator_replacement",
+            line: 5,
+            id: "abcd",
+        }
+    }
+
+    #[test]
+    fn name_template_placeholders_are_substituted() {
+        let name = render_name_template("{file}_{operator}_{line}_{index}.move", &name_fields(), 2)
+            .unwrap();
+        assert_eq!(name, "Sum_binary_operator_replacement_5_2.move");
+
+        let name =
+            render_name_template("{module}-{function}-{id}.move", &name_fields(), 0).unwrap();
+        assert_eq!(name, "Sum-sum-abcd.move");
+    }
+
+    #[test]
+    fn invalid_name_templates_are_rejected() {
+        assert!(check_name_template("{file}_{index}.move").is_ok());
+        assert!(check_name_template("{file}_{unknown}.move").is_err());
+        assert!(check_name_template("{file}_{line.move").is_err());
+        assert!(check_name_template("{file}_{line}").is_err());
+        assert!(check_name_template("{module}/{id}.move").is_err());
+    }
+
+    #[test]
+    fn templated_mutant_path_increases_index() {
+        let output_dir = tempdir().unwrap();
+
+        let template = "{file}_{line}_{index}.move";
+        let first =
+            setup_templated_mutant_path(output_dir.path(), template, &name_fields()).unwrap();
+        assert_eq!(first, output_dir.path().join("Sum_5_0.move"));
+        fs::write(&first, "").unwrap();
+        let second =
+            setup_templated_mutant_path(output_dir.path(), template, &name_fields()).unwrap();
+        assert_eq!(second, output_dir.path().join("Sum_5_1.move"));
+
+        let template = "{file}_{line}.move";
+        let first =
+            setup_templated_mutant_path(output_dir.path(), template, &name_fields()).unwrap();
+        fs::write(&first, "").unwrap();
+        let second =
+            setup_templated_mutant_path(output_dir.path(), template, &name_fields()).unwrap();
+        assert_eq!(second, output_dir.path().join("Sum_5_1.move"));
+    }
 }
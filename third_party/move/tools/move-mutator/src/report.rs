@@ -2,19 +2,121 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::diff::{self, DiffStyle};
+use clap::ValueEnum;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use std::{
-    io::{Error, ErrorKind, Result, Write},
+    collections::{BTreeMap, BTreeSet},
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Result, Write},
     path::{Path, PathBuf},
 };
 
+/// Supported formats of the mutator report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// Machine-readable JSON report (`report.json`).
+    Json,
+    /// Human-readable text report (`report.txt`).
+    Text,
+    /// Streaming JSON lines report (`report.jsonl`), where the mutants are written as they are generated and
+    /// the summary is the last line. Not generated by default.
+    #[value(name = "jsonl")]
+    #[serde(rename = "jsonl")]
+    JsonLines,
+    /// YAML report (`report.yaml`) with the same content as the JSON report. Not generated by default.
+    Yaml,
+    /// TOML report (`report.toml`) with the same content as the JSON report. Not generated by default.
+    Toml,
+}
+
+impl ReportFormat {
+    /// Returns the report formats generated by default.
+    #[must_use]
+    pub fn all() -> Vec<ReportFormat> {
+        vec![ReportFormat::Json, ReportFormat::Text]
+    }
+
+    /// Returns the name of the report file for the given format.
+    #[must_use]
+    pub fn file_name(&self) -> &'static str {
+        match self {
+            ReportFormat::Json => "report.json",
+            ReportFormat::Text => "report.txt",
+            ReportFormat::JsonLines => "report.jsonl",
+            ReportFormat::Yaml => "report.yaml",
+            ReportFormat::Toml => "report.toml",
+        }
+    }
+
+    /// Returns the format of the report file, based on its extension (`None` if it's unknown).
+    #[must_use]
+    pub fn from_path(path: &Path) -> Option<ReportFormat> {
+        match path.extension()?.to_str()? {
+            "json" => Some(ReportFormat::Json),
+            "txt" => Some(ReportFormat::Text),
+            "jsonl" => Some(ReportFormat::JsonLines),
+            "yaml" | "yml" => Some(ReportFormat::Yaml),
+            "toml" => Some(ReportFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+/// Level of detail of the mutants in the JSON reports (the text report always contains the diffs).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportDetails {
+    /// The diffs and the source excerpts are included in the report.
+    #[default]
+    Full,
+    /// The diffs and the source excerpts are omitted.
+    Compact,
+    /// The diffs are written to the separate files (`diffs/<mutant-id>.diff` next to the report), which are
+    /// referenced by path (`diff_path` field), and the source excerpts are omitted.
+    External,
+}
+
+/// Directory of the externalized diffs (next to the report).
+pub const DIFFS_DIR: &str = "diffs";
+
+/// Version of the report format written by this version of the tool.
+/// It must be increased whenever the format changes in a way that older readers can't handle.
+pub const REPORT_SCHEMA_VERSION: u32 = 4;
+
+/// Version of the report format written before the versioning was introduced.
+const LEGACY_SCHEMA_VERSION: u32 = 1;
+
 /// The `Report` struct represents a report of mutations.
 /// It contains a vector of `MutationReport` instances.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Report {
+    /// Version of the report format (reports without the version are treated as version 1).
+    #[serde(default = "legacy_schema_version")]
+    schema_version: u32,
     /// The vector of `ReportEntry` instances.
     mutants: Vec<MutationReport>,
+    /// Applicability statistics of the mutation operators, keyed by the operator name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    operators: BTreeMap<String, OperatorStats>,
+    /// Number of the places in the code skipped because of the syntax the mutation operators can't rewrite.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    unsupported_sites: u32,
+    /// Files which couldn't be mutated, so the run continued without them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    failures: Vec<FileFailure>,
+}
+
+// Internal function checking if the counter is zero, so it's omitted from the report.
+fn is_zero(value: &u32) -> bool {
+    *value == 0
+}
+
+// Internal function returning the version of the reports written without the version field.
+fn legacy_schema_version() -> u32 {
+    LEGACY_SCHEMA_VERSION
 }
 
 impl Report {
@@ -22,16 +124,81 @@ impl Report {
     #[must_use]
     pub fn new() -> Self {
         Self {
+            schema_version: REPORT_SCHEMA_VERSION,
             mutants: Vec::new(),
+            operators: BTreeMap::new(),
+            unsupported_sites: 0,
+            failures: Vec::new(),
         }
     }
 
     /// Adds a new `MutationReport` to the report.
+    /// The mutant is counted as generated by each of the operators applied to it.
     pub fn add_entry(&mut self, entry: MutationReport) {
         trace!("Adding a mutant to the report: {entry:?}");
+        self.count_entry(&entry);
         self.mutants.push(entry);
     }
 
+    /// Counts the mutant written only to the JSON lines report, without keeping it in memory.
+    /// The mutant is counted as generated by each of the operators applied to it.
+    pub fn count_entry(&mut self, entry: &MutationReport) {
+        for operator in entry.operator_names() {
+            self.operators.entry(operator).or_default().generated += 1;
+        }
+    }
+
+    /// Adds the number of the mutation sites (places in the code the operator can be applied to) found
+    /// for the given operator.
+    pub fn add_operator_sites(&mut self, operator: &str, sites: u32) {
+        self.operators.entry(operator.to_owned()).or_default().sites += sites;
+    }
+
+    /// Counts the mutant generated by the given operator, which was skipped (e.g. because it doesn't
+    /// compile or type check) and is therefore not included in the report.
+    pub fn increment_operator_skipped(&mut self, operator: &str) {
+        self.operators
+            .entry(operator.to_owned())
+            .or_default()
+            .skipped += 1;
+    }
+
+    /// Counts the mutant generated by the given operator, which doesn't change the source (e.g. the literal
+    /// replaced with the same value) and is therefore not included in the report.
+    pub fn increment_operator_no_op(&mut self, operator: &str) {
+        self.operators.entry(operator.to_owned()).or_default().no_op += 1;
+    }
+
+    /// Returns the applicability statistics of the mutation operators, keyed by the operator name.
+    #[must_use]
+    pub fn get_operators(&self) -> &BTreeMap<String, OperatorStats> {
+        &self.operators
+    }
+
+    /// Sets the number of the places in the code skipped because of the unsupported syntax.
+    pub fn set_unsupported_sites(&mut self, sites: u32) {
+        self.unsupported_sites = sites;
+    }
+
+    /// Returns the number of the places in the code skipped because of the unsupported syntax.
+    #[must_use]
+    pub fn get_unsupported_sites(&self) -> u32 {
+        self.unsupported_sites
+    }
+
+    /// Records the file which couldn't be mutated. Each file is recorded once, with its first error.
+    pub fn add_failure(&mut self, failure: FileFailure) {
+        if !self.failures.iter().any(|f| f.file == failure.file) {
+            self.failures.push(failure);
+        }
+    }
+
+    /// Returns the files which couldn't be mutated.
+    #[must_use]
+    pub fn get_failures(&self) -> &[FileFailure] {
+        &self.failures
+    }
+
     /// Saves the `Report` as a JSON file.
     ///
     /// # Errors
@@ -45,39 +212,440 @@ impl Report {
         serde_json::to_writer_pretty(file, &self).map_err(|e| Error::new(ErrorKind::Other, e))
     }
 
+    /// Saves the `Report` as a YAML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written to.
+    pub fn save_to_yaml_file(&self, path: &Path) -> Result<()> {
+        let file = std::fs::File::create(path)?;
+
+        info!("Saving report to {}", path.display());
+
+        serde_yaml::to_writer(file, &self).map_err(|e| Error::new(ErrorKind::Other, e))
+    }
+
+    /// Saves the `Report` as a TOML file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created or written to.
+    pub fn save_to_toml_file(&self, path: &Path) -> Result<()> {
+        info!("Saving report to {}", path.display());
+
+        // The report is converted to the TOML value first, which puts the plain values of each table before
+        // the nested tables, as required by the format.
+        let value = toml::Value::try_from(self).map_err(|e| Error::new(ErrorKind::Other, e))?;
+        let toml = toml::to_string_pretty(&value).map_err(|e| Error::new(ErrorKind::Other, e))?;
+        fs::write(path, toml)
+    }
+
+    /// Saves the `Report` in the given format (JSON, YAML or TOML) with the given level of detail of the
+    /// mutants. The externalized diffs are written to the `diffs` directory next to the report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the report or the diffs cannot be written, or if the format isn't the serialized
+    /// report (the text and the JSON lines reports have their own writers).
+    pub fn save_with_details(
+        &self,
+        path: &Path,
+        format: ReportFormat,
+        details: ReportDetails,
+    ) -> Result<()> {
+        let reduced;
+        let report = if details == ReportDetails::Full {
+            self
+        } else {
+            let report_dir = path.parent().unwrap_or_else(|| Path::new(""));
+            let mut report = self.clone();
+            for mutant in &mut report.mutants {
+                mutant.reduce_details(details, report_dir)?;
+            }
+            reduced = report;
+            &reduced
+        };
+
+        match format {
+            ReportFormat::Json => report.save_to_json_file(path),
+            ReportFormat::Yaml => report.save_to_yaml_file(path),
+            ReportFormat::Toml => report.save_to_toml_file(path),
+            ReportFormat::Text | ReportFormat::JsonLines => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!("{} isn't written with the details", format.file_name()),
+            )),
+        }
+    }
+
+    /// Loads the `Report` from the file in any of the structured formats (JSON, JSON lines, YAML or TOML),
+    /// chosen by the extension of the file. Files with other extensions are read as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, or if the report was written in a newer,
+    /// unsupported version of the format.
+    pub fn load_from_file(path: &Path) -> Result<Self> {
+        match ReportFormat::from_path(path) {
+            Some(ReportFormat::JsonLines) => Self::load_from_json_lines_file(path),
+            Some(ReportFormat::Yaml) => Self::load_from_yaml_file(path),
+            Some(ReportFormat::Toml) => Self::load_from_toml_file(path),
+            _ => Self::load_from_json_file(path),
+        }
+    }
+
+    /// Returns the path to the report stored in the directory of the generated mutants. Any structured
+    /// format is accepted, the JSON report is preferred.
+    #[must_use]
+    pub fn find_in_dir(dir: &Path) -> Option<PathBuf> {
+        [
+            ReportFormat::Json,
+            ReportFormat::Yaml,
+            ReportFormat::Toml,
+            ReportFormat::JsonLines,
+        ]
+        .iter()
+        .map(|format| dir.join(format.file_name()))
+        .find(|path| path.is_file())
+    }
+
+    /// Loads the `Report` from a YAML file.
+    /// Reports written in older versions of the format are upgraded to the current version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read from, or if the report was written
+    /// in a newer, unsupported version of the format.
+    pub fn load_from_yaml_file(path: &Path) -> Result<Self> {
+        info!("Reading report from {}", path.display());
+
+        let file = std::fs::File::open(path)?;
+
+        let report: Self =
+            serde_yaml::from_reader(file).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let mut report = report.upgrade()?;
+        report.load_external_diffs(path);
+        Ok(report)
+    }
+
+    /// Loads the `Report` from a TOML file.
+    /// Reports written in older versions of the format are upgraded to the current version.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened or read from, or if the report was written
+    /// in a newer, unsupported version of the format.
+    pub fn load_from_toml_file(path: &Path) -> Result<Self> {
+        info!("Reading report from {}", path.display());
+
+        let report: Self = toml::from_str(&fs::read_to_string(path)?)
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let mut report = report.upgrade()?;
+        report.load_external_diffs(path);
+        Ok(report)
+    }
+
     /// Loads the `Report` from a JSON file.
+    /// Reports written in older versions of the format are upgraded to the current version.
     ///
     /// # Errors
     ///
-    /// Returns an error if the file cannot be opened or read from.
+    /// Returns an error if the file cannot be opened or read from, or if the report was written
+    /// in a newer, unsupported version of the format.
     pub fn load_from_json_file(path: &Path) -> Result<Self> {
         info!("Reading report from {}", path.display());
 
         let file = std::fs::File::open(path)?;
 
-        serde_json::from_reader(file).map_err(|e| Error::new(ErrorKind::Other, e))
+        let report: Self =
+            serde_json::from_reader(file).map_err(|e| Error::new(ErrorKind::Other, e))?;
+        let mut report = report.upgrade()?;
+        report.load_external_diffs(path);
+        Ok(report)
+    }
+
+    /// Loads the `Report` from a JSON lines file written by the `ReportStream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, if any line is invalid, if the summary line is missing
+    /// (e.g. the run didn't finish) or if the report was written in a newer, unsupported version of the format.
+    pub fn load_from_json_lines_file(path: &Path) -> Result<Self> {
+        info!("Reading report from {}", path.display());
+
+        let mut report = Self::new();
+        let mut summary = None;
+        for line in Self::read_json_lines(path)? {
+            match line? {
+                ReportLine::Mutant(entry) => report.mutants.push(entry),
+                ReportLine::Summary(line) => summary = Some(line),
+            }
+        }
+
+        let summary = summary.ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("{} doesn't contain the summary line", path.display()),
+            )
+        })?;
+        report.schema_version = summary.schema_version;
+        report.operators = summary.operators;
+        report.unsupported_sites = summary.unsupported_sites;
+        report.failures = summary.failures;
+        let mut report = report.upgrade()?;
+        report.load_external_diffs(path);
+        Ok(report)
+    }
+
+    // Internal function loading the externalized diffs of the mutants (the paths are relative to the
+    // directory of the report). Missing diff files are skipped, so the report can be read without them.
+    fn load_external_diffs(&mut self, report_path: &Path) {
+        let report_dir = report_path.parent().unwrap_or_else(|| Path::new(""));
+        for mutant in &mut self.mutants {
+            let Some(diff_path) = &mutant.diff_path else {
+                continue;
+            };
+            match fs::read_to_string(report_dir.join(diff_path)) {
+                Ok(diff) => mutant.diff = diff,
+                Err(e) => debug!("Cannot read the diff {}: {e}", diff_path.display()),
+            }
+            for (index, file) in mutant.changed_files.iter_mut().enumerate() {
+                let file_diff_path = changed_file_diff_path(diff_path, index);
+                match fs::read_to_string(report_dir.join(&file_diff_path)) {
+                    Ok(diff) => file.diff = diff,
+                    Err(e) => debug!("Cannot read the diff {}: {e}", file_diff_path.display()),
+                }
+            }
+        }
+    }
+
+    /// Reads the lines of the JSON lines report one by one, so the mutants don't need to be kept in memory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be opened. Errors of the single lines are returned by the iterator.
+    pub fn read_json_lines(path: &Path) -> Result<impl Iterator<Item = Result<ReportLine>>> {
+        let lines = BufReader::new(File::open(path)?).lines();
+        Ok(lines.filter_map(|line| match line {
+            Ok(line) if line.trim().is_empty() => None,
+            Ok(line) => {
+                Some(serde_json::from_str(&line).map_err(|e| Error::new(ErrorKind::InvalidData, e)))
+            },
+            Err(e) => Some(Err(e)),
+        }))
+    }
+
+    /// Returns the version of the report format.
+    #[must_use]
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Returns the JSON Schema describing the current version of the report format.
+    #[must_use]
+    pub fn json_schema() -> serde_json::Value {
+        serde_json::json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "title": "Move mutator report",
+            "type": "object",
+            "required": ["schema_version", "mutants"],
+            "properties": {
+                "schema_version": { "const": REPORT_SCHEMA_VERSION },
+                "mutants": {
+                    "type": "array",
+                    "items": { "$ref": "#/$defs/mutant" }
+                },
+                "operators": {
+                    "type": "object",
+                    "description": "Applicability statistics keyed by the name of the mutation operator",
+                    "additionalProperties": { "$ref": "#/$defs/operator_stats" }
+                },
+                "unsupported_sites": {
+                    "type": "integer",
+                    "minimum": 0,
+                    "description": "Number of the places in the code skipped because of the syntax the mutation operators can't rewrite"
+                },
+                "failures": {
+                    "type": "array",
+                    "description": "Files which couldn't be mutated",
+                    "items": {
+                        "type": "object",
+                        "required": ["file", "error"],
+                        "properties": {
+                            "file": { "type": "string" },
+                            "error": { "type": "string" }
+                        }
+                    }
+                }
+            },
+            "$defs": {
+                "operator_stats": {
+                    "type": "object",
+                    "required": ["sites", "generated", "skipped"],
+                    "properties": {
+                        "sites": { "type": "integer", "minimum": 0 },
+                        "generated": { "type": "integer", "minimum": 0 },
+                        "skipped": { "type": "integer", "minimum": 0 },
+                        "no_op": {
+                            "type": "integer",
+                            "minimum": 0,
+                            "description": "Number of the mutants identical to the original source, which are skipped"
+                        }
+                    }
+                },
+                "mutant": {
+                    "type": "object",
+                    "required": ["id", "mutant_path", "original_file", "module_name", "function_name", "mutations"],
+                    "properties": {
+                        "id": { "type": "string", "pattern": "^[0-9a-f]{16}$" },
+                        "mutant_path": { "type": "string" },
+                        "original_file": { "type": "string" },
+                        "module_name": { "type": "string" },
+                        "module_address": { "type": "string" },
+                        "function_name": { "type": "string" },
+                        "script": { "type": "boolean" },
+                        "mutations": {
+                            "type": "array",
+                            "items": { "$ref": "#/$defs/mutation" }
+                        },
+                        "diff": { "type": "string" },
+                        "diff_path": {
+                            "type": "string",
+                            "description": "Path to the externalized diff (relative to the report directory)"
+                        },
+                        "excerpt": { "$ref": "#/$defs/source_excerpt" },
+                        "valid": { "type": "boolean" },
+                        "changed_files": {
+                            "type": "array",
+                            "items": { "$ref": "#/$defs/changed_file" }
+                        }
+                    }
+                },
+                "changed_file": {
+                    "type": "object",
+                    "required": ["mutant_path", "original_file", "module_name", "function_name", "mutations"],
+                    "properties": {
+                        "mutant_path": { "type": "string" },
+                        "original_file": { "type": "string" },
+                        "module_name": { "type": "string" },
+                        "function_name": { "type": "string" },
+                        "mutations": {
+                            "type": "array",
+                            "items": { "$ref": "#/$defs/mutation" }
+                        },
+                        "diff": { "type": "string" },
+                        "excerpt": { "$ref": "#/$defs/source_excerpt" }
+                    }
+                },
+                "source_excerpt": {
+                    "type": "object",
+                    "description": "Source lines around the change, in the original and the mutated file",
+                    "required": ["start_line", "original", "mutated"],
+                    "properties": {
+                        "start_line": { "type": "integer", "minimum": 1 },
+                        "original": { "type": "string" },
+                        "mutated": { "type": "string" }
+                    }
+                },
+                "mutation": {
+                    "type": "object",
+                    "required": ["changed_place", "operator_name", "old_value", "new_value"],
+                    "properties": {
+                        "changed_place": {
+                            "type": "object",
+                            "required": ["start", "end"],
+                            "properties": {
+                                "start": { "type": "integer", "minimum": 0 },
+                                "end": { "type": "integer", "minimum": 0 }
+                            }
+                        },
+                        "operator_name": { "type": "string" },
+                        "old_value": { "type": "string" },
+                        "new_value": { "type": "string" },
+                        "variant_change": {
+                            "type": "object",
+                            "required": ["from", "to"],
+                            "properties": {
+                                "from": { "type": "string" },
+                                "to": { "type": "string" }
+                            }
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    // Internal function upgrading the report loaded from the file to the current version of the format.
+    fn upgrade(mut self) -> Result<Self> {
+        if self.schema_version > REPORT_SCHEMA_VERSION {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Unsupported report schema version {} (the newest supported version is {REPORT_SCHEMA_VERSION})",
+                    self.schema_version
+                ),
+            ));
+        }
+
+        if self.schema_version == LEGACY_SCHEMA_VERSION {
+            // Version 1 reports don't contain mutant IDs.
+            for mutant in &mut self.mutants {
+                if mutant.id.is_empty() {
+                    mutant.id = mutant.compute_id();
+                }
+            }
+        }
+
+        self.schema_version = REPORT_SCHEMA_VERSION;
+        Ok(self)
     }
 
-    /// Saves the `Report` as a text file.
+    /// Saves the `Report` as a text file, rendering the diffs in the given style.
     ///
     /// # Errors
     ///
     /// Returns an error if the file cannot be created or written to.
-    pub fn save_to_text_file(&self, path: &Path) -> Result<()> {
+    pub fn save_to_text_file(&self, path: &Path, style: DiffStyle) -> Result<()> {
         let mut file = std::fs::File::create(path)?;
 
         info!("Saving report to {}", path.display());
 
         for entry in &self.mutants {
+            writeln!(file, "Mutant ID: {}", entry.id)?;
             writeln!(file, "Mutant path: {}", entry.mutant_path.display())?;
             writeln!(file, "Original file: {}", entry.original_file.display())?;
             writeln!(file, "Module name: {}", entry.module_name)?;
+            if let Some(address) = &entry.module_address {
+                writeln!(file, "Module address: {address}")?;
+            }
             writeln!(file, "Function name: {}", entry.function_name)?;
+            if entry.script {
+                writeln!(file, "Script: true")?;
+            }
+            if let Some(valid) = entry.valid {
+                writeln!(file, "Valid: {valid}")?;
+            }
+            if !entry.changed_files.is_empty() {
+                writeln!(file, "Changed files:")?;
+                for changed in &entry.changed_files {
+                    writeln!(
+                        file,
+                        "  {} ({}::{}) -> {}",
+                        changed.original_file.display(),
+                        changed.module_name,
+                        changed.function_name,
+                        changed.mutant_path.display()
+                    )?;
+                }
+            }
             writeln!(file, "Mutations:")?;
             for modification in &entry.mutations {
                 writeln!(file, "  Operator: {}", modification.operator_name)?;
                 writeln!(file, "  Old value: {}", modification.old_value)?;
                 writeln!(file, "  New value: {}", modification.new_value)?;
+                if let Some(change) = &modification.variant_change {
+                    writeln!(file, "  Variant change: {} -> {}", change.from, change.to)?;
+                }
                 writeln!(
                     file,
                     "  Changed place: {}-{}",
@@ -85,10 +653,19 @@ impl Report {
                 )?;
             }
             writeln!(file, "Diff:")?;
-            writeln!(file, "{}", entry.diff)?;
+            writeln!(file, "{}", diff::render_diff(&entry.diff, style))?;
             writeln!(file, "----------------------------------------")?;
         }
 
+        for failure in &self.failures {
+            writeln!(
+                file,
+                "Failed file: {} ({})",
+                failure.file.display(),
+                failure.error
+            )?;
+        }
+
         debug!("Report saved to {}", path.display());
 
         Ok(())
@@ -113,6 +690,158 @@ impl Default for Report {
     }
 }
 
+/// Line of the JSON lines report. Mutants are written one per line as they are generated, and the summary
+/// is the last line.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReportLine {
+    /// The generated mutant.
+    Mutant(MutationReport),
+    /// The aggregate summary written when the generation finishes.
+    Summary(ReportSummary),
+}
+
+// Borrowed version of the `ReportLine`, so the entries don't need to be cloned when they are written.
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum ReportLineRef<'a> {
+    Mutant(&'a MutationReport),
+    Summary(&'a ReportSummary),
+}
+
+/// Aggregate summary of the JSON lines report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSummary {
+    /// Version of the report format.
+    pub schema_version: u32,
+    /// The number of the mutants written to the report.
+    pub mutants: usize,
+    /// Applicability statistics of the mutation operators, keyed by the operator name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub operators: BTreeMap<String, OperatorStats>,
+    /// Number of the places in the code skipped because of the unsupported syntax.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub unsupported_sites: u32,
+    /// Files which couldn't be mutated.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<FileFailure>,
+}
+
+/// Writer of the JSON lines report (`report.jsonl`).
+///
+/// Each mutant is written as soon as it's generated, so the mutants don't need to be kept in memory for enormous
+/// runs. The `finish` method writes the aggregate summary as the last line.
+pub struct ReportStream {
+    writer: BufWriter<File>,
+    mutants: usize,
+    details: ReportDetails,
+    report_dir: PathBuf,
+}
+
+impl ReportStream {
+    /// Creates the JSON lines report file (an existing file is overwritten).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be created.
+    pub fn create(path: &Path) -> Result<Self> {
+        info!("Streaming report to {}", path.display());
+
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+            mutants: 0,
+            details: ReportDetails::Full,
+            report_dir: path.parent().unwrap_or_else(|| Path::new("")).to_path_buf(),
+        })
+    }
+
+    /// Sets the level of detail of the written mutants (the externalized diffs are written to the `diffs`
+    /// directory next to the report).
+    #[must_use]
+    pub fn with_details(mut self, details: ReportDetails) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Writes the mutant to the report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mutant cannot be written.
+    pub fn write_entry(&mut self, entry: &MutationReport) -> Result<()> {
+        if self.details == ReportDetails::Full {
+            self.write_line(&ReportLineRef::Mutant(entry))?;
+        } else {
+            let mut entry = entry.clone();
+            entry.reduce_details(self.details, &self.report_dir)?;
+            self.write_line(&ReportLineRef::Mutant(&entry))?;
+        }
+        self.mutants += 1;
+        Ok(())
+    }
+
+    /// Writes the summary with the statistics of the given report as the last line and flushes the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the summary cannot be written.
+    pub fn finish(mut self, report: &Report) -> Result<ReportSummary> {
+        let summary = ReportSummary {
+            schema_version: REPORT_SCHEMA_VERSION,
+            mutants: self.mutants,
+            operators: report.operators.clone(),
+            unsupported_sites: report.unsupported_sites,
+            failures: report.failures.clone(),
+        };
+        self.write_line(&ReportLineRef::Summary(&summary))?;
+        self.writer.flush()?;
+        Ok(summary)
+    }
+
+    // Internal function writing the line of the report.
+    fn write_line(&mut self, line: &ReportLineRef<'_>) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, line)
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+/// Applicability statistics of the mutation operator, showing how often the operator applies to the
+/// mutated code.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OperatorStats {
+    /// The number of places in the code the operator was applied to.
+    pub sites: u32,
+    /// The number of mutants generated by the operator and included in the report.
+    pub generated: u32,
+    /// The number of mutants generated by the operator, but skipped (e.g. because they don't compile).
+    pub skipped: u32,
+    /// The number of mutants generated by the operator, but identical to the original source (so they are
+    /// skipped as well).
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub no_op: u32,
+}
+
+/// The file which couldn't be mutated (e.g. because of the error while traversing or saving its mutants).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFailure {
+    /// The path to the file.
+    pub file: PathBuf,
+    /// The description of the error.
+    pub error: String,
+}
+
+impl FileFailure {
+    /// Creates a new `FileFailure` instance.
+    #[must_use]
+    pub fn new(file: &Path, error: String) -> Self {
+        Self {
+            file: file.to_path_buf(),
+            error,
+        }
+    }
+}
+
 /// The `Range` struct represents a range with a start and end.
 /// It is used to represent the location of a mutation inside the source file.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -133,6 +862,84 @@ impl Range {
         assert!(start <= end);
         Self { start, end }
     }
+
+    /// Returns the start of the range.
+    #[must_use]
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Returns the end of the range.
+    #[must_use]
+    pub fn end(&self) -> usize {
+        self.end
+    }
+}
+
+/// The `SourceExcerpt` struct represents the lines around the change of the mutant, in the original and
+/// the mutated version of the file, so the report consumers can render the change without the sources.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SourceExcerpt {
+    /// Number of the first line of the excerpt (1-based, the same in both versions).
+    start_line: usize,
+    /// The excerpt of the original file.
+    original: String,
+    /// The excerpt of the mutated file.
+    mutated: String,
+}
+
+impl SourceExcerpt {
+    /// Creates the excerpt of the changed lines with the given number of context lines around them.
+    /// Returns `None` if the sources are the same.
+    #[must_use]
+    pub fn new(original_source: &str, mutated_source: &str, context: usize) -> Option<Self> {
+        let original = original_source.split_inclusive('\n').collect::<Vec<_>>();
+        let mutated = mutated_source.split_inclusive('\n').collect::<Vec<_>>();
+
+        let prefix = original
+            .iter()
+            .zip(&mutated)
+            .take_while(|(a, b)| a == b)
+            .count();
+        if prefix == original.len() && prefix == mutated.len() {
+            return None;
+        }
+        let suffix = original[prefix..]
+            .iter()
+            .rev()
+            .zip(mutated[prefix..].iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let start = prefix.saturating_sub(context);
+        let excerpt = |lines: &[&str]| {
+            let end = (lines.len() - suffix + context).min(lines.len());
+            lines[start..end].concat()
+        };
+        Some(Self {
+            start_line: start + 1,
+            original: excerpt(&original),
+            mutated: excerpt(&mutated),
+        })
+    }
+
+    /// Returns the number of the first line of the excerpt (1-based).
+    #[must_use]
+    pub fn start_line(&self) -> usize {
+        self.start_line
+    }
+
+    /// Returns the excerpt of the original file.
+    #[must_use]
+    pub fn original(&self) -> &str {
+        &self.original
+    }
+
+    /// Returns the excerpt of the mutated file.
+    #[must_use]
+    pub fn mutated(&self) -> &str {
+        &self.mutated
+    }
 }
 
 /// The `Mutation` struct represents a modification that was applied to a file.
@@ -148,6 +955,19 @@ pub struct Mutation {
     old_value: String,
     /// The new operator value.
     new_value: String,
+    /// The change of the enum variant (for the operators mutating Move 2 enums).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    variant_change: Option<VariantChange>,
+}
+
+/// The `VariantChange` struct names the enum variants affected by the modification
+/// (e.g. the constructed variant replaced with its sibling or the variants of the swapped match arms).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VariantChange {
+    /// The original variant.
+    pub from: String,
+    /// The variant used instead.
+    pub to: String,
 }
 
 impl Mutation {
@@ -164,14 +984,49 @@ impl Mutation {
             operator_name,
             old_value,
             new_value,
+            variant_change: None,
         }
     }
 
+    /// Sets the change of the enum variant applied by the modification.
+    #[must_use]
+    pub fn with_variant_change(mut self, from: &str, to: &str) -> Self {
+        self.variant_change = Some(VariantChange {
+            from: from.to_owned(),
+            to: to.to_owned(),
+        });
+        self
+    }
+
+    /// Returns the location of the modification.
+    #[must_use]
+    pub fn get_changed_place(&self) -> &Range {
+        &self.changed_place
+    }
+
     /// Returns the operator name.
     #[must_use]
     pub fn get_operator_name(&self) -> &str {
         &self.operator_name
     }
+
+    /// Returns the old value.
+    #[must_use]
+    pub fn get_old_value(&self) -> &str {
+        &self.old_value
+    }
+
+    /// Returns the new value.
+    #[must_use]
+    pub fn get_new_value(&self) -> &str {
+        &self.new_value
+    }
+
+    /// Returns the change of the enum variant (if any).
+    #[must_use]
+    pub fn get_variant_change(&self) -> Option<&VariantChange> {
+        self.variant_change.as_ref()
+    }
 }
 
 /// The `MutationReport` struct represents an entry in a report.
@@ -179,18 +1034,110 @@ impl Mutation {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[allow(clippy::module_name_repetitions)]
 pub struct MutationReport {
+    /// Stable identifier of the mutant (derived from its location and applied modifications).
+    #[serde(default)]
+    id: String,
     /// The path to the mutated file.
     mutant_path: PathBuf,
     /// The path to the original file.
     original_file: PathBuf,
     /// The name of the module that the mutation is in.
     module_name: String,
+    /// The address of the module that the mutation is in (it tells apart the modules with the same name).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    module_address: Option<String>,
+    /// The function name that the mutation is in.
+    function_name: String,
+    /// Indicates if the mutation is in a script (the module name is `script` then).
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    script: bool,
+    /// The modifications that were applied to the file.
+    mutations: Vec<Mutation>,
+    /// The diff between the original and mutated file (empty if it's omitted from the report).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    diff: String,
+    /// The path to the externalized diff (relative to the report directory).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    diff_path: Option<PathBuf>,
+    /// The source lines around the change (with the same number of context lines as the diff).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    excerpt: Option<SourceExcerpt>,
+    /// Indicates if the mutant type checks (`None` if the mutant wasn't checked).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    valid: Option<bool>,
+    /// All files changed by the mutant spanning multiple files (empty for the single-file mutants). The mutant
+    /// path of such mutant is the directory containing all the mutated files.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    changed_files: Vec<ChangedFile>,
+    /// The root directory of the mutated package, used to compute the identifier from the package-relative
+    /// paths of the original files (not serialized, as the identifier is a part of the report).
+    #[serde(skip)]
+    package_root: Option<PathBuf>,
+}
+
+/// The `ChangedFile` struct represents a single file changed by the mutant spanning multiple files.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ChangedFile {
+    /// The path to the mutated file (inside the mutant directory).
+    mutant_path: PathBuf,
+    /// The path to the original file.
+    original_file: PathBuf,
+    /// The name of the module that the mutation is in.
+    module_name: String,
     /// The function name that the mutation is in.
     function_name: String,
     /// The modifications that were applied to the file.
     mutations: Vec<Mutation>,
-    /// The diff between the original and mutated file.
+    /// The diff between the original and mutated file (empty if it's omitted from the report).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     diff: String,
+    /// The source lines around the change.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    excerpt: Option<SourceExcerpt>,
+}
+
+impl ChangedFile {
+    /// Return the mutant path.
+    #[must_use]
+    pub fn mutant_path(&self) -> &PathBuf {
+        &self.mutant_path
+    }
+
+    /// Return the original file path.
+    #[must_use]
+    pub fn original_file_path(&self) -> &PathBuf {
+        &self.original_file
+    }
+
+    /// Return the module name.
+    #[must_use]
+    pub fn get_module_name(&self) -> &str {
+        &self.module_name
+    }
+
+    /// Return the function name.
+    #[must_use]
+    pub fn get_function_name(&self) -> &str {
+        &self.function_name
+    }
+
+    /// Return the diff.
+    #[must_use]
+    pub fn get_diff(&self) -> &str {
+        &self.diff
+    }
+
+    /// Return the source lines around the change.
+    #[must_use]
+    pub fn get_excerpt(&self) -> Option<&SourceExcerpt> {
+        self.excerpt.as_ref()
+    }
+
+    /// Return the modifications applied to the file.
+    #[must_use]
+    pub fn get_mutations(&self) -> &[Mutation] {
+        &self.mutations
+    }
 }
 
 impl MutationReport {
@@ -205,21 +1152,160 @@ impl MutationReport {
         mutated_source: &str,
         original_source: &str,
     ) -> Self {
-        let patch = diffy::create_patch(original_source, mutated_source);
-        Self {
+        Self::with_diff_context(
+            mutant_path,
+            original_file,
+            module_name,
+            function_name,
+            mutated_source,
+            original_source,
+            diff::DEFAULT_DIFF_CONTEXT,
+        )
+    }
+
+    /// Creates a new `MutationReport` instance.
+    /// Generates diff (patch) between the original and mutated source with the given number of context lines.
+    #[must_use]
+    pub fn with_diff_context(
+        mutant_path: &Path,
+        original_file: &Path,
+        module_name: &str,
+        function_name: &str,
+        mutated_source: &str,
+        original_source: &str,
+        diff_context: usize,
+    ) -> Self {
+        let mut report = Self {
+            id: String::new(),
             mutant_path: mutant_path.to_path_buf(),
             original_file: original_file.to_path_buf(),
             module_name: module_name.to_owned(),
             function_name: function_name.to_owned(),
             mutations: vec![],
-            diff: patch.to_string(),
+            diff: diff::create_diff(original_source, mutated_source, diff_context),
+            diff_path: None,
+            excerpt: SourceExcerpt::new(original_source, mutated_source, diff_context),
+            module_address: None,
+            script: false,
+            valid: None,
+            changed_files: vec![],
+            package_root: None,
+        };
+        report.id = report.compute_id();
+        report
+    }
+
+    /// Creates a new `MutationReport` instance of the mutant spanning multiple files from the reports of
+    /// the single-file mutants combined in it. The first file is the primary one - the module and function
+    /// names and the original file of the mutant are taken from it. The mutant path is the directory
+    /// containing all the mutated files.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic if no file is given.
+    #[must_use]
+    pub fn multi_file(mutant_dir: &Path, files: Vec<MutationReport>) -> Self {
+        let primary = files.first().expect("Mutant must change at least one file");
+        let mut report = Self {
+            id: String::new(),
+            mutant_path: mutant_dir.to_path_buf(),
+            original_file: primary.original_file.clone(),
+            module_name: primary.module_name.clone(),
+            function_name: primary.function_name.clone(),
+            mutations: files.iter().flat_map(|f| f.mutations.clone()).collect(),
+            diff: files.iter().map(|f| f.diff.as_str()).collect(),
+            diff_path: None,
+            excerpt: primary.excerpt.clone(),
+            module_address: primary.module_address.clone(),
+            script: primary.script,
+            valid: None,
+            changed_files: files
+                .into_iter()
+                .map(|f| ChangedFile {
+                    mutant_path: f.mutant_path,
+                    original_file: f.original_file,
+                    module_name: f.module_name,
+                    function_name: f.function_name,
+                    mutations: f.mutations,
+                    diff: f.diff,
+                    excerpt: f.excerpt,
+                })
+                .collect(),
+            package_root: primary.package_root.clone(),
+        };
+        report.id = report.compute_id();
+        report
+    }
+
+    // Internal function reducing the level of detail of the entry written to the report. The externalized
+    // diff is written to the `diffs` directory of the report (the diffs of the changed files are part of it).
+    fn reduce_details(&mut self, details: ReportDetails, report_dir: &Path) -> Result<()> {
+        if details == ReportDetails::Full {
+            return Ok(());
+        }
+
+        if details == ReportDetails::External {
+            let diff_path = Path::new(DIFFS_DIR).join(format!("{}.diff", self.id));
+            fs::create_dir_all(report_dir.join(DIFFS_DIR))?;
+            fs::write(report_dir.join(&diff_path), &self.diff)?;
+            for (index, file) in self.changed_files.iter().enumerate() {
+                fs::write(
+                    report_dir.join(changed_file_diff_path(&diff_path, index)),
+                    &file.diff,
+                )?;
+            }
+            self.diff_path = Some(diff_path);
+        }
+        self.diff.clear();
+        self.excerpt = None;
+        for file in &mut self.changed_files {
+            file.diff.clear();
+            file.excerpt = None;
         }
+        Ok(())
+    }
+
+    /// Sets the path to the mutated file.
+    pub fn set_mutant_path(&mut self, mutant_path: &Path) {
+        self.mutant_path = mutant_path.to_path_buf();
+    }
+
+    /// Sets the result of the type checking of the mutant.
+    pub fn set_valid(&mut self, valid: bool) {
+        self.valid = Some(valid);
+    }
+
+    /// Sets the address of the mutated module.
+    pub fn set_module_address(&mut self, module_address: &str) {
+        self.module_address = Some(module_address.to_owned());
+        self.id = self.compute_id();
+    }
+
+    /// Sets the root directory of the mutated package. The identifier depends on the paths of the original
+    /// files relative to it, so it's the same for the different checkouts of the package.
+    pub fn set_package_root(&mut self, package_root: &Path) {
+        self.package_root = Some(package_root.to_path_buf());
+        self.id = self.compute_id();
+    }
+
+    /// Marks the mutant as the mutant of a script.
+    pub fn set_script(&mut self, script: bool) {
+        self.script = script;
     }
 
     /// Adds a `Mutation` to the `MutationReport`.
     pub fn add_modification(&mut self, modification: Mutation) {
         trace!("Adding modification to report: {modification:?}");
         self.mutations.push(modification);
+        self.id = self.compute_id();
+    }
+
+    /// Return the stable identifier of the mutant.
+    /// The identifier doesn't depend on the mutant file name, so it stays the same between runs as long
+    /// as the mutated code doesn't change.
+    #[must_use]
+    pub fn get_id(&self) -> &str {
+        &self.id
     }
 
     /// Return the mutant path.
@@ -240,17 +1326,144 @@ impl MutationReport {
         &self.module_name
     }
 
+    /// Return the address of the module (`None` for the scripts and the reports written by older versions).
+    #[must_use]
+    pub fn get_module_address(&self) -> Option<&str> {
+        self.module_address.as_deref()
+    }
+
+    /// Return the module name qualified with the module address (e.g. `0xcafe::Sum`), which tells apart the
+    /// modules with the same name. Just the module name is returned if the address is not known.
+    #[must_use]
+    pub fn qualified_module_name(&self) -> String {
+        match &self.module_address {
+            Some(address) => format!("{address}::{}", self.module_name),
+            None => self.module_name.clone(),
+        }
+    }
+
+    /// Return the function name qualified with the module name and address (e.g. `0xcafe::Sum::sum`).
+    #[must_use]
+    pub fn qualified_function_name(&self) -> String {
+        format!("{}::{}", self.qualified_module_name(), self.function_name)
+    }
+
     /// Return the function name.
     #[must_use]
     pub fn get_function_name(&self) -> &str {
         &self.function_name
     }
 
+    /// Return true if the mutant is in a script (not in a module).
+    #[must_use]
+    pub fn is_script(&self) -> bool {
+        self.script
+    }
+
     /// Return the diff.
     #[must_use]
     pub fn get_diff(&self) -> &str {
         &self.diff
     }
+
+    /// Return the path to the externalized diff (relative to the report directory).
+    #[must_use]
+    pub fn get_diff_path(&self) -> Option<&Path> {
+        self.diff_path.as_deref()
+    }
+
+    /// Return the source lines around the change.
+    #[must_use]
+    pub fn get_excerpt(&self) -> Option<&SourceExcerpt> {
+        self.excerpt.as_ref()
+    }
+
+    /// Return the result of the type checking of the mutant (`None` if the mutant wasn't checked).
+    #[must_use]
+    pub fn is_valid(&self) -> Option<bool> {
+        self.valid
+    }
+
+    /// Return the modifications applied to the file (to all files, for the mutants spanning multiple files).
+    #[must_use]
+    pub fn get_mutations(&self) -> &[Mutation] {
+        &self.mutations
+    }
+
+    /// Return the files changed by the mutant spanning multiple files (empty for the single-file mutants).
+    #[must_use]
+    pub fn get_changed_files(&self) -> &[ChangedFile] {
+        &self.changed_files
+    }
+
+    /// Returns the names of the distinct mutation operators applied to the mutant, in sorted order.
+    #[must_use]
+    pub fn operator_names(&self) -> BTreeSet<String> {
+        self.mutations
+            .iter()
+            .map(|m| m.operator_name.clone())
+            .collect()
+    }
+
+    // Internal function computing the identifier using the 64-bit FNV-1a hash. We use our own hash
+    // implementation as the `std` hashers are not guaranteed to be stable between Rust releases.
+    fn compute_id(&self) -> String {
+        const FNV_OFFSET_BASIS: u64 = 0xCBF2_9CE4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01B3;
+
+        let mut hash = FNV_OFFSET_BASIS;
+        let mut update = |data: &str| {
+            // Separate the fields, so different splits of the same string give different hashes.
+            for byte in data.bytes().chain(std::iter::once(0)) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        };
+
+        // Modules of the same name may be published at different addresses, and the scripts all have the
+        // same module name, so the address and the file are a part of the identifier.
+        update(&self.package_relative(&self.original_file));
+        if let Some(address) = &self.module_address {
+            update(address);
+        }
+        update(&self.module_name);
+        update(&self.function_name);
+        for mutation in &self.mutations {
+            update(&mutation.operator_name);
+            update(&mutation.changed_place.start.to_string());
+            update(&mutation.changed_place.end.to_string());
+            update(&mutation.old_value);
+            update(&mutation.new_value);
+        }
+        // The same modifications may be applied to the functions of different files.
+        for file in &self.changed_files {
+            update(&self.package_relative(&file.original_file));
+            update(&file.module_name);
+            update(&file.function_name);
+        }
+
+        format!("{hash:016x}")
+    }
+
+    // Internal function returning the path relative to the package root (if it's known), with the
+    // components separated by `/` on all platforms.
+    fn package_relative(&self, path: &Path) -> String {
+        let path = self
+            .package_root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        path.components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+// Internal function returning the path to the externalized diff of the file changed by the mutant spanning
+// multiple files (e.g. `diffs/<mutant-id>.0.diff` for the first file).
+fn changed_file_diff_path(diff_path: &Path, index: usize) -> PathBuf {
+    diff_path.with_extension(format!("{index}.diff"))
 }
 
 #[cfg(test)]
@@ -261,7 +1474,10 @@ mod tests {
     #[test]
     fn test_report() {
         let mut report = Report::new();
-        assert_eq!(report.to_json().unwrap(), "{\n  \"mutants\": []\n}");
+        assert_eq!(
+            report.to_json().unwrap(),
+            "{\n  \"schema_version\": 4,\n  \"mutants\": []\n}"
+        );
 
         let range = Range::new(0, 10);
         let modification = Mutation::new(
@@ -281,10 +1497,498 @@ mod tests {
         report_entry.add_modification(modification);
 
         report.add_entry(report_entry.clone());
+        assert_eq!(report_entry.get_id(), "e1b336d3691b5e55");
         assert_eq!(
             report.to_json().unwrap(),
-            "{\n  \"mutants\": [\n    {\n      \"mutant_path\": \"file\",\n      \"original_file\": \"original_file\",\n      \"module_name\": \"module\",\n      \"function_name\": \"function\",\n      \"mutations\": [\n        {\n          \"changed_place\": {\n            \"start\": 0,\n            \"end\": 10\n          },\n          \"operator_name\": \"operator\",\n          \"old_value\": \"old\",\n          \"new_value\": \"new\"\n        }\n      ],\n      \"diff\": \"--- original\\n+++ modified\\n@@ -1 +1 @@\\n-diff\\n+\\n\"\n    }\n  ]\n}"
+            "{\n  \"schema_version\": 4,\n  \"mutants\": [\n    {\n      \"id\": \"e1b336d3691b5e55\",\n      \"mutant_path\": \"file\",\n      \"original_file\": \"original_file\",\n      \"module_name\": \"module\",\n      \"function_name\": \"function\",\n      \"mutations\": [\n        {\n          \"changed_place\": {\n            \"start\": 0,\n            \"end\": 10\n          },\n          \"operator_name\": \"operator\",\n          \"old_value\": \"old\",\n          \"new_value\": \"new\"\n        }\n      ],\n      \"diff\": \"--- original\\n+++ modified\\n@@ -1 +1 @@\\n-diff\\n+\\n\",\n      \"excerpt\": {\n        \"start_line\": 1,\n        \"original\": \"diff\\n\",\n        \"mutated\": \"\\n\"\n      }\n    }\n  ],\n  \"operators\": {\n    \"operator\": {\n      \"sites\": 0,\n      \"generated\": 1,\n      \"skipped\": 0\n    }\n  }\n}"
+        );
+    }
+
+    #[test]
+    fn source_excerpt_covers_changed_lines_with_context() {
+        let original = "a\nb\nc\nd\ne\nf\n";
+        let mutated = "a\nb\nc\nX\nY\ne\nf\n";
+
+        let excerpt = SourceExcerpt::new(original, mutated, 1).unwrap();
+        assert_eq!(excerpt.start_line(), 3);
+        assert_eq!(excerpt.original(), "c\nd\ne\n");
+        assert_eq!(excerpt.mutated(), "c\nX\nY\ne\n");
+
+        // The context is limited by the start and the end of the file.
+        let excerpt = SourceExcerpt::new(original, mutated, 10).unwrap();
+        assert_eq!(excerpt.start_line(), 1);
+        assert_eq!(excerpt.original(), original);
+        assert_eq!(excerpt.mutated(), mutated);
+
+        let excerpt = SourceExcerpt::new(original, "a\nb\nc\ne\nf\n", 0).unwrap();
+        assert_eq!(excerpt.start_line(), 4);
+        assert_eq!(excerpt.original(), "d\n");
+        assert_eq!(excerpt.mutated(), "");
+
+        assert!(SourceExcerpt::new(original, original, 3).is_none());
+    }
+
+    #[test]
+    fn json_lines_report_is_streamed_and_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(ReportFormat::JsonLines.file_name());
+
+        let mut report = Report::new();
+        report.add_failure(FileFailure::new(
+            Path::new("broken.move"),
+            "error".to_owned(),
+        ));
+        let mut stream = ReportStream::create(&path).unwrap();
+        for function in ["f", "g"] {
+            let mut entry =
+                MutationReport::new(Path::new("a"), Path::new("o"), "module", function, "", "");
+            entry.add_modification(Mutation::new(
+                Range::new(0, 1),
+                "binary_operator_replacement".to_string(),
+                "+".to_string(),
+                "-".to_string(),
+            ));
+            stream.write_entry(&entry).unwrap();
+            report.count_entry(&entry);
+        }
+        let summary = stream.finish(&report).unwrap();
+        assert_eq!(summary.mutants, 2);
+        assert!(report.get_mutants().is_empty());
+
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines = contents.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("{\"kind\":\"mutant\""));
+        assert!(lines[2].starts_with("{\"kind\":\"summary\""));
+
+        let loaded = Report::load_from_json_lines_file(&path).unwrap();
+        assert_eq!(loaded.get_mutants().len(), 2);
+        assert_eq!(loaded.get_mutants()[1].get_function_name(), "g");
+        assert_eq!(
+            loaded.get_operators()["binary_operator_replacement"].generated,
+            2
+        );
+        assert_eq!(loaded.get_failures().len(), 1);
+
+        // The report of an unfinished run has no summary.
+        fs::write(&path, lines[0]).unwrap();
+        assert!(Report::load_from_json_lines_file(&path).is_err());
+    }
+
+    #[test]
+    fn report_details_are_configurable() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut entry = MutationReport::new(
+            Path::new("a"),
+            Path::new("o"),
+            "module",
+            "function",
+            "a - b\n",
+            "a + b\n",
+        );
+        entry.add_modification(Mutation::new(
+            Range::new(2, 3),
+            "binary_operator_replacement".to_string(),
+            "+".to_string(),
+            "-".to_string(),
+        ));
+        let diff = entry.get_diff().to_owned();
+        let mut report = Report::new();
+        report.add_entry(entry);
+
+        let path = dir.path().join("compact.json");
+        report
+            .save_with_details(&path, ReportFormat::Json, ReportDetails::Compact)
+            .unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(json["mutants"][0].get("diff").is_none());
+        assert!(json["mutants"][0].get("excerpt").is_none());
+        let loaded = Report::load_from_json_file(&path).unwrap();
+        assert_eq!(loaded.get_mutants()[0].get_diff(), "");
+        // The report in memory keeps the details.
+        assert_eq!(report.get_mutants()[0].get_diff(), diff);
+
+        let path = dir.path().join("external.json");
+        report
+            .save_with_details(&path, ReportFormat::Json, ReportDetails::External)
+            .unwrap();
+        let diff_path =
+            Path::new(DIFFS_DIR).join(format!("{}.diff", report.get_mutants()[0].get_id()));
+        assert_eq!(
+            fs::read_to_string(dir.path().join(&diff_path)).unwrap(),
+            diff
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(json["mutants"][0].get("diff").is_none());
+        let loaded = Report::load_from_json_file(&path).unwrap();
+        assert_eq!(
+            loaded.get_mutants()[0].get_diff_path(),
+            Some(diff_path.as_path())
+        );
+        assert_eq!(loaded.get_mutants()[0].get_diff(), diff);
+
+        let path = dir.path().join(ReportFormat::JsonLines.file_name());
+        let mut stream = ReportStream::create(&path)
+            .unwrap()
+            .with_details(ReportDetails::External);
+        stream.write_entry(&report.get_mutants()[0]).unwrap();
+        stream.finish(&report).unwrap();
+        let loaded = Report::load_from_json_lines_file(&path).unwrap();
+        assert_eq!(loaded.get_mutants()[0].get_diff(), diff);
+    }
+
+    #[test]
+    fn yaml_and_toml_reports_are_loaded_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut entry = MutationReport::new(
+            Path::new("a"),
+            Path::new("o"),
+            "module",
+            "function",
+            "a - b\n",
+            "a + b\n",
+        );
+        entry.add_modification(Mutation::new(
+            Range::new(2, 3),
+            "binary_operator_replacement".to_string(),
+            "+".to_string(),
+            "-".to_string(),
+        ));
+        entry.set_valid(true);
+        let mut report = Report::new();
+        report.add_entry(entry);
+        report.add_operator_sites("binary_operator_replacement", 2);
+        report.set_unsupported_sites(1);
+        report.add_failure(FileFailure::new(
+            Path::new("broken.move"),
+            "error".to_owned(),
+        ));
+
+        for format in [ReportFormat::Json, ReportFormat::Yaml, ReportFormat::Toml] {
+            let path = dir.path().join(format.file_name());
+            report
+                .save_with_details(&path, format, ReportDetails::Full)
+                .unwrap();
+            assert_eq!(ReportFormat::from_path(&path), Some(format));
+
+            let loaded = Report::load_from_file(&path).unwrap();
+            assert_eq!(
+                serde_json::to_value(&loaded).unwrap(),
+                serde_json::to_value(&report).unwrap()
+            );
+        }
+        assert_eq!(
+            Report::find_in_dir(dir.path()),
+            Some(dir.path().join("report.json"))
+        );
+        fs::remove_file(dir.path().join("report.json")).unwrap();
+        assert_eq!(
+            Report::find_in_dir(dir.path()),
+            Some(dir.path().join("report.yaml"))
+        );
+
+        assert!(report
+            .save_with_details(
+                &dir.path().join("report.txt"),
+                ReportFormat::Text,
+                ReportDetails::Full
+            )
+            .is_err());
+        assert_eq!(
+            ReportFormat::from_path(Path::new("report.yml")),
+            Some(ReportFormat::Yaml)
+        );
+        assert_eq!(ReportFormat::from_path(Path::new("report")), None);
+    }
+
+    #[test]
+    fn operator_statistics_are_counted() {
+        let mut report = Report::new();
+        report.add_operator_sites("binary_operator_replacement", 2);
+        report.add_operator_sites("literal_replacement", 1);
+        report.increment_operator_skipped("literal_replacement");
+        report.increment_operator_no_op("literal_replacement");
+
+        let mut entry =
+            MutationReport::new(Path::new("a"), Path::new("o"), "module", "function", "", "");
+        for _ in 0..2 {
+            entry.add_modification(Mutation::new(
+                Range::new(0, 1),
+                "binary_operator_replacement".to_string(),
+                "+".to_string(),
+                "-".to_string(),
+            ));
+        }
+        report.add_entry(entry);
+
+        assert_eq!(
+            report.get_operators()["binary_operator_replacement"],
+            OperatorStats {
+                sites: 2,
+                generated: 1,
+                skipped: 0,
+                no_op: 0,
+            }
+        );
+        assert_eq!(
+            report.get_operators()["literal_replacement"],
+            OperatorStats {
+                sites: 1,
+                generated: 0,
+                skipped: 1,
+                no_op: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn failures_are_recorded_once_per_file() {
+        let mut report = Report::new();
+        report.add_failure(FileFailure::new(Path::new("a.move"), "first".to_owned()));
+        report.add_failure(FileFailure::new(Path::new("a.move"), "second".to_owned()));
+        report.add_failure(FileFailure::new(Path::new("b.move"), "third".to_owned()));
+
+        assert_eq!(report.get_failures(), [
+            FileFailure::new(Path::new("a.move"), "first".to_owned()),
+            FileFailure::new(Path::new("b.move"), "third".to_owned())
+        ]);
+        let json = report.to_json().unwrap();
+        let loaded: Report = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_failures(), report.get_failures());
+    }
+
+    #[test]
+    fn unsupported_sites_are_saved_only_when_present() {
+        let mut report = Report::new();
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json.get("unsupported_sites").is_none());
+
+        report.set_unsupported_sites(3);
+        let json = serde_json::to_string(&report).unwrap();
+        let loaded: Report = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_unsupported_sites(), 3);
+    }
+
+    #[test]
+    fn mutant_id_depends_on_mutations() {
+        let mut first =
+            MutationReport::new(Path::new("a"), Path::new("o"), "module", "function", "", "");
+        let mut second =
+            MutationReport::new(Path::new("b"), Path::new("o"), "module", "function", "", "");
+        assert_eq!(first.get_id(), second.get_id());
+
+        first.add_modification(Mutation::new(
+            Range::new(0, 1),
+            "operator".to_string(),
+            "+".to_string(),
+            "-".to_string(),
+        ));
+        second.add_modification(Mutation::new(
+            Range::new(0, 1),
+            "operator".to_string(),
+            "+".to_string(),
+            "*".to_string(),
+        ));
+        assert_ne!(first.get_id(), second.get_id());
+        assert_eq!(first.get_id().len(), 16);
+    }
+
+    #[test]
+    fn validity_is_serialized_only_if_checked() {
+        let mut entry =
+            MutationReport::new(Path::new("a"), Path::new("o"), "module", "function", "", "");
+        let id = entry.get_id().to_owned();
+        assert_eq!(entry.is_valid(), None);
+        assert!(serde_json::to_value(&entry).unwrap().get("valid").is_none());
+
+        entry.set_valid(false);
+        assert_eq!(entry.is_valid(), Some(false));
+        assert_eq!(entry.get_id(), id);
+        let json = serde_json::to_string(&entry).unwrap();
+        assert!(json.contains("\"valid\":false"));
+        let entry: MutationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(entry.is_valid(), Some(false));
+    }
+
+    #[test]
+    fn multi_file_report_lists_changed_files() {
+        let file = |mutant: &str, original: &str, function: &str| {
+            let mut entry = MutationReport::new(
+                Path::new(mutant),
+                Path::new(original),
+                "module",
+                function,
+                "b\n",
+                "a\n",
+            );
+            entry.add_modification(Mutation::new(
+                Range::new(0, 1),
+                "operator".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+            ));
+            entry
+        };
+        let first = file("mutant/A.move", "sources/A.move", "callee");
+        let second = file("mutant/B.move", "sources/B.move", "caller");
+
+        let entry =
+            MutationReport::multi_file(Path::new("mutant"), vec![first.clone(), second.clone()]);
+        assert_eq!(entry.mutant_path(), Path::new("mutant"));
+        assert_eq!(entry.original_file_path(), Path::new("sources/A.move"));
+        assert_eq!(entry.get_function_name(), "callee");
+        assert_eq!(entry.get_mutations().len(), 2);
+        assert_eq!(
+            entry.get_diff(),
+            format!("{}{}", first.get_diff(), second.get_diff())
         );
+        assert_ne!(entry.get_id(), first.get_id());
+
+        let changed = entry.get_changed_files();
+        assert_eq!(changed.len(), 2);
+        assert_eq!(changed[1].mutant_path(), Path::new("mutant/B.move"));
+        assert_eq!(changed[1].original_file_path(), Path::new("sources/B.move"));
+        assert_eq!(changed[1].get_function_name(), "caller");
+        assert_eq!(changed[1].get_mutations(), second.get_mutations());
+        assert_eq!(changed[1].get_diff(), second.get_diff());
+
+        // The diffs of the changed files are externalized along with the diff of the mutant.
+        let dir = tempfile::tempdir().unwrap();
+        let mut report = Report::new();
+        report.add_entry(entry.clone());
+        let path = dir.path().join("external.json");
+        report
+            .save_with_details(&path, ReportFormat::Json, ReportDetails::External)
+            .unwrap();
+        let loaded = Report::load_from_json_file(&path).unwrap();
+        let loaded = &loaded.get_mutants()[0];
+        assert_eq!(loaded.get_diff(), entry.get_diff());
+        assert_eq!(loaded.get_changed_files()[0].get_diff(), first.get_diff());
+        assert_eq!(loaded.get_changed_files()[1].get_diff(), second.get_diff());
+
+        let schema = Report::json_schema();
+        let json = serde_json::to_value(&entry).unwrap();
+        for field in json["changed_files"][0].as_object().unwrap().keys() {
+            assert!(
+                schema["$defs"]["changed_file"]["properties"]
+                    .get(field)
+                    .is_some(),
+                "{field}"
+            );
+        }
+        assert!(serde_json::to_value(&first)
+            .unwrap()
+            .get("changed_files")
+            .is_none());
+    }
+
+    #[test]
+    fn ids_depend_on_address_and_package_relative_file() {
+        let entry = |original: &str, address: Option<&str>| {
+            let mut entry =
+                MutationReport::new(Path::new(""), Path::new(original), "M", "f", "b\n", "a\n");
+            entry.add_modification(Mutation::new(
+                Range::new(0, 1),
+                "operator".to_string(),
+                "a".to_string(),
+                "b".to_string(),
+            ));
+            if let Some(address) = address {
+                entry.set_module_address(address);
+            }
+            entry
+        };
+
+        let mut first = entry("/a/sources/M.move", Some("0x1"));
+        assert_ne!(
+            first.get_id(),
+            entry("/a/sources/M.move", Some("0x2")).get_id()
+        );
+        assert_ne!(
+            first.get_id(),
+            entry("/a/sources/N.move", Some("0x1")).get_id()
+        );
+
+        assert_eq!(first.qualified_function_name(), "0x1::M::f");
+        assert_eq!(
+            entry("/a/scripts/main.move", None).qualified_function_name(),
+            "M::f"
+        );
+
+        // The identifier doesn't depend on the location of the package.
+        first.set_package_root(Path::new("/a"));
+        let mut moved = entry("/b/sources/M.move", Some("0x1"));
+        moved.set_package_root(Path::new("/b"));
+        assert_eq!(first.get_id(), moved.get_id());
+    }
+
+    #[test]
+    fn loads_legacy_report_without_version() {
+        let path = Path::new("test_legacy_report.json");
+        fs::write(
+            path,
+            r#"{"mutants":[{"mutant_path":"file","original_file":"original_file","module_name":"module","function_name":"function","mutations":[{"changed_place":{"start":0,"end":10},"operator_name":"operator","old_value":"old","new_value":"new"}],"diff":""}]}"#,
+        )
+        .unwrap();
+        let report = Report::load_from_json_file(path);
+        fs::remove_file(path).unwrap();
+
+        let report = report.unwrap();
+        assert_eq!(report.schema_version(), REPORT_SCHEMA_VERSION);
+        assert_eq!(report.get_mutants()[0].get_id(), "e1b336d3691b5e55");
+    }
+
+    #[test]
+    fn rejects_report_with_newer_version() {
+        let path = Path::new("test_future_report.json");
+        fs::write(path, r#"{"schema_version":1000,"mutants":[]}"#).unwrap();
+        let report = Report::load_from_json_file(path);
+        fs::remove_file(path).unwrap();
+
+        let err = report.unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Unsupported report schema version 1000"));
+    }
+
+    #[test]
+    fn json_schema_describes_all_report_fields() {
+        let schema = Report::json_schema();
+        let report = serde_json::to_value(Report::new()).unwrap();
+        for field in report.as_object().unwrap().keys() {
+            assert!(schema["properties"].get(field).is_some(), "{field}");
+        }
+
+        let mut report = Report::new();
+        report.add_operator_sites("operator", 1);
+        let stats = serde_json::to_value(report.get_operators()["operator"]).unwrap();
+        for field in stats.as_object().unwrap().keys() {
+            assert!(
+                schema["$defs"]["operator_stats"]["properties"]
+                    .get(field)
+                    .is_some(),
+                "{field}"
+            );
+        }
+
+        let mut entry =
+            MutationReport::new(Path::new("a"), Path::new("o"), "module", "function", "", "");
+        entry.add_modification(Mutation::new(
+            Range::new(0, 1),
+            "operator".to_string(),
+            "+".to_string(),
+            "-".to_string(),
+        ));
+        entry.set_valid(true);
+        let entry = serde_json::to_value(&entry).unwrap();
+        for field in entry.as_object().unwrap().keys() {
+            assert!(
+                schema["$defs"]["mutant"]["properties"].get(field).is_some(),
+                "{field}"
+            );
+        }
     }
 
     #[test]
@@ -308,6 +2012,23 @@ mod tests {
         assert_eq!(serde_json::to_string(&modification).unwrap(), "{\"changed_place\":{\"start\":0,\"end\":10},\"operator_name\":\"operator\",\"old_value\":\"old\",\"new_value\":\"new\"}");
     }
 
+    #[test]
+    fn test_modification_with_variant_change() {
+        let modification = Mutation::new(
+            Range::new(0, 10),
+            "operator".to_string(),
+            "Shape::Square".to_string(),
+            "Shape::Empty".to_string(),
+        )
+        .with_variant_change("Square", "Empty");
+        let json = serde_json::to_string(&modification).unwrap();
+        assert!(json.ends_with(r#""variant_change":{"from":"Square","to":"Empty"}}"#));
+        assert_eq!(
+            serde_json::from_str::<Mutation>(&json).unwrap(),
+            modification
+        );
+    }
+
     #[test]
     fn saves_report_as_text_file_successfully() {
         let mut report = Report::new();
@@ -330,11 +2051,14 @@ mod tests {
         report.add_entry(report_entry);
 
         let path = Path::new("test_report.txt");
-        report.save_to_text_file(path).unwrap();
+        report
+            .save_to_text_file(path, DiffStyle::default())
+            .unwrap();
 
         let mut file = fs::File::open(path).unwrap();
         let mut contents = String::new();
         file.read_to_string(&mut contents).unwrap();
+        assert!(contents.contains("Mutant ID: "));
         assert!(contents.contains("Mutant path: file"));
         assert!(contents.contains("Original file: original_file"));
         assert!(contents.contains("Module name: module"));
@@ -353,6 +2077,8 @@ mod tests {
     fn fails_to_save_report_to_non_existent_directory() {
         let report = Report::new();
         let path = Path::new("non_existent_directory/test_report.txt");
-        report.save_to_text_file(path).unwrap();
+        report
+            .save_to_text_file(path, DiffStyle::default())
+            .unwrap();
     }
 }
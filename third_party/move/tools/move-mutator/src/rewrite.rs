@@ -0,0 +1,395 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Formatting-preserving rewriting of the sources.
+//!
+//! The mutants differ from the original source only in the mutated span: the rest of the file is kept byte
+//! for byte, the replacement uses the line endings of the file and it never has fewer lines than the
+//! replaced code. So the diff of each mutant is a single hunk and the line numbers of the mutant map 1:1 to
+//! the original (up to the end of the span, if the replacement adds lines).
+//!
+//! The spans are byte offsets, so they must lie on the character boundaries (multi-byte characters may
+//! appear in the comments) and they must not split the CRLF line endings. Such spans are rejected instead
+//! of panicking or misaligning the lines of the mutant.
+//!
+//! The operators mutate the places given by the locations of the AST nodes, so the mutated spans never
+//! start or end inside a comment or a string literal - unless the locations don't match the source (e.g.
+//! because of a stale source map). Such spans are rejected as well (see `splits_comment_or_string`).
+
+use move_command_line_common::files::FileHash;
+use move_compiler::parser::lexer::{Lexer, Tok};
+
+/// Replacement of a span of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rewrite {
+    start: usize,
+    end: usize,
+    replacement: String,
+}
+
+impl Rewrite {
+    /// Creates the rewrite replacing the `start..end` span of the source. The line endings of the
+    /// replacement are converted to the ones used by the source, and if the replacement has fewer lines
+    /// than the span, it's padded with the line endings, so the following lines keep their numbers.
+    #[must_use]
+    pub fn new(source: &str, start: usize, end: usize, replacement: &str) -> Self {
+        let span = source.get(start..end).unwrap_or_default();
+        let line_ending =
+            if span.contains("\r\n") || (!span.contains('\n') && source.contains("\r\n")) {
+                "\r\n"
+            } else {
+                "\n"
+            };
+
+        let mut replacement = if line_ending == "\r\n" {
+            replacement.replace("\r\n", "\n").replace('\n', "\r\n")
+        } else {
+            replacement.to_owned()
+        };
+        let missing_lines = span
+            .matches('\n')
+            .count()
+            .saturating_sub(replacement.matches('\n').count());
+        replacement.push_str(&line_ending.repeat(missing_lines));
+
+        Self {
+            start,
+            end,
+            replacement,
+        }
+    }
+
+    /// Returns the rewrite of the `start..end` span turning the original source into the mutated one, or
+    /// `None` if the sources differ outside of the span.
+    #[must_use]
+    pub fn of_span(original: &str, mutated: &str, start: usize, end: usize) -> Option<Self> {
+        if !is_valid_span(original, start, end) {
+            return None;
+        }
+        let prefix = &original[..start];
+        let suffix = &original[end..];
+        if !mutated.starts_with(prefix)
+            || !mutated.ends_with(suffix)
+            || mutated.len() < prefix.len() + suffix.len()
+        {
+            return None;
+        }
+
+        Some(Self {
+            start,
+            end,
+            replacement: mutated.get(start..mutated.len() - suffix.len())?.to_owned(),
+        })
+    }
+
+    /// Applies the rewrite to the source.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the span isn't a valid range of the source (see `is_valid_span`).
+    pub fn apply(&self, source: &str) -> anyhow::Result<String> {
+        if !is_valid_span(source, self.start, self.end) {
+            anyhow::bail!(
+                "The span {}..{} isn't a valid range of the source",
+                self.start,
+                self.end
+            );
+        }
+
+        let mut rewritten =
+            String::with_capacity(source.len() - (self.end - self.start) + self.replacement.len());
+        rewritten.push_str(&source[..self.start]);
+        rewritten.push_str(&self.replacement);
+        rewritten.push_str(&source[self.end..]);
+        Ok(rewritten)
+    }
+}
+
+/// Checks if the `start..end` span is a valid range of the source: it's inside of the source, it lies on
+/// the character boundaries and it doesn't split the CRLF line ending.
+#[must_use]
+pub fn is_valid_span(source: &str, start: usize, end: usize) -> bool {
+    let splits_line_ending = |offset: usize| {
+        offset > 0
+            && source.as_bytes().get(offset - 1) == Some(&b'\r')
+            && source.as_bytes().get(offset) == Some(&b'\n')
+    };
+    source.get(start..end).is_some() && !splits_line_ending(start) && !splits_line_ending(end)
+}
+
+/// Returns the spans of the comments (including the doc comments) and of the byte and hex string literals
+/// of the Move source, in the order of the source, as found by the Move lexer. The comments between two
+/// tokens form a single span (without the whitespaces around it), and the unterminated comments and
+/// literals extend to the end of the source.
+#[must_use]
+pub fn comment_and_string_spans(source: &str) -> Vec<(usize, usize)> {
+    // The whitespaces around the comments are not a part of their span.
+    let comments = |from: usize, to: usize| {
+        let (from, to) = trim_span(source, from, to);
+        (from < to).then_some((from, to))
+    };
+
+    let mut spans = vec![];
+    let mut lexer = Lexer::new(source, FileHash::empty());
+    loop {
+        if lexer.advance().is_err() {
+            // The rest of the source can't be lexed, it's a span only if it's an unterminated comment or
+            // literal.
+            let (from, _) = trim_span(source, lexer.previous_end_loc(), source.len());
+            let rest = &source[from..];
+            if ["/*", "//", "b\"", "x\""]
+                .iter()
+                .any(|opening| rest.starts_with(opening))
+            {
+                spans.extend(comments(from, source.len()));
+            }
+            break;
+        }
+
+        spans.extend(comments(lexer.previous_end_loc(), lexer.start_loc()));
+        match lexer.peek() {
+            Tok::EOF => break,
+            Tok::ByteStringValue => {
+                spans.push((lexer.start_loc(), lexer.start_loc() + lexer.content().len()))
+            },
+            _ => {},
+        }
+    }
+    spans
+}
+
+/// Checks if the `start..end` span starts or ends inside a comment or a string literal of the source (see
+/// `comment_and_string_spans`). The spans containing whole comments (e.g. between the operands of the
+/// mutated operator) are fine.
+#[must_use]
+pub fn splits_comment_or_string(source: &str, start: usize, end: usize) -> bool {
+    comment_and_string_spans(source)
+        .into_iter()
+        .any(|(from, to)| (from < start && start < to) || (from < end && end < to))
+}
+
+/// Returns the `start..end` span of the source without the leading and the trailing whitespaces and
+/// comments (e.g. the operator between the operands, without the comments next to it).
+///
+/// # Panics
+///
+/// The function will panic if the span isn't a valid range of the source.
+#[must_use]
+pub fn trim_comments(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let comments = comment_and_string_spans(source)
+        .into_iter()
+        .filter(|(from, _)| source[*from..].starts_with('/'))
+        .collect::<Vec<_>>();
+    let (mut start, mut end) = trim_span(source, start, end);
+    while let Some(&(_, to)) = comments
+        .iter()
+        .find(|(from, to)| *from == start && *to <= end)
+    {
+        (start, end) = trim_span(source, to, end);
+    }
+    while let Some(&(from, _)) = comments
+        .iter()
+        .find(|(from, to)| *to == end && *from >= start)
+    {
+        (start, end) = trim_span(source, start, from);
+    }
+    (start, end)
+}
+
+/// Returns the `start..end` span of the source without the leading and the trailing whitespaces. The
+/// returned span lies on the character boundaries, as the given one.
+///
+/// # Panics
+///
+/// The function will panic if the span isn't a valid range of the source.
+#[must_use]
+pub fn trim_span(source: &str, start: usize, end: usize) -> (usize, usize) {
+    let span = &source[start..end];
+    let trimmed = span.trim_start();
+    let start = start + span.len() - trimmed.len();
+    (start, start + trimmed.trim_end().len())
+}
+
+/// Replaces the `start..end` span of the source with the replacement (see `Rewrite::new`) and returns the
+/// rewritten source.
+///
+/// # Panics
+///
+/// The function will panic if the span isn't a valid range of the source (the spans come from the
+/// locations of the compiled source, so they always are).
+#[must_use]
+pub fn replace(source: &str, start: usize, end: usize, replacement: &str) -> String {
+    Rewrite::new(source, start, end, replacement)
+        .apply(source)
+        .expect("Mutated span must be a valid range of the source")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::SourceExcerpt;
+    use proptest::prelude::*;
+
+    #[test]
+    fn only_mutated_span_differs() {
+        let source = "fun f() {\r\n    a + b\r\n}\r\n";
+        let start = source.find('+').unwrap();
+        let mutated = replace(source, start, start + 1, "-");
+        assert_eq!(mutated, "fun f() {\r\n    a - b\r\n}\r\n");
+
+        let rewrite = Rewrite::of_span(source, &mutated, start, start + 1).unwrap();
+        assert_eq!(rewrite.replacement, "-");
+        assert_eq!(rewrite.apply(source).unwrap(), mutated);
+        // The sources differ outside of the span.
+        assert!(
+            Rewrite::of_span(source, &mutated.replace("\r\n", "\n"), start, start + 1).is_none()
+        );
+    }
+
+    #[test]
+    fn lines_keep_their_numbers() {
+        let source = "{\n    foo(\n        a,\n    );\n    bar();\n}";
+        let start = source.find("foo").unwrap();
+        let end = source.find(");").unwrap() + 1;
+        let mutated = replace(source, start, end, "{}");
+        assert_eq!(mutated, "{\n    {}\n\n;\n    bar();\n}");
+        assert_eq!(mutated.lines().count(), source.lines().count());
+
+        // The line endings of the replacement follow the source.
+        let source = "a;\r\nb;\r\nc;";
+        let mutated = replace(source, 0, 6, "b;\na;");
+        assert_eq!(mutated, "b;\r\na;\r\nc;");
+    }
+
+    #[test]
+    fn invalid_spans_are_rejected() {
+        assert!(Rewrite::new("abc", 2, 5, "").apply("abc").is_err());
+        assert!(Rewrite::of_span("abc", "abc", 2, 5).is_none());
+        // Multi-byte characters can't be split.
+        assert!(Rewrite::of_span("żółw", "żółć", 1, 2).is_none());
+        assert!(Rewrite::new("żółw", 1, 2, "o").apply("żółw").is_err());
+        // The CRLF line endings can't be split.
+        assert!(!is_valid_span("a\r\nb", 2, 4));
+        assert!(!is_valid_span("a\r\nb", 0, 2));
+        assert!(is_valid_span("a\r\nb", 1, 3));
+        assert_eq!(
+            Rewrite::of_span("żółw", "żółć", 6, 7).unwrap().replacement,
+            "ć"
+        );
+    }
+
+    #[test]
+    fn spans_are_trimmed_on_char_boundaries() {
+        let source = "x + /* ż */\u{3000}y";
+        let (start, end) = trim_span(source, 1, source.find('y').unwrap());
+        assert_eq!(&source[start..end], "+ /* ż */");
+        assert_eq!(trim_span(" \r\n ", 0, 4), (4, 4));
+    }
+
+    #[test]
+    fn comments_and_strings_are_found() {
+        let source =
+            "/// doc\nfun f() { /* a /* nested */ b */ x\"1\" + b\"a\\\"//\" - y // end\r\n}";
+        let spans = comment_and_string_spans(source)
+            .into_iter()
+            .map(|(start, end)| &source[start..end])
+            .collect::<Vec<_>>();
+        assert_eq!(spans, [
+            "/// doc",
+            "/* a /* nested */ b */",
+            "x\"1\"",
+            "b\"a\\\"//\"",
+            "// end"
+        ]);
+        // The identifiers ending with `b` or `x` don't start the literals.
+        assert!(comment_and_string_spans("fn_x\"").is_empty());
+        assert_eq!(comment_and_string_spans("a /* open"), [(2, 9)]);
+
+        let source = "x /* c */ + y // -";
+        let plus = source.find('+').unwrap();
+        assert!(!splits_comment_or_string(source, 2, plus + 1));
+        assert!(splits_comment_or_string(source, 4, plus + 1));
+        assert!(splits_comment_or_string(source, plus, source.len() - 1));
+        assert!(!splits_comment_or_string(source, plus, source.len()));
+
+        let source = "x /* a */ // b\r\n + /* c */ y";
+        let (start, end) = trim_comments(source, 1, source.find('y').unwrap());
+        assert_eq!(&source[start..end], "+");
+        assert_eq!(trim_comments("/* a */", 0, 7), (7, 7));
+    }
+
+    // Pieces the generated sources are made of: CRLF is a single piece, so the piece boundaries are the
+    // valid spans of the source.
+    const PIECES: &[&str] = &[
+        "a", "+", " ", "\t", "\n", "\r\n", "ż", "€", "𝄞", "e\u{301}", "\u{3000}",
+    ];
+
+    fn pieces(max: usize) -> impl Strategy<Value = Vec<&'static str>> {
+        prop::collection::vec(prop::sample::select(PIECES), 0..max)
+    }
+
+    // Returns the source with the span given by the piece indexes.
+    fn source_with_span(pieces: &[&str], first: usize, last: usize) -> (String, usize, usize) {
+        let (first, last) = (first.min(last), first.max(last));
+        let offset = |index: usize| pieces[..index.min(pieces.len())].concat().len();
+        (pieces.concat(), offset(first), offset(last))
+    }
+
+    proptest! {
+        #[test]
+        fn rewrites_keep_the_rest_of_the_source(
+            pieces in pieces(40),
+            first in 0..40usize,
+            last in 0..40usize,
+            replacement in pieces(10),
+        ) {
+            let (source, start, end) = source_with_span(&pieces, first, last);
+            let replacement = replacement.concat();
+            prop_assert!(is_valid_span(&source, start, end));
+
+            let mutated = replace(&source, start, end, &replacement);
+            prop_assert!(mutated.starts_with(&source[..start]));
+            prop_assert!(mutated.ends_with(&source[end..]));
+            // The following lines keep their numbers, unless the replacement adds lines.
+            let lines = |text: &str| text.matches('\n').count();
+            prop_assert_eq!(
+                lines(&mutated),
+                lines(&source) + lines(&replacement).saturating_sub(lines(&source[start..end]))
+            );
+            // A source using only CRLF doesn't get bare line feeds.
+            if source.contains("\r\n")
+                && source.matches('\n').count() == source.matches("\r\n").count()
+            {
+                prop_assert_eq!(mutated.matches('\n').count(), mutated.matches("\r\n").count());
+            }
+
+            let rewrite = Rewrite::of_span(&source, &mutated, start, end).unwrap();
+            prop_assert_eq!(rewrite.apply(&source).unwrap(), mutated.clone());
+            if let Some(excerpt) = SourceExcerpt::new(&source, &mutated, 0) {
+                prop_assert!(excerpt.start_line() > lines(&source[..start]));
+            }
+        }
+
+        #[test]
+        fn invalid_spans_never_panic(
+            pieces in pieces(20),
+            start in 0..80usize,
+            end in 0..80usize,
+            replacement in pieces(5),
+        ) {
+            let source = pieces.concat();
+            let replacement = replacement.concat();
+            let rewrite = Rewrite::new(&source, start, end, &replacement);
+            prop_assert_eq!(rewrite.apply(&source).is_ok(), is_valid_span(&source, start, end));
+            prop_assert_eq!(
+                Rewrite::of_span(&source, &source, start, end).is_some(),
+                is_valid_span(&source, start, end)
+            );
+            if is_valid_span(&source, start, end) {
+                let (trimmed_start, trimmed_end) = trim_span(&source, start, end);
+                prop_assert_eq!(&source[trimmed_start..trimmed_end], source[start..end].trim());
+            }
+        }
+    }
+}
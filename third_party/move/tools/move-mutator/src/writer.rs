@@ -0,0 +1,124 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, SyncSender},
+    thread::{self, JoinHandle},
+};
+
+/// Default number of the mutants waiting in the queue of the writer thread.
+pub const DEFAULT_WRITE_BUFFER: usize = 64;
+
+/// Writer saving the mutant files on a dedicated thread, so the generation of the next mutants doesn't
+/// wait for the disk.
+///
+/// At most `buffer` mutants wait in the queue, so the memory stays bounded for large runs. With the buffer
+/// of zero mutants, the files are written synchronously by the calling thread.
+pub(crate) struct MutantWriter {
+    sender: Option<SyncSender<(PathBuf, String)>>,
+    worker: Option<JoinHandle<BTreeMap<PathBuf, String>>>,
+}
+
+impl MutantWriter {
+    /// Creates the writer with the queue of the given size.
+    pub(crate) fn new(buffer: usize) -> Self {
+        if buffer == 0 {
+            return Self {
+                sender: None,
+                worker: None,
+            };
+        }
+
+        let (sender, receiver) = mpsc::sync_channel::<(PathBuf, String)>(buffer);
+        let worker = thread::spawn(move || {
+            let mut failures = BTreeMap::new();
+            for (path, contents) in receiver {
+                if let Err(e) = fs::write(&path, contents) {
+                    failures.insert(path, e.to_string());
+                }
+            }
+            failures
+        });
+
+        Self {
+            sender: Some(sender),
+            worker: Some(worker),
+        }
+    }
+
+    /// Schedules writing the contents to the file. Errors of the scheduled writes are returned by `finish`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written synchronously or if the writer thread has stopped.
+    pub(crate) fn write(&self, path: &Path, contents: &str) -> anyhow::Result<()> {
+        match &self.sender {
+            Some(sender) => sender
+                .send((path.to_path_buf(), contents.to_owned()))
+                .map_err(|_| anyhow::anyhow!("The mutant writer thread has stopped")),
+            None => Ok(fs::write(path, contents)?),
+        }
+    }
+
+    /// Waits until all scheduled files are written and returns the errors of the failed writes, keyed by
+    /// the path of the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the writer thread panicked.
+    pub(crate) fn finish(mut self) -> anyhow::Result<BTreeMap<PathBuf, String>> {
+        // Closing the queue stops the writer thread once it writes the remaining files.
+        drop(self.sender.take());
+        match self.worker.take() {
+            Some(worker) => worker
+                .join()
+                .map_err(|_| anyhow::anyhow!("The mutant writer thread panicked")),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queued_files_are_written_before_finish_returns() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = MutantWriter::new(2);
+        for index in 0..10 {
+            writer
+                .write(
+                    &dir.path().join(format!("{index}.move")),
+                    &index.to_string(),
+                )
+                .unwrap();
+        }
+        let missing = dir.path().join("missing").join("mutant.move");
+        writer.write(&missing, "module").unwrap();
+
+        let failures = writer.finish().unwrap();
+        for index in 0..10 {
+            let contents = fs::read_to_string(dir.path().join(format!("{index}.move"))).unwrap();
+            assert_eq!(contents, index.to_string());
+        }
+        assert_eq!(failures.len(), 1);
+        assert!(failures.contains_key(&missing));
+    }
+
+    #[test]
+    fn zero_buffer_writes_synchronously() {
+        let dir = tempfile::tempdir().unwrap();
+        let writer = MutantWriter::new(0);
+        writer.write(&dir.path().join("a.move"), "a").unwrap();
+        assert_eq!(fs::read_to_string(dir.path().join("a.move")).unwrap(), "a");
+        assert!(writer
+            .write(&dir.path().join("missing").join("b.move"), "b")
+            .is_err());
+        assert!(writer.finish().unwrap().is_empty());
+    }
+}
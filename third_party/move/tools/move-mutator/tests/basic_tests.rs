@@ -2,7 +2,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use move_mutator::cli::{CLIOptions, ModuleFilter};
+use move_model::metadata::LanguageVersion;
+use move_mutator::{
+    cli::{CLIOptions, ModuleFilter, OutputLayout},
+    configuration::Configuration,
+    generator::MutantGenerator,
+    report::ReportFormat,
+};
 use move_package::BuildConfig;
 use std::path::{Path, PathBuf};
 use tempfile::tempdir;
@@ -31,6 +37,7 @@ fn check_mutator_works_correctly() {
         downsample_filter: None,
         downsampling_ratio_percentage: None,
         configuration_file: None,
+        ..Default::default()
     };
 
     let config = BuildConfig::default();
@@ -62,6 +69,7 @@ fn check_mutator_verify_mutants_correctly() {
         downsample_filter: None,
         downsampling_ratio_percentage: None,
         configuration_file: None,
+        ..Default::default()
     };
 
     let config = BuildConfig::default();
@@ -92,6 +100,7 @@ fn check_mutator_fails_on_non_existing_path() {
         downsample_filter: None,
         downsampling_ratio_percentage: None,
         configuration_file: None,
+        ..Default::default()
     };
 
     let config = BuildConfig::default();
@@ -114,6 +123,7 @@ fn check_mutator_fails_on_non_existing_output_path() {
         downsample_filter: None,
         downsampling_ratio_percentage: None,
         configuration_file: None,
+        ..Default::default()
     };
 
     let config = BuildConfig::default();
@@ -138,6 +148,7 @@ fn check_mutator_works_with_single_files() {
         downsample_filter: None,
         downsampling_ratio_percentage: None,
         configuration_file: None,
+        ..Default::default()
     };
 
     let config = BuildConfig::default();
@@ -169,6 +180,7 @@ fn check_mutator_fails_verify_file_without_package() {
         downsample_filter: None,
         downsampling_ratio_percentage: None,
         configuration_file: None,
+        ..Default::default()
     };
 
     let config = BuildConfig::default();
@@ -184,3 +196,1393 @@ fn check_mutator_fails_verify_file_without_package() {
     let report = move_mutator::report::Report::load_from_json_file(&report_path).unwrap();
     assert!(report.get_mutants().is_empty());
 }
+
+// Check if the mutator mutates only the externally reachable functions when requested.
+#[test]
+fn check_mutator_mutates_only_entry_functions() {
+    let outdir = tempdir().unwrap().into_path();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        entry_functions_only: true,
+        ..Default::default()
+    };
+
+    let config = BuildConfig::default();
+
+    // The externally reachable functions of the basic_coin package are the `BasicCoin::publish_balance`
+    // entry function and the scripts (`publish_balance`, `mint_some` and `test`).
+    let package_path = Path::new("tests/move-assets/basic_coin");
+
+    let result = move_mutator::run_move_mutator(options.clone(), &config, package_path);
+    assert!(result.is_ok());
+
+    let report_path = outdir.join("report.json");
+    let report = move_mutator::report::Report::load_from_json_file(&report_path).unwrap();
+    assert!(!report.get_mutants().is_empty());
+    assert!(report
+        .get_mutants()
+        .iter()
+        .all(|m| ["publish_balance", "mint_some", "test"].contains(&m.get_function_name())));
+
+    // The simple package doesn't contain any entry functions.
+    let package_path = Path::new("tests/move-assets/simple");
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report = move_mutator::report::Report::load_from_json_file(&report_path).unwrap();
+    assert!(report.get_mutants().is_empty());
+}
+
+// Mutants are verified in parallel, but the output must not depend on the order of the verification.
+#[test]
+fn check_mutator_output_is_deterministic() {
+    let config = BuildConfig::default();
+    let package_path = Path::new(PACKAGE_PATHS[1]);
+
+    let runs = (0..2)
+        .map(|_| {
+            let outdir = tempdir().unwrap().into_path();
+            let options = CLIOptions {
+                out_mutant_dir: Some(outdir.clone()),
+                verify_mutants: true,
+                ..Default::default()
+            };
+
+            let result = move_mutator::run_move_mutator(options, &config, package_path);
+            assert!(result.is_ok());
+
+            let report =
+                move_mutator::report::Report::load_from_json_file(&outdir.join("report.json"))
+                    .unwrap();
+            report
+                .get_mutants()
+                .iter()
+                .map(|m| {
+                    let file_name = m.mutant_path().file_name().unwrap().to_owned();
+                    let operator = m.get_mutations()[0].get_operator_name().to_owned();
+                    (m.get_id().to_owned(), operator, file_name)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!runs[0].is_empty());
+    assert_eq!(runs[0], runs[1]);
+
+    // Mutant file names are derived from the mutants, e.g. `Sum_binary_operator_replacement_5_<id>.move`.
+    for (id, operator, file_name) in &runs[0] {
+        let file_name = file_name.to_string_lossy();
+        assert!(file_name.contains(&format!("_{operator}_")), "{file_name}");
+        assert!(file_name.ends_with(&format!("_{id}.move")), "{file_name}");
+    }
+}
+
+// In the deterministic mode, the downsampled mutants are the same in every run.
+#[test]
+fn check_mutator_downsampling_is_deterministic() {
+    let config = BuildConfig::default();
+    let package_path = Path::new(PACKAGE_PATHS[1]);
+
+    let runs = (0..2)
+        .map(|_| {
+            let outdir = tempdir().unwrap().into_path();
+            let options = CLIOptions {
+                out_mutant_dir: Some(outdir.clone()),
+                downsampling_ratio_percentage: Some(50),
+                deterministic: true,
+                ..Default::default()
+            };
+
+            let result = move_mutator::run_move_mutator(options, &config, package_path);
+            assert!(result.is_ok());
+
+            let report =
+                move_mutator::report::Report::load_from_json_file(&outdir.join("report.json"))
+                    .unwrap();
+            report
+                .get_mutants()
+                .iter()
+                .map(|m| m.get_id().to_owned())
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    assert!(!runs[0].is_empty());
+    assert_eq!(runs[0], runs[1]);
+}
+
+// The manifest describing the generated mutants must match the mutants directory and the package sources.
+#[test]
+fn check_mutator_writes_valid_manifest() {
+    let outdir = tempdir().unwrap().into_path();
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        ..Default::default()
+    };
+    let package_path = Path::new(PACKAGE_PATHS[1]);
+
+    let result = move_mutator::run_move_mutator(options, &BuildConfig::default(), package_path);
+    assert!(result.is_ok());
+
+    let manifest = move_mutator::manifest::Manifest::load(&outdir)
+        .unwrap()
+        .unwrap();
+    assert!(manifest.verify_integrity(&outdir).is_ok());
+    assert!(manifest.stale_sources(package_path).is_empty());
+    assert_eq!(
+        manifest.package_root().unwrap(),
+        package_path.canonicalize().unwrap()
+    );
+}
+
+#[test]
+fn check_mutator_mutates_callees_of_selected_functions() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/callees");
+    let config = BuildConfig::default();
+
+    let mutated_functions = |include_callees| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            entry_functions_only: true,
+            include_callees,
+            ..Default::default()
+        };
+
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        report
+            .get_mutants()
+            .iter()
+            .map(|m| m.get_function_name().to_owned())
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    assert_eq!(mutated_functions(false), ["run".to_owned()].into());
+    // Private helpers are mutated transitively, unrelated and public functions are not.
+    assert_eq!(
+        mutated_functions(true),
+        ["helper".to_owned(), "inner".to_owned(), "run".to_owned()].into()
+    );
+}
+
+// Check if the test code (test functions, test only functions and modules) is skipped unless it's included
+// explicitly.
+#[test]
+fn check_mutator_skips_test_code() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/test_code");
+    // The test code is compiled only in the test mode.
+    let config = BuildConfig {
+        test_mode: true,
+        ..Default::default()
+    };
+
+    let mutated_functions = |include_test_code| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            include_test_code,
+            ..Default::default()
+        };
+
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        report
+            .get_mutants()
+            .iter()
+            .map(|m| m.get_function_name().to_owned())
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    assert_eq!(mutated_functions(false), ["increment".to_owned()].into());
+    assert_eq!(
+        mutated_functions(true),
+        [
+            "expected".to_owned(),
+            "increment".to_owned(),
+            "increment_twice".to_owned(),
+            "test_increment".to_owned(),
+            "test_increment_twice".to_owned()
+        ]
+        .into()
+    );
+}
+
+// Check if the JSON lines report alone streams the mutants without generating the other reports, and if it
+// contains the same mutants as the JSON report.
+#[test]
+fn check_mutator_streams_json_lines_report() {
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let run = |report_formats| {
+        let outdir = tempdir().unwrap().into_path();
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            report_formats,
+            ..Default::default()
+        };
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+        outdir
+    };
+
+    let streamed = run(vec![ReportFormat::JsonLines]);
+    assert!(streamed.join("report.jsonl").exists());
+    assert!(!streamed.join("report.json").exists());
+    assert!(streamed.join("manifest.json").exists());
+
+    let full = run(vec![ReportFormat::Json]);
+    let ids = |report: move_mutator::report::Report| {
+        report
+            .get_mutants()
+            .iter()
+            .map(|m| m.get_id().to_owned())
+            .collect::<Vec<_>>()
+    };
+    let streamed_report =
+        move_mutator::report::Report::load_from_json_lines_file(&streamed.join("report.jsonl"))
+            .unwrap();
+    assert!(!streamed_report.get_mutants().is_empty());
+    assert_eq!(
+        ids(streamed_report),
+        ids(move_mutator::report::Report::load_from_json_file(&full.join("report.json")).unwrap())
+    );
+}
+
+// Check if the inline functions are mutated in their definitions (once, regardless of the number of the
+// expansion sites) and included as the callees of the selected functions.
+#[test]
+fn check_mutator_mutates_inline_functions() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/inline");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        entry_functions_only: true,
+        include_callees: true,
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let functions = report
+        .get_mutants()
+        .iter()
+        .map(|m| m.get_function_name().to_owned())
+        .collect::<std::collections::BTreeSet<_>>();
+    // The public inline function is expanded into `run`, the one called by the unselected function isn't.
+    assert_eq!(functions, ["double".to_owned(), "run".to_owned()].into());
+
+    // Each change of the inline function is generated once, although the function is expanded twice.
+    let changes = report
+        .get_mutants()
+        .iter()
+        .filter(|m| m.get_function_name() == "double")
+        .flat_map(|m| m.get_mutations())
+        .map(|m| {
+            let place = m.get_changed_place();
+            (place.start(), place.end(), m.get_new_value().to_owned())
+        })
+        .collect::<Vec<_>>();
+    let unique = changes
+        .iter()
+        .cloned()
+        .collect::<std::collections::BTreeSet<_>>();
+    assert!(!changes.is_empty());
+    assert_eq!(unique.len(), changes.len());
+}
+
+#[test]
+fn check_mutator_mutates_specs_on_request() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/specs");
+    let config = BuildConfig::default();
+
+    let mutated_functions = |mutate_specs| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            mutate_specs,
+            ..Default::default()
+        };
+
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        report
+            .get_mutants()
+            .iter()
+            .map(|m| m.get_function_name().to_owned())
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    assert_eq!(mutated_functions(false), ["double".to_owned()].into());
+    assert_eq!(
+        mutated_functions(true),
+        [
+            "double".to_owned(),
+            "invariant".to_owned(),
+            "times_two".to_owned()
+        ]
+        .into()
+    );
+}
+
+// Check if the specification files are left untouched, unless their mutation is requested.
+#[test]
+fn check_mutator_mutates_spec_files_on_request() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/spec_files");
+    let config = BuildConfig::default();
+
+    let mutated_functions = |mutate_specs, mutate_spec_files| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            mutate_specs,
+            mutate_spec_files,
+            ..Default::default()
+        };
+
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        report
+            .get_mutants()
+            .iter()
+            .map(|m| {
+                let file = m.original_file_path().file_name().unwrap();
+                (
+                    file.to_string_lossy().into_owned(),
+                    m.get_function_name().to_owned(),
+                )
+            })
+            .collect::<std::collections::BTreeSet<_>>()
+    };
+
+    let code = ("Double.move".to_owned(), "double".to_owned());
+    let spec = ("Double.spec.move".to_owned(), "times_two".to_owned());
+    assert_eq!(mutated_functions(false, false), [code.clone()].into());
+    assert_eq!(mutated_functions(true, false), [code.clone()].into());
+    assert_eq!(mutated_functions(false, true), [code, spec].into());
+}
+
+// Check if the mutants are stored in the per-module and per-function subdirectories with the module layout.
+#[test]
+fn check_mutator_uses_module_output_layout() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        output_layout: OutputLayout::Module,
+        ..Default::default()
+    };
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    assert!(!report.get_mutants().is_empty());
+    for mutant in report.get_mutants() {
+        let dir = outdir
+            .join(mutant.get_module_name())
+            .join(mutant.get_function_name());
+        assert_eq!(mutant.mutant_path().parent(), Some(dir.as_path()));
+        // An index is appended to the duplicated mutants.
+        let file_name = mutant.mutant_path().file_name().unwrap().to_string_lossy();
+        assert!(file_name.starts_with(mutant.get_id()));
+        assert!(mutant.mutant_path().exists());
+    }
+}
+
+// Check if the mutants are named after the mutant name template.
+#[test]
+fn check_mutator_uses_name_template() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        name_template: Some("{module}_{operator}_{line}_{index}.move".to_owned()),
+        ..Default::default()
+    };
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    assert!(!report.get_mutants().is_empty());
+    for mutant in report.get_mutants() {
+        let file_name = mutant.mutant_path().file_name().unwrap().to_string_lossy();
+        let operator = mutant.get_mutations()[0].get_operator_name();
+        assert!(file_name.starts_with(&format!("{}_{operator}_", mutant.get_module_name())));
+        assert!(file_name.ends_with(".move"));
+        assert!(mutant.mutant_path().exists());
+    }
+}
+
+// Check if the invalid mutant name template is rejected.
+#[test]
+fn check_mutator_rejects_invalid_name_template() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir),
+        name_template: Some("{file}_{column}.move".to_owned()),
+        ..Default::default()
+    };
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_err());
+}
+
+// Check if a self-contained package is emitted for each mutant.
+#[test]
+fn check_mutator_emits_mutant_packages() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        emit_packages: true,
+        ..Default::default()
+    };
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    assert!(!report.get_mutants().is_empty());
+    for mutant in report.get_mutants() {
+        let package_dir = outdir.join("packages").join(mutant.get_id());
+        assert!(package_dir.join("Move.toml").exists());
+
+        let file_name = mutant.original_file_path().file_name().unwrap();
+        let mutated = std::fs::read_to_string(package_dir.join("sources").join(file_name)).unwrap();
+        assert_eq!(
+            mutated,
+            std::fs::read_to_string(mutant.mutant_path()).unwrap()
+        );
+    }
+}
+
+// Check if the output directory is archived and the archive can be extracted anywhere.
+#[test]
+fn check_mutator_archives_output_directory() {
+    let dir = tempdir().unwrap();
+    let outdir = dir.path().join("mutants_output");
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        archive: true,
+        ..Default::default()
+    };
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let archive = move_mutator::archive::archive_path(&outdir);
+    assert!(archive.exists());
+
+    let extracted = dir.path().join("extracted");
+    move_mutator::archive::extract_archive(&archive, &extracted).unwrap();
+    let manifest = move_mutator::manifest::Manifest::load(&extracted)
+        .unwrap()
+        .unwrap();
+    assert!(manifest.verify_integrity(&extracted).is_ok());
+}
+
+// Check if the mutants spanning multiple files are generated as directories with all of the mutated files.
+#[test]
+fn check_mutator_generates_multi_file_mutants() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/cross_module");
+    let config = BuildConfig::default();
+
+    let multi_file_mutants = |multi_file_mutants| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            multi_file_mutants,
+            ..Default::default()
+        };
+
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        report
+            .get_mutants()
+            .iter()
+            .filter(|m| !m.get_changed_files().is_empty())
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    assert!(multi_file_mutants(None).is_empty());
+
+    let mutants = multi_file_mutants(Some(2));
+    assert_eq!(mutants.len(), 2);
+    for mutant in mutants {
+        assert!(mutant.mutant_path().is_dir());
+        assert_eq!(mutant.get_function_name(), "fee_percent");
+
+        let files = mutant.get_changed_files();
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            (files[0].get_module_name(), files[0].get_function_name()),
+            ("Config", "fee_percent")
+        );
+        assert_eq!(
+            (files[1].get_module_name(), files[1].get_function_name()),
+            ("Payment", "fee")
+        );
+        for file in files {
+            assert!(file.mutant_path().starts_with(mutant.mutant_path()));
+            assert_eq!(
+                file.mutant_path().file_name(),
+                file.original_file_path().file_name()
+            );
+            assert_ne!(
+                std::fs::read_to_string(file.mutant_path()).unwrap(),
+                std::fs::read_to_string(file.original_file_path()).unwrap()
+            );
+        }
+    }
+}
+
+// Check if the mutants are type checked and the invalid ones are dropped with `only_valid`.
+#[test]
+fn check_mutator_type_checks_mutants() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+    let config = BuildConfig::default();
+
+    let validity = |type_check_mutants, only_valid| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            type_check_mutants,
+            only_valid,
+            ..Default::default()
+        };
+
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        report
+            .get_mutants()
+            .iter()
+            .map(|m| m.is_valid())
+            .collect::<Vec<_>>()
+    };
+
+    let unchecked = validity(false, false);
+    assert!(unchecked.iter().all(Option::is_none));
+
+    let checked = validity(true, false);
+    assert_eq!(checked.len(), unchecked.len());
+    assert!(checked.iter().all(Option::is_some));
+
+    let valid = validity(false, true);
+    assert!(valid.iter().all(|v| *v == Some(true)));
+    assert_eq!(
+        valid.len(),
+        checked.iter().filter(|v| **v == Some(true)).count()
+    );
+}
+
+// Check if the applicability statistics of the operators are consistent with the reported mutants.
+#[test]
+fn check_mutator_reports_operator_statistics() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        only_valid: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let operators = report.get_operators();
+    assert!(operators["binary_operator_replacement"].sites > 0);
+    for (operator, stats) in operators {
+        let generated = report
+            .get_mutants()
+            .iter()
+            .filter(|m| m.operator_names().contains(operator))
+            .count();
+        assert_eq!(stats.generated as usize, generated, "{operator}");
+        assert!(stats.sites > 0 || stats.generated == 0, "{operator}");
+    }
+}
+
+// Check if the mutants identical to the original source are counted, but not written to the report.
+#[test]
+fn check_mutator_skips_no_op_mutants() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/no_op");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let swap = report.get_operators()["binary_operator_swap"];
+    assert_eq!((swap.generated, swap.no_op), (0, 1));
+    assert!(!report.get_mutants().is_empty());
+    assert!(report
+        .get_mutants()
+        .iter()
+        .all(|m| !m.operator_names().contains("binary_operator_swap") && !m.get_diff().is_empty()));
+}
+
+// Check if the packages using Move 2 language features (enums, receiver style calls) can be mutated.
+#[test]
+fn check_mutator_works_with_move_2_packages() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/move2");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let mut config = BuildConfig::default();
+    config.compiler_config.language_version = Some(LanguageVersion::V2_0);
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    // Match arms are mutated as any other expression.
+    assert!(report
+        .get_mutants()
+        .iter()
+        .any(|m| m.get_function_name() == "area"));
+}
+
+// Check if the expressions synthesized by the compiler for the `for` loop are skipped (and counted), while the
+// expressions written in the loop body are still mutated.
+#[test]
+fn check_mutator_skips_unsupported_syntax() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/move2");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        ..Default::default()
+    };
+    let mut config = BuildConfig::default();
+    config.compiler_config.language_version = Some(LanguageVersion::V2_0);
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    assert!(report.get_unsupported_sites() > 0);
+
+    let mutations = report
+        .get_mutants()
+        .iter()
+        .filter(|m| m.get_module_name() == "Loops")
+        .flat_map(|m| m.get_mutations())
+        .collect::<Vec<_>>();
+    // Only the loop bound replacement rewrites the whole loop.
+    assert!(mutations
+        .iter()
+        .filter(|m| m.get_operator_name() != "loop_bound_replacement")
+        .all(|m| !m.get_old_value().starts_with("for")));
+    assert!(mutations.iter().any(
+        |m| m.get_operator_name() == "binary_operator_replacement" && m.get_old_value() == "+"
+    ));
+}
+
+// Check if the enum specific operators are applied and name the variant change in the report.
+#[test]
+fn check_mutator_applies_enum_operators() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/move2");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        ..Default::default()
+    };
+    let mut config = BuildConfig::default();
+    config.compiler_config.language_version = Some(LanguageVersion::V2_0);
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let mutations = report
+        .get_mutants()
+        .iter()
+        .flat_map(|m| m.get_mutations())
+        .collect::<Vec<_>>();
+    let operator_mutations = |operator: &str| {
+        mutations
+            .iter()
+            .filter(|m| m.get_operator_name() == operator)
+            .collect::<Vec<_>>()
+    };
+
+    // `Square` and `Cube` have the same payload, `Rectangle` and `Empty` don't.
+    let replaced = operator_mutations("enum_variant_replacement");
+    assert_eq!(replaced.len(), 1);
+    let change = replaced[0].get_variant_change().unwrap();
+    assert_eq!(
+        (change.from.as_str(), change.to.as_str()),
+        ("Square", "Cube")
+    );
+
+    assert!(!operator_mutations("match_arm_swap").is_empty());
+    let removed = operator_mutations("match_guard_removal");
+    assert_eq!(removed.len(), 1);
+    assert!(removed[0].get_old_value().starts_with("if"));
+}
+
+// Check if the integer casts are narrowed and the nested casts are removed, keeping the mutants valid.
+#[test]
+fn check_mutator_replaces_casts() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/casts");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let mut new_values = report
+        .get_mutants()
+        .iter()
+        .flat_map(|m| m.get_mutations())
+        .filter(|m| m.get_operator_name() == "cast_replacement")
+        .map(|m| m.get_new_value().to_owned())
+        .collect::<Vec<_>>();
+    new_values.sort();
+
+    assert_eq!(new_values, vec![
+        "((amount as u16) as u8) as u32".to_owned(),
+        "(amount as u16) as u64".to_owned(),
+        "(amount as u32) as u64".to_owned(),
+        "(amount as u8) as u16".to_owned(),
+        "(amount as u8) as u64".to_owned(),
+        "amount".to_owned(),
+    ]);
+}
+
+// Check if the negation is inserted into the operands of the logical operators, but not into the negated
+// expressions (the negation is removed by the unary operator replacement).
+#[test]
+fn check_mutator_inserts_negation() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let negated = |function: &str| {
+        let mut new_values = report
+            .get_mutants()
+            .iter()
+            .filter(|m| m.get_function_name() == function)
+            .flat_map(|m| m.get_mutations())
+            .filter(|m| m.get_operator_name() == "negation_insertion")
+            .map(|m| m.get_new_value().to_owned())
+            .collect::<Vec<_>>();
+        new_values.sort();
+        new_values
+    };
+
+    assert_eq!(negated("logical_and"), ["!(x)", "!(y)"]);
+    assert_eq!(negated("logical_or"), ["!(x)", "!(y)"]);
+    assert!(negated("logical_not").is_empty());
+}
+
+// Check if the branches are swapped only for the `if` expressions with the `else` branch.
+#[test]
+fn check_mutator_swaps_if_else_branches() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let swapped = report
+        .get_mutants()
+        .iter()
+        .filter(|m| m.get_mutations()[0].get_operator_name() == "if_else_branch_swap")
+        .collect::<Vec<_>>();
+
+    // Only `sample3` has the `else` branch, the other `if` expressions abort or continue the loop.
+    assert_eq!(swapped.len(), 1);
+    assert_eq!(swapped[0].get_function_name(), "sample3");
+    let new_value = swapped[0].get_mutations()[0].get_new_value();
+    let (then_branch, else_branch) = new_value.split_once("else").unwrap();
+    assert!(then_branch.contains("n * sample3(n, e - 1)"));
+    assert!(else_branch.contains('1') && !else_branch.contains("sample3"));
+}
+
+// Check if the bounds of the `while` and `for` loops are shifted and the loop conditions are replaced
+// with `false` instead of the `if` replacements of the desugared loops.
+#[test]
+fn check_mutator_replaces_loop_bounds() {
+    let outdir = tempdir().unwrap().into_path();
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let report_path = outdir.join("report.json");
+
+    let new_values = |module: &str, operator: &str| {
+        let report = move_mutator::report::Report::load_from_json_file(&report_path).unwrap();
+        let mut new_values = report
+            .get_mutants()
+            .iter()
+            .filter(|m| m.get_module_name() == module)
+            .flat_map(|m| m.get_mutations())
+            .filter(|m| m.get_operator_name() == operator)
+            .map(|m| m.get_new_value().to_owned())
+            .collect::<Vec<_>>();
+        new_values.sort();
+        new_values
+    };
+
+    let config = BuildConfig::default();
+    let package_path = Path::new("tests/move-assets/breakcontinue");
+    let result = move_mutator::run_move_mutator(options.clone(), &config, package_path);
+    assert!(result.is_ok());
+
+    for module in ["Break", "Continue"] {
+        assert_eq!(new_values(module, "loop_bound_replacement"), [
+            "(n + 1)", "(n - 1)", "false"
+        ]);
+    }
+    // The conditions of the `if` expressions in the loop bodies are still replaced.
+    assert_eq!(new_values("Continue", "if_else_replacement"), [
+        "false", "true"
+    ]);
+
+    let mut config = BuildConfig::default();
+    config.compiler_config.language_version = Some(LanguageVersion::V2_0);
+    let package_path = Path::new("tests/move-assets/move2");
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    assert_eq!(new_values("Loops", "loop_bound_replacement"), [
+        "(1 + 1)..n",
+        "1..(n + 1)",
+        "1..(n - 1)",
+        "1..1",
+    ]);
+}
+
+// Check if the independent statements operating on the global storage are reordered, but the statements
+// sharing the written local variables aren't.
+#[test]
+fn check_mutator_reorders_resource_operations() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/storage");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let reordered = report
+        .get_mutants()
+        .iter()
+        .filter(|m| m.get_mutations()[0].get_operator_name() == "resource_operation_reorder")
+        .collect::<Vec<_>>();
+
+    assert_eq!(reordered.len(), 1);
+    assert_eq!(reordered[0].get_function_name(), "open");
+    assert!(reordered[0].get_mutations()[0]
+        .get_new_value()
+        .starts_with("move_to(account, Receipt { amount })"));
+}
+
+// Check if the addresses of the signers are replaced with the other addresses in scope of the function.
+#[test]
+fn check_mutator_replaces_signer_addresses() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/basic_coin");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let replaced = |function: &str| {
+        let mut new_values = report
+            .get_mutants()
+            .iter()
+            .filter(|m| m.get_function_name() == function)
+            .flat_map(|m| m.get_mutations())
+            .filter(|m| m.get_operator_name() == "signer_address_replacement")
+            .map(|m| m.get_new_value().to_owned())
+            .collect::<Vec<_>>();
+        new_values.sort();
+        new_values
+    };
+
+    assert_eq!(replaced("publish_balance"), ["@0x0", "MODULE_OWNER"]);
+    assert_eq!(replaced("mint"), ["@0x0", "MODULE_OWNER", "mint_addr"]);
+    assert_eq!(replaced("transfer"), ["@0x0", "MODULE_OWNER", "to"]);
+    assert!(replaced("deposit").is_empty());
+}
+
+// Check if the emissions of the module events and of the legacy event handles are deleted.
+#[test]
+fn check_mutator_removes_event_emissions() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/events");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let mut removed = report
+        .get_mutants()
+        .iter()
+        .filter(|m| m.get_mutations()[0].get_operator_name() == "event_emission_removal")
+        .map(|m| {
+            (
+                m.get_function_name().to_owned(),
+                m.get_mutations()[0].get_old_value().to_owned(),
+            )
+        })
+        .collect::<Vec<_>>();
+    removed.sort();
+
+    assert_eq!(removed, [
+        ("pay".to_owned(), "event::emit(Paid { amount })".to_owned()),
+        (
+            "record".to_owned(),
+            "event::emit_event(&mut ledger.paid, Paid { amount })".to_owned()
+        ),
+    ]);
+}
+
+// Check if the calls returning the current time are replaced with the boundary constants.
+#[test]
+fn check_mutator_replaces_timestamps() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/timelock");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        mutate_modules: ModuleFilter::Selected(vec!["Lock".to_owned()]),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let replaced = |function: &str| {
+        let mut replaced = report
+            .get_mutants()
+            .iter()
+            .filter(|m| m.get_function_name() == function)
+            .flat_map(|m| m.get_mutations())
+            .filter(|m| m.get_operator_name() == "timestamp_replacement")
+            .map(|m| (m.get_old_value().to_owned(), m.get_new_value().to_owned()))
+            .collect::<Vec<_>>();
+        replaced.sort();
+        replaced
+    };
+
+    for (function, call) in [
+        ("is_unlocked", "timestamp::now_seconds()"),
+        ("elapsed_micros", "timestamp::now_microseconds()"),
+    ] {
+        assert_eq!(replaced(function), [
+            (call.to_owned(), "0".to_owned()),
+            (call.to_owned(), u64::MAX.to_string()),
+        ]);
+    }
+}
+
+// Check if the visibility of the functions is lowered only as far as their callers allow it (the callers from
+// the friend module keep the function `public(friend)`).
+#[test]
+fn check_mutator_lowers_visibility() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/visibility");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let mut lowered = report
+        .get_mutants()
+        .iter()
+        .flat_map(|m| {
+            m.get_mutations()
+                .iter()
+                .filter(|m| m.get_operator_name() == "visibility_replacement")
+                .map(move |mutation| {
+                    (
+                        format!("{}::{}", m.get_module_name(), m.get_function_name()),
+                        mutation.get_old_value().trim().to_owned(),
+                        mutation.get_new_value().to_owned(),
+                    )
+                })
+        })
+        .collect::<Vec<_>>();
+    lowered.sort();
+
+    let lowering = |function: &str, old: &str, new: &str| {
+        (function.to_owned(), old.to_owned(), new.to_owned())
+    };
+    assert_eq!(lowered, [
+        lowering("Admin::reset_if_positive", "public", ""),
+        lowering("Admin::reset_if_positive", "public", "public(friend)"),
+        lowering("Counter::is_zero", "public", ""),
+        lowering("Counter::is_zero", "public", "public(friend)"),
+        lowering("Counter::unused", "public(friend)", ""),
+        lowering("Counter::value", "public", "public(friend)"),
+    ]);
+}
+
+// Check if the amounts passed to the framework coin functions and to the monetary functions listed in the
+// configuration are mutated.
+#[test]
+fn check_mutator_replaces_amounts() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/payments");
+    let configuration_file = outdir.join("mutator.toml");
+    std::fs::write(
+        &configuration_file,
+        "individual = []\n[project]\n[mutation]\nmonetary_functions = [\"Bank::charge\"]\n",
+    )
+    .unwrap();
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        mutate_modules: ModuleFilter::Selected(vec!["Bank".to_owned()]),
+        configuration_file: Some(configuration_file),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let replaced = |function: &str| {
+        let mut new_values = report
+            .get_mutants()
+            .iter()
+            .filter(|m| m.get_function_name() == function)
+            .flat_map(|m| m.get_mutations())
+            .filter(|m| m.get_operator_name() == "amount_replacement")
+            .map(|m| m.get_new_value().to_owned())
+            .collect::<Vec<_>>();
+        new_values.sort();
+        new_values
+    };
+
+    assert_eq!(replaced("pay"), ["(amount + 1)", "0"]);
+    assert_eq!(replaced("pay_with_fee"), [
+        "(fee + 1)",
+        "(principal + 1)",
+        "0",
+        "0",
+        "fee, principal",
+    ]);
+}
+
+// Check if the named addresses are resolved and swapped with the other named addresses of the package.
+#[test]
+fn check_mutator_swaps_named_addresses() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/named_addresses");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: true,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let new_values = |operator: &str| {
+        report
+            .get_mutants()
+            .iter()
+            .flat_map(|m| m.get_mutations())
+            .filter(|m| m.get_operator_name() == operator)
+            .map(|m| m.get_new_value().to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    // `owner` resolves to the same address as `admin`, so it's not used as a replacement.
+    assert_eq!(new_values("named_address_swap"), vec![
+        "TestAccount".to_owned(),
+        "treasury".to_owned()
+    ]);
+    // Address literals keep the `@` prefix, so the mutants compile.
+    let literals = report
+        .get_mutants()
+        .iter()
+        .filter(|m| m.get_mutations()[0].get_operator_name() == "literal_replacement")
+        .collect::<Vec<_>>();
+    assert_eq!(literals.len(), 2);
+    assert!(literals
+        .iter()
+        .all(|m| m.get_diff().contains("addr == @0x")));
+}
+
+// Check if the generator API yields the same mutants as the mutator tool.
+#[test]
+fn check_generator_yields_mutants_of_mutator() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/simple");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: false,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options.clone(), &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let mut report_ids = report
+        .get_mutants()
+        .iter()
+        .map(|m| m.get_id().to_owned())
+        .collect::<Vec<_>>();
+    report_ids.sort();
+
+    let configuration = Configuration::new(options, Some(package_path.to_owned()));
+    let generator = MutantGenerator::new(configuration).unwrap();
+    let mut ids = vec![];
+    for mutant in generator.iter() {
+        assert_eq!(
+            mutant.apply(mutant.original_source()).unwrap(),
+            mutant.mutated_source()
+        );
+        ids.push(mutant.id());
+    }
+    ids.sort();
+
+    assert!(!ids.is_empty());
+    assert_eq!(ids, report_ids);
+}
+
+// Check if the mutants of the scripts are reported apart from the mutants of the modules.
+#[test]
+fn check_mutator_mutates_scripts() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/basic_coin");
+
+    let options = CLIOptions {
+        out_mutant_dir: Some(outdir.clone()),
+        verify_mutants: false,
+        ..Default::default()
+    };
+    let config = BuildConfig::default();
+
+    let result = move_mutator::run_move_mutator(options.clone(), &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    let (scripts, modules): (Vec<_>, Vec<_>) =
+        report.get_mutants().iter().partition(|m| m.is_script());
+    assert!(!scripts.is_empty());
+    assert!(!modules.is_empty());
+    assert!(scripts.iter().all(
+        |m| m.get_module_name() == "script" && m.original_file_path().ends_with("GetCoin.move")
+    ));
+    assert!(modules.iter().all(|m| m.get_module_name() == "BasicCoin"));
+
+    // The scripts are selected under the common module name.
+    let options = CLIOptions {
+        mutate_modules: ModuleFilter::Selected(vec!["script".to_owned()]),
+        ..options
+    };
+    let result = move_mutator::run_move_mutator(options, &config, package_path);
+    assert!(result.is_ok());
+
+    let report =
+        move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+    assert!(!report.get_mutants().is_empty());
+    assert!(report.get_mutants().iter().all(|m| m.is_script()));
+}
+
+// Check if the mutants of the modules sharing a file are attributed to their modules and can be selected
+// even if the modules have the same name.
+#[test]
+fn check_mutator_attributes_mutants_to_modules_of_file() {
+    let outdir = tempdir().unwrap().into_path();
+    let package_path = Path::new("tests/move-assets/multi_module");
+    let config = BuildConfig::default();
+
+    let identities = |mutate_modules| {
+        let options = CLIOptions {
+            out_mutant_dir: Some(outdir.clone()),
+            verify_mutants: false,
+            mutate_modules,
+            ..Default::default()
+        };
+        let result = move_mutator::run_move_mutator(options, &config, package_path);
+        assert!(result.is_ok());
+
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json")).unwrap();
+        let mut identities = report
+            .get_mutants()
+            .iter()
+            .map(|m| {
+                (
+                    m.get_module_address().unwrap_or_default().to_owned(),
+                    m.get_module_name().to_owned(),
+                    m.get_function_name().to_owned(),
+                )
+            })
+            .collect::<Vec<_>>();
+        identities.sort();
+        identities.dedup();
+        identities
+    };
+    let identity = |address: &str, module: &str, function: &str| {
+        (address.to_owned(), module.to_owned(), function.to_owned())
+    };
+
+    let all = identities(ModuleFilter::All);
+    assert!(all.contains(&identity("0xcafe", "Math", "add")));
+    assert!(all.contains(&identity("0xbeef", "Math", "mul")));
+    assert!(all.contains(&identity("0xcafe", "Logic", "and")));
+
+    // The name selects all modules with that name, the qualified name just one of them.
+    let selected = |modules: &[&str]| {
+        identities(ModuleFilter::Selected(
+            modules.iter().map(|m| (*m).to_owned()).collect(),
+        ))
+    };
+    assert_eq!(selected(&["Math"]), vec![
+        identity("0xbeef", "Math", "mul"),
+        identity("0xcafe", "Math", "add")
+    ]);
+    assert_eq!(selected(&["0xBEEF::Math"]), vec![identity(
+        "0xbeef", "Math", "mul"
+    )]);
+    assert_eq!(selected(&["TestAccount::Math", "Logic"]), vec![
+        identity("0xcafe", "Logic", "and"),
+        identity("0xcafe", "Math", "add")
+    ]);
+}
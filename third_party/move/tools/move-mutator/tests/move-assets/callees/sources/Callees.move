@@ -0,0 +1,21 @@
+module TestAccount::Callees {
+    public entry fun run(x: u64) {
+        assert!(helper(x) > 0, 1);
+    }
+
+    fun helper(x: u64): u64 {
+        inner(x) + 1
+    }
+
+    fun inner(x: u64): u64 {
+        x * 2
+    }
+
+    fun unused(x: u64): u64 {
+        x - 1
+    }
+
+    public fun api(x: u64): u64 {
+        x + 3
+    }
+}
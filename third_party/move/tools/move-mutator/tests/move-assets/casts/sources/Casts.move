@@ -0,0 +1,19 @@
+module TestAccount::Casts {
+    public fun to_u64(amount: u128): u64 {
+        (amount as u64)
+    }
+
+    public fun to_u32(amount: u64): u32 {
+        ((amount as u16) as u32)
+    }
+
+    spec to_u64 {
+        aborts_if amount > MAX_U64;
+        ensures result == amount;
+    }
+
+    spec to_u32 {
+        aborts_if amount > MAX_U16;
+        ensures result == amount;
+    }
+}
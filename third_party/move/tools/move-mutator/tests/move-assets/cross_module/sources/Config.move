@@ -0,0 +1,9 @@
+module TestAccount::Config {
+    public fun fee_percent(): u64 {
+        5
+    }
+
+    spec fee_percent {
+        ensures result == 5;
+    }
+}
@@ -0,0 +1,12 @@
+module TestAccount::Payment {
+    use TestAccount::Config;
+
+    public fun fee(amount: u64): u64 {
+        amount * Config::fee_percent() / 100
+    }
+
+    spec fee {
+        aborts_if amount * 5 > MAX_U64;
+        ensures result == amount * 5 / 100;
+    }
+}
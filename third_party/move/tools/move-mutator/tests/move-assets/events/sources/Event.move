@@ -0,0 +1,16 @@
+/// Minimal stand-in of the framework event module, so the package builds without the framework.
+module 0x1::event {
+    struct EventHandle<phantom T: drop + store> has store {
+        counter: u64
+    }
+
+    public fun new_event_handle<T: drop + store>(): EventHandle<T> {
+        EventHandle<T> { counter: 0 }
+    }
+
+    public fun emit<T: drop + store>(_msg: T) {}
+
+    public fun emit_event<T: drop + store>(handle: &mut EventHandle<T>, _msg: T) {
+        handle.counter = handle.counter + 1;
+    }
+}
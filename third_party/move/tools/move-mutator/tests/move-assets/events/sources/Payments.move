@@ -0,0 +1,20 @@
+module TestAccount::Payments {
+    use 0x1::event;
+
+    struct Paid has drop, store {
+        amount: u64
+    }
+
+    struct Ledger has key {
+        paid: event::EventHandle<Paid>
+    }
+
+    public fun pay(amount: u64) {
+        event::emit(Paid { amount });
+    }
+
+    public fun record(addr: address, amount: u64) acquires Ledger {
+        let ledger = borrow_global_mut<Ledger>(addr);
+        event::emit_event(&mut ledger.paid, Paid { amount });
+    }
+}
@@ -0,0 +1,18 @@
+module TestAccount::Inline {
+    public entry fun run(x: u64) {
+        assert!(double(x) > 0, 1);
+        assert!(double(x + 1) > x, 2);
+    }
+
+    public fun apply_twice(x: u64): u64 {
+        twice(x, |y| y + 1)
+    }
+
+    public inline fun double(x: u64): u64 {
+        x * 2
+    }
+
+    inline fun twice(x: u64, f: |u64| u64): u64 {
+        f(f(x))
+    }
+}
@@ -0,0 +1,9 @@
+module TestAccount::Loops {
+    fun sum_to(n: u64): u64 {
+        let sum = 0;
+        for (i in 1..n) {
+            sum = sum + i;
+        };
+        sum
+    }
+}
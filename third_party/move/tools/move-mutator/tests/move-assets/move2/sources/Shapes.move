@@ -0,0 +1,27 @@
+module TestAccount::Shapes {
+    enum Shape has drop {
+        Square { side: u64 },
+        Cube { side: u64 },
+        Rectangle { width: u64, height: u64 },
+        Empty,
+    }
+
+    fun area(self: &Shape): u64 {
+        match (self) {
+            Shape::Square { side } if (*side == 0) => 0,
+            Shape::Square { side } => *side * *side,
+            Shape::Cube { side } => 6 * *side * *side,
+            Shape::Rectangle { width, height } => *width * *height,
+            Shape::Empty => 0,
+        }
+    }
+
+    public fun square_area(side: u64): u64 {
+        let shape = Shape::Square { side };
+        shape.area()
+    }
+
+    spec square_area {
+        ensures result == side * side;
+    }
+}
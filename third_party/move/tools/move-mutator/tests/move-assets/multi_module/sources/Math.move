@@ -0,0 +1,17 @@
+module TestAccount::Math {
+    fun add(x: u64, y: u64): u64 {
+        x + y
+    }
+}
+
+module Other::Math {
+    fun mul(x: u64, y: u64): u64 {
+        x * y
+    }
+}
+
+module TestAccount::Logic {
+    fun and(x: bool, y: bool): bool {
+        x && y
+    }
+}
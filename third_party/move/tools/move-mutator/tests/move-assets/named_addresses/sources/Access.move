@@ -0,0 +1,9 @@
+module TestAccount::Access {
+    public fun is_admin(addr: address): bool {
+        addr == @admin
+    }
+
+    spec is_admin {
+        ensures result == (addr == @admin);
+    }
+}
@@ -0,0 +1,6 @@
+module TestAccount::NoOp {
+    // Swapping the operands of the subtraction doesn't change the source.
+    public fun zero(x: u64): u64 {
+        x - x
+    }
+}
@@ -0,0 +1,17 @@
+module TestAccount::Bank {
+    use 0x1::coin;
+
+    struct USD {}
+
+    public fun pay(from: &signer, to: address, amount: u64) {
+        coin::transfer<USD>(from, to, amount);
+    }
+
+    public fun pay_with_fee(from: &signer, to: address, principal: u64, fee: u64) {
+        charge(from, to, principal, fee);
+    }
+
+    public fun charge(from: &signer, to: address, principal: u64, fee: u64) {
+        coin::transfer<USD>(from, to, principal + fee);
+    }
+}
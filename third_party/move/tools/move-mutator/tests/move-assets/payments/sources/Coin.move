@@ -0,0 +1,4 @@
+/// Minimal stand-in of the framework coin module, so the package builds without the framework.
+module 0x1::coin {
+    public fun transfer<CoinType>(_from: &signer, _to: address, _amount: u64) {}
+}
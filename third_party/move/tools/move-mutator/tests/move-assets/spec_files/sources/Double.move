@@ -0,0 +1,9 @@
+module TestAccount::Double {
+    public fun double(x: u64): u64 {
+        x * 2
+    }
+
+    spec double {
+        ensures result == x + x;
+    }
+}
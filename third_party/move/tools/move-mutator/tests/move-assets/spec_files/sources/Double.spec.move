@@ -0,0 +1,10 @@
+spec TestAccount::Double {
+    spec double {
+        aborts_if times_two(x) > MAX_U64;
+        ensures result == times_two(x);
+    }
+
+    spec fun times_two(x: num): num {
+        x * 2
+    }
+}
@@ -0,0 +1,22 @@
+module TestAccount::Specs {
+    struct Counter has key {
+        value: u64,
+    }
+
+    fun double(x: u64): u64 {
+        x * 2
+    }
+
+    spec double {
+        aborts_if times_two(x) > MAX_U64;
+        ensures result == times_two(x);
+    }
+
+    spec fun times_two(x: num): num {
+        x * 2
+    }
+
+    spec module {
+        invariant forall addr: address where exists<Counter>(addr): global<Counter>(addr).value > 0;
+    }
+}
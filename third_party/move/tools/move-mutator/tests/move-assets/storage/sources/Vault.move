@@ -0,0 +1,20 @@
+module TestAccount::Vault {
+    struct Vault has key {
+        amount: u64
+    }
+
+    struct Receipt has key {
+        amount: u64
+    }
+
+    public fun open(account: &signer, amount: u64) {
+        move_to(account, Vault { amount });
+        move_to(account, Receipt { amount });
+    }
+
+    public fun deposit(addr: address, amount: u64) acquires Vault {
+        let total = amount;
+        total = total + borrow_global<Vault>(addr).amount;
+        borrow_global_mut<Vault>(addr).amount = total;
+    }
+}
@@ -0,0 +1,29 @@
+module TestAccount::Counter {
+    public fun increment(n: u64): u64 {
+        n + 1
+    }
+
+    #[test_only]
+    public fun increment_twice(n: u64): u64 {
+        n + 2
+    }
+
+    #[test]
+    fun test_increment() {
+        assert!(increment(1) == 2, 0);
+    }
+}
+
+#[test_only]
+module TestAccount::CounterTests {
+    use TestAccount::Counter;
+
+    fun expected(n: u64): u64 {
+        n + 2
+    }
+
+    #[test]
+    fun test_increment_twice() {
+        assert!(Counter::increment_twice(1) == expected(1), 0);
+    }
+}
@@ -0,0 +1,15 @@
+module TestAccount::Lock {
+    use 0x1::timestamp;
+
+    struct Lock has key {
+        unlock_at: u64
+    }
+
+    public fun is_unlocked(addr: address): bool acquires Lock {
+        timestamp::now_seconds() >= borrow_global<Lock>(addr).unlock_at
+    }
+
+    public fun elapsed_micros(since: u64): u64 {
+        timestamp::now_microseconds() - since
+    }
+}
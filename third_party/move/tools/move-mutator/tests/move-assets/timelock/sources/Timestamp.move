@@ -0,0 +1,14 @@
+/// Minimal stand-in of the framework timestamp module, so the package builds without the framework.
+module 0x1::timestamp {
+    struct CurrentTimeMicroseconds has key {
+        microseconds: u64
+    }
+
+    public fun now_microseconds(): u64 acquires CurrentTimeMicroseconds {
+        borrow_global<CurrentTimeMicroseconds>(@0x1).microseconds
+    }
+
+    public fun now_seconds(): u64 acquires CurrentTimeMicroseconds {
+        now_microseconds() / 1000000
+    }
+}
@@ -0,0 +1,9 @@
+module TestAccount::Admin {
+    use TestAccount::Counter;
+
+    public fun reset_if_positive(addr: address) {
+        if (Counter::value(addr) > 0) {
+            Counter::reset(addr);
+        }
+    }
+}
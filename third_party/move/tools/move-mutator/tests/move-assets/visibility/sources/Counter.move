@@ -0,0 +1,23 @@
+module TestAccount::Counter {
+    friend TestAccount::Admin;
+
+    struct Counter has key {
+        value: u64,
+    }
+
+    public fun value(addr: address): u64 acquires Counter {
+        borrow_global<Counter>(addr).value
+    }
+
+    public fun is_zero(addr: address): bool acquires Counter {
+        value(addr) == 0
+    }
+
+    public(friend) fun reset(addr: address) acquires Counter {
+        borrow_global_mut<Counter>(addr).value = 0;
+    }
+
+    public(friend) fun unused(): u64 {
+        1
+    }
+}
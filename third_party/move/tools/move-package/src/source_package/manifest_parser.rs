@@ -22,6 +22,8 @@ const ADDRESSES_NAME: &str = "addresses";
 const DEV_ADDRESSES_NAME: &str = "dev-addresses";
 const DEPENDENCY_NAME: &str = "dependencies";
 const DEV_DEPENDENCY_NAME: &str = "dev-dependencies";
+// Configuration of the mutation testing tools, read by the move-mutator (ignored by the package system).
+const MUTATION_NAME: &str = "mutation";
 
 const KNOWN_NAMES: &[&str] = &[
     PACKAGE_NAME,
@@ -30,6 +32,7 @@ const KNOWN_NAMES: &[&str] = &[
     DEV_ADDRESSES_NAME,
     DEPENDENCY_NAME,
     DEV_DEPENDENCY_NAME,
+    MUTATION_NAME,
 ];
 
 const REQUIRED_FIELDS: &[&str] = &[PACKAGE_NAME];
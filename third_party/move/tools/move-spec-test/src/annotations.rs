@@ -0,0 +1,114 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::report::Report;
+use std::{fs, path::Path};
+
+/// Returns the GitHub workflow commands (`::warning file=...,line=...::message`) annotating the surviving
+/// mutants, so they appear on the pull request diff without any additional action configured.
+///
+/// Similar mutants in the same location are collapsed into a single annotation. The file paths are relative
+/// to `workdir` (the root of the repository checkout) if the package is inside of it.
+///
+/// # Errors
+///
+/// Returns an error if the mutated file can't be read (to find the line of the mutant).
+pub(crate) fn github_annotations(
+    report: &Report,
+    package_path: &Path,
+    workdir: &Path,
+) -> anyhow::Result<Vec<String>> {
+    let mut annotations = vec![];
+
+    for group in report.reduced_surviving_mutants() {
+        let file = package_path.join(group.path);
+        let mutant = group.representative;
+
+        let line = match mutant.location {
+            Some(offset) => {
+                let source = fs::read_to_string(&file)?;
+                source.bytes().take(offset).filter(|b| *b == b'\n').count() + 1
+            },
+            None => 1,
+        };
+
+        let mut message = format!("Mutant {} survived in {}", mutant.id, group.module_func);
+        if !mutant.operators.is_empty() {
+            message.push_str(&format!(" ({})", mutant.operators.join(", ")));
+        }
+        if !group.similar.is_empty() {
+            message.push_str(&format!(" and {} similar", group.similar.len()));
+        }
+        if let Some(hint) = mutant.hints.first() {
+            message.push_str(&format!(": {}", hint.message()));
+        }
+        message.push_str("\n\n");
+        message.push_str(&mutant.diff);
+
+        let file = file.strip_prefix(workdir).unwrap_or(&file);
+        annotations.push(format!(
+            "::warning file={},line={line},title={}::{}",
+            escape_property(&file.to_string_lossy()),
+            escape_property("Surviving mutant"),
+            escape_data(&message)
+        ));
+    }
+
+    Ok(annotations)
+}
+
+// Internal function escaping the message of the workflow command.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+// Internal function escaping the property value of the workflow command.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::SurvivingMutant;
+    use std::path::PathBuf;
+
+    #[test]
+    fn workflow_commands_are_escaped() {
+        assert_eq!(escape_data("50%\nnext"), "50%25%0Anext");
+        assert_eq!(escape_property("a:b,c"), "a%3Ab%2Cc");
+    }
+
+    #[test]
+    fn surviving_mutants_are_annotated_with_their_lines() {
+        let workdir = tempfile::tempdir().unwrap();
+        let package_path = workdir.path().join("pkg");
+        fs::create_dir_all(package_path.join("sources")).unwrap();
+        fs::write(
+            package_path.join("sources/Sum.move"),
+            "module 0x1::Sum {\n    fun sum(a: u64): u64 { a + 1 }\n}\n",
+        )
+        .unwrap();
+
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        report.increment_mutants_tested(&path, "Sum::sum");
+        report.add_surviving_mutant(&path, "Sum::sum", SurvivingMutant {
+            id: "0123abcd".to_owned(),
+            diff: "-a + 1\n+a - 1\n".to_owned(),
+            operators: vec!["binary_operator_replacement".to_owned()],
+            location: Some(44),
+            ..Default::default()
+        });
+
+        let annotations = github_annotations(&report, &package_path, workdir.path()).unwrap();
+        assert_eq!(annotations, vec![
+            "::warning file=pkg/sources/Sum.move,line=2,title=Surviving mutant::Mutant 0123abcd survived in \
+             Sum::sum (binary_operator_replacement)%0A%0A-a + 1%0A+a - 1%0A"
+                .to_owned()
+        ]);
+    }
+}
@@ -0,0 +1,587 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cli::{CLIOptions, Isolation, ProverPreset, VerifyScope},
+    interrupt,
+    limits::{ResourceLimits, RESOURCE_LIMIT_MARKER},
+    prover::prove,
+};
+use anyhow::anyhow;
+use move_package::{
+    source_package::{layout::SourcePackageLayout, manifest_parser},
+    BuildConfig,
+};
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+use termcolor::Buffer;
+
+/// Exit code of the remote command indicating a genuine verification failure.
+/// Any other non-zero exit code is treated as an infrastructure error.
+const VERIFICATION_FAILURE_EXIT_CODE: i32 = 1;
+
+/// Interval of checking if the prover command finished (or the run was interrupted).
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Backend executing the proving jobs.
+///
+/// The backend only runs the prover and reports its output - classification of the results, retries
+/// and kill policies are handled by the caller, so they work the same way for every backend.
+pub(crate) trait ExecutionBackend {
+    /// Proves the package located at `package_path` using the given prover options.
+    /// The prover output is written to the `output` buffer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package couldn't be verified (for any reason).
+    fn prove(
+        &self,
+        package_path: &Path,
+        prover_conf: &move_prover::cli::Options,
+        output: &mut Buffer,
+    ) -> anyhow::Result<()>;
+}
+
+/// Backend running the prover in the current process.
+pub(crate) struct LocalBackend {
+    /// The build configuration used to build the package model.
+    config: BuildConfig,
+    /// The scope of the verified modules.
+    verify_scope: VerifyScope,
+}
+
+impl LocalBackend {
+    pub(crate) fn new(config: &BuildConfig, verify_scope: VerifyScope) -> Self {
+        Self {
+            config: config.clone(),
+            verify_scope,
+        }
+    }
+}
+
+impl ExecutionBackend for LocalBackend {
+    fn prove(
+        &self,
+        package_path: &Path,
+        prover_conf: &move_prover::cli::Options,
+        output: &mut Buffer,
+    ) -> anyhow::Result<()> {
+        prove(
+            &self.config,
+            package_path,
+            prover_conf,
+            self.verify_scope,
+            output,
+        )
+    }
+}
+
+/// Backend delegating the proving jobs to an external command (e.g. a script dispatching the job to
+/// a remote worker via SSH or an HTTP job API).
+///
+/// The command is run by the system shell after substituting the placeholders (quoted for the shell):
+/// - `{package}` - absolute path to the package to prove,
+/// - `{timeout}` - the solver timeout in seconds,
+/// - `{seed}` - the solver random seed,
+/// - `{boogie_flags}` - the extra Boogie flags (e.g. from the prover overrides), each quoted on its own.
+///
+/// The other prover options can't be passed to the command, so the options changing them are rejected
+/// when the backend is created (see `create_backend`).
+///
+/// Exit code `0` means the package was verified, exit code `1` means a verification failure and any
+/// other exit code an infrastructure error. The command output is treated as the prover output.
+pub(crate) struct CommandBackend {
+    /// The command template.
+    template: String,
+}
+
+impl CommandBackend {
+    pub(crate) fn new(template: &str) -> Self {
+        Self {
+            template: template.to_owned(),
+        }
+    }
+
+    // Internal function substituting the placeholders in the command template.
+    fn command_line(&self, package_path: &Path, prover_conf: &move_prover::cli::Options) -> String {
+        let boogie_flags = prover_conf
+            .backend
+            .boogie_flags
+            .iter()
+            .map(|flag| shell_quote(flag))
+            .collect::<Vec<_>>()
+            .join(" ");
+        self.template
+            .replace("{package}", &shell_quote(&package_path.to_string_lossy()))
+            .replace(
+                "{timeout}",
+                &shell_quote(&prover_conf.backend.vc_timeout.to_string()),
+            )
+            .replace(
+                "{seed}",
+                &shell_quote(&prover_conf.backend.random_seed.to_string()),
+            )
+            .replace("{boogie_flags}", &boogie_flags)
+    }
+}
+
+impl ExecutionBackend for CommandBackend {
+    fn prove(
+        &self,
+        package_path: &Path,
+        prover_conf: &move_prover::cli::Options,
+        output: &mut Buffer,
+    ) -> anyhow::Result<()> {
+        let command_line = self.command_line(package_path, prover_conf);
+        trace!("Running remote prover command: {command_line}");
+
+        let (shell, flag) = if cfg!(windows) {
+            ("cmd", "/C")
+        } else {
+            ("sh", "-c")
+        };
+
+        let mut command = Command::new(shell);
+        command.arg(flag).arg(&command_line);
+        run_prover_command(&mut command, output, false, None)
+    }
+}
+
+/// Backend running each proving job in a separate Docker container.
+///
+/// The container runs `aptos move prove` for the package, which is mounted at the same path as on the
+/// host. Local dependencies of the package and the Move home directory (with the git dependencies) are
+/// mounted read-only. Containers have no network access and are removed once the job is finished.
+/// Resource limits are applied to the whole container.
+pub(crate) struct DockerBackend {
+    /// The Docker image providing the `aptos` tool and the prover dependencies.
+    image: String,
+    /// Directories mounted read-only in the container.
+    read_only_mounts: Vec<PathBuf>,
+    /// Extra arguments of the `docker run` command (e.g. resource limits).
+    container_args: Vec<String>,
+    /// Extra arguments passed to the `aptos move prove` command.
+    prover_args: Vec<String>,
+}
+
+impl DockerBackend {
+    /// Creates the backend for proving the given package (and its mutants).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package manifest can't be read.
+    pub(crate) fn new(
+        image: &str,
+        package_path: &Path,
+        config: &BuildConfig,
+        limits: &ResourceLimits,
+    ) -> anyhow::Result<Self> {
+        let mut read_only_mounts = BTreeSet::new();
+        collect_local_dependencies(package_path, &mut read_only_mounts)?;
+
+        let move_home = PathBuf::from(move_command_line_common::env::MOVE_HOME.as_str());
+        if move_home.exists() {
+            read_only_mounts.insert(move_home.canonicalize()?);
+        }
+
+        let mut prover_args = vec!["--skip-fetch-latest-git-deps".to_owned()];
+        if config.dev_mode {
+            prover_args.push("--dev".to_owned());
+        }
+        if !config.additional_named_addresses.is_empty() {
+            prover_args.push("--named-addresses".to_owned());
+            prover_args.push(
+                config
+                    .additional_named_addresses
+                    .iter()
+                    .map(|(name, address)| format!("{name}={}", address.to_hex_literal()))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+        }
+
+        Ok(Self {
+            image: image.to_owned(),
+            read_only_mounts: read_only_mounts.into_iter().collect(),
+            container_args: limits.docker_args(),
+            prover_args,
+        })
+    }
+
+    // Internal function preparing the arguments of the `docker` command.
+    fn docker_args(
+        &self,
+        package_path: &Path,
+        prover_conf: &move_prover::cli::Options,
+    ) -> Vec<String> {
+        let package = package_path.to_string_lossy().to_string();
+
+        let mut args = vec![
+            "run".to_owned(),
+            "--rm".to_owned(),
+            "--network".to_owned(),
+            "none".to_owned(),
+        ];
+        args.extend(self.container_args.iter().cloned());
+        args.extend(["-v".to_owned(), format!("{package}:{package}")]);
+        for mount in &self.read_only_mounts {
+            let mount = mount.to_string_lossy();
+            args.push("-v".to_owned());
+            args.push(format!("{mount}:{mount}:ro"));
+        }
+        args.extend([
+            "-w".to_owned(),
+            package.clone(),
+            self.image.clone(),
+            "aptos".to_owned(),
+            "move".to_owned(),
+            "prove".to_owned(),
+            "--package-dir".to_owned(),
+            package,
+            "--vc-timeout".to_owned(),
+            prover_conf.backend.vc_timeout.to_string(),
+            "--random-seed".to_owned(),
+            prover_conf.backend.random_seed.to_string(),
+        ]);
+        args.extend(self.prover_args.iter().cloned());
+        args
+    }
+}
+
+impl ExecutionBackend for DockerBackend {
+    fn prove(
+        &self,
+        package_path: &Path,
+        prover_conf: &move_prover::cli::Options,
+        output: &mut Buffer,
+    ) -> anyhow::Result<()> {
+        // Containers are named, so they can be stopped if the run is interrupted (killing the `docker`
+        // client doesn't stop the container).
+        static CONTAINERS: AtomicUsize = AtomicUsize::new(0);
+        let name = format!(
+            "move-spec-test-{}-{}",
+            std::process::id(),
+            CONTAINERS.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let mut args = self.docker_args(package_path, prover_conf);
+        args.splice(1..1, ["--name".to_owned(), name.clone()]);
+        trace!("Running prover in Docker: docker {}", args.join(" "));
+
+        let mut command = Command::new("docker");
+        command.args(args);
+        let mut cleanup = Command::new("docker");
+        cleanup.args(["kill", &name]);
+        // Containers killed by the kernel (e.g. because of the memory limit) exit with status above 128.
+        run_prover_command(
+            &mut command,
+            output,
+            !self.container_args.is_empty(),
+            Some(&mut cleanup),
+        )
+    }
+}
+
+// Internal function quoting the value substituted in the command line, so the shell passes it to the command
+// as a single argument.
+fn shell_quote(value: &str) -> String {
+    if cfg!(windows) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+// Internal function checking that the options don't change the prover settings which can't be passed to
+// the remote command (the timeout, the seed and the Boogie flags are passed using the placeholders).
+fn check_remote_command_options(options: &CLIOptions) -> anyhow::Result<()> {
+    let mut unsupported = vec![];
+    if options.prover_conf.is_some() {
+        unsupported.push("--prover-conf");
+    }
+    if options.prover_preset != ProverPreset::Custom {
+        unsupported.push("--prover-preset");
+    }
+    if options.extra_prover_args.is_some() {
+        unsupported.push("--extra-prover-args");
+    }
+    if !options.matrix_prover_conf.is_empty() {
+        unsupported.push("--matrix-prover-conf");
+    }
+    if options.verify_scope != VerifyScope::All {
+        unsupported.push("--verify-scope");
+    }
+    if options.max_prover_cpu_time.is_some() {
+        unsupported.push("--max-prover-cpu-time");
+    }
+
+    if unsupported.is_empty() {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "The `--remote-command` option can't be used with {}, as the prover settings they change \
+             can't be passed to the command",
+            unsupported
+                .iter()
+                .map(|option| format!("`{option}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ))
+    }
+}
+
+// Internal function running the prover command and interpreting its exit code.
+// If `killed_is_resource_limit` is set, processes killed by a signal are reported as resource limit breaches.
+// If the run is interrupted, the command is killed and the `cleanup` command (if any) is run.
+fn run_prover_command(
+    command: &mut Command,
+    output: &mut Buffer,
+    killed_is_resource_limit: bool,
+    cleanup: Option<&mut Command>,
+) -> anyhow::Result<()> {
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow!("Prover error: can't run the prover command: {e}"))?;
+
+    // The output is read in the separate threads, so the child doesn't block on the full pipes while
+    // it's polled for the interruption.
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if interrupt::is_interrupted() {
+            // The prover command is killed, so no orphaned processes are left behind.
+            let _ = child.kill();
+            let _ = child.wait();
+            if let Some(cleanup) = cleanup {
+                let _ = cleanup.stdout(Stdio::null()).stderr(Stdio::null()).status();
+            }
+            return Err(anyhow!("Prover error: the run was interrupted"));
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    output.write_all(&stdout.join().unwrap_or_default())?;
+    output.write_all(&stderr.join().unwrap_or_default())?;
+
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(VERIFICATION_FAILURE_EXIT_CODE) => Err(anyhow!("exiting with verification errors")),
+        Some(code) if killed_is_resource_limit && code > 128 => {
+            Err(anyhow!("{RESOURCE_LIMIT_MARKER} (exit status {code})"))
+        },
+        _ => Err(anyhow!("Prover error: prover command failed ({status})")),
+    }
+}
+
+// Internal function reading the whole pipe of the child process in a separate thread.
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buffer);
+        }
+        buffer
+    })
+}
+
+// Internal function collecting the canonical paths of the local dependencies of the package (including
+// the transitive ones).
+fn collect_local_dependencies(
+    package_path: &Path,
+    dependencies: &mut BTreeSet<PathBuf>,
+) -> anyhow::Result<()> {
+    let manifest_string =
+        fs::read_to_string(package_path.join(SourcePackageLayout::Manifest.path()))?;
+    let manifest = manifest_parser::parse_move_manifest_string(manifest_string)?;
+    let manifest = manifest_parser::parse_source_manifest(manifest)?;
+
+    for dep in manifest
+        .dependencies
+        .values()
+        .chain(manifest.dev_dependencies.values())
+    {
+        // Dependencies which don't exist yet (e.g. not fetched git dependencies) are skipped.
+        let Ok(dep_path) = package_path.join(&dep.local).canonicalize() else {
+            continue;
+        };
+
+        if dependencies.insert(dep_path.clone()) {
+            collect_local_dependencies(&dep_path, dependencies)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the execution backend selected in the options.
+///
+/// # Errors
+///
+/// Returns an error if the options select more than one backend, change the prover settings the selected
+/// backend can't apply or the backend can't be created.
+pub(crate) fn create_backend(
+    options: &CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+    limits: &ResourceLimits,
+) -> anyhow::Result<Box<dyn ExecutionBackend>> {
+    match (&options.remote_command, &options.isolate) {
+        (Some(_), Isolation::Docker(_)) => Err(anyhow!(
+            "The `--remote-command` and `--isolate` options can't be used together"
+        )),
+        (Some(template), Isolation::None) => {
+            check_remote_command_options(options)?;
+            Ok(Box::new(CommandBackend::new(template)))
+        },
+        (None, Isolation::Docker(image)) => Ok(Box::new(DockerBackend::new(
+            image,
+            package_path,
+            config,
+            limits,
+        )?)),
+        (None, Isolation::None) => Ok(Box::new(LocalBackend::new(config, options.verify_scope))),
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn run(template: &str) -> (anyhow::Result<()>, String) {
+        run_in(template, Path::new("/pkg"))
+    }
+
+    fn run_in(template: &str, package_path: &Path) -> (anyhow::Result<()>, String) {
+        let mut output = Buffer::no_color();
+        let mut prover_conf = move_prover::cli::Options::default();
+        prover_conf.backend.vc_timeout = 40;
+        prover_conf.backend.random_seed = 7;
+        prover_conf.backend.boogie_flags = vec!["-flag".to_owned(), "-opt:a b".to_owned()];
+
+        let result = CommandBackend::new(template).prove(package_path, &prover_conf, &mut output);
+        (
+            result,
+            String::from_utf8_lossy(output.as_slice()).to_string(),
+        )
+    }
+
+    #[test]
+    fn placeholders_are_substituted() {
+        let (result, output) = run("echo {package} {timeout} {seed}");
+        assert!(result.is_ok());
+        assert_eq!(output, "/pkg 40 7\n");
+
+        let (result, output) = run("printf '<%s>' {boogie_flags}");
+        assert!(result.is_ok());
+        assert_eq!(output, "<-flag><-opt:a b>");
+    }
+
+    #[test]
+    fn placeholders_are_quoted() {
+        let (result, output) = run_in("printf '<%s>' {package}", Path::new("/my pkg; exit 3"));
+        assert!(result.is_ok());
+        assert_eq!(output, "</my pkg; exit 3>");
+
+        let (result, output) = run_in("printf '<%s>' {package}", Path::new("/it's"));
+        assert!(result.is_ok());
+        assert_eq!(output, "</it's>");
+    }
+
+    #[test]
+    fn remote_command_rejects_unsupported_options() {
+        let mut options = CLIOptions {
+            remote_command: Some("prove {package}".to_owned()),
+            ..Default::default()
+        };
+        assert!(check_remote_command_options(&options).is_ok());
+
+        options.prover_preset = ProverPreset::AptosFramework;
+        options.verify_scope = VerifyScope::Targets;
+        assert_eq!(
+            check_remote_command_options(&options)
+                .unwrap_err()
+                .to_string(),
+            "The `--remote-command` option can't be used with `--prover-preset`, `--verify-scope`, as the \
+             prover settings they change can't be passed to the command"
+        );
+    }
+
+    #[test]
+    fn exit_code_one_is_verification_failure() {
+        let (result, _) = run("echo 'error: post-condition does not hold' >&2; exit 1");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "exiting with verification errors"
+        );
+    }
+
+    #[test]
+    fn docker_args_mount_package_and_dependencies() {
+        let backend = DockerBackend {
+            image: "image".to_owned(),
+            read_only_mounts: vec![PathBuf::from("/deps/stdlib")],
+            container_args: vec!["--memory".to_owned(), "2048m".to_owned()],
+            prover_args: vec!["--dev".to_owned()],
+        };
+        let mut prover_conf = move_prover::cli::Options::default();
+        prover_conf.backend.vc_timeout = 40;
+        prover_conf.backend.random_seed = 7;
+
+        assert_eq!(
+            backend
+                .docker_args(Path::new("/pkg"), &prover_conf)
+                .join(" "),
+            "run --rm --network none --memory 2048m -v /pkg:/pkg -v /deps/stdlib:/deps/stdlib:ro -w /pkg image \
+             aptos move prove --package-dir /pkg --vc-timeout 40 --random-seed 7 --dev"
+        );
+    }
+
+    #[test]
+    fn local_dependencies_are_collected_transitively() {
+        let root = tempfile::tempdir().unwrap();
+        let write_package = |name: &str, deps: &str| {
+            let dir = root.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(
+                dir.join("Move.toml"),
+                format!(
+                    "[package]\nname = \"{name}\"\nversion = \"0.0.0\"\n\n[dependencies]\n{deps}"
+                ),
+            )
+            .unwrap();
+        };
+        write_package("app", "Lib = { local = \"../lib\" }\n");
+        write_package("lib", "Base = { local = \"../base\" }\n");
+        write_package("base", "");
+
+        let mut dependencies = BTreeSet::new();
+        collect_local_dependencies(&root.path().join("app"), &mut dependencies).unwrap();
+
+        let root = root.path().canonicalize().unwrap();
+        assert_eq!(dependencies.into_iter().collect::<Vec<_>>(), vec![
+            root.join("base"),
+            root.join("lib")
+        ]);
+    }
+
+    #[test]
+    fn other_exit_codes_are_infrastructure_errors() {
+        let (result, _) = run("exit 255");
+        assert!(result.unwrap_err().to_string().starts_with("Prover error:"));
+    }
+}
@@ -0,0 +1,289 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backend::ExecutionBackend,
+    cli::KillPolicy,
+    prover::{prove_modules, MutantOutcome, Verification},
+    scope::ModuleGroups,
+    workspace::Workspace,
+};
+use move_mutator::report::MutationReport;
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use termcolor::WriteColor;
+
+/// Mutant waiting for the verification in a batch.
+pub(crate) struct PendingMutant<'a> {
+    /// The mutator report entry of the mutant.
+    pub(crate) elem: &'a MutationReport,
+    /// Path of the mutated file, relative to the package directory.
+    pub(crate) original_file: PathBuf,
+    /// Name of the mutated function (`Module::function`).
+    pub(crate) qname: String,
+    /// The mutated source of the file.
+    pub(crate) source: String,
+    /// The modules verified for the mutant (`None` means the whole package).
+    pub(crate) modules: Option<Vec<String>>,
+}
+
+/// Everything needed to verify the package with the batch of mutants applied.
+pub(crate) struct BatchProver<'a> {
+    /// The backend running the prover.
+    pub(crate) backend: &'a dyn ExecutionBackend,
+    /// The original package directory.
+    pub(crate) package_path: &'a Path,
+    /// The workspace with the scratch directories.
+    pub(crate) workspace: &'a Workspace,
+    /// The prover configurations used to verify each batch.
+    pub(crate) prover_confs: &'a [move_prover::cli::Options],
+    /// The kill policy of the prover configurations.
+    pub(crate) policy: KillPolicy,
+    /// The maximum number of retries for each configuration.
+    pub(crate) retries: usize,
+    /// The groups of the interacting modules of the package.
+    pub(crate) module_groups: &'a ModuleGroups,
+}
+
+impl BatchProver<'_> {
+    /// Verifies the pending mutants in batches of at most `size` mutants and returns the outcome of
+    /// each mutant (in the order of the pending mutants). The verification errors are attributed only
+    /// to the mutants verified alone.
+    ///
+    /// Mutants of a batch are applied to different files simultaneously and verified by a single prover
+    /// invocation. The modules of the mutants of a batch have no dependency path between them, so a mutant
+    /// can't hide the effect of another one. If all mutants of the batch survive, the batch is done.
+    /// Otherwise, the batch is bisected until the kills (and other non-alive outcomes) are attributed to
+    /// the single mutants.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the scratch package of a batch can't be prepared.
+    pub(crate) fn prove_batches<W: WriteColor>(
+        &self,
+        pending: &[PendingMutant],
+        size: usize,
+        error_writer: &mut W,
+    ) -> anyhow::Result<Vec<Verification>> {
+        let mutants = pending
+            .iter()
+            .map(|mutant| {
+                (
+                    mutant.original_file.as_path(),
+                    self.module_groups
+                        .group_of(&mutant.elem.qualified_module_name()),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut outcomes = vec![Verification::from(MutantOutcome::Undecided); pending.len()];
+        let mut invocation = 0;
+        for batch in group_batches(&mutants, size.max(1)) {
+            info!("Proving batch of {} mutants", batch.len());
+            let results = bisect(&batch, &mut |indices| {
+                invocation += 1;
+                self.prove_batch(pending, indices, invocation, error_writer)
+            })?;
+            for (index, outcome) in results {
+                outcomes[index] = outcome;
+            }
+        }
+
+        Ok(outcomes)
+    }
+
+    // Internal function verifying the package with the given mutants applied.
+    fn prove_batch<W: WriteColor>(
+        &self,
+        pending: &[PendingMutant],
+        indices: &[usize],
+        invocation: usize,
+        error_writer: &mut W,
+    ) -> anyhow::Result<Verification> {
+        let outdir_prove = self.workspace.create_mutant_dir(invocation)?;
+        self.workspace
+            .copy_package(self.package_path, &outdir_prove)?;
+
+        let mut modules = Some(BTreeSet::new());
+        for &index in indices {
+            let mutant = &pending[index];
+            trace!(
+                "Applying mutant {:?} to the batch package directory {outdir_prove:?}",
+                mutant.elem.mutant_path()
+            );
+            fs::write(outdir_prove.join(&mutant.original_file), &mutant.source)?;
+
+            // The batch is verified against the whole package if any of its mutants requires it.
+            modules = match (modules, &mutant.modules) {
+                (Some(mut all), Some(verified)) => {
+                    all.extend(verified.iter().cloned());
+                    Some(all)
+                },
+                _ => None,
+            };
+        }
+
+        move_mutator::compiler::rewrite_manifest_for_mutant(self.package_path, &outdir_prove)?;
+
+        let modules = modules.map(|modules| modules.into_iter().collect::<Vec<_>>());
+        let verification = prove_modules(
+            self.backend,
+            &outdir_prove,
+            self.prover_confs,
+            modules.as_deref(),
+            self.policy,
+            self.retries,
+            error_writer,
+        );
+        trace!(
+            "Batch of mutants {indices:?} outcome: {:?}",
+            verification.outcome
+        );
+
+        self.workspace.cleanup_mutant_dir(&outdir_prove)?;
+        Ok(verification)
+    }
+}
+
+// Internal function grouping the mutants (given by the mutated file and the group of the mutated module)
+// into batches of at most `size` mutants, so the mutants of each batch change different files and the
+// modules of different groups. Mutants of the modules without a group are verified alone. Returns the
+// indices of the mutants in each batch.
+fn group_batches(mutants: &[(&Path, Option<usize>)], size: usize) -> Vec<Vec<usize>> {
+    // The groups of a batch are `None` if the batch can't take more mutants.
+    let mut batches: Vec<(BTreeSet<&Path>, Option<BTreeSet<usize>>, Vec<usize>)> = vec![];
+
+    for (index, (file, group)) in mutants.iter().enumerate() {
+        match batches.iter_mut().find(|(files, groups, batch)| {
+            batch.len() < size
+                && !files.contains(file)
+                && groups
+                    .as_ref()
+                    .zip(*group)
+                    .is_some_and(|(groups, group)| !groups.contains(&group))
+        }) {
+            Some((files, groups, batch)) => {
+                files.insert(*file);
+                groups.iter_mut().for_each(|groups| groups.extend(*group));
+                batch.push(index);
+            },
+            None => batches.push((
+                BTreeSet::from([*file]),
+                group.map(|group| BTreeSet::from([group])),
+                vec![index],
+            )),
+        }
+    }
+
+    batches.into_iter().map(|(_, _, batch)| batch).collect()
+}
+
+// Internal function verifying the batch of mutants and bisecting it until the outcome of each mutant is known.
+// A batch in which all mutants survive doesn't need to be bisected.
+fn bisect<F>(batch: &[usize], prove: &mut F) -> anyhow::Result<Vec<(usize, Verification)>>
+where
+    F: FnMut(&[usize]) -> anyhow::Result<Verification>,
+{
+    if batch.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let verification = prove(batch)?;
+    if batch.len() == 1 || verification.outcome == MutantOutcome::Alive {
+        return Ok(batch
+            .iter()
+            .map(|index| (*index, verification.clone()))
+            .collect());
+    }
+
+    let (left, right) = batch.split_at(batch.len() / 2);
+    let mut outcomes = bisect(left, prove)?;
+    outcomes.extend(bisect(right, prove)?);
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_contain_mutants_of_different_files() {
+        let (a, b, c) = (
+            Path::new("a.move"),
+            Path::new("b.move"),
+            Path::new("c.move"),
+        );
+        let batches = group_batches(
+            &[
+                (a, Some(0)),
+                (a, Some(0)),
+                (b, Some(1)),
+                (c, Some(2)),
+                (a, Some(0)),
+                (b, Some(1)),
+            ],
+            2,
+        );
+        assert_eq!(batches, vec![vec![0, 2], vec![1, 3], vec![4, 5]]);
+        assert_eq!(
+            group_batches(&[(a, Some(0)), (b, Some(1)), (c, Some(2))], 1),
+            vec![vec![0], vec![1], vec![2]]
+        );
+    }
+
+    #[test]
+    fn batches_contain_mutants_of_independent_modules() {
+        let (a, b, c) = (
+            Path::new("a.move"),
+            Path::new("b.move"),
+            Path::new("c.move"),
+        );
+        // The modules of `a.move` and `b.move` call each other, so they're never in the same batch.
+        let batches = group_batches(&[(a, Some(0)), (b, Some(0)), (c, Some(1))], 3);
+        assert_eq!(batches, vec![vec![0, 2], vec![1]]);
+
+        // Mutants of the modules which may interact with any other module are verified alone.
+        let batches = group_batches(&[(a, None), (b, Some(0)), (c, Some(1))], 3);
+        assert_eq!(batches, vec![vec![0], vec![1, 2]]);
+    }
+
+    #[test]
+    fn surviving_batch_is_not_bisected() {
+        let mut invocations = 0;
+        let outcomes = bisect(&[0, 1, 2, 3], &mut |_| {
+            invocations += 1;
+            Ok(MutantOutcome::Alive.into())
+        })
+        .unwrap();
+
+        assert_eq!(invocations, 1);
+        assert!(outcomes
+            .iter()
+            .all(|(_, verification)| verification.outcome == MutantOutcome::Alive));
+    }
+
+    #[test]
+    fn kills_are_attributed_by_bisection() {
+        let killers = [1, 2];
+        let outcomes = bisect(&[0, 1, 2, 3], &mut |batch| {
+            Ok(if batch.iter().any(|index| killers.contains(index)) {
+                MutantOutcome::Killed
+            } else {
+                MutantOutcome::Alive
+            }
+            .into())
+        })
+        .unwrap();
+
+        assert_eq!(outcomes, vec![
+            (0, MutantOutcome::Alive.into()),
+            (1, MutantOutcome::Killed.into()),
+            (2, MutantOutcome::Killed.into()),
+            (3, MutantOutcome::Alive.into()),
+        ]);
+    }
+}
@@ -2,8 +2,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use serde::Serialize;
 use std::time::{Duration, Instant};
 
+/// Upper bounds (in milliseconds) of the buckets of the timing histograms. Longer times are counted in
+/// the last, unbounded bucket.
+const HISTOGRAM_BUCKETS: [u64; 12] = [
+    10, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000, 60_000, 300_000,
+];
+
 /// A benchmark for a specific operation.
 #[derive(Debug, Clone)]
 pub struct Benchmark {
@@ -37,6 +44,8 @@ impl Benchmark {
 pub struct Benchmarks {
     /// Benchmark for the spec test.
     pub spec_test: Benchmark,
+    /// Benchmark for the verification of the original code.
+    pub original: Benchmark,
     /// Benchmark for the mutator.
     pub mutator: Benchmark,
     /// Benchmark for the prover.
@@ -50,6 +59,7 @@ impl Benchmarks {
     pub fn new() -> Self {
         Self {
             spec_test: Benchmark::new(),
+            original: Benchmark::new(),
             mutator: Benchmark::new(),
             prover: Benchmark::new(),
             prover_results: Vec::new(),
@@ -62,6 +72,10 @@ impl Benchmarks {
             "Specification testing took {} msecs",
             self.spec_test.elapsed.as_millis()
         );
+        info!(
+            "Verifying the original code took {} msecs",
+            self.original.elapsed.as_millis()
+        );
         info!(
             "Generating mutants took {} msecs",
             self.mutator.elapsed.as_millis()
@@ -96,6 +110,162 @@ impl Benchmarks {
     }
 }
 
+/// Timing histogram of a repeated operation (e.g. the verification of a mutant).
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct Histogram {
+    /// Number of the measured operations.
+    pub count: usize,
+    /// Shortest time (in milliseconds).
+    pub min_ms: u64,
+    /// Longest time (in milliseconds).
+    pub max_ms: u64,
+    /// Average time (in milliseconds).
+    pub mean_ms: f64,
+    /// Median time (in milliseconds).
+    pub p50_ms: u64,
+    /// 90th percentile of the times (in milliseconds).
+    pub p90_ms: u64,
+    /// 99th percentile of the times (in milliseconds).
+    pub p99_ms: u64,
+    /// Number of the operations in each bucket.
+    pub buckets: Vec<HistogramBucket>,
+}
+
+/// Bucket of the timing histogram.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct HistogramBucket {
+    /// Upper bound of the bucket (in milliseconds, inclusive). `None` for the last, unbounded bucket.
+    pub le_ms: Option<u64>,
+    /// Number of the operations which took longer than the previous bound and at most `le_ms`.
+    pub count: usize,
+}
+
+impl Histogram {
+    /// Creates the histogram of the given times.
+    pub fn new(times: &[Duration]) -> Self {
+        let mut millis = times
+            .iter()
+            .map(|time| time.as_millis() as u64)
+            .collect::<Vec<_>>();
+        millis.sort_unstable();
+
+        let mut counts = [0; HISTOGRAM_BUCKETS.len() + 1];
+        for ms in &millis {
+            let bucket = HISTOGRAM_BUCKETS
+                .iter()
+                .position(|le_ms| ms <= le_ms)
+                .unwrap_or(HISTOGRAM_BUCKETS.len());
+            counts[bucket] += 1;
+        }
+        let buckets = HISTOGRAM_BUCKETS
+            .iter()
+            .copied()
+            .map(Some)
+            .chain([None])
+            .zip(counts)
+            .map(|(le_ms, count)| HistogramBucket { le_ms, count })
+            .collect();
+
+        let percentile = |percent: usize| {
+            millis
+                .len()
+                .checked_sub(1)
+                .map_or(0, |last| millis[last * percent / 100])
+        };
+
+        Self {
+            count: millis.len(),
+            min_ms: millis.first().copied().unwrap_or_default(),
+            max_ms: millis.last().copied().unwrap_or_default(),
+            mean_ms: if millis.is_empty() {
+                0.0
+            } else {
+                millis.iter().sum::<u64>() as f64 / millis.len() as f64
+            },
+            p50_ms: percentile(50),
+            p90_ms: percentile(90),
+            p99_ms: percentile(99),
+            buckets,
+        }
+    }
+}
+
+/// Machine-readable performance report printed in the benchmark mode (the `--bench` option).
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct PerformanceReport {
+    /// Version of the spec-test tool, so the reports of different releases can be compared.
+    pub version: &'static str,
+    /// Duration of the whole run (in milliseconds).
+    pub total_ms: u64,
+    /// Duration of the verification of the original code (in milliseconds).
+    pub original_verification_ms: u64,
+    /// Duration of the generation of the mutants (in milliseconds). `None` if the previously generated
+    /// mutants were used.
+    pub generation_ms: Option<u64>,
+    /// Number of the generated mutants.
+    pub mutants_generated: usize,
+    /// Number of the mutants generated per second.
+    pub generation_throughput: Option<f64>,
+    /// Duration of the verification of the mutants (in milliseconds).
+    pub verification_ms: u64,
+    /// Number of the verified mutants.
+    pub mutants_verified: usize,
+    /// Number of the mutants verified per second.
+    pub verification_throughput: f64,
+    /// Histogram of the verification times of the mutants verified on their own (the mutants verified
+    /// in batches are counted in the throughput only).
+    pub verification: Histogram,
+}
+
+impl PerformanceReport {
+    /// Creates the performance report from the benchmarks of the run.
+    ///
+    /// `verification_times` are the verification times of the mutants verified on their own and
+    /// `mutants_verified` is the number of all verified mutants (including the batched ones).
+    pub fn new(
+        benchmarks: &Benchmarks,
+        generated: bool,
+        mutants_generated: usize,
+        mutants_verified: usize,
+        verification_times: &[Duration],
+    ) -> Self {
+        let throughput = |count: usize, elapsed: Duration| {
+            if elapsed.is_zero() {
+                0.0
+            } else {
+                count as f64 / elapsed.as_secs_f64()
+            }
+        };
+        let generation = generated.then_some(benchmarks.mutator.elapsed);
+
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            total_ms: benchmarks.spec_test.elapsed.as_millis() as u64,
+            original_verification_ms: benchmarks.original.elapsed.as_millis() as u64,
+            generation_ms: generation.map(|elapsed| elapsed.as_millis() as u64),
+            mutants_generated,
+            generation_throughput: generation.map(|elapsed| throughput(mutants_generated, elapsed)),
+            verification_ms: benchmarks.prover.elapsed.as_millis() as u64,
+            mutants_verified,
+            verification_throughput: throughput(mutants_verified, benchmarks.prover.elapsed),
+            verification: Histogram::new(verification_times),
+        }
+    }
+}
+
+/// Selects the mutants verified in the benchmark mode: `count` mutants evenly spread over all `total`
+/// mutants (or all of them, if `count` isn't given). Returns a flag for each mutant.
+pub(crate) fn sample_mutants(total: usize, count: Option<usize>) -> Vec<bool> {
+    let mut sampled = vec![count.is_none(); total];
+    if let Some(count) = count.filter(|count| *count > 0) {
+        let count = count.min(total);
+        for i in 0..count {
+            sampled[i * total / count] = true;
+        }
+    }
+    sampled
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -134,6 +304,7 @@ mod tests {
     fn benchmarks_records_multiple_benchmarks() {
         let mut benchmarks = Benchmarks {
             spec_test: Benchmark::new(),
+            original: Benchmark::new(),
             mutator: Benchmark::new(),
             prover: Benchmark::new(),
             prover_results: Vec::new(),
@@ -155,4 +326,54 @@ mod tests {
         assert!(benchmarks.mutator.elapsed >= Duration::from_millis(100));
         assert!(benchmarks.prover.elapsed >= Duration::from_millis(100));
     }
+
+    #[test]
+    fn histogram_counts_times_in_buckets() {
+        let histogram = Histogram::new(&[
+            Duration::from_millis(5),
+            Duration::from_millis(40),
+            Duration::from_millis(50),
+            Duration::from_millis(400_000),
+        ]);
+
+        assert_eq!(histogram.count, 4);
+        assert_eq!(histogram.min_ms, 5);
+        assert_eq!(histogram.max_ms, 400_000);
+        assert_eq!(histogram.p50_ms, 40);
+        assert_eq!(histogram.p99_ms, 50);
+        assert_eq!(histogram.buckets[0], HistogramBucket {
+            le_ms: Some(10),
+            count: 1
+        });
+        assert_eq!(histogram.buckets[1], HistogramBucket {
+            le_ms: Some(50),
+            count: 2
+        });
+        assert_eq!(
+            histogram.buckets.last(),
+            Some(&HistogramBucket {
+                le_ms: None,
+                count: 1
+            })
+        );
+        assert_eq!(histogram.buckets.iter().map(|b| b.count).sum::<usize>(), 4);
+    }
+
+    #[test]
+    fn histogram_of_no_times_is_empty() {
+        let histogram = Histogram::new(&[]);
+        assert_eq!(histogram.count, 0);
+        assert_eq!(histogram.mean_ms, 0.0);
+        assert!(histogram.buckets.iter().all(|b| b.count == 0));
+    }
+
+    #[test]
+    fn mutants_are_sampled_evenly() {
+        assert_eq!(sample_mutants(3, None), vec![true, true, true]);
+        assert_eq!(sample_mutants(6, Some(3)), vec![
+            true, false, true, false, true, false
+        ]);
+        assert_eq!(sample_mutants(2, Some(5)), vec![true, true]);
+        assert_eq!(sample_mutants(2, Some(0)), vec![false, false]);
+    }
 }
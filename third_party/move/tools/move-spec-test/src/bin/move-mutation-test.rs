@@ -0,0 +1,134 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![forbid(unsafe_code)]
+
+//! Unified frontend of the mutation testing tools, running both the mutator and the specification tester
+//! (and the tools working with their reports) as subcommands of a single binary.
+
+use clap::{Args, Parser, Subcommand};
+use move_package::BuildConfig;
+use move_spec_test::{
+    commands::{self, run_command, ReportCommand},
+    suppressions::DEFAULT_SUPPRESSIONS_FILE,
+};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug, Clone)]
+#[clap(name = "move-mutation-test", author, version)]
+pub struct Opts {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+/// Options selecting and building the Move package, shared by the subcommands working with the package.
+#[derive(Args, Debug, Clone)]
+pub struct PackageOptions {
+    /// The path to the Move package.
+    #[clap(long, short, value_parser)]
+    pub package_path: Option<PathBuf>,
+    /// The build configuration for the Move package.
+    #[clap(flatten)]
+    pub build_config: BuildConfig,
+}
+
+impl PackageOptions {
+    fn package_path(&self) -> PathBuf {
+        self.package_path.clone().unwrap_or(PathBuf::from("."))
+    }
+}
+
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Generate the mutants of the package (the same as the `move-mutator` tool).
+    Mutate {
+        #[clap(flatten)]
+        package: PackageOptions,
+        /// Command line options for mutator
+        #[clap(flatten)]
+        options: move_mutator::cli::CLIOptions,
+    },
+    /// Test the specifications of the package (the same as the `move-spec-test` tool).
+    SpecTest {
+        #[clap(flatten)]
+        package: PackageOptions,
+        /// Command line options for specification tester
+        #[clap(flatten)]
+        options: move_spec_test::cli::CLIOptions,
+        /// Optional subcommand. If not provided, the specification testing is run.
+        #[clap(subcommand)]
+        command: Option<commands::Command>,
+    },
+    /// Work with the JSON reports.
+    Report {
+        #[clap(subcommand)]
+        command: ReportCommand,
+    },
+    /// Interactively triage surviving mutants from a JSON report.
+    Triage {
+        /// The JSON report generated with the `--output` option (or the archive created with the `--archive`
+        /// option).
+        #[clap(long, value_parser)]
+        report: PathBuf,
+        /// The suppression file where the decisions are stored.
+        #[clap(long, value_parser, default_value = DEFAULT_SUPPRESSIONS_FILE)]
+        suppressions: PathBuf,
+    },
+    /// Compare two JSON reports (e.g. of the base branch and of a pull request).
+    Compare {
+        /// The base report generated with the `--output` option (or the archive created with the `--archive`
+        /// option).
+        #[clap(value_parser)]
+        base: PathBuf,
+        /// The report compared with the base one.
+        #[clap(value_parser)]
+        head: PathBuf,
+    },
+}
+
+fn main() -> anyhow::Result<()> {
+    let opts = Opts::parse();
+
+    // Commands working only with the reports don't need the package, so they are run with the default options.
+    let command = match opts.command {
+        Command::Mutate { package, options } => {
+            return move_mutator::run_move_mutator(
+                options,
+                &package.build_config,
+                &package.package_path(),
+            )
+        },
+        Command::SpecTest {
+            package,
+            options,
+            command,
+        } => {
+            let package_path = package.package_path();
+            return match command {
+                Some(command) => {
+                    run_command(&command, &options, &package.build_config, &package_path)
+                },
+                None => {
+                    move_spec_test::run_spec_test(&options, &package.build_config, &package_path)
+                },
+            };
+        },
+        Command::Report { command } => commands::Command::Report { command },
+        Command::Triage {
+            report,
+            suppressions,
+        } => commands::Command::Triage {
+            report,
+            suppressions,
+        },
+        Command::Compare { base, head } => commands::Command::Compare { base, head },
+    };
+
+    run_command(
+        &command,
+        &Default::default(),
+        &BuildConfig::default(),
+        &PathBuf::from("."),
+    )
+}
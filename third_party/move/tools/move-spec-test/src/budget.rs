@@ -0,0 +1,83 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+/// Wall-clock limit of the whole run, started when the budget is created.
+///
+/// The budget is checked between the verifications of the mutants, so the prover already running when the
+/// budget is exhausted is not stopped and the run exceeds the budget by at most one verification.
+pub(crate) struct TimeBudget {
+    deadline: Instant,
+}
+
+impl TimeBudget {
+    /// Creates the budget expiring after the given duration.
+    pub(crate) fn new(budget: Duration) -> Self {
+        Self {
+            deadline: Instant::now() + budget,
+        }
+    }
+
+    /// Returns true if the budget has expired.
+    pub(crate) fn is_exhausted(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+/// Reorders the items, so the groups (e.g. the mutants of the same function) take turns: the first item of
+/// each group comes first, then the second item of each group, etc.
+///
+/// Groups are ordered by their first item and items of each group keep their order, so a run stopped in the
+/// middle verifies a sample spread evenly over all groups instead of all items of the first groups.
+pub(crate) fn interleave_groups<T, K, F>(items: Vec<T>, key: F) -> Vec<T>
+where
+    K: Ord,
+    F: Fn(&T) -> K,
+{
+    let mut groups: Vec<Vec<T>> = vec![];
+    let mut positions = BTreeMap::new();
+    for item in items {
+        let position = *positions.entry(key(&item)).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[position].push(item);
+    }
+
+    let mut groups = groups.into_iter().map(Vec::into_iter).collect::<Vec<_>>();
+    let mut interleaved = vec![];
+    loop {
+        let round = groups
+            .iter_mut()
+            .filter_map(Iterator::next)
+            .collect::<Vec<_>>();
+        if round.is_empty() {
+            return interleaved;
+        }
+        interleaved.extend(round);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn groups_take_turns() {
+        let items = vec!["f1", "f2", "g1", "f3", "h1", "g2"];
+        let interleaved = interleave_groups(items, |item| item.chars().next());
+        assert_eq!(interleaved, vec!["f1", "g1", "h1", "f2", "g2", "f3"]);
+        assert!(interleave_groups(Vec::<u8>::new(), |item| *item).is_empty());
+    }
+
+    #[test]
+    fn budget_expires() {
+        assert!(TimeBudget::new(Duration::ZERO).is_exhausted());
+        assert!(!TimeBudget::new(Duration::from_secs(3600)).is_exhausted());
+    }
+}
@@ -2,10 +2,17 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use clap::Parser;
-use move_mutator::cli::ModuleFilter;
+use clap::{Parser, ValueEnum};
+use move_mutator::{
+    cli::ModuleFilter,
+    configuration::{Configuration, Profile},
+};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
 
 /// Command line options for specification test tool.
 #[derive(Parser, Default, Debug, Clone, Deserialize, Serialize)]
@@ -17,15 +24,37 @@ pub struct CLIOptions {
     /// Work only over specified modules.
     #[clap(long, short, value_parser, default_value = "all")]
     pub include_modules: ModuleFilter,
-    /// Optional configuration file for mutator tool.
+    /// Verify only mutants of the specified functions (e.g. `Sum::sum`).
+    #[clap(long, value_parser, value_delimiter = ',')]
+    pub include_functions: Vec<String>,
+    /// Optional configuration file for mutator tool (`-` reads it from the standard input).
     #[clap(long, value_parser)]
     pub mutator_conf: Option<PathBuf>,
     /// Optional configuration file for prover tool.
     #[clap(long, value_parser)]
     pub prover_conf: Option<PathBuf>,
-    /// Save report to a JSON file.
+    /// Built-in prover settings (timeouts, number of Boogie instances) tuned for the given target:
+    /// `aptos-framework`, `move-stdlib` or `custom` (the prover defaults or the given prover configuration).
+    #[clap(long, value_enum, default_value = "custom")]
+    pub prover_preset: ProverPreset,
+    /// Save report to a JSON file (or to a YAML or TOML file, if the path has the `.yaml`, `.yml` or `.toml`
+    /// extension). Use `github` to print the surviving mutants as GitHub workflow annotations instead.
     #[clap(short, long, value_parser)]
     pub output: Option<PathBuf>,
+    /// Save report (including spec hints for surviving mutants) to a Markdown file.
+    #[clap(long, value_parser)]
+    pub markdown_report: Option<PathBuf>,
+    /// Save the outcomes of the mutants (one row per mutant) to a CSV file, e.g. for the spreadsheet analysis.
+    #[clap(long, value_parser)]
+    pub csv_report: Option<PathBuf>,
+    /// Write the mutants, the mutator reports and the JSON report to the single `.tar.zst` archive at the
+    /// given path (e.g. to upload it as a CI artifact). The `verify` and `triage` commands read the archive.
+    #[clap(long, value_parser)]
+    pub archive: Option<PathBuf>,
+    /// Print the newline-delimited JSON events (`run_started`, `mutant_generated`, `mutant_proving`,
+    /// `mutant_killed`, `run_finished`) to the standard output, so the progress can be observed in real time.
+    #[clap(long, default_value = "false")]
+    pub event_stream: bool,
     /// Use previously generated mutants.
     #[clap(long, short, value_parser)]
     pub use_generated_mutants: Option<PathBuf>,
@@ -35,6 +64,326 @@ pub struct CLIOptions {
     /// Extra arguments to pass to the prover.
     #[clap(long, value_parser)]
     pub extra_prover_args: Option<Vec<String>>,
+    /// Name of the configuration profile to use (e.g. quick, thorough, ci).
+    #[clap(long)]
+    pub profile: Option<String>,
+    /// Additional prover configuration files used to verify each mutant (matrix mode).
+    #[clap(long, value_parser, value_delimiter = ',')]
+    pub matrix_prover_conf: Vec<PathBuf>,
+    /// Solver seeds used to verify each mutant (matrix mode).
+    #[clap(long, value_delimiter = ',')]
+    pub matrix_seeds: Vec<usize>,
+    /// Decides when a mutant verified against multiple prover configurations is considered killed.
+    #[clap(long, value_enum, default_value = "all")]
+    pub kill_policy: KillPolicy,
+    /// Number of times the prover is re-run when it fails because of an infrastructure error
+    /// (e.g. Boogie crash or solver timeout).
+    #[clap(long, default_value = "0")]
+    pub prover_retries: usize,
+    /// Modules verified by the prover: `all` (including the dependencies) or `targets` (only the modules
+    /// of the tested package, so a known-unverifiable dependency doesn't block the spec testing).
+    #[clap(long, value_enum, default_value = "all")]
+    pub verify_scope: VerifyScope,
+    /// Modules verified by the prover for each mutant: `package` (the whole package), `module` (only the
+    /// mutated module), `dependents` (the mutated module and the modules of the package using it) or
+    /// `observers` (the modules with the specifications which can observe the mutated function).
+    #[clap(long, value_enum, default_value = "package")]
+    pub mutant_scope: MutantScope,
+    /// Experimental: verify up to the given number of mutants (of different files and independent modules) in
+    /// a single prover invocation. Batches with a non-surviving mutant are bisected to attribute the kills.
+    #[clap(long, value_parser)]
+    pub batch_mutants: Option<usize>,
+    /// Mutate only the externally reachable functions (`public entry` functions and scripts).
+    #[clap(long, default_value = "false")]
+    pub entry_functions_only: bool,
+    /// Mutate also the private functions called (directly or transitively) by the selected functions.
+    #[clap(long, default_value = "false")]
+    pub include_callees: bool,
+    /// Mutate also the test code (`#[test]` and `#[test_only]` functions and `#[test_only]` modules), which is
+    /// skipped by default, as its mutants can't be killed by the specifications.
+    #[clap(long, default_value = "false")]
+    pub include_test_code: bool,
+    /// Mutate also the spec functions and the global invariants, to check if the prover notices the changes
+    /// of the specifications.
+    #[clap(long, default_value = "false")]
+    pub mutate_specs: bool,
+    /// Mutate also the specification files (`*.spec.move`), which are left untouched by default (they are
+    /// still copied to the verified packages).
+    #[clap(long, default_value = "false")]
+    pub mutate_spec_files: bool,
+    /// Type check the mutants before running the prover and skip the ones which don't type check (e.g.
+    /// mutants changing the type of an expression), as the prover would reject them anyway.
+    #[clap(long, default_value = "false")]
+    pub only_valid: bool,
+    /// Generate also up to the given number of mutants spanning two files, which combine a mutant of a function
+    /// with a mutant of its caller from another module.
+    #[clap(long)]
+    pub multi_file_mutants: Option<usize>,
+    /// Number of context lines in the diffs of the mutants (3 by default).
+    #[clap(long)]
+    pub diff_context: Option<usize>,
+    /// Highlight the changed words of the diffs (`[-old-]{+new+}`).
+    #[clap(long, default_value = "false")]
+    pub word_diff: bool,
+    /// Stop at the first file which can't be mutated, instead of skipping it and continuing with the other
+    /// files.
+    #[clap(long, default_value = "false")]
+    pub strict: bool,
+    /// Number of the generated mutants waiting to be written by the writer thread (64 by default). Zero
+    /// writes the mutants synchronously.
+    #[clap(long)]
+    pub write_buffer: Option<usize>,
+    /// Fix all randomness of the run (e.g. the downsampling of the mutants), so the same mutants are tested
+    /// and reported in the same order in every run (for reproducible CI runs).
+    #[clap(long, default_value = "false")]
+    pub deterministic: bool,
+    /// Print the diffs of the surviving mutants in the console summary.
+    #[clap(long, default_value = "false")]
+    pub show_diffs: bool,
+    /// Color the diffs printed in the console summary: `auto` (if the output is a terminal), `always` or
+    /// `never`.
+    #[clap(long, value_enum, default_value = "auto")]
+    pub diff_color: DiffColor,
+    /// Plain console output: draw the summary table with the ASCII characters only and don't color the diffs
+    /// (regardless of `--diff-color`).
+    #[clap(long, default_value = "false")]
+    pub no_color: bool,
+    /// Skip mutants marked as equivalent or suppressed in the given suppression file (see the `triage`
+    /// subcommand).
+    #[clap(long, value_parser)]
+    pub suppressions: Option<PathBuf>,
+    /// Keep the temporary files (package copies, mutants, prover outputs) for debugging purposes.
+    #[clap(long, default_value = "false")]
+    pub keep_temp: bool,
+    /// Glob patterns (relative to the package root) of the entries which are not copied into the package copies
+    /// used for proving, besides the build artifacts and the version control directories (e.g. `docs/**`).
+    #[clap(long, value_delimiter = ',')]
+    pub copy_ignore: Vec<String>,
+    /// Append the scores and mutant outcomes of this run to the given history file (see the `history`
+    /// subcommand).
+    #[clap(long, value_parser)]
+    pub history: Option<PathBuf>,
+    /// Verify only the given shard of the mutants (e.g. `2/4` verifies the second of four shards).
+    /// Mutants are partitioned deterministically, so reports of all shards can be merged later.
+    #[clap(long, value_parser)]
+    pub shard: Option<Shard>,
+    /// Stop the verification once the given number of surviving mutants (1 if the number is omitted) is
+    /// found and fail the run, instead of verifying the remaining mutants of an obviously weak spec suite.
+    #[clap(long, num_args = 0..=1, default_missing_value = "1")]
+    pub fail_fast: Option<usize>,
+    /// Wall-clock limit of the run (e.g. `30m` or `1h 30m`). Mutants are verified in turns across the functions
+    /// until the budget is exhausted, then the partial score is reported with the share of the verified mutants.
+    #[clap(long, value_parser = humantime::parse_duration)]
+    pub time_budget: Option<Duration>,
+    /// Verify the most informative mutants first: the mutants surviving the last run of the history file
+    /// (`--history`), then the mutants of the entry functions and of the security-relevant operators.
+    /// Implied by `--time-budget`.
+    #[clap(long, default_value = "false")]
+    pub prioritize: bool,
+    /// Coverage map collected by `move test --coverage` (usually `.coverage_map.mvcov` in the package
+    /// directory). Mutants of the lines executed by the tests are verified first.
+    #[clap(long, value_parser)]
+    pub coverage_map: Option<PathBuf>,
+    /// Don't verify the mutants of the lines not executed by any test (according to `--coverage-map`),
+    /// report them as not covered surviving mutants instead.
+    #[clap(long, default_value = "false")]
+    pub skip_uncovered: bool,
+    /// Run the prover using the given command instead of the built-in prover (e.g. a script
+    /// dispatching the job to a remote worker). Placeholders `{package}`, `{timeout}`, `{seed}` and
+    /// `{boogie_flags}` are substituted (quoted for the shell) before running the command.
+    #[clap(long, value_parser)]
+    pub remote_command: Option<String>,
+    /// Run each prover invocation in an isolated environment: `none` or `docker[:image]`.
+    #[clap(long, value_parser, default_value = "none")]
+    pub isolate: Isolation,
+    /// Maximum memory (in megabytes) the solver can use for a single prover invocation. On Unix systems, it
+    /// also limits the virtual memory of Boogie and the solver. With the Docker isolation, the limit applies
+    /// to the whole container.
+    #[clap(long, value_parser)]
+    pub max_prover_memory: Option<u64>,
+    /// Maximum CPU time (in seconds) of the prover processes for a single prover invocation.
+    #[clap(long, value_parser)]
+    pub max_prover_cpu_time: Option<u64>,
+    /// Watch the package sources and re-run the specification testing for the functions affected by
+    /// each change.
+    #[clap(long)]
+    pub watch: bool,
+    /// Run in the benchmark mode: measure the generation and verification throughput and print a
+    /// machine-readable performance report (a single line of JSON) at the end.
+    #[clap(long, default_value = "false")]
+    pub bench: bool,
+    /// Verify only the given number of mutants (evenly spread over all mutants) in the benchmark mode.
+    #[clap(long, value_parser, requires = "bench")]
+    pub bench_mutants: Option<usize>,
+}
+
+/// Value of the `--output` option printing the surviving mutants as GitHub workflow annotations.
+pub const GITHUB_OUTPUT: &str = "github";
+
+/// Docker image used when no image is given in the `--isolate docker` option.
+pub const DEFAULT_DOCKER_IMAGE: &str = "aptoslabs/tools:nightly";
+
+/// Isolation of the prover invocations.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Isolation {
+    /// The prover runs in the spec-test process.
+    #[default]
+    None,
+    /// Each prover invocation runs in a separate container created from the given Docker image.
+    Docker(String),
+}
+
+impl FromStr for Isolation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "none" => Ok(Isolation::None),
+            _ if s == "docker" => Ok(Isolation::Docker(DEFAULT_DOCKER_IMAGE.to_owned())),
+            Some(("docker", image)) if !image.is_empty() => Ok(Isolation::Docker(image.to_owned())),
+            _ => Err(format!(
+                "Invalid isolation '{s}', expected 'none' or 'docker[:image]'"
+            )),
+        }
+    }
+}
+
+/// Shard of the mutants verified by a single run, given as `index/count` (1-based index).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Shard {
+    /// Index of the shard (from 1 to `count`).
+    pub index: u64,
+    /// Total number of shards.
+    pub count: u64,
+}
+
+impl Shard {
+    /// Checks if the mutant with the given ID belongs to this shard.
+    /// The assignment depends only on the mutant ID, so it's stable between runs and machines.
+    #[must_use]
+    pub fn contains(&self, mutant_id: &str) -> bool {
+        let hash = mutant_id.bytes().fold(0u64, |hash, byte| {
+            hash.wrapping_mul(31).wrapping_add(u64::from(byte))
+        });
+        hash % self.count == self.index - 1
+    }
+}
+
+impl FromStr for Shard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid shard '{s}', expected format is 'k/n'"))?;
+        let index = index
+            .trim()
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid shard index '{index}': {e}"))?;
+        let count = count
+            .trim()
+            .parse::<u64>()
+            .map_err(|e| format!("Invalid shard count '{count}': {e}"))?;
+
+        if count == 0 || index == 0 || index > count {
+            return Err(format!(
+                "Invalid shard '{s}', the index must be between 1 and the shard count"
+            ));
+        }
+
+        Ok(Shard { index, count })
+    }
+}
+
+/// Built-in prover settings for the common targets, so the prover doesn't have to be tuned to get a usable run.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ProverPreset {
+    /// Settings used by `aptos move prove` for the Aptos framework, with a hard timeout, so a single mutant
+    /// can't stall the whole run.
+    AptosFramework,
+    /// Settings for small packages depending only on the Move standard library.
+    MoveStdlib,
+    /// No preset, the prover defaults (or the given prover configuration) are used.
+    #[default]
+    Custom,
+}
+
+impl ProverPreset {
+    /// Applies the settings of the preset to the prover options.
+    pub fn apply(self, prover_conf: &mut move_prover::cli::Options) {
+        let backend = &mut prover_conf.backend;
+        match self {
+            ProverPreset::AptosFramework => {
+                backend.vc_timeout = 40;
+                backend.proc_cores = 4;
+                backend.stratification_depth = 6;
+                backend.global_timeout_overwrite = true;
+                backend.num_instances = 4;
+                backend.hard_timeout_secs = 600;
+            },
+            ProverPreset::MoveStdlib => {
+                backend.vc_timeout = 40;
+                backend.proc_cores = 4;
+                backend.num_instances = 1;
+                backend.hard_timeout_secs = 300;
+            },
+            ProverPreset::Custom => {},
+        }
+    }
+}
+
+/// Policy deciding when a mutant is killed if it's verified against multiple prover configurations.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum KillPolicy {
+    /// Mutant is killed only if all prover configurations fail.
+    #[default]
+    All,
+    /// Mutant is killed if any prover configuration fails.
+    Any,
+}
+
+/// Scope of the modules verified by the prover (both for the original code and the mutants).
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum VerifyScope {
+    /// All modules are verified, including the dependencies of the package.
+    #[default]
+    All,
+    /// Only the modules of the tested package are verified.
+    Targets,
+}
+
+/// Scope of the modules verified by the prover for each mutant.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum MutantScope {
+    /// The whole package is verified.
+    #[default]
+    Package,
+    /// Only the mutated module is verified.
+    Module,
+    /// The mutated module and the modules of the package using it (directly or transitively) are verified.
+    Dependents,
+    /// Only the modules with the specifications which can observe the mutated function (its own spec and
+    /// the specs of its callers) are verified. Mutants which no specification can observe are reported
+    /// as unobservable without running the prover.
+    Observers,
+}
+
+/// Coloring of the diffs printed in the console.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffColor {
+    /// Diffs are colored if the output is a terminal.
+    #[default]
+    Auto,
+    /// Diffs are always colored.
+    Always,
+    /// Diffs are never colored.
+    Never,
 }
 
 /// This function creates a mutator CLI options from the given spec-test options.
@@ -45,6 +394,19 @@ pub fn create_mutator_options(options: &CLIOptions) -> move_mutator::cli::CLIOpt
         mutate_modules: options.include_modules.clone(),
         configuration_file: options.mutator_conf.clone(),
         verify_mutants: options.verify_mutants,
+        profile: options.profile.clone(),
+        entry_functions_only: options.entry_functions_only,
+        include_callees: options.include_callees,
+        include_test_code: options.include_test_code,
+        mutate_specs: options.mutate_specs,
+        mutate_spec_files: options.mutate_spec_files,
+        only_valid: options.only_valid,
+        multi_file_mutants: options.multi_file_mutants,
+        diff_context: options.diff_context,
+        word_diff: options.word_diff,
+        strict: options.strict,
+        write_buffer: options.write_buffer,
+        deterministic: options.deterministic,
         ..Default::default()
     }
 }
@@ -54,30 +416,116 @@ pub fn create_mutator_options(options: &CLIOptions) -> move_mutator::cli::CLIOpt
 /// # Errors
 /// Errors are returned as `anyhow::Result`.
 pub fn generate_prover_options(options: &CLIOptions) -> anyhow::Result<move_prover::cli::Options> {
-    let prover_conf = if let Some(conf) = &options.prover_conf {
+    if options.prover_preset != ProverPreset::Custom
+        && (options.prover_conf.is_some() || options.extra_prover_args.is_some())
+    {
+        anyhow::bail!(
+            "The `--prover-preset` option can't be combined with the prover configuration (`--prover-conf`, `--matrix-prover-conf` or `--extra-prover-args`), use the `custom` preset instead"
+        );
+    }
+
+    let mut prover_conf = if let Some(conf) = &options.prover_conf {
         move_prover::cli::Options::create_from_toml_file(conf.to_str().unwrap_or(""))?
     } else if let Some(args) = &options.extra_prover_args {
         move_prover::cli::Options::create_from_args(args)?
     } else {
         move_prover::cli::Options::default()
     };
+    options.prover_preset.apply(&mut prover_conf);
+
+    if let Some(profile) = resolve_profile(options)? {
+        if let Some(timeout) = profile.prover_timeout {
+            prover_conf.backend.vc_timeout = timeout;
+        }
+    }
 
     Ok(prover_conf)
 }
 
-/// This function checks if the mutator output path is provided in the configuration file.
-/// We don't need to check if the mutator output path is provided in the options as they were created
-/// from the spec-test options which does not allow setting it.
+/// This function generates the list of prover options used to verify each mutant.
+///
+/// Each prover configuration file given in the matrix options is combined with each solver seed.
+/// If no matrix options are given, the list contains only the options created by the
+/// `generate_prover_options` function.
+///
+/// # Errors
+/// Errors are returned as `anyhow::Result`.
+pub fn generate_prover_matrix(
+    options: &CLIOptions,
+) -> anyhow::Result<Vec<move_prover::cli::Options>> {
+    let mut configurations = if options.matrix_prover_conf.is_empty() {
+        vec![generate_prover_options(options)?]
+    } else {
+        options
+            .matrix_prover_conf
+            .iter()
+            .map(|conf| {
+                let conf = CLIOptions {
+                    prover_conf: Some(conf.clone()),
+                    ..options.clone()
+                };
+                generate_prover_options(&conf)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?
+    };
+
+    if !options.matrix_seeds.is_empty() {
+        configurations = configurations
+            .iter()
+            .flat_map(|conf| {
+                options.matrix_seeds.iter().map(|seed| {
+                    let mut conf = conf.clone();
+                    conf.backend.random_seed = *seed;
+                    conf
+                })
+            })
+            .collect();
+    }
+
+    Ok(configurations)
+}
+
+/// This function resolves the configuration profile selected in the options, in the environment
+/// variables or in the mutator configuration file.
+///
+/// # Errors
+/// Errors are returned as `anyhow::Result`.
+pub fn resolve_profile(options: &CLIOptions) -> anyhow::Result<Option<Profile>> {
+    let configuration = resolve_mutator_configuration(options, None)?;
+
+    Ok(configuration
+        .project
+        .profile
+        .as_deref()
+        .and_then(|name| configuration.get_profile(name)))
+}
+
+/// This function resolves the configuration of the mutator tool (e.g. to weigh the mutants by their
+/// operators). The `[mutation]` table of the manifest of the package is used if the package path is given.
+///
+/// # Errors
+/// Errors are returned as `anyhow::Result`.
+pub fn resolve_mutator_configuration(
+    options: &CLIOptions,
+    package_path: Option<&Path>,
+) -> anyhow::Result<Configuration> {
+    Configuration::resolve(
+        create_mutator_options(options),
+        package_path.map(Path::to_path_buf),
+    )
+}
+
+/// This function checks if the mutator output path is provided in the configuration file or in the
+/// environment variables. We don't need to check if the mutator output path is provided in the options
+/// as they were created from the spec-test options which does not allow setting it.
 #[must_use]
 pub fn check_mutator_output_path(options: &move_mutator::cli::CLIOptions) -> Option<PathBuf> {
-    if let Some(conf) = &options.configuration_file {
-        let c = move_mutator::configuration::Configuration::from_file(conf);
-        if let Ok(c) = c {
-            return c.project.out_mutant_dir;
-        }
-    };
+    let default_output_dir = move_mutator::cli::CLIOptions::default().out_mutant_dir;
 
-    None
+    Configuration::resolve(options.clone(), None)
+        .ok()
+        .and_then(|c| c.project.out_mutant_dir)
+        .filter(|path| Some(path) != default_output_dir.as_ref())
 }
 
 #[cfg(test)]
@@ -90,10 +538,29 @@ mod tests {
         let options = CLIOptions::default();
         assert!(options.move_sources.is_empty());
         assert_eq!(ModuleFilter::All, options.include_modules);
+        assert!(options.include_functions.is_empty());
         assert!(options.mutator_conf.is_none());
         assert!(options.prover_conf.is_none());
         assert!(options.output.is_none());
         assert!(options.extra_prover_args.is_none());
+        assert!(!options.watch);
+        assert!(!options.bench);
+        assert!(options.bench_mutants.is_none());
+        assert_eq!(options.verify_scope, VerifyScope::All);
+        assert_eq!(options.mutant_scope, MutantScope::Package);
+        assert!(options.batch_mutants.is_none());
+        assert!(options.coverage_map.is_none());
+        assert!(!options.skip_uncovered);
+        assert!(!options.only_valid);
+        assert!(options.multi_file_mutants.is_none());
+        assert!(options.archive.is_none());
+        assert_eq!(options.prover_preset, ProverPreset::Custom);
+        assert!(!options.event_stream);
+        assert_eq!(options.diff_color, DiffColor::Auto);
+        assert!(!options.no_color);
+        assert!(options.fail_fast.is_none());
+        assert!(options.time_budget.is_none());
+        assert!(!options.prioritize);
     }
 
     #[test]
@@ -103,12 +570,36 @@ mod tests {
         options.include_modules =
             ModuleFilter::Selected(vec!["test1".to_string(), "test2".to_string()]);
         options.mutator_conf = Some(PathBuf::from("path/to/mutator/conf"));
+        options.entry_functions_only = true;
+        options.include_callees = true;
+        options.include_test_code = true;
+        options.mutate_specs = true;
+        options.mutate_spec_files = true;
+        options.only_valid = true;
+        options.multi_file_mutants = Some(5);
+        options.diff_context = Some(1);
+        options.word_diff = true;
+        options.strict = true;
+        options.write_buffer = Some(0);
+        options.deterministic = true;
 
         let mutator_options = create_mutator_options(&options);
 
         assert_eq!(mutator_options.move_sources, options.move_sources);
         assert_eq!(mutator_options.mutate_modules, options.include_modules);
         assert_eq!(mutator_options.configuration_file, options.mutator_conf);
+        assert!(mutator_options.entry_functions_only);
+        assert!(mutator_options.include_callees);
+        assert!(mutator_options.include_test_code);
+        assert!(mutator_options.mutate_specs);
+        assert!(mutator_options.mutate_spec_files);
+        assert!(mutator_options.only_valid);
+        assert_eq!(mutator_options.multi_file_mutants, Some(5));
+        assert_eq!(mutator_options.diff_context, Some(1));
+        assert!(mutator_options.word_diff);
+        assert!(mutator_options.strict);
+        assert_eq!(mutator_options.write_buffer, Some(0));
+        assert!(mutator_options.deterministic);
     }
 
     #[test]
@@ -168,4 +659,151 @@ mod tests {
         );
         assert_eq!(prover_options.backend.z3_exe, "/path/to/z3".to_owned());
     }
+
+    #[test]
+    fn generate_prover_options_applies_preset() {
+        let options = CLIOptions {
+            prover_preset: ProverPreset::AptosFramework,
+            ..Default::default()
+        };
+
+        let prover_options = generate_prover_options(&options).unwrap();
+        assert_eq!(prover_options.backend.vc_timeout, 40);
+        assert_eq!(prover_options.backend.num_instances, 4);
+        assert_eq!(prover_options.backend.hard_timeout_secs, 600);
+
+        let options = CLIOptions {
+            prover_preset: ProverPreset::MoveStdlib,
+            extra_prover_args: Some(vec!["--vc-timeout=10".to_owned()]),
+            ..Default::default()
+        };
+        assert!(generate_prover_options(&options).is_err());
+    }
+
+    #[test]
+    fn generate_prover_matrix_returns_single_configuration_by_default() {
+        let options = CLIOptions::default();
+        assert_eq!(generate_prover_matrix(&options).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn generate_prover_matrix_combines_configurations_with_seeds() {
+        let toml_content = r#"
+            [backend]
+            boogie_exe = "/path/to/boogie"
+        "#;
+
+        fs::write("test_matrix_prover_conf.toml", toml_content).unwrap();
+
+        let options = CLIOptions {
+            matrix_prover_conf: vec![
+                PathBuf::from("test_matrix_prover_conf.toml"),
+                PathBuf::from("test_matrix_prover_conf.toml"),
+            ],
+            matrix_seeds: vec![1, 2, 3],
+            ..Default::default()
+        };
+
+        let matrix = generate_prover_matrix(&options);
+        fs::remove_file("test_matrix_prover_conf.toml").unwrap();
+
+        let matrix = matrix.unwrap();
+        assert_eq!(matrix.len(), 6);
+        assert!(matrix
+            .iter()
+            .all(|conf| conf.backend.boogie_exe == "/path/to/boogie"));
+        assert_eq!(
+            matrix
+                .iter()
+                .map(|conf| conf.backend.random_seed)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn generate_prover_options_applies_profile_timeout() {
+        let options = CLIOptions {
+            profile: Some("quick".to_owned()),
+            ..Default::default()
+        };
+
+        let prover_options = generate_prover_options(&options).unwrap();
+        let profile = Profile::builtin("quick").unwrap();
+
+        assert_eq!(
+            Some(prover_options.backend.vc_timeout),
+            profile.prover_timeout
+        );
+    }
+
+    #[test]
+    fn generate_prover_options_fails_for_unknown_profile() {
+        let options = CLIOptions {
+            profile: Some("unknown".to_owned()),
+            ..Default::default()
+        };
+
+        assert!(generate_prover_options(&options).is_err());
+    }
+
+    #[test]
+    fn shard_is_parsed_from_string() {
+        assert_eq!(Shard::from_str("2/4"), Ok(Shard { index: 2, count: 4 }));
+        assert!(Shard::from_str("0/4").is_err());
+        assert!(Shard::from_str("5/4").is_err());
+        assert!(Shard::from_str("1/0").is_err());
+        assert!(Shard::from_str("1").is_err());
+        assert!(Shard::from_str("a/b").is_err());
+    }
+
+    #[test]
+    fn fail_fast_limit_defaults_to_one_mutant() {
+        let parse = |args: &[&str]| {
+            CLIOptions::try_parse_from(std::iter::once("spec-test").chain(args.iter().copied()))
+                .unwrap()
+                .fail_fast
+        };
+        assert_eq!(parse(&[]), None);
+        assert_eq!(parse(&["--fail-fast"]), Some(1));
+        assert_eq!(parse(&["--fail-fast", "3"]), Some(3));
+        assert_eq!(parse(&["--fail-fast", "--strict"]), Some(1));
+    }
+
+    #[test]
+    fn time_budget_is_parsed_from_human_readable_duration() {
+        let options = CLIOptions::try_parse_from(["spec-test", "--time-budget", "1h 30m"]).unwrap();
+        assert_eq!(options.time_budget, Some(Duration::from_secs(5400)));
+        assert!(CLIOptions::try_parse_from(["spec-test", "--time-budget", "soon"]).is_err());
+    }
+
+    #[test]
+    fn shards_partition_mutants() {
+        let shards = (1..=3)
+            .map(|index| Shard { index, count: 3 })
+            .collect::<Vec<_>>();
+        let ids = (0..100).map(|i| format!("{i:016x}")).collect::<Vec<_>>();
+
+        for id in &ids {
+            assert_eq!(shards.iter().filter(|s| s.contains(id)).count(), 1);
+        }
+        for shard in &shards {
+            assert!(ids.iter().any(|id| shard.contains(id)));
+        }
+    }
+
+    #[test]
+    fn isolation_is_parsed_from_string() {
+        assert_eq!(Isolation::from_str("none"), Ok(Isolation::None));
+        assert_eq!(
+            Isolation::from_str("docker"),
+            Ok(Isolation::Docker(DEFAULT_DOCKER_IMAGE.to_owned()))
+        );
+        assert_eq!(
+            Isolation::from_str("docker:my/prover:1.0"),
+            Ok(Isolation::Docker("my/prover:1.0".to_owned()))
+        );
+        assert!(Isolation::from_str("docker:").is_err());
+        assert!(Isolation::from_str("podman").is_err());
+    }
 }
@@ -0,0 +1,224 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cli::CLIOptions, history::DEFAULT_HISTORY_FILE, report::Report, serve::DEFAULT_SERVE_ADDRESS,
+    suggest::DEFAULT_SUGGESTIONS_DIR, suppressions::DEFAULT_SUPPRESSIONS_FILE,
+};
+use clap::{Subcommand, ValueEnum};
+use move_package::BuildConfig;
+use std::path::{Path, PathBuf};
+
+/// Subcommands of the specification testing tool, shared by the `move-spec-test` and `move-mutation-test`
+/// binaries.
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Interactively triage surviving mutants from a JSON report.
+    Triage {
+        /// The JSON report generated with the `--output` option (or the archive created with the `--archive`
+        /// option).
+        #[clap(long, value_parser)]
+        report: PathBuf,
+        /// The suppression file where the decisions are stored.
+        #[clap(long, value_parser, default_value = DEFAULT_SUPPRESSIONS_FILE)]
+        suppressions: PathBuf,
+    },
+    /// Write the skeletons of the spec blocks for the functions with surviving mutants (experimental).
+    SuggestSpecs {
+        /// The JSON report generated with the `--output` option (or the archive created with the `--archive`
+        /// option).
+        #[clap(long, value_parser)]
+        report: PathBuf,
+        /// The directory where the spec skeletons are written.
+        #[clap(long, value_parser, default_value = DEFAULT_SUGGESTIONS_DIR)]
+        output: PathBuf,
+    },
+    /// Print the trend of the mutation score recorded with the `--history` option.
+    History {
+        /// The history file storing the results of the previous runs.
+        #[clap(long, value_parser, default_value = DEFAULT_HISTORY_FILE)]
+        file: PathBuf,
+        /// Print the score of the matching modules only (e.g. `Sum` or `Sum::sum`).
+        #[clap(long, value_parser)]
+        module: Option<String>,
+        /// Print only the given number of the most recent runs.
+        #[clap(long, value_parser)]
+        last: Option<usize>,
+    },
+    /// Merge JSON reports (e.g. from sharded runs) into a single report.
+    MergeReports {
+        /// The JSON reports generated with the `--output` option.
+        #[clap(value_parser, required = true)]
+        reports: Vec<PathBuf>,
+        /// The path where to save the merged report.
+        #[clap(long, value_parser)]
+        output: PathBuf,
+    },
+    /// Compare two JSON reports (e.g. of the base branch and of a pull request).
+    Compare {
+        /// The base report generated with the `--output` option (or the archive created with the `--archive`
+        /// option).
+        #[clap(value_parser)]
+        base: PathBuf,
+        /// The report compared with the base one.
+        #[clap(value_parser)]
+        head: PathBuf,
+    },
+    /// Verify previously generated mutants against the current version of the package.
+    Verify {
+        /// The directory with the generated mutants and their `report.json` file (or the `.tar.zst` archive
+        /// created with the `--archive` option).
+        #[clap(long, value_parser)]
+        mutants_dir: PathBuf,
+    },
+    /// Replay the verification of a single mutant with the debug logs, keeping its workspace for inspection.
+    Replay {
+        /// The ID of the mutant to replay.
+        #[clap(long, value_parser)]
+        mutant_id: String,
+        /// The directory with the generated mutants and their `report.json` file (or the `.tar.zst` archive
+        /// created with the `--archive` option). If not given, the mutants are generated again.
+        #[clap(long, value_parser)]
+        mutants_dir: Option<PathBuf>,
+    },
+    /// Work with the JSON reports.
+    Report {
+        #[clap(subcommand)]
+        command: ReportCommand,
+    },
+    /// Run the JSON-RPC server for the IDE integration (e.g. to generate and test the mutants of the
+    /// function under the cursor).
+    Serve {
+        /// The address to listen on.
+        #[clap(long, value_parser, default_value = DEFAULT_SERVE_ADDRESS)]
+        address: String,
+    },
+    /// Print the JSON Schema of the report format.
+    Schema {
+        /// The report to print the schema for.
+        #[clap(long, value_enum, default_value = "spec-test")]
+        report: ReportKind,
+    },
+}
+
+/// Subcommands working with the JSON reports.
+#[derive(Subcommand, Debug, Clone)]
+pub enum ReportCommand {
+    /// Generate a static website (e.g. for GitHub Pages) from the JSON reports. Multiple reports
+    /// (e.g. from sharded runs) are merged first.
+    HtmlSite {
+        /// The JSON reports generated with the `--output` option.
+        #[clap(value_parser, required = true)]
+        reports: Vec<PathBuf>,
+        /// The directory where to generate the site.
+        #[clap(long, value_parser)]
+        output: PathBuf,
+        /// The title of the site.
+        #[clap(long, value_parser, default_value = "Specification testing report")]
+        title: String,
+    },
+}
+
+/// Kinds of the reports generated by the tools.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportKind {
+    /// The specification testing report (generated with the `--output` option).
+    SpecTest,
+    /// The mutator report (`report.json` stored with the generated mutants).
+    Mutator,
+}
+
+/// This function runs the subcommand of the specification testing tool.
+///
+/// # Arguments
+///
+/// * `command` - The subcommand to run.
+/// * `options` - A `cli::CLIOptions` representing the options for the spec test.
+/// * `config` - A `BuildConfig` representing the build configuration.
+/// * `package_path` - A `Path` to the package.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_command(
+    command: &Command,
+    options: &CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+) -> anyhow::Result<()> {
+    match command {
+        Command::Triage {
+            report,
+            suppressions,
+        } => crate::triage::run_triage(report, suppressions),
+        Command::SuggestSpecs { report, output } => {
+            crate::suggest::run_suggest_specs(report, output)
+        },
+        Command::History { file, module, last } => {
+            crate::history::print_history(file, module.as_deref(), *last)
+        },
+        Command::MergeReports { reports, output } => {
+            let merged = load_reports(reports)?;
+            merged.save_to_file(output)?;
+
+            println!("\nTotal mutants tested: {}", merged.mutants_tested());
+            println!("Total mutants killed: {}", merged.mutants_killed());
+            println!(
+                "Total mutants inconclusive: {}\n",
+                merged.mutants_inconclusive()
+            );
+            merged.print_table();
+            Ok(())
+        },
+        Command::Compare { base, head } => crate::compare::run_compare(base, head),
+        Command::Verify { mutants_dir } => {
+            crate::verify::run_verify(options, config, package_path, mutants_dir)
+        },
+        Command::Replay {
+            mutant_id,
+            mutants_dir,
+        } => crate::replay::run_replay(
+            options,
+            config,
+            package_path,
+            mutants_dir.as_deref(),
+            mutant_id,
+        ),
+        Command::Report {
+            command:
+                ReportCommand::HtmlSite {
+                    reports,
+                    output,
+                    title,
+                },
+        } => {
+            let merged = load_reports(reports)?;
+            crate::site::generate_site(&merged, title, output)?;
+            println!("Site generated in {}", output.display());
+            Ok(())
+        },
+        Command::Serve { address } => {
+            crate::serve::run_serve(options, config, package_path, address)
+        },
+        Command::Schema { report } => {
+            let schema = match report {
+                ReportKind::SpecTest => Report::json_schema(),
+                ReportKind::Mutator => move_mutator::report::Report::json_schema(),
+            };
+            println!("{}", serde_json::to_string_pretty(&schema)?);
+            Ok(())
+        },
+    }
+}
+
+/// Loads the JSON reports and merges them into a single report.
+fn load_reports(reports: &[PathBuf]) -> anyhow::Result<Report> {
+    let mut merged = Report::new();
+    for path in reports {
+        let report = Report::load_from_file(path)
+            .map_err(|e| anyhow::anyhow!("Can't load report {}: {e}", path.display()))?;
+        merged.merge(report);
+    }
+    Ok(merged)
+}
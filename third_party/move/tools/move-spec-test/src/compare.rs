@@ -0,0 +1,227 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::report::{MutantStatus, Report};
+use std::{collections::BTreeMap, path::Path};
+use tabled::{builder::Builder, settings::Style};
+
+/// Change of the mutation score of a single module between two reports.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScoreChange {
+    /// Name of the module (or `Total` for the whole package).
+    pub module: String,
+    /// Score in the base report (`None` if the module wasn't tested).
+    pub base: Option<f64>,
+    /// Score in the compared report (`None` if the module wasn't tested).
+    pub head: Option<f64>,
+}
+
+impl ScoreChange {
+    /// Returns the score difference, if the module was tested in both reports.
+    pub fn delta(&self) -> Option<f64> {
+        Some(self.head? - self.base?)
+    }
+}
+
+/// Comparison of two spec-test reports (e.g. of the base branch and of a pull request).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Comparison {
+    /// Score changes of the modules tested in any of the reports, sorted by the module name.
+    pub modules: Vec<ScoreChange>,
+    /// Score change of the whole package.
+    pub total: ScoreChange,
+    /// IDs of the mutants killed in the base report, which aren't killed in the compared one.
+    pub regressions: Vec<String>,
+    /// IDs of the mutants not killed in the base report, which are killed in the compared one.
+    pub improvements: Vec<String>,
+}
+
+impl Comparison {
+    /// Compares the `head` report with the `base` one. Mutants are matched by their stable IDs.
+    pub fn new(base: &Report, head: &Report) -> Self {
+        let mut modules: BTreeMap<String, ScoreChange> = BTreeMap::new();
+        for summary in base.module_summaries() {
+            modules.insert(summary.module.clone(), ScoreChange {
+                module: summary.module.clone(),
+                base: Some(summary.score()),
+                head: None,
+            });
+        }
+        for summary in head.module_summaries() {
+            modules
+                .entry(summary.module.clone())
+                .or_insert_with(|| ScoreChange {
+                    module: summary.module.clone(),
+                    base: None,
+                    head: None,
+                })
+                .head = Some(summary.score());
+        }
+
+        let base_statuses = statuses(base);
+        let head_statuses = statuses(head);
+        let killed = |statuses: &BTreeMap<&str, MutantStatus>, id: &str| {
+            statuses.get(id) == Some(&MutantStatus::Killed)
+        };
+
+        let regressions = base_statuses
+            .keys()
+            .filter(|id| head_statuses.contains_key(*id))
+            .filter(|id| killed(&base_statuses, id) && !killed(&head_statuses, id))
+            .map(|id| (*id).to_owned())
+            .collect();
+        let improvements = head_statuses
+            .keys()
+            .filter(|id| base_statuses.contains_key(*id))
+            .filter(|id| !killed(&base_statuses, id) && killed(&head_statuses, id))
+            .map(|id| (*id).to_owned())
+            .collect();
+
+        Self {
+            modules: modules.into_values().collect(),
+            total: ScoreChange {
+                module: "Total".to_owned(),
+                base: Some(base.total_summary().score()),
+                head: Some(head.total_summary().score()),
+            },
+            regressions,
+            improvements,
+        }
+    }
+
+    /// Renders the table with the score changes of the modules.
+    pub fn table(&self) -> String {
+        let format_score =
+            |score: Option<f64>| score.map_or("-".to_owned(), |s| format!("{s:.2}%"));
+
+        let mut builder = Builder::new();
+        builder.push_record(["Module", "Base score", "Score", "Change"]);
+        for change in self.modules.iter().chain([&self.total]) {
+            builder.push_record([
+                change.module.clone(),
+                format_score(change.base),
+                format_score(change.head),
+                change
+                    .delta()
+                    .map_or("-".to_owned(), |delta| format!("{delta:+.2}")),
+            ]);
+        }
+
+        builder.build().with(Style::modern_rounded()).to_string()
+    }
+}
+
+/// This function compares two JSON reports generated by the spec-test tool and prints the score changes
+/// of the modules together with the mutants which are no longer (or newly) killed.
+///
+/// # Arguments
+///
+/// * `base` - A `Path` to the base report (or to the archive created with the `--archive` option).
+/// * `head` - A `Path` to the report compared with the base one.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_compare(base: &Path, head: &Path) -> anyhow::Result<()> {
+    let base = Report::load_from_json_or_archive(base)?;
+    let head = Report::load_from_json_or_archive(head)?;
+    let comparison = Comparison::new(&base, &head);
+
+    // Scores of the runs with different tools may differ even for the same specifications.
+    if let (Some(base_metadata), Some(head_metadata)) = (base.metadata(), head.metadata()) {
+        for difference in base_metadata.differences(head_metadata) {
+            println!("Reports were produced with different tools ({difference})");
+        }
+    }
+
+    println!("{}\n", comparison.table());
+    if !comparison.regressions.is_empty() {
+        println!(
+            "Mutants no longer killed: {}",
+            comparison.regressions.join(", ")
+        );
+    }
+    if !comparison.improvements.is_empty() {
+        println!(
+            "Mutants newly killed: {}",
+            comparison.improvements.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+// Internal function collecting the statuses of all mutants of the report, keyed by the mutant ID.
+fn statuses(report: &Report) -> BTreeMap<&str, MutantStatus> {
+    report
+        .entries()
+        .values()
+        .flatten()
+        .flat_map(|stat| {
+            stat.mutants
+                .iter()
+                .map(|(id, status)| (id.as_str(), *status))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn report(mutants: &[(&str, &str, MutantStatus)]) -> Report {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        for (module_func, id, status) in mutants {
+            report.increment_mutants_tested(&path, module_func);
+            if *status == MutantStatus::Killed {
+                report.increment_mutants_killed(&path, module_func);
+            }
+            report.set_mutant_status(&path, module_func, id, *status);
+        }
+        report
+    }
+
+    #[test]
+    fn scores_of_modules_are_compared() {
+        let base = report(&[
+            ("Sum::sum", "first", MutantStatus::Killed),
+            ("Sum::sum", "second", MutantStatus::Alive),
+            ("Old::f", "third", MutantStatus::Killed),
+        ]);
+        let head = report(&[
+            ("Sum::sum", "first", MutantStatus::Killed),
+            ("Sum::sum", "second", MutantStatus::Killed),
+        ]);
+
+        let comparison = Comparison::new(&base, &head);
+        assert_eq!(comparison.modules, vec![
+            ScoreChange {
+                module: "Old".to_owned(),
+                base: Some(100.0),
+                head: None,
+            },
+            ScoreChange {
+                module: "Sum".to_owned(),
+                base: Some(50.0),
+                head: Some(100.0),
+            },
+        ]);
+        assert_eq!(comparison.modules[1].delta(), Some(50.0));
+        assert_eq!(comparison.modules[0].delta(), None);
+        assert_eq!(comparison.improvements, vec!["second".to_owned()]);
+        assert!(comparison.regressions.is_empty());
+    }
+
+    #[test]
+    fn mutants_no_longer_killed_are_reported() {
+        let base = report(&[("Sum::sum", "first", MutantStatus::Killed)]);
+        let head = report(&[("Sum::sum", "first", MutantStatus::Alive)]);
+
+        let comparison = Comparison::new(&base, &head);
+        assert_eq!(comparison.regressions, vec!["first".to_owned()]);
+        assert!(comparison.table().contains("-100.00"));
+    }
+}
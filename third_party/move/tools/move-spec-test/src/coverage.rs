@@ -0,0 +1,183 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_binary_format::{access::ModuleAccess, file_format::FunctionDefinitionIndex};
+use move_compiler::compiled_unit::{CompiledUnit, NamedCompiledModule};
+use move_coverage::{
+    coverage_map::CoverageMap,
+    source_coverage::{SourceCoverageBuilder, StringSegment},
+};
+use move_mutator::report::MutationReport;
+use move_package::BuildConfig;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Lines of the package sources which aren't executed by any test, according to the coverage map collected
+/// by `move test --coverage`.
+#[derive(Debug, Default)]
+pub(crate) struct Coverage {
+    /// The coverage of each source file, keyed by the path relative to the package directory.
+    files: BTreeMap<PathBuf, FileCoverage>,
+}
+
+/// Coverage of a single source file.
+#[derive(Debug, Default)]
+struct FileCoverage {
+    /// Offsets of the beginnings of the lines.
+    line_starts: Vec<usize>,
+    /// The uncovered lines (starting from 1).
+    uncovered: BTreeSet<usize>,
+}
+
+impl Coverage {
+    /// Loads the coverage map and maps it to the source lines of the package modules.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the coverage map can't be read or the package can't be compiled.
+    pub(crate) fn new(
+        coverage_map: &Path,
+        config: &BuildConfig,
+        package_path: &Path,
+    ) -> anyhow::Result<Self> {
+        let coverage_map = CoverageMap::from_binary_file(coverage_map)?;
+        let executed = coverage_map.to_unified_exec_map().module_maps;
+
+        // The coverage map is collected by `move test`, so the package is compiled in the test mode to get
+        // the same bytecode offsets.
+        let mut config = config.clone();
+        config.test_mode = true;
+        let package = config.compile_package(package_path, &mut Vec::new())?;
+
+        let mut files = BTreeMap::new();
+        for unit in package.root_modules() {
+            let CompiledUnit::Module(NamedCompiledModule {
+                module, source_map, ..
+            }) = &unit.unit
+            else {
+                continue;
+            };
+
+            let path = unit
+                .source_path
+                .strip_prefix(package_path)
+                .unwrap_or(&unit.source_path);
+            let file = files.entry(path.to_path_buf()).or_insert_with(|| {
+                let source = fs::read_to_string(&unit.source_path).unwrap_or_default();
+                FileCoverage::new(&source)
+            });
+
+            let id = module.self_id();
+            if executed.contains_key(&(*id.address(), id.name().to_owned())) {
+                let coverage = SourceCoverageBuilder::new(module, &coverage_map, source_map)
+                    .compute_source_coverage(&unit.source_path);
+                file.uncovered.extend(
+                    coverage
+                        .annotated_lines
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, segments)| is_uncovered(segments))
+                        .map(|(index, _)| index + 1),
+                );
+            } else {
+                // No test executed the module, so all of its functions are uncovered.
+                for index in 0..module.function_defs().len() {
+                    let function = source_map
+                        .get_function_source_map(FunctionDefinitionIndex(index as u16))?;
+                    let location = function.definition_location;
+                    let (start, end) = (
+                        file.line(location.start() as usize),
+                        file.line(location.end() as usize),
+                    );
+                    file.uncovered.extend(start..=end);
+                }
+            }
+        }
+
+        Ok(Self { files })
+    }
+
+    /// Checks if any of the places modified by the mutant of the given file (relative to the package directory)
+    /// is covered by the tests. Mutants of the files missing in the coverage map and the mutants spanning
+    /// multiple files are treated as covered.
+    pub(crate) fn is_covered(&self, file: &Path, mutant: &MutationReport) -> bool {
+        let Some(coverage) = self.files.get(file) else {
+            return true;
+        };
+        if !mutant.get_changed_files().is_empty() {
+            return true;
+        }
+
+        mutant.get_mutations().is_empty()
+            || mutant.get_mutations().iter().any(|mutation| {
+                let line = coverage.line(mutation.get_changed_place().start());
+                !coverage.uncovered.contains(&line)
+            })
+    }
+}
+
+impl FileCoverage {
+    // Internal function creating the coverage of the source file without any uncovered lines.
+    fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(offset, _)| offset + 1))
+            .collect();
+        Self {
+            line_starts,
+            uncovered: BTreeSet::new(),
+        }
+    }
+
+    // Internal function returning the line (starting from 1) containing the given offset.
+    fn line(&self, offset: usize) -> usize {
+        self.line_starts.partition_point(|start| *start <= offset)
+    }
+}
+
+// Internal function checking if the annotated source line is uncovered. Lines covered only partially (e.g.
+// a condition evaluated by the tests, with the branch never taken) count as covered, so mutants aren't
+// classified as uncovered by mistake.
+fn is_uncovered(segments: &[StringSegment]) -> bool {
+    let mut uncovered = false;
+    for segment in segments {
+        match segment {
+            StringSegment::Covered(text) if !text.trim().is_empty() => return false,
+            StringSegment::Uncovered(text) if !text.trim().is_empty() => uncovered = true,
+            _ => {},
+        }
+    }
+    uncovered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets_are_mapped_to_lines() {
+        let file = FileCoverage::new("fun a() {\n    1\n}\n");
+        assert_eq!(file.line(0), 1);
+        assert_eq!(file.line(9), 1);
+        assert_eq!(file.line(10), 2);
+        assert_eq!(file.line(16), 3);
+    }
+
+    #[test]
+    fn partially_covered_lines_are_covered() {
+        use StringSegment::{Covered, Uncovered};
+
+        assert!(is_uncovered(&[
+            Covered("    ".to_owned()),
+            Uncovered("x + 1".to_owned())
+        ]));
+        assert!(!is_uncovered(&[
+            Covered("if (x) ".to_owned()),
+            Uncovered("abort 1".to_owned())
+        ]));
+        assert!(!is_uncovered(&[Covered("}".to_owned())]));
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::prover::MutantOutcome;
+use move_mutator::report::MutationReport;
+use std::{collections::BTreeMap, fmt::Write as _, fs, path::Path, time::Duration};
+
+/// Header of the CSV report.
+const HEADER: &str = "id,module,function,operator,line,outcome,prover_time_s";
+
+/// Outcome of a single mutant, written as a row of the CSV report.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CsvRow {
+    /// Stable identifier of the mutant.
+    pub id: String,
+    /// The module of the mutant (qualified with its address, if it's known).
+    pub module: String,
+    /// The function of the mutant.
+    pub function: String,
+    /// Names of the mutation operators applied to the mutant (separated by `+`).
+    pub operator: String,
+    /// Line of the first modification in the original file (if the file could be read).
+    pub line: Option<usize>,
+    /// The outcome of the mutant.
+    pub outcome: MutantOutcome,
+    /// Time spent proving the mutant (unknown for the mutants verified in batches and for the mutants which
+    /// weren't verified).
+    pub prover_time: Option<Duration>,
+}
+
+impl CsvRow {
+    /// Creates the row of the mutant with the given outcome. The line of the mutant is found in the given
+    /// source of its original file.
+    pub fn new(
+        elem: &MutationReport,
+        source: Option<&str>,
+        outcome: MutantOutcome,
+        prover_time: Option<Duration>,
+    ) -> Self {
+        let line = source
+            .zip(elem.get_mutations().first())
+            .map(|(source, mutation)| {
+                let offset = mutation.get_changed_place().start();
+                source.bytes().take(offset).filter(|b| *b == b'\n').count() + 1
+            });
+
+        Self {
+            id: elem.get_id().to_owned(),
+            module: elem.qualified_module_name(),
+            function: elem.get_function_name().to_owned(),
+            operator: elem
+                .operator_names()
+                .into_iter()
+                .collect::<Vec<_>>()
+                .join("+"),
+            line,
+            outcome,
+            prover_time,
+        }
+    }
+}
+
+/// Returns the CSV document with the header and one row per mutant, for the spreadsheet analysis of the
+/// outcomes.
+pub(crate) fn to_csv(rows: &[CsvRow]) -> String {
+    let mut csv = format!("{HEADER}\n");
+    for row in rows {
+        let _ = writeln!(
+            csv,
+            "{},{},{},{},{},{},{}",
+            escape(&row.id),
+            escape(&row.module),
+            escape(&row.function),
+            escape(&row.operator),
+            row.line.map(|line| line.to_string()).unwrap_or_default(),
+            row.outcome.name(),
+            row.prover_time
+                .map(|time| format!("{:.3}", time.as_secs_f64()))
+                .unwrap_or_default()
+        );
+    }
+    csv
+}
+
+/// Saves the CSV report of the mutants with the recorded outcomes, in the order of the mutator report.
+/// The original files of the mutants are read from the package (to find the lines of the mutants).
+///
+/// # Errors
+///
+/// Returns an error if the CSV file can't be written.
+pub(crate) fn save_csv_report<'a>(
+    path: &Path,
+    package_path: &Path,
+    mutants: impl IntoIterator<Item = (&'a MutationReport, &'a Path)>,
+    outcomes: &BTreeMap<String, MutantOutcome>,
+    prover_times: &BTreeMap<String, Duration>,
+) -> anyhow::Result<()> {
+    let mut sources = BTreeMap::new();
+    let rows = mutants
+        .into_iter()
+        .filter_map(|(elem, original_file)| {
+            let outcome = *outcomes.get(elem.get_id())?;
+            let source = sources
+                .entry(original_file)
+                .or_insert_with(|| fs::read_to_string(package_path.join(original_file)).ok());
+            Some(CsvRow::new(
+                elem,
+                source.as_deref(),
+                outcome,
+                prover_times.get(elem.get_id()).copied(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    fs::write(path, to_csv(&rows))?;
+    println!(
+        "CSV report of {} mutants saved to {}",
+        rows.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+// Internal function quoting the CSV field if it contains the separator, quotes or line breaks.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_mutator::report::{Mutation, Range};
+
+    #[test]
+    fn rows_are_written_with_escaping() {
+        let mut elem = MutationReport::new(
+            Path::new("mutant.move"),
+            Path::new("sources/Sum.move"),
+            "Sum",
+            "sum",
+            "",
+            "",
+        );
+        elem.add_modification(Mutation::new(
+            Range::new(20, 21),
+            "binary_operator_replacement".to_owned(),
+            "+".to_owned(),
+            "-".to_owned(),
+        ));
+        let source = "module Sum {\n  fun\n  a + b\n}\n";
+
+        let killed = CsvRow::new(
+            &elem,
+            Some(source),
+            MutantOutcome::Killed,
+            Some(Duration::from_millis(1500)),
+        );
+        assert_eq!(killed.line, Some(3));
+        let mut alive = CsvRow::new(&elem, None, MutantOutcome::Alive, None);
+        alive.function = "say \"hi\", world".to_owned();
+
+        let csv = to_csv(&[killed, alive]);
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], HEADER);
+        assert_eq!(
+            lines[1],
+            format!(
+                "{},Sum,sum,binary_operator_replacement,3,killed,1.500",
+                elem.get_id()
+            )
+        );
+        assert_eq!(
+            lines[2],
+            format!(
+                "{},Sum,\"say \"\"hi\"\", world\",binary_operator_replacement,,alive,",
+                elem.get_id()
+            )
+        );
+    }
+}
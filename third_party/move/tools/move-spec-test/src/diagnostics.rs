@@ -0,0 +1,244 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the location line of the prover diagnostic (e.g. `┌─ sources/Sum.move:8:9`).
+const LOCATION_PREFIX: &str = "┌─";
+
+/// Prefix of the notes of the prover diagnostic, which contain the execution trace and the values of the
+/// counterexample model.
+const NOTE_PREFIX: &str = "=";
+
+/// Verification error reported by the prover, extracted from its output.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProverDiagnostic {
+    /// The condition which doesn't hold (e.g. `post-condition does not hold`).
+    pub condition: String,
+    /// Path of the file with the failed specification, as reported by the prover.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Line of the failed specification.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    /// The counterexample model: the execution trace and the values of the variables, line by line.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub counterexample: Vec<String>,
+}
+
+/// Step of the counterexample execution trace with the values of the variables at that point.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct TraceStep {
+    /// The location of the step (e.g. `sources/Sum.move:4: sum`), empty for the values reported before
+    /// the first location.
+    pub location: String,
+    /// The variable assignments (name and value) reported at the step.
+    pub assignments: Vec<(String, String)>,
+    /// Other notes reported at the step.
+    pub notes: Vec<String>,
+}
+
+impl ProverDiagnostic {
+    /// Groups the counterexample lines into the steps of the execution trace.
+    ///
+    /// Each `at <location>` line starts a new step, the `<variable> = <value>` lines following it are
+    /// the values of the variables at the step.
+    pub fn trace(&self) -> Vec<TraceStep> {
+        // The first step collects the values reported before the first location.
+        let mut steps = vec![TraceStep::default()];
+
+        for line in &self.counterexample {
+            if let Some(location) = line.strip_prefix("at ") {
+                steps.push(TraceStep {
+                    location: location.trim().to_owned(),
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let last = steps.len() - 1;
+            let step = &mut steps[last];
+            match line.split_once(" = ") {
+                Some((name, value)) => step
+                    .assignments
+                    .push((name.trim().to_owned(), value.trim().to_owned())),
+                None => step.notes.push(line.clone()),
+            }
+        }
+
+        if steps[0] == TraceStep::default() {
+            steps.remove(0);
+        }
+        steps
+    }
+}
+
+/// Parses the prover output and returns the reported verification errors.
+///
+/// Each error starts with an `error:` line followed by the location of the failed condition and the notes
+/// (lines starting with `=`) containing the counterexample. Warnings and other lines are ignored.
+pub fn parse_prover_output(output: &str) -> Vec<ProverDiagnostic> {
+    let mut diagnostics = vec![];
+    let mut current: Option<ProverDiagnostic> = None;
+
+    for line in output.lines() {
+        if let Some(condition) = error_condition(line) {
+            diagnostics.extend(current.take());
+            current = Some(ProverDiagnostic {
+                condition,
+                ..Default::default()
+            });
+            continue;
+        }
+
+        // A new diagnostic of other severity (e.g. a warning) ends the current error.
+        if line.starts_with("warning") || line.starts_with("note") || line.starts_with("bug") {
+            diagnostics.extend(current.take());
+            continue;
+        }
+
+        let Some(diagnostic) = current.as_mut() else {
+            continue;
+        };
+
+        let trimmed = line.trim();
+        if let Some(location) = trimmed.strip_prefix(LOCATION_PREFIX) {
+            // Only the primary location (the first one) is recorded.
+            if diagnostic.file.is_none() {
+                let (file, line) = parse_location(location.trim());
+                diagnostic.file = Some(file);
+                diagnostic.line = line;
+            }
+        } else if let Some(note) = trimmed.strip_prefix(NOTE_PREFIX) {
+            let note = note.trim();
+            if !note.is_empty() {
+                diagnostic.counterexample.push(note.to_owned());
+            }
+        }
+    }
+    diagnostics.extend(current);
+
+    diagnostics
+}
+
+// Internal function returning the message of the error line (e.g. `error: post-condition does not hold` or
+// `error[E0001]: ...`), or `None` if the line doesn't start an error.
+fn error_condition(line: &str) -> Option<String> {
+    let rest = line.strip_prefix("error")?;
+    let rest = match rest.strip_prefix('[') {
+        Some(code) => code.split_once(']')?.1,
+        None => rest,
+    };
+    rest.strip_prefix(':')
+        .map(|message| message.trim().to_owned())
+}
+
+// Internal function splitting the location (`path:line:column`) into the path and the line.
+// Locations without the line and the column are returned as the path only.
+fn parse_location(location: &str) -> (String, Option<usize>) {
+    let mut parts = location.rsplitn(3, ':');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(column), Some(line), Some(file)) if column.parse::<usize>().is_ok() => {
+            (file.to_owned(), line.parse().ok())
+        },
+        _ => (location.to_owned(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verification_errors_are_parsed() {
+        let output = "\
+error: post-condition does not hold
+   ┌─ sources/Sum.move:8:9
+   │
+ 8 │         ensures result == a + b;
+   │         ^^^^^^^^^^^^^^^^^^^^^^^^
+   │
+   =     at sources/Sum.move:4: sum
+   =         a = 1
+   =         b = 2
+   =     at sources/Sum.move:8: sum (spec)
+
+warning: unused variable
+   ┌─ sources/Sum.move:3:5
+   =   unrelated note
+
+error: abort not covered by any of the `aborts_if` clauses
+   ┌─ sources/Sum.move:12:5
+";
+        let diagnostics = parse_prover_output(output);
+
+        assert_eq!(diagnostics, vec![
+            ProverDiagnostic {
+                condition: "post-condition does not hold".to_owned(),
+                file: Some("sources/Sum.move".to_owned()),
+                line: Some(8),
+                counterexample: vec![
+                    "at sources/Sum.move:4: sum".to_owned(),
+                    "a = 1".to_owned(),
+                    "b = 2".to_owned(),
+                    "at sources/Sum.move:8: sum (spec)".to_owned(),
+                ],
+            },
+            ProverDiagnostic {
+                condition: "abort not covered by any of the `aborts_if` clauses".to_owned(),
+                file: Some("sources/Sum.move".to_owned()),
+                line: Some(12),
+                counterexample: vec![],
+            },
+        ]);
+    }
+
+    #[test]
+    fn counterexample_is_grouped_into_trace_steps() {
+        let diagnostic = ProverDiagnostic {
+            condition: "post-condition does not hold".to_owned(),
+            counterexample: vec![
+                "<redacted> = 0x1".to_owned(),
+                "at sources/Sum.move:4: sum".to_owned(),
+                "a = 1".to_owned(),
+                "b = 2".to_owned(),
+                "at sources/Sum.move:8: sum (spec)".to_owned(),
+                "result = 4".to_owned(),
+                "ABORTED".to_owned(),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(diagnostic.trace(), vec![
+            TraceStep {
+                assignments: vec![("<redacted>".to_owned(), "0x1".to_owned())],
+                ..Default::default()
+            },
+            TraceStep {
+                location: "sources/Sum.move:4: sum".to_owned(),
+                assignments: vec![
+                    ("a".to_owned(), "1".to_owned()),
+                    ("b".to_owned(), "2".to_owned())
+                ],
+                notes: vec![],
+            },
+            TraceStep {
+                location: "sources/Sum.move:8: sum (spec)".to_owned(),
+                assignments: vec![("result".to_owned(), "4".to_owned())],
+                notes: vec!["ABORTED".to_owned()],
+            },
+        ]);
+    }
+
+    #[test]
+    fn errors_without_location_are_kept() {
+        let diagnostics = parse_prover_output("error[E04001]: unbound module\nexiting\n");
+
+        assert_eq!(diagnostics, vec![ProverDiagnostic {
+            condition: "unbound module".to_owned(),
+            ..Default::default()
+        }]);
+        assert!(parse_prover_output("errors:\nall good\n").is_empty());
+    }
+}
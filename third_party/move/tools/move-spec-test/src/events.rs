@@ -0,0 +1,130 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::report::ModuleSummary;
+use move_mutator::report::MutationReport;
+use serde::Serialize;
+use std::path::Path;
+
+/// Event reported in the event stream, serialized as a single line of JSON with the `event` field naming
+/// the kind of the event (e.g. `{"event":"mutant_killed","id":"0123abcd"}`).
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub(crate) enum Event<'a> {
+    /// The specification testing of the package has started.
+    RunStarted { package: &'a Path },
+    /// The mutant has been generated (or loaded from the previously generated mutants).
+    MutantGenerated {
+        id: &'a str,
+        file: &'a Path,
+        module: &'a str,
+        function: &'a str,
+        operators: Vec<&'a str>,
+    },
+    /// The prover is verifying the mutant (`index` out of `total`).
+    MutantProving {
+        id: &'a str,
+        index: usize,
+        total: usize,
+    },
+    /// The prover killed the mutant.
+    MutantKilled { id: &'a str },
+    /// The specification testing has finished.
+    RunFinished {
+        tested: u32,
+        killed: u32,
+        survived: u32,
+        inconclusive: u32,
+        score: f64,
+    },
+}
+
+impl<'a> Event<'a> {
+    /// Creates the `mutant_generated` event of the mutant from the mutator report.
+    pub(crate) fn mutant_generated(elem: &'a MutationReport) -> Self {
+        Event::MutantGenerated {
+            id: elem.get_id(),
+            file: elem.original_file_path(),
+            module: elem.get_module_name(),
+            function: elem.get_function_name(),
+            operators: elem
+                .get_mutations()
+                .iter()
+                .map(|mutation| mutation.get_operator_name())
+                .collect(),
+        }
+    }
+
+    /// Creates the `run_finished` event from the summary of the whole report.
+    pub(crate) fn run_finished(total: &ModuleSummary) -> Self {
+        Event::RunFinished {
+            tested: total.generated,
+            killed: total.killed,
+            survived: total.survived,
+            inconclusive: total.timeout,
+            score: total.score(),
+        }
+    }
+}
+
+/// Stream of the newline-delimited JSON events printed to the standard output, so the IDEs and orchestrators
+/// can observe the progress of the run. Events are printed only if the stream is enabled.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct EventStream {
+    enabled: bool,
+}
+
+impl EventStream {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    /// Prints the event, if the stream is enabled.
+    pub(crate) fn emit(&self, event: &Event) {
+        if !self.enabled {
+            return;
+        }
+
+        match serde_json::to_string(event) {
+            Ok(line) => println!("{line}"),
+            Err(e) => warn!("Cannot serialize event {event:?}: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn events_are_tagged_with_their_kind() {
+        assert_eq!(
+            serde_json::to_string(&Event::MutantKilled { id: "0123abcd" }).unwrap(),
+            r#"{"event":"mutant_killed","id":"0123abcd"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&Event::MutantProving {
+                id: "0123abcd",
+                index: 1,
+                total: 2
+            })
+            .unwrap(),
+            r#"{"event":"mutant_proving","id":"0123abcd","index":1,"total":2}"#
+        );
+    }
+
+    #[test]
+    fn run_finished_event_contains_summary() {
+        let mut total = ModuleSummary::new("Total");
+        total.generated = 4;
+        total.killed = 3;
+        total.survived = 1;
+
+        let event = serde_json::to_value(Event::run_finished(&total)).unwrap();
+        assert_eq!(event["event"], "run_finished");
+        assert_eq!(event["tested"], 4);
+        assert_eq!(event["killed"], 3);
+        assert_eq!(event["score"], 75.0);
+    }
+}
@@ -0,0 +1,190 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Harness running the specification testing from the test suites of the Rust repositories which embed
+//! Move packages. The test fails if the mutation score of the package drops below the threshold:
+//!
+//! ```no_run
+//! #[test]
+//! fn coin_specification_kills_mutants() {
+//!     move_spec_test::harness::SpecTest::new("move/coin")
+//!         .with_min_score(80.0)
+//!         .run();
+//! }
+//! ```
+//!
+//! Relative package paths are resolved against the working directory, which is the directory of the
+//! tested crate when the tests are run with `cargo test`.
+
+use crate::{cli::CLIOptions, report::Report};
+use anyhow::anyhow;
+use move_package::BuildConfig;
+use std::{
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+/// The number of surviving mutants listed in the failure message.
+const LISTED_SURVIVORS: usize = 10;
+
+/// Specification testing of a Move package, run as a test.
+#[derive(Debug, Clone)]
+pub struct SpecTest {
+    package_path: PathBuf,
+    options: CLIOptions,
+    build_config: BuildConfig,
+    min_score: Option<f64>,
+}
+
+impl SpecTest {
+    /// Creates the specification testing of the package with the default options. Without the threshold,
+    /// only the `min_score` of the package's mutator configuration (if any) is enforced.
+    pub fn new(package_path: impl AsRef<Path>) -> Self {
+        Self {
+            package_path: package_path.as_ref().to_path_buf(),
+            options: CLIOptions::default(),
+            build_config: BuildConfig::default(),
+            min_score: None,
+        }
+    }
+
+    /// Sets the options of the specification testing (e.g. the mutation operators or the prover timeout).
+    pub fn with_options(mut self, options: CLIOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Sets the build configuration of the package.
+    pub fn with_build_config(mut self, build_config: BuildConfig) -> Self {
+        self.build_config = build_config;
+        self
+    }
+
+    /// Sets the minimum mutation score (in percent) required to pass the test.
+    pub fn with_min_score(mut self, min_score: f64) -> Self {
+        self.min_score = Some(min_score);
+        self
+    }
+
+    /// Runs the specification testing and returns its report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the specification testing fails (e.g. the original code can't be verified) or
+    /// if the mutation score is below the threshold (the surviving mutants are listed in the error).
+    pub fn try_run(&self) -> anyhow::Result<Report> {
+        if let Some(min_score) = self
+            .min_score
+            .filter(|score| !(0.0..=100.0).contains(score))
+        {
+            anyhow::bail!("Invalid minimum score {min_score} - it must be a percentage (0-100)");
+        }
+
+        // The report is written to a temporary file, unless the options ask for it.
+        let report_dir = tempfile::tempdir()?;
+        let mut options = self.options.clone();
+        let output = match &options.output {
+            Some(output) if output != Path::new("github") => output.clone(),
+            _ => report_dir.path().join("report.json"),
+        };
+        options.output = Some(output.clone());
+
+        crate::run_spec_test(&options, &self.build_config, &self.package_path)?;
+        let report = Report::load_from_file(&output)?;
+        if let Some(min_score) = self.min_score {
+            check_score(&report, min_score)?;
+        }
+        Ok(report)
+    }
+
+    /// Runs the specification testing and returns its report.
+    ///
+    /// # Panics
+    ///
+    /// The function will panic (failing the test) if the specification testing fails or if the mutation
+    /// score is below the threshold.
+    pub fn run(&self) -> Report {
+        self.try_run().unwrap_or_else(|err| {
+            panic!(
+                "Specification testing of {} failed: {err:#}",
+                self.package_path.display()
+            )
+        })
+    }
+}
+
+/// Checks if the mutation score of the report reaches the minimum score. The error lists the most
+/// actionable surviving mutants, so the failed test shows where the specification is weak.
+///
+/// # Errors
+///
+/// Returns an error if the mutation score is below the minimum score.
+pub fn check_score(report: &Report, min_score: f64) -> anyhow::Result<()> {
+    let score = report.score();
+    if score >= min_score {
+        return Ok(());
+    }
+
+    let survivors = report.ranked_surviving_mutants();
+    let mut message = format!(
+        "Mutation score {score:.2}% is below the minimum score {min_score:.2}% ({})",
+        report.status_line()
+    );
+    for (path, module_func, mutant) in survivors.iter().take(LISTED_SURVIVORS) {
+        let _ = write!(
+            message,
+            "\n  surviving mutant {} in {module_func} ({})",
+            mutant.id,
+            path.display()
+        );
+    }
+    if survivors.len() > LISTED_SURVIVORS {
+        let _ = write!(
+            message,
+            "\n  ... and {} more",
+            survivors.len() - LISTED_SURVIVORS
+        );
+    }
+    Err(anyhow!(message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::{MutantStatus, SurvivingMutant};
+
+    #[test]
+    fn score_below_threshold_lists_surviving_mutants() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        for id in ["a", "b", "c", "d"] {
+            report.increment_mutants_tested(&path, "Sum::sum");
+            if id == "a" {
+                report.increment_mutants_killed(&path, "Sum::sum");
+                report.set_mutant_status(&path, "Sum::sum", id, MutantStatus::Killed);
+            } else {
+                report.add_surviving_mutant(&path, "Sum::sum", SurvivingMutant {
+                    id: id.to_owned(),
+                    ..Default::default()
+                });
+                report.set_mutant_status(&path, "Sum::sum", id, MutantStatus::Alive);
+            }
+        }
+
+        assert!(check_score(&report, 25.0).is_ok());
+        let err = check_score(&report, 80.0).unwrap_err().to_string();
+        assert!(err.starts_with("Mutation score 25.00% is below the minimum score 80.00%"));
+        assert!(err.contains("surviving mutant b in Sum::sum (sources/Sum.move)"));
+        assert!(err.contains("surviving mutant d"));
+    }
+
+    #[test]
+    fn invalid_threshold_is_rejected() {
+        let err = SpecTest::new("missing").with_min_score(120.0).try_run();
+        assert!(err
+            .unwrap_err()
+            .to_string()
+            .contains("Invalid minimum score"));
+    }
+}
@@ -0,0 +1,267 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_mutator::report::MutationReport;
+use serde::{Deserialize, Serialize};
+
+/// Operators which change the computed value (and may also introduce new aborts, e.g. overflow).
+const ARITHMETIC_OPERATORS: &[&str] = &["+", "-", "*", "/", "%", "<<", ">>", "&", "|", "^"];
+
+/// Suggestion about the kind of specification clause likely missing, which would kill a surviving mutant.
+///
+/// Variants are ordered by priority - the most actionable suggestions come first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecHint {
+    /// No specification of the package can observe the mutated function.
+    MissingSpec,
+    /// Mutant changes the abort behavior of the function.
+    MissingAbortsIf,
+    /// Mutant changes the value computed by the function.
+    MissingEnsures,
+    /// Mutant changes the global state modified by the function.
+    MissingGlobalStateEnsures,
+    /// Mutant removes the event emitted by the function.
+    MissingEmits,
+    /// Mutant changes the condition deciding which branch is taken.
+    MissingBranchCoverage,
+    /// Mutant changes the loop behavior.
+    MissingLoopInvariant,
+}
+
+impl SpecHint {
+    /// Returns the human-readable description of the hint.
+    pub fn message(&self) -> &'static str {
+        match self {
+            SpecHint::MissingSpec => {
+                "no specification observes the mutated function (neither its own nor of its callers)"
+            },
+            SpecHint::MissingAbortsIf => "no `aborts_if` covers this abort condition",
+            SpecHint::MissingEnsures => "no `ensures` constrains the return value",
+            SpecHint::MissingGlobalStateEnsures => {
+                "no `ensures` constrains the global state (e.g. `exists<T>(addr)` or `global<T>(addr)`)"
+            },
+            SpecHint::MissingEmits => "no `emits` clause (or test) checks this event",
+            SpecHint::MissingBranchCoverage => "no `aborts_if` or `ensures` covers this branch",
+            SpecHint::MissingLoopInvariant => {
+                "no loop invariant or `ensures` constrains the loop result"
+            },
+        }
+    }
+
+    /// Suggests the spec clauses likely missing for the surviving mutant.
+    /// Suggestions are based on the applied mutation operators and the mutated source lines.
+    /// The returned list is sorted by priority and contains no duplicates.
+    pub fn for_mutant(mutant: &MutationReport) -> Vec<SpecHint> {
+        let mut hints = mutant
+            .get_mutations()
+            .iter()
+            .flat_map(|m| Self::for_operator(m.get_operator_name(), m.get_old_value()))
+            .chain(Self::for_diff(mutant.get_diff()))
+            .collect::<Vec<_>>();
+
+        hints.sort();
+        hints.dedup();
+        hints
+    }
+
+    // Internal function suggesting hints based on the mutation operator.
+    fn for_operator(operator: &str, old_value: &str) -> Vec<SpecHint> {
+        match operator {
+            "binary_operator_replacement" if ARITHMETIC_OPERATORS.contains(&old_value.trim()) => {
+                vec![SpecHint::MissingAbortsIf, SpecHint::MissingEnsures]
+            },
+            "binary_operator_replacement"
+            | "unary_operator_replacement"
+            | "if_else_replacement"
+            | "if_else_branch_swap"
+            | "negation_insertion"
+            | "match_arm_swap"
+            | "match_guard_removal" => {
+                vec![SpecHint::MissingBranchCoverage]
+            },
+            "binary_operator_swap"
+            | "literal_replacement"
+            | "enum_variant_replacement"
+            | "amount_replacement" => {
+                vec![SpecHint::MissingEnsures]
+            },
+            "delete_statement" if old_value.contains("abort") => vec![SpecHint::MissingAbortsIf],
+            "named_address_swap"
+            | "cast_replacement"
+            | "signer_address_replacement"
+            | "timestamp_replacement" => {
+                vec![SpecHint::MissingAbortsIf]
+            },
+            "delete_statement" | "resource_operation_reorder" => {
+                vec![SpecHint::MissingGlobalStateEnsures]
+            },
+            "event_emission_removal" => vec![SpecHint::MissingEmits],
+            "break_continue_replacement" | "loop_bound_replacement" => {
+                vec![SpecHint::MissingLoopInvariant]
+            },
+            _ => vec![],
+        }
+    }
+
+    // Internal function suggesting hints based on the lines changed by the mutant.
+    fn for_diff(diff: &str) -> Vec<SpecHint> {
+        diff.lines()
+            .filter(|line| line.starts_with('-') && !line.starts_with("---"))
+            .filter_map(|line| {
+                let line = line[1..].trim();
+                if line.contains("assert!") || line.starts_with("abort") {
+                    Some(SpecHint::MissingAbortsIf)
+                } else if line.starts_with("return") {
+                    Some(SpecHint::MissingEnsures)
+                } else if line.starts_with("while") || line.starts_with("loop") {
+                    Some(SpecHint::MissingLoopInvariant)
+                } else if line.starts_with("if") || line.contains("else") {
+                    Some(SpecHint::MissingBranchCoverage)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_mutator::report::{Mutation, Range};
+    use std::path::Path;
+
+    fn mutant(operator: &str, old_value: &str, original: &str, mutated: &str) -> MutationReport {
+        let mut report = MutationReport::new(
+            Path::new("mutant.move"),
+            Path::new("original.move"),
+            "module",
+            "function",
+            mutated,
+            original,
+        );
+        report.add_modification(Mutation::new(
+            Range::new(0, 1),
+            operator.to_owned(),
+            old_value.to_owned(),
+            "new".to_owned(),
+        ));
+        report
+    }
+
+    #[test]
+    fn arithmetic_replacement_suggests_aborts_if_and_ensures() {
+        let m = mutant(
+            "binary_operator_replacement",
+            "+",
+            "let a = x + y;\n",
+            "let a = x - y;\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![
+            SpecHint::MissingAbortsIf,
+            SpecHint::MissingEnsures
+        ]);
+    }
+
+    #[test]
+    fn comparison_replacement_in_assert_suggests_aborts_if_first() {
+        let m = mutant(
+            "binary_operator_replacement",
+            "<",
+            "assert!(x < y, 1);\n",
+            "assert!(x <= y, 1);\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![
+            SpecHint::MissingAbortsIf,
+            SpecHint::MissingBranchCoverage
+        ]);
+    }
+
+    #[test]
+    fn deleted_move_to_suggests_global_state_ensures() {
+        let m = mutant(
+            "delete_statement",
+            "move_to(account, Coin {})",
+            "move_to(account, Coin {});\n",
+            "{};\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![
+            SpecHint::MissingGlobalStateEnsures
+        ]);
+    }
+
+    #[test]
+    fn enum_mutations_suggest_ensures_and_branch_coverage() {
+        let m = mutant(
+            "enum_variant_replacement",
+            "Square",
+            "Shape::Square { side }\n",
+            "Shape::Cube { side }\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![SpecHint::MissingEnsures]);
+
+        let m = mutant(
+            "match_guard_removal",
+            "if (side > 0)",
+            "Shape::Square { side } if (side > 0) => side,\n",
+            "Shape::Square { side } => side,\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![
+            SpecHint::MissingBranchCoverage
+        ]);
+    }
+
+    #[test]
+    fn swapped_named_address_suggests_aborts_if() {
+        let m = mutant(
+            "named_address_swap",
+            "admin",
+            "addr == @admin\n",
+            "addr == @treasury\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![SpecHint::MissingAbortsIf]);
+    }
+
+    #[test]
+    fn narrowed_cast_suggests_aborts_if() {
+        let m = mutant(
+            "cast_replacement",
+            "amount as u64",
+            "(amount as u64)\n",
+            "((amount as u8) as u64)\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![SpecHint::MissingAbortsIf]);
+    }
+
+    #[test]
+    fn inserted_negation_suggests_branch_coverage() {
+        let m = mutant(
+            "negation_insertion",
+            "frozen",
+            "frozen && x\n",
+            "!(frozen) && x\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![
+            SpecHint::MissingBranchCoverage
+        ]);
+    }
+
+    #[test]
+    fn removed_event_suggests_emits() {
+        let m = mutant(
+            "event_emission_removal",
+            "event::emit(Paid { amount })",
+            "event::emit(Paid { amount });\n",
+            "{};\n",
+        );
+        assert_eq!(SpecHint::for_mutant(&m), vec![SpecHint::MissingEmits]);
+    }
+
+    #[test]
+    fn unknown_operator_gives_no_hints() {
+        let m = mutant("unknown", "x", "x\n", "y\n");
+        assert!(SpecHint::for_mutant(&m).is_empty());
+    }
+}
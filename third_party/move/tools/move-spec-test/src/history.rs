@@ -0,0 +1,479 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{prover::MutantOutcome, report::Report};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    path::Path,
+    process::{Command, Stdio},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Default name of the SQLite database storing the history of runs.
+pub const DEFAULT_HISTORY_FILE: &str = ".mutation-history.db";
+
+/// The SQLite command line tool used to access the history database.
+const SQLITE_EXE: &str = "sqlite3";
+
+/// Schema of the history database: one row of `runs` per run, with the scores of its modules
+/// in `module_scores` and the outcomes of its mutants in `mutant_outcomes`.
+const HISTORY_SCHEMA: &str = "\
+CREATE TABLE IF NOT EXISTS runs (
+    id INTEGER PRIMARY KEY,
+    timestamp INTEGER NOT NULL,
+    package TEXT NOT NULL,
+    tested INTEGER NOT NULL,
+    killed INTEGER NOT NULL,
+    inconclusive INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS module_scores (
+    run_id INTEGER NOT NULL REFERENCES runs (id),
+    module TEXT NOT NULL,
+    tested INTEGER NOT NULL,
+    killed INTEGER NOT NULL,
+    inconclusive INTEGER NOT NULL,
+    PRIMARY KEY (run_id, module)
+);
+CREATE TABLE IF NOT EXISTS mutant_outcomes (
+    run_id INTEGER NOT NULL REFERENCES runs (id),
+    mutant_id TEXT NOT NULL,
+    outcome TEXT NOT NULL,
+    PRIMARY KEY (run_id, mutant_id)
+);
+";
+
+/// Width of the score bar printed by the `history` subcommand.
+const BAR_WIDTH: usize = 40;
+
+/// Mutation score of a single module (or the whole package).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Score {
+    /// The number of mutants tested.
+    pub tested: u32,
+    /// The number of mutants killed.
+    pub killed: u32,
+    /// The number of mutants with inconclusive results.
+    pub inconclusive: u32,
+}
+
+impl Score {
+    /// Returns the percentage of killed mutants.
+    pub fn percentage(&self) -> f64 {
+        if self.tested == 0 {
+            0.0
+        } else {
+            f64::from(self.killed) / f64::from(self.tested) * 100.0
+        }
+    }
+}
+
+/// Single run recorded in the history database.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct HistoryRecord {
+    /// Time of the run in seconds since the UNIX epoch.
+    pub timestamp: u64,
+    /// Path to the tested package.
+    pub package: String,
+    /// Score of the whole package.
+    pub total: Score,
+    /// Scores of the individual modules (keyed by `path::module::function`).
+    pub modules: BTreeMap<String, Score>,
+    /// Outcomes of the individual mutants (keyed by mutant ID).
+    pub outcomes: BTreeMap<String, MutantOutcome>,
+}
+
+impl HistoryRecord {
+    /// Creates a new record for the run which has just finished.
+    pub fn new(package: &Path, report: &Report, outcomes: BTreeMap<String, MutantOutcome>) -> Self {
+        let modules = report
+            .entries()
+            .iter()
+            .flat_map(|(path, stats)| {
+                stats.iter().map(move |stat| {
+                    (
+                        format!("{}::{}", path.to_string_lossy(), stat.module_func),
+                        Score {
+                            tested: stat.tested,
+                            killed: stat.killed,
+                            inconclusive: stat.inconclusive,
+                        },
+                    )
+                })
+            })
+            .collect();
+
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            package: package.to_string_lossy().to_string(),
+            total: Score {
+                tested: report.mutants_tested(),
+                killed: report.mutants_killed(),
+                inconclusive: report.mutants_inconclusive(),
+            },
+            modules,
+            outcomes,
+        }
+    }
+}
+
+/// Appends the record to the SQLite history database, in a single transaction.
+/// The database (and its tables) is created if it does not exist.
+///
+/// # Errors
+///
+/// Returns an error if the `sqlite3` tool can't be run or the database can't be written.
+pub(crate) fn append_record(path: &Path, record: &HistoryRecord) -> anyhow::Result<()> {
+    // The rows of the modules and the mutants refer to the run inserted last in the transaction.
+    const RUN_ID: &str = "(SELECT max(id) FROM runs)";
+
+    let mut script = format!("{HISTORY_SCHEMA}BEGIN;\n");
+    script.push_str(&format!(
+        "INSERT INTO runs (timestamp, package, tested, killed, inconclusive) VALUES ({}, {}, {}, {}, {});\n",
+        record.timestamp,
+        sql_text(&record.package),
+        record.total.tested,
+        record.total.killed,
+        record.total.inconclusive
+    ));
+    for (module, score) in &record.modules {
+        script.push_str(&format!(
+            "INSERT INTO module_scores VALUES ({RUN_ID}, {}, {}, {}, {});\n",
+            sql_text(module),
+            score.tested,
+            score.killed,
+            score.inconclusive
+        ));
+    }
+    for (id, outcome) in &record.outcomes {
+        script.push_str(&format!(
+            "INSERT INTO mutant_outcomes VALUES ({RUN_ID}, {}, {});\n",
+            sql_text(id),
+            sql_text(outcome.name())
+        ));
+    }
+    script.push_str("COMMIT;\n");
+
+    run_sqlite(path, &[], &script)?;
+    Ok(())
+}
+
+/// Loads all records from the SQLite history database, in the order they were appended.
+///
+/// # Errors
+///
+/// Returns an error if the database doesn't exist, the `sqlite3` tool can't be run or any record has
+/// an invalid format.
+pub(crate) fn load_records(path: &Path) -> anyhow::Result<Vec<HistoryRecord>> {
+    #[derive(Deserialize)]
+    struct RunRow {
+        id: i64,
+        timestamp: u64,
+        package: String,
+        tested: u32,
+        killed: u32,
+        inconclusive: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct ModuleRow {
+        run_id: i64,
+        module: String,
+        tested: u32,
+        killed: u32,
+        inconclusive: u32,
+    }
+
+    #[derive(Deserialize)]
+    struct OutcomeRow {
+        run_id: i64,
+        mutant_id: String,
+        outcome: MutantOutcome,
+    }
+
+    anyhow::ensure!(
+        path.exists(),
+        "History database {} doesn't exist",
+        path.display()
+    );
+
+    let runs: Vec<RunRow> = query(
+        path,
+        "SELECT id, timestamp, package, tested, killed, inconclusive FROM runs ORDER BY id",
+    )?;
+    let mut records = runs
+        .into_iter()
+        .map(|run| {
+            let record = HistoryRecord {
+                timestamp: run.timestamp,
+                package: run.package,
+                total: Score {
+                    tested: run.tested,
+                    killed: run.killed,
+                    inconclusive: run.inconclusive,
+                },
+                modules: BTreeMap::new(),
+                outcomes: BTreeMap::new(),
+            };
+            (run.id, record)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    let modules: Vec<ModuleRow> = query(
+        path,
+        "SELECT run_id, module, tested, killed, inconclusive FROM module_scores",
+    )?;
+    for row in modules {
+        if let Some(record) = records.get_mut(&row.run_id) {
+            record.modules.insert(row.module, Score {
+                tested: row.tested,
+                killed: row.killed,
+                inconclusive: row.inconclusive,
+            });
+        }
+    }
+
+    let outcomes: Vec<OutcomeRow> = query(
+        path,
+        "SELECT run_id, mutant_id, outcome FROM mutant_outcomes",
+    )?;
+    for row in outcomes {
+        if let Some(record) = records.get_mut(&row.run_id) {
+            record.outcomes.insert(row.mutant_id, row.outcome);
+        }
+    }
+
+    Ok(records.into_values().collect())
+}
+
+// Internal function running the query on the history database and deserializing the returned rows.
+fn query<T: DeserializeOwned>(path: &Path, sql: &str) -> anyhow::Result<Vec<T>> {
+    let output = run_sqlite(path, &["-json", "-readonly"], sql)?;
+    // No output is printed for the queries without any rows.
+    if output.trim().is_empty() {
+        return Ok(vec![]);
+    }
+    serde_json::from_str(&output)
+        .map_err(|e| anyhow::anyhow!("Invalid history record in {}: {e}", path.display()))
+}
+
+// Internal function running the SQL script on the database with the `sqlite3` tool and returning its output.
+fn run_sqlite(path: &Path, args: &[&str], script: &str) -> anyhow::Result<String> {
+    let mut child = Command::new(SQLITE_EXE)
+        .args(["-bail", "-batch"])
+        .args(args)
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| {
+            anyhow::anyhow!("The history database needs the `{SQLITE_EXE}` command line tool: {e}")
+        })?;
+    child
+        .stdin
+        .take()
+        .ok_or_else(|| anyhow::anyhow!("Can't write to `{SQLITE_EXE}`"))?
+        .write_all(script.as_bytes())?;
+
+    let output = child.wait_with_output()?;
+    anyhow::ensure!(
+        output.status.success(),
+        "Can't access the history database {}: {}",
+        path.display(),
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Internal function quoting the text as an SQL string literal.
+fn sql_text(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// This function prints the trend of the mutation score recorded in the history database, followed by the
+/// mutants whose outcome changed in the most recent printed run (e.g. the mutants which survive after
+/// being killed before).
+///
+/// # Arguments
+///
+/// * `path` - A `Path` to the history database.
+/// * `module` - Optional name of the module to print the trend for (substring of `path::module::function`).
+///   If not provided, the score of the whole package is printed.
+/// * `last` - Optional number of the most recent runs to print.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn print_history(path: &Path, module: Option<&str>, last: Option<usize>) -> anyhow::Result<()> {
+    let records = load_records(path)?;
+    let skip = last.map_or(0, |last| records.len().saturating_sub(last));
+
+    for line in format_trend(&records[skip..], module) {
+        println!("{line}");
+    }
+
+    if let [.., previous, last] = &records[skip..] {
+        let changes = outcome_changes(previous, last);
+        if !changes.is_empty() {
+            println!("\nMutants with changed outcomes since the previous run:");
+            for line in changes {
+                println!("  {line}");
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Internal function listing the mutants tested in both runs whose outcome differs, one line per mutant.
+fn outcome_changes(previous: &HistoryRecord, last: &HistoryRecord) -> Vec<String> {
+    last.outcomes
+        .iter()
+        .filter_map(|(id, outcome)| {
+            let old = previous.outcomes.get(id)?;
+            (old != outcome).then(|| format!("{id}: {} -> {}", old.name(), outcome.name()))
+        })
+        .collect()
+}
+
+// Internal function formatting the score trend, one line per run.
+fn format_trend(records: &[HistoryRecord], module: Option<&str>) -> Vec<String> {
+    let mut previous: Option<f64> = None;
+
+    records
+        .iter()
+        .map(|record| {
+            let score = match module {
+                Some(module) => record
+                    .modules
+                    .iter()
+                    .filter(|(name, _)| name.contains(module))
+                    .fold(Score::default(), |acc, (_, s)| Score {
+                        tested: acc.tested + s.tested,
+                        killed: acc.killed + s.killed,
+                        inconclusive: acc.inconclusive + s.inconclusive,
+                    }),
+                None => record.total,
+            };
+
+            let percentage = score.percentage();
+            // The value is within 0..=100, so the conversion is safe.
+            let filled = ((percentage / 100.0) * BAR_WIDTH as f64).round() as usize;
+            let delta = previous.map_or(String::new(), |p| format!(" ({:+.2})", percentage - p));
+            previous = Some(percentage);
+
+            format!(
+                "{} {:>7.2}% [{}{}] {}/{}{}",
+                humantime::format_rfc3339_seconds(
+                    UNIX_EPOCH + Duration::from_secs(record.timestamp)
+                ),
+                percentage,
+                "#".repeat(filled),
+                " ".repeat(BAR_WIDTH - filled),
+                score.killed,
+                score.tested,
+                delta
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn record(timestamp: u64, killed: u32) -> HistoryRecord {
+        HistoryRecord {
+            timestamp,
+            package: "package".to_owned(),
+            total: Score {
+                tested: 4,
+                killed,
+                inconclusive: 0,
+            },
+            modules: BTreeMap::from([("sources/Sum.move::Sum::sum".to_owned(), Score {
+                tested: 2,
+                killed: killed.min(2),
+                inconclusive: 0,
+            })]),
+            outcomes: BTreeMap::from([("0123456789abcdef".to_owned(), MutantOutcome::Killed)]),
+        }
+    }
+
+    #[test]
+    fn records_are_appended_and_loaded() {
+        if Command::new(SQLITE_EXE).arg("-version").output().is_err() {
+            eprintln!("Skipping the test, `{SQLITE_EXE}` is not installed");
+            return;
+        }
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.db");
+
+        let mut first = record(0, 1);
+        first.package = "it's a package".to_owned();
+        append_record(&path, &first).unwrap();
+        append_record(&path, &record(60, 2)).unwrap();
+
+        assert_eq!(load_records(&path).unwrap(), vec![first, record(60, 2)]);
+    }
+
+    #[test]
+    fn missing_database_is_an_error() {
+        assert!(load_records(&PathBuf::from("missing-history.db")).is_err());
+    }
+
+    #[test]
+    fn text_is_quoted_for_sql() {
+        assert_eq!(sql_text("it's"), "'it''s'");
+    }
+
+    #[test]
+    fn record_is_created_from_report() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        report.increment_mutants_tested(&path, "Sum::sum");
+        report.increment_mutants_killed(&path, "Sum::sum");
+
+        let record = HistoryRecord::new(Path::new("package"), &report, BTreeMap::new());
+        assert_eq!(record.total.tested, 1);
+        assert_eq!(record.total.killed, 1);
+        assert_eq!(record.modules["sources/Sum.move::Sum::sum"].killed, 1);
+    }
+
+    #[test]
+    fn trend_shows_score_and_delta() {
+        let lines = format_trend(&[record(0, 1), record(60, 3)], None);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("1970-01-01T00:00:00Z   25.00% [##########"));
+        assert!(lines[0].ends_with("] 1/4"));
+        assert!(lines[1].ends_with("] 3/4 (+50.00)"));
+    }
+
+    #[test]
+    fn changed_outcomes_are_listed() {
+        let previous = record(0, 1);
+        let mut last = record(60, 2);
+        assert!(outcome_changes(&previous, &last).is_empty());
+
+        last.outcomes
+            .insert("0123456789abcdef".to_owned(), MutantOutcome::Alive);
+        last.outcomes
+            .insert("fedcba9876543210".to_owned(), MutantOutcome::Alive);
+        assert_eq!(outcome_changes(&previous, &last), [
+            "0123456789abcdef: killed -> alive"
+        ]);
+    }
+
+    #[test]
+    fn trend_for_module_uses_module_score() {
+        let lines = format_trend(&[record(0, 1)], Some("Sum::sum"));
+        assert!(lines[0].ends_with("] 1/2"));
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Mutex,
+};
+
+/// Exit code used when the run is interrupted again, as by the shells.
+#[cfg(unix)]
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Set when the user interrupted the runs in progress.
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+/// The runs in progress in the process, sharing the Ctrl-C handler.
+static ACTIVE_RUNS: Mutex<ActiveRuns> = Mutex::new(ActiveRuns {
+    count: 0,
+    #[cfg(unix)]
+    previous_handler: None,
+});
+
+struct ActiveRuns {
+    count: usize,
+    /// The SIGINT handler replaced by the Ctrl-C handler, restored once no run is in progress.
+    #[cfg(unix)]
+    previous_handler: Option<libc::sigaction>,
+}
+
+/// Guard marking the run in progress. While any guard is alive, Ctrl-C only requests the interruption
+/// (checked with `is_interrupted`), so the runs can stop the provers and write partial reports. Second
+/// Ctrl-C exits right away.
+///
+/// The Ctrl-C handler is installed when the first run starts and the previous one is restored when the last
+/// run ends, so Ctrl-C outside of the runs is handled as the process (e.g. the application calling
+/// the library) decided.
+pub(crate) struct InterruptGuard;
+
+impl InterruptGuard {
+    /// Marks the run in progress, installing the Ctrl-C handler if it's the first one.
+    pub(crate) fn new() -> Self {
+        let mut runs = ACTIVE_RUNS.lock().unwrap_or_else(|e| e.into_inner());
+        if runs.count == 0 {
+            INTERRUPTED.store(false, Ordering::SeqCst);
+            install_handler(&mut runs);
+        }
+        runs.count += 1;
+        Self
+    }
+}
+
+impl Drop for InterruptGuard {
+    fn drop(&mut self) {
+        let mut runs = ACTIVE_RUNS.lock().unwrap_or_else(|e| e.into_inner());
+        runs.count -= 1;
+        if runs.count == 0 {
+            restore_handler(&mut runs);
+            INTERRUPTED.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// Checks if the user interrupted the run.
+pub(crate) fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+// Internal function handling SIGINT during the runs. Only async-signal-safe functions are used here.
+#[cfg(unix)]
+extern "C" fn handle_interrupt(_signal: libc::c_int) {
+    if INTERRUPTED.swap(true, Ordering::SeqCst) {
+        // SAFETY: `_exit` is async-signal-safe.
+        unsafe { libc::_exit(INTERRUPTED_EXIT_CODE) };
+    }
+    let message = b"Interrupting the run, press Ctrl-C again to exit immediately\n";
+    // SAFETY: `write` is async-signal-safe and the message outlives the call.
+    unsafe { libc::write(libc::STDERR_FILENO, message.as_ptr().cast(), message.len()) };
+}
+
+// Internal function installing the Ctrl-C handler and saving the replaced one.
+#[cfg(unix)]
+fn install_handler(runs: &mut ActiveRuns) {
+    // SAFETY: the actions are fully initialized before they're passed to `sigaction`.
+    let result = unsafe {
+        let mut action: libc::sigaction = std::mem::zeroed();
+        action.sa_sigaction = handle_interrupt as extern "C" fn(libc::c_int) as libc::sighandler_t;
+        libc::sigemptyset(&mut action.sa_mask);
+        let mut previous: libc::sigaction = std::mem::zeroed();
+        match libc::sigaction(libc::SIGINT, &action, &mut previous) {
+            0 => Ok(previous),
+            _ => Err(std::io::Error::last_os_error()),
+        }
+    };
+
+    match result {
+        Ok(previous) => runs.previous_handler = Some(previous),
+        Err(e) => {
+            warn!("Can't install the Ctrl-C handler, interrupted runs won't write any report: {e}")
+        },
+    }
+}
+
+#[cfg(not(unix))]
+fn install_handler(_runs: &mut ActiveRuns) {
+    warn!("Ctrl-C can't be handled on this platform, interrupted runs won't write any report");
+}
+
+// Internal function restoring the Ctrl-C handler replaced when the first run started.
+#[cfg(unix)]
+fn restore_handler(runs: &mut ActiveRuns) {
+    if let Some(previous) = runs.previous_handler.take() {
+        // SAFETY: the action was returned by `sigaction` when the handler was installed.
+        unsafe { libc::sigaction(libc::SIGINT, &previous, std::ptr::null_mut()) };
+    }
+}
+
+#[cfg(not(unix))]
+fn restore_handler(_runs: &mut ActiveRuns) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interruption_is_kept_until_the_last_run_ends() {
+        let first = InterruptGuard::new();
+        let second = InterruptGuard::new();
+        INTERRUPTED.store(true, Ordering::SeqCst);
+
+        drop(second);
+        assert!(is_interrupted());
+        let third = InterruptGuard::new();
+        assert!(is_interrupted());
+
+        drop(third);
+        drop(first);
+        assert!(!is_interrupted());
+    }
+}
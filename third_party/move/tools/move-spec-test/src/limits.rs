@@ -0,0 +1,198 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::CLIOptions;
+use std::path::Path;
+
+/// Message printed by the Boogie wrapper script when Boogie is killed after exceeding the CPU time limit.
+pub(crate) const RESOURCE_LIMIT_MARKER: &str = "prover exceeded the resource limits";
+
+/// Resource limits applied to each prover invocation.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ResourceLimits {
+    /// Maximum memory used by the solver in megabytes.
+    pub max_memory_mb: Option<u64>,
+    /// Maximum CPU time of the prover processes in seconds.
+    pub max_cpu_time: Option<u64>,
+}
+
+impl ResourceLimits {
+    /// Creates the limits from the spec-test options.
+    pub(crate) fn new(options: &CLIOptions) -> Self {
+        Self {
+            max_memory_mb: options.max_prover_memory,
+            max_cpu_time: options.max_prover_cpu_time,
+        }
+    }
+
+    /// Applies the limits to the prover options run by the built-in prover.
+    ///
+    /// The memory limit is passed to the solver. On Unix systems, both limits are also applied by running
+    /// Boogie through a wrapper script (created in the `dir` directory), which sets them for Boogie and
+    /// the solver.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the wrapper script can't be created.
+    pub(crate) fn apply(
+        &self,
+        prover_conf: &mut move_prover::cli::Options,
+        dir: &Path,
+        index: usize,
+    ) -> anyhow::Result<()> {
+        if let Some(memory) = self.max_memory_mb {
+            prover_conf
+                .backend
+                .boogie_flags
+                .push(format!("-proverOpt:O:memory_max_size={memory}"));
+        }
+
+        if self.max_memory_mb.is_some() || self.max_cpu_time.is_some() {
+            if cfg!(unix) {
+                let wrapper = dir.join(format!("boogie_limited_{index}.sh"));
+                write_wrapper_script(&wrapper, &prover_conf.backend.boogie_exe, self)?;
+                prover_conf.backend.boogie_exe = wrapper.to_string_lossy().to_string();
+            } else if self.max_cpu_time.is_some() {
+                warn!("CPU time limit for the prover is supported only on Unix systems");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the arguments of the `docker run` command applying the limits to the container.
+    pub(crate) fn docker_args(&self) -> Vec<String> {
+        let mut args = vec![];
+        if let Some(memory) = self.max_memory_mb {
+            args.push("--memory".to_owned());
+            args.push(format!("{memory}m"));
+        }
+        if let Some(cpu_time) = self.max_cpu_time {
+            args.push("--ulimit".to_owned());
+            args.push(format!("cpu={cpu_time}"));
+        }
+        args
+    }
+}
+
+// Internal function writing the script running Boogie with the CPU time and the virtual memory limits.
+// Processes killed by a signal exit with status above 128, which is reported with the marker message.
+fn write_wrapper_script(
+    path: &Path,
+    boogie_exe: &str,
+    limits: &ResourceLimits,
+) -> anyhow::Result<()> {
+    let mut ulimits = String::new();
+    if let Some(cpu_time) = limits.max_cpu_time {
+        ulimits.push_str(&format!("ulimit -t {cpu_time}\n"));
+    }
+    if let Some(memory) = limits.max_memory_mb {
+        // The limit is given in kilobytes.
+        ulimits.push_str(&format!("ulimit -v {}\n", memory.saturating_mul(1024)));
+    }
+
+    let script = format!(
+        "#!/bin/sh\n\
+         {ulimits}\
+         '{}' \"$@\"\n\
+         status=$?\n\
+         if [ $status -gt 128 ]; then\n  \
+           echo \"{RESOURCE_LIMIT_MARKER} (exit status $status)\" >&2\n\
+         fi\n\
+         exit $status\n",
+        boogie_exe.replace('\'', "'\\''")
+    );
+    std::fs::write(path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_limits_leave_options_unchanged() {
+        let mut prover_conf = move_prover::cli::Options::default();
+        let original = prover_conf.backend.clone();
+
+        ResourceLimits::default()
+            .apply(&mut prover_conf, Path::new("."), 0)
+            .unwrap();
+        assert_eq!(prover_conf.backend.boogie_exe, original.boogie_exe);
+        assert_eq!(prover_conf.backend.boogie_flags, original.boogie_flags);
+        assert!(ResourceLimits::default().docker_args().is_empty());
+    }
+
+    #[test]
+    fn memory_limit_is_passed_to_solver_and_docker() {
+        let dir = tempfile::tempdir().unwrap();
+        let limits = ResourceLimits {
+            max_memory_mb: Some(2048),
+            max_cpu_time: None,
+        };
+        let mut prover_conf = move_prover::cli::Options::default();
+        limits.apply(&mut prover_conf, dir.path(), 0).unwrap();
+
+        assert_eq!(
+            prover_conf.backend.boogie_flags.last().unwrap(),
+            "-proverOpt:O:memory_max_size=2048"
+        );
+        assert_eq!(limits.docker_args(), vec!["--memory", "2048m"]);
+
+        #[cfg(unix)]
+        {
+            let script = std::fs::read_to_string(dir.path().join("boogie_limited_0.sh")).unwrap();
+            assert!(script.contains("ulimit -v 2097152"));
+            assert!(!script.contains("ulimit -t"));
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cpu_limit_wraps_boogie() {
+        let dir = tempfile::tempdir().unwrap();
+        let limits = ResourceLimits {
+            max_memory_mb: None,
+            max_cpu_time: Some(60),
+        };
+        let mut prover_conf = move_prover::cli::Options::default();
+        prover_conf.backend.boogie_exe = "/opt/boogie".to_owned();
+        limits.apply(&mut prover_conf, dir.path(), 1).unwrap();
+
+        let wrapper = dir.path().join("boogie_limited_1.sh");
+        assert_eq!(prover_conf.backend.boogie_exe, wrapper.to_string_lossy());
+        let script = std::fs::read_to_string(wrapper).unwrap();
+        assert!(script.contains("ulimit -t 60"));
+        assert!(script.contains("'/opt/boogie' \"$@\""));
+        assert_eq!(limits.docker_args(), vec!["--ulimit", "cpu=60"]);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wrapper_reports_killed_process() {
+        let dir = tempfile::tempdir().unwrap();
+        let wrapper = dir.path().join("wrapper.sh");
+        let fake_boogie = dir.path().join("fake_boogie.sh");
+        std::fs::write(&fake_boogie, "#!/bin/sh\nkill -9 $$\n").unwrap();
+        let limits = ResourceLimits {
+            max_memory_mb: None,
+            max_cpu_time: Some(60),
+        };
+        write_wrapper_script(&wrapper, "sh", &limits).unwrap();
+
+        let output = std::process::Command::new(&wrapper)
+            .arg(&fake_boogie)
+            .output()
+            .unwrap();
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains(RESOURCE_LIMIT_MARKER));
+    }
+}
@@ -6,8 +6,11 @@
 
 use clap::Parser;
 use move_package::BuildConfig;
-use move_spec_test::cli::CLIOptions;
-use move_spec_test::run_spec_test;
+use move_spec_test::{
+    cli::CLIOptions,
+    commands::{run_command, Command},
+    run_spec_test,
+};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -23,11 +26,24 @@ pub struct Opts {
     /// The build configuration options.
     #[clap(flatten)]
     pub build_config: BuildConfig,
+    /// Optional subcommand. If not provided, the specification testing is run.
+    #[clap(subcommand)]
+    #[serde(skip)]
+    pub command: Option<Command>,
 }
 
 fn main() -> anyhow::Result<()> {
     let opts = Opts::parse();
-    let package_path = opts.package_path.unwrap_or(PathBuf::from("."));
+    let package_path = opts.package_path.clone().unwrap_or(PathBuf::from("."));
+
+    if let Some(command) = &opts.command {
+        return run_command(
+            command,
+            &opts.cli_options,
+            &opts.build_config,
+            &package_path,
+        );
+    }
 
     run_spec_test(&opts.cli_options, &opts.build_config, &package_path)
 }
@@ -0,0 +1,209 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::CLIOptions;
+use move_mutator::configuration::Configuration;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Metadata of the run recorded in the report: the versions of the tools, the tested sources and the
+/// configuration. They are needed to audit the results and to check if two reports can be compared.
+///
+/// Information which can't be determined (e.g. the git commit of a package outside of a repository) is left
+/// out.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RunMetadata {
+    /// Version of the spec-test tool.
+    pub spec_test_version: String,
+    /// Version of the mutator.
+    pub mutator_version: String,
+    /// Version of Boogie (the first line of its version output).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boogie_version: Option<String>,
+    /// Version of the Z3 solver (the first line of its version output).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub z3_version: Option<String>,
+    /// SHA-256 digest of the package sources (the manifest and the Move files).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub package_digest: Option<String>,
+    /// Commit checked out in the git repository of the package.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git_commit: Option<String>,
+    /// Snapshot of the configuration: the spec-test options (`spec_test`) and the effective mutator
+    /// configuration (`mutator`).
+    pub configuration: serde_json::Value,
+    /// Start of the run in seconds since the UNIX epoch.
+    pub started_at: u64,
+    /// End of the run in seconds since the UNIX epoch (`None` for the runs which haven't finished yet).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finished_at: Option<u64>,
+}
+
+impl RunMetadata {
+    /// Starts the metadata of the run testing the package with the given options and prover configuration.
+    pub(crate) fn start(
+        options: &CLIOptions,
+        package_path: &Path,
+        prover_conf: &move_prover::cli::Options,
+    ) -> Self {
+        // On Mac, the version argument of Boogie is `/version`.
+        let boogie_version_arg = if cfg!(target_os = "macos") {
+            "/version"
+        } else {
+            "-version"
+        };
+
+        Self {
+            spec_test_version: env!("CARGO_PKG_VERSION").to_owned(),
+            mutator_version: move_mutator::VERSION.to_owned(),
+            boogie_version: tool_version(&prover_conf.backend.boogie_exe, boogie_version_arg),
+            z3_version: tool_version(&prover_conf.backend.z3_exe, "--version"),
+            package_digest: move_mutator::manifest::package_digest(package_path)
+                .map_err(|e| warn!("Can't compute the digest of the package: {e}"))
+                .ok(),
+            git_commit: command_output(
+                Command::new("git")
+                    .args(["rev-parse", "HEAD"])
+                    .current_dir(package_path),
+            ),
+            configuration: serde_json::json!({
+                "spec_test": serde_json::to_value(options).unwrap_or_default(),
+            }),
+            started_at: now(),
+            finished_at: None,
+        }
+    }
+
+    /// Records the effective mutator configuration of the run.
+    pub(crate) fn set_mutator_configuration(&mut self, configuration: &Configuration) {
+        self.configuration["mutator"] = serde_json::to_value(configuration).unwrap_or_default();
+    }
+
+    /// Records the end of the run.
+    pub(crate) fn finish(&mut self) {
+        self.finished_at = Some(now());
+    }
+
+    /// Returns the descriptions of the tool versions which differ between the runs, so their results
+    /// mustn't be compared blindly.
+    pub fn differences(&self, other: &RunMetadata) -> Vec<String> {
+        [
+            (
+                "spec-test version",
+                Some(self.spec_test_version.as_str()),
+                Some(other.spec_test_version.as_str()),
+            ),
+            (
+                "mutator version",
+                Some(self.mutator_version.as_str()),
+                Some(other.mutator_version.as_str()),
+            ),
+            (
+                "Boogie version",
+                self.boogie_version.as_deref(),
+                other.boogie_version.as_deref(),
+            ),
+            (
+                "Z3 version",
+                self.z3_version.as_deref(),
+                other.z3_version.as_deref(),
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, base, head)| base != head)
+        .map(|(name, base, head)| {
+            format!(
+                "{name}: {} vs {}",
+                base.unwrap_or("unknown"),
+                head.unwrap_or("unknown")
+            )
+        })
+        .collect()
+    }
+}
+
+// Internal function returning the current time in seconds since the UNIX epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+// Internal function returning the first line of the version output of the tool.
+fn tool_version(exe: &str, version_arg: &str) -> Option<String> {
+    if exe.is_empty() {
+        return None;
+    }
+    command_output(Command::new(exe).arg(version_arg))
+}
+
+// Internal function returning the first non-empty line of the output of the successful command.
+fn command_output(command: &mut Command) -> Option<String> {
+    let output = command
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_owned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn metadata_describes_run() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("Move.toml"), "[package]").unwrap();
+        let mut prover_conf = move_prover::cli::Options::default();
+        prover_conf.backend.boogie_exe = String::new();
+        prover_conf.backend.z3_exe = String::new();
+
+        let mut metadata = RunMetadata::start(&CLIOptions::default(), dir.path(), &prover_conf);
+        assert_eq!(metadata.spec_test_version, env!("CARGO_PKG_VERSION"));
+        assert!(metadata.boogie_version.is_none());
+        assert!(metadata.package_digest.is_some());
+        assert!(metadata.configuration["spec_test"].is_object());
+        assert!(metadata.finished_at.is_none());
+
+        metadata.set_mutator_configuration(&Configuration::new(
+            move_mutator::cli::CLIOptions::default(),
+            None,
+        ));
+        metadata.finish();
+        assert!(metadata.configuration["mutator"].is_object());
+        assert!(metadata.finished_at.unwrap() >= metadata.started_at);
+    }
+
+    #[test]
+    fn differences_list_changed_tool_versions() {
+        let base = RunMetadata {
+            spec_test_version: "1.0.0".to_owned(),
+            mutator_version: "1.0.0".to_owned(),
+            z3_version: Some("Z3 version 4.11.2".to_owned()),
+            ..Default::default()
+        };
+        assert!(base.differences(&base).is_empty());
+
+        let head = RunMetadata {
+            spec_test_version: "1.1.0".to_owned(),
+            z3_version: None,
+            package_digest: Some("abc".to_owned()),
+            ..base.clone()
+        };
+        assert_eq!(base.differences(&head), vec![
+            "spec-test version: 1.0.0 vs 1.1.0".to_owned(),
+            "Z3 version: Z3 version 4.11.2 vs unknown".to_owned(),
+        ]);
+    }
+}
@@ -0,0 +1,242 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_mutator::{
+    configuration::{Configuration, ProverOverride},
+    report::MutationReport,
+};
+use std::path::Path;
+
+/// Prover settings of the mutator configuration overridden for some of the mutants (`[[prover_override]]`
+/// tables), e.g. a longer timeout for a module known to be heavy.
+#[derive(Debug, Default)]
+pub(crate) struct ProverOverrides {
+    overrides: Vec<ProverOverride>,
+}
+
+impl ProverOverrides {
+    /// Collects the prover overrides of the mutator configuration.
+    pub(crate) fn new(configuration: &Configuration) -> Self {
+        Self {
+            overrides: configuration.prover_override.clone(),
+        }
+    }
+
+    /// Checks if any override applies to the mutant.
+    pub(crate) fn applies_to(&self, elem: &MutationReport) -> bool {
+        self.matching(elem).next().is_some()
+    }
+
+    /// Returns the prover options used to verify the mutant: the given options with the matching overrides
+    /// merged in the order of the configuration (so the later ones take precedence).
+    ///
+    /// The environment variables are set by running Boogie through a wrapper script (created in the `dir`
+    /// directory), so they reach Boogie and the solver, but not the other backends than the built-in prover.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name of an environment variable is invalid or the wrapper script can't be
+    /// created.
+    pub(crate) fn prover_confs(
+        &self,
+        prover_confs: &[move_prover::cli::Options],
+        elem: &MutationReport,
+        dir: &Path,
+        index: usize,
+    ) -> anyhow::Result<Vec<move_prover::cli::Options>> {
+        let mut prover_confs = prover_confs.to_vec();
+        let mut env = vec![];
+        for prover_override in self.matching(elem) {
+            for prover_conf in &mut prover_confs {
+                if let Some(timeout) = prover_override.prover_timeout {
+                    prover_conf.backend.vc_timeout = timeout;
+                }
+                if let Some(seed) = prover_override.random_seed {
+                    prover_conf.backend.random_seed = seed;
+                }
+                prover_conf
+                    .backend
+                    .boogie_flags
+                    .extend(prover_override.boogie_flags.iter().cloned());
+            }
+            env.extend(prover_override.env.iter());
+        }
+
+        if env.is_empty() {
+            return Ok(prover_confs);
+        }
+        if !cfg!(unix) {
+            warn!(
+                "Environment variables of the prover overrides are supported only on Unix systems"
+            );
+            return Ok(prover_confs);
+        }
+
+        for (conf_index, prover_conf) in prover_confs.iter_mut().enumerate() {
+            let wrapper = dir.join(format!("boogie_env_{index}_{conf_index}.sh"));
+            write_env_script(&wrapper, &prover_conf.backend.boogie_exe, &env)?;
+            prover_conf.backend.boogie_exe = wrapper.to_string_lossy().to_string();
+        }
+        Ok(prover_confs)
+    }
+
+    // Internal function returning the overrides applying to the mutant.
+    fn matching<'a>(
+        &'a self,
+        elem: &'a MutationReport,
+    ) -> impl Iterator<Item = &'a ProverOverride> {
+        self.overrides.iter().filter(|prover_override| {
+            prover_override.applies_to(elem.get_module_name(), elem.get_id())
+        })
+    }
+}
+
+// Internal function writing the script running Boogie with the given environment variables (the later
+// values of the same variable take precedence).
+fn write_env_script(
+    path: &Path,
+    boogie_exe: &str,
+    env: &[(&String, &String)],
+) -> anyhow::Result<()> {
+    let mut script = "#!/bin/sh\n".to_owned();
+    for (name, value) in env {
+        let valid = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            anyhow::bail!(
+                "Invalid name of the environment variable in the prover overrides: {name}"
+            );
+        }
+        script.push_str(&format!(
+            "export {name}='{}'\n",
+            value.replace('\'', "'\\''")
+        ));
+    }
+    script.push_str(&format!(
+        "exec '{}' \"$@\"\n",
+        boogie_exe.replace('\'', "'\\''")
+    ));
+    std::fs::write(path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeMap;
+
+    fn mutant(module: &str) -> MutationReport {
+        MutationReport::new(
+            Path::new("mutant.move"),
+            Path::new("original.move"),
+            module,
+            "function",
+            "mutated",
+            "original",
+        )
+    }
+
+    fn overrides() -> ProverOverrides {
+        ProverOverrides {
+            overrides: vec![
+                ProverOverride {
+                    modules: vec!["vault".to_owned()],
+                    prover_timeout: Some(120),
+                    random_seed: Some(1),
+                    boogie_flags: vec!["-flag".to_owned()],
+                    ..Default::default()
+                },
+                ProverOverride {
+                    modules: vec!["vault".to_owned()],
+                    random_seed: Some(7),
+                    ..Default::default()
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn matching_overrides_are_merged_in_order() {
+        let overrides = overrides();
+        let prover_confs = vec![move_prover::cli::Options::default(); 2];
+
+        let vault = mutant("vault");
+        assert!(overrides.applies_to(&vault));
+        let confs = overrides
+            .prover_confs(&prover_confs, &vault, Path::new("."), 0)
+            .unwrap();
+        for conf in &confs {
+            assert_eq!(conf.backend.vc_timeout, 120);
+            assert_eq!(conf.backend.random_seed, 7);
+            assert_eq!(conf.backend.boogie_flags.last().unwrap(), "-flag");
+        }
+
+        let bank = mutant("bank");
+        assert!(!overrides.applies_to(&bank));
+        let confs = overrides
+            .prover_confs(&prover_confs, &bank, Path::new("."), 0)
+            .unwrap();
+        assert_eq!(
+            confs[0].backend.vc_timeout,
+            prover_confs[0].backend.vc_timeout
+        );
+        assert_eq!(
+            confs[0].backend.boogie_exe,
+            prover_confs[0].backend.boogie_exe
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn environment_variables_wrap_boogie() {
+        let dir = tempfile::tempdir().unwrap();
+        let overrides = ProverOverrides {
+            overrides: vec![ProverOverride {
+                modules: vec!["vault".to_owned()],
+                env: BTreeMap::from([("Z3_LOG".to_owned(), "it's on".to_owned())]),
+                ..Default::default()
+            }],
+        };
+
+        let confs = overrides
+            .prover_confs(
+                &[move_prover::cli::Options::default()],
+                &mutant("vault"),
+                dir.path(),
+                3,
+            )
+            .unwrap();
+        let wrapper = dir.path().join("boogie_env_3_0.sh");
+        assert_eq!(confs[0].backend.boogie_exe, wrapper.to_string_lossy());
+        let script = std::fs::read_to_string(wrapper).unwrap();
+        assert!(script.contains("export Z3_LOG='it'\\''s on'\n"));
+        assert!(script.contains("exec '"));
+
+        let invalid = ProverOverrides {
+            overrides: vec![ProverOverride {
+                modules: vec!["vault".to_owned()],
+                env: BTreeMap::from([("BAD NAME".to_owned(), "1".to_owned())]),
+                ..Default::default()
+            }],
+        };
+        assert!(invalid
+            .prover_confs(
+                &[move_prover::cli::Options::default()],
+                &mutant("vault"),
+                dir.path(),
+                0
+            )
+            .is_err());
+    }
+}
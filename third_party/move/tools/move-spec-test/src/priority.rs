@@ -0,0 +1,150 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{history, prover::MutantOutcome, scope};
+use move_mutator::report::MutationReport;
+use move_package::BuildConfig;
+use std::{collections::BTreeSet, path::Path};
+
+/// Mutation operators tampering with the security-relevant code: the access control, the transferred
+/// amounts, the resources, the time checks and the emitted events.
+const SECURITY_OPERATORS: &[&str] = &[
+    "signer_address_replacement",
+    "named_address_swap",
+    "amount_replacement",
+    "resource_operation_reorder",
+    "timestamp_replacement",
+    "event_emission_removal",
+];
+
+/// Heuristic ordering the verification queue, so the early results are the most informative ones (e.g. when
+/// the run is limited by the time budget).
+///
+/// Mutants which survived the previous run come first, as they likely survive again, then the mutants of
+/// the entry functions and the scripts (reachable by anyone) and then the mutants applying
+/// the security-relevant operators.
+#[derive(Debug, Default)]
+pub(crate) struct Priorities {
+    /// Entry functions of the package (keyed by `address::Module::function`).
+    entry_functions: BTreeSet<String>,
+    /// IDs of the mutants which survived the last run recorded in the history file.
+    surviving: BTreeSet<String>,
+}
+
+impl Priorities {
+    /// Collects the entry functions of the package and the mutants which survived the last run recorded in
+    /// the history file (if given and it exists).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package model can't be built or the history file can't be read.
+    pub(crate) fn new(
+        config: &BuildConfig,
+        package_path: &Path,
+        history: Option<&Path>,
+    ) -> anyhow::Result<Self> {
+        let env = scope::package_model(config, package_path)?;
+
+        let surviving = match history.filter(|path| path.exists()) {
+            Some(path) => history::load_records(path)?
+                .pop()
+                .map(|record| {
+                    record
+                        .outcomes
+                        .into_iter()
+                        .filter(|(_, outcome)| *outcome == MutantOutcome::Alive)
+                        .map(|(id, _)| id)
+                        .collect()
+                })
+                .unwrap_or_default(),
+            None => BTreeSet::new(),
+        };
+
+        Ok(Self {
+            entry_functions: scope::entry_functions(&env),
+            surviving,
+        })
+    }
+
+    /// Reorders the mutants by their priority. The sort is stable, so the mutants with the same priority
+    /// keep their order.
+    pub(crate) fn sort(&self, mutants: &mut [&MutationReport]) {
+        mutants.sort_by_key(|elem| self.rank(elem));
+    }
+
+    // Internal function returning the sort key of the mutant (the most informative mutants get the lowest).
+    fn rank(&self, elem: &MutationReport) -> (bool, bool, bool) {
+        let qname = elem.qualified_function_name();
+        let security = elem
+            .get_mutations()
+            .iter()
+            .any(|m| SECURITY_OPERATORS.contains(&m.get_operator_name()));
+
+        (
+            !self.surviving.contains(elem.get_id()),
+            !(elem.is_script() || self.entry_functions.contains(&qname)),
+            !security,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_mutator::report::{Mutation, Range};
+
+    fn mutant(function: &str, operator: &str) -> MutationReport {
+        let mut report = MutationReport::new(
+            Path::new("mutant.move"),
+            Path::new("original.move"),
+            "bank",
+            function,
+            "mutated",
+            "original",
+        );
+        report.add_modification(Mutation::new(
+            Range::new(0, 1),
+            operator.to_owned(),
+            "old".to_owned(),
+            "new".to_owned(),
+        ));
+        report.set_module_address("0x1");
+        report
+    }
+
+    #[test]
+    fn informative_mutants_come_first() {
+        let plain = mutant("helper", "binary_operator_replacement");
+        let security = mutant("helper", "amount_replacement");
+        let entry = mutant("withdraw", "binary_operator_replacement");
+        let surviving = mutant("helper", "literal_replacement");
+        let priorities = Priorities {
+            entry_functions: BTreeSet::from(["0x1::bank::withdraw".to_owned()]),
+            surviving: BTreeSet::from([surviving.get_id().to_owned()]),
+        };
+
+        let mut mutants = vec![&plain, &security, &entry, &surviving];
+        priorities.sort(&mut mutants);
+        let ids = mutants.iter().map(|m| m.get_id()).collect::<Vec<_>>();
+        assert_eq!(ids, vec![
+            surviving.get_id(),
+            entry.get_id(),
+            security.get_id(),
+            plain.get_id()
+        ]);
+
+        // The scripts are entry points as well.
+        let mut script = mutant("main", "binary_operator_replacement");
+        script.set_script(true);
+        let mut mutants = vec![&plain, &script];
+        priorities.sort(&mut mutants);
+        assert_eq!(mutants[0].get_id(), script.get_id());
+
+        // Mutants with the same priority keep their order.
+        let other = mutant("other", "binary_operator_replacement");
+        let mut mutants = vec![&other, &plain];
+        Priorities::default().sort(&mut mutants);
+        assert_eq!(mutants[0].get_id(), other.get_id());
+    }
+}
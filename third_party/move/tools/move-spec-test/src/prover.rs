@@ -2,10 +2,112 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::{
+    backend::ExecutionBackend,
+    cli::{KillPolicy, VerifyScope},
+    diagnostics::{parse_prover_output, ProverDiagnostic},
+    limits::RESOURCE_LIMIT_MARKER,
+    scope::scoped_prover_confs,
+};
 use move_model::metadata::{CompilerVersion, LanguageVersion};
 use move_package::{BuildConfig, ModelConfig};
-use std::{path::Path, time::Instant};
-use termcolor::WriteColor;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::Instant,
+};
+use termcolor::{Buffer, WriteColor};
+
+/// Patterns in the prover output indicating a problem with the proving infrastructure (e.g. Boogie crash)
+/// rather than a genuine verification error.
+const INFRASTRUCTURE_ERROR_PATTERNS: &[&str] = &[
+    "Boogie error",
+    "[internal] boogie exited",
+    "could not be found",
+    "Prover error:",
+];
+
+/// Patterns in the prover output indicating that the prover exceeded the memory or CPU time limits.
+const RESOURCE_LIMIT_PATTERNS: &[&str] = &[RESOURCE_LIMIT_MARKER, "out of memory", "OutOfMemory"];
+
+/// Patterns of the prover diagnostics reported when the solver could not decide (e.g. timeout).
+const UNKNOWN_RESULT_PATTERNS: &[&str] = &[
+    "verification out of resources/timeout",
+    "verification inconclusive",
+    "exceeded hard timeout",
+];
+
+/// Result of a single proving process.
+#[derive(Debug)]
+pub(crate) enum ProverResult {
+    /// The package was verified successfully.
+    Verified,
+    /// The prover found a genuine verification error (or the package could not be built).
+    /// The verification errors parsed from the prover output are attached.
+    Failed(anyhow::Error, Vec<ProverDiagnostic>),
+    /// The solver could not decide (timeout, solver resource count or inconclusive result).
+    Inconclusive(anyhow::Error),
+    /// The prover could not complete because of an infrastructure problem.
+    InfrastructureError(anyhow::Error),
+    /// The prover exceeded the memory or CPU time limits.
+    ResourceLimit(anyhow::Error),
+}
+
+/// Outcome of verifying a mutant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum MutantOutcome {
+    /// The specification caught the mutant.
+    Killed,
+    /// The mutant passed the verification.
+    Alive,
+    /// The solver could not decide if the mutant violates the specification.
+    Inconclusive,
+    /// The outcome could not be decided because of infrastructure problems.
+    Undecided,
+    /// The prover exceeded the memory or CPU time limits.
+    ResourceLimit,
+    /// No specification can observe the mutant, so the prover wasn't run.
+    Unobservable,
+    /// No test executes the mutated lines, so the prover wasn't run.
+    NotCovered,
+}
+
+impl MutantOutcome {
+    /// Returns the name of the outcome, as used in the serialized reports.
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            MutantOutcome::Killed => "killed",
+            MutantOutcome::Alive => "alive",
+            MutantOutcome::Inconclusive => "inconclusive",
+            MutantOutcome::Undecided => "undecided",
+            MutantOutcome::ResourceLimit => "resource_limit",
+            MutantOutcome::Unobservable => "unobservable",
+            MutantOutcome::NotCovered => "not_covered",
+        }
+    }
+}
+
+/// Outcome of verifying a mutant, along with the verification errors which killed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Verification {
+    /// The outcome of the verification.
+    pub(crate) outcome: MutantOutcome,
+    /// The verification errors reported by the prover (empty unless the mutant was killed).
+    pub(crate) diagnostics: Vec<ProverDiagnostic>,
+}
+
+impl From<MutantOutcome> for Verification {
+    fn from(outcome: MutantOutcome) -> Self {
+        Self {
+            outcome,
+            diagnostics: vec![],
+        }
+    }
+}
 
 /// The `prove` function is responsible for proving the package.
 ///
@@ -14,6 +116,7 @@ use termcolor::WriteColor;
 /// * `config` - A `BuildConfig` representing the build configuration.
 /// * `package_path` - A `Path` to the package.
 /// * `prover_conf` - `move_prover::cli::Options` the options for the prover.
+/// * `verify_scope` - `VerifyScope` deciding if the dependencies are verified as well.
 /// * `error_writer` - `&mut dyn std::io::Write` the error writer.
 ///
 /// # Returns
@@ -23,12 +126,13 @@ pub(crate) fn prove<W: WriteColor>(
     config: &BuildConfig,
     package_path: &Path,
     prover_conf: &move_prover::cli::Options,
+    verify_scope: VerifyScope,
     mut error_writer: &mut W,
 ) -> anyhow::Result<()> {
     let mut model = config.clone().move_model_for_package(
         package_path,
         ModelConfig {
-            all_files_as_targets: true,
+            all_files_as_targets: verify_scope == VerifyScope::All,
             target_filter: None,
             compiler_version: config
                 .compiler_config
@@ -51,5 +155,456 @@ pub(crate) fn prove<W: WriteColor>(
 
     let now = Instant::now();
 
+    // Boogie and the solvers left running by the prover (e.g. after a timeout or a panic) are killed once
+    // the verification ends.
+    let _supervision = SupervisionGuard::new(Path::new(&prover_conf.output_path));
     move_prover::run_move_prover_with_model(&mut model, &mut error_writer, prover_conf, Some(now))
 }
+
+/// The `supervise` function makes Boogie (and the solvers started by it) supervised during the verification.
+///
+/// On Unix systems, Boogie is run through a supervisor script (created in the `dir` directory), which starts
+/// it in its own process group and records the group next to the Boogie file it's given. The recorded process
+/// groups are killed when the `SupervisionGuard` of the verification is dropped, so no Boogie or solver
+/// processes outlive the verification of a mutant.
+/// The supervisor wraps the other Boogie wrappers (e.g. the CPU time limit one), so it must be applied last.
+///
+/// # Errors
+///
+/// Returns an error if the supervisor script can't be created.
+pub(crate) fn supervise(
+    prover_conf: &mut move_prover::cli::Options,
+    dir: &Path,
+    index: usize,
+) -> anyhow::Result<()> {
+    // Without Boogie configured, the prover reports the missing tool itself.
+    if !cfg!(unix) || prover_conf.backend.boogie_exe.is_empty() {
+        return Ok(());
+    }
+    // The job control needed to start Boogie in its own process group is available in non-interactive
+    // shells only in bash.
+    if Command::new("bash")
+        .arg("--version")
+        .stdout(Stdio::null())
+        .status()
+        .is_err()
+    {
+        warn!("Bash not found, Boogie and solver processes are not supervised");
+        return Ok(());
+    }
+
+    let supervisor = dir.join(format!("boogie_supervised_{index}.sh"));
+    write_supervisor_script(&supervisor, &prover_conf.backend.boogie_exe)?;
+    prover_conf.backend.boogie_exe = supervisor.to_string_lossy().to_string();
+    Ok(())
+}
+
+/// Guard killing the process groups recorded by the supervisor scripts for the Boogie file of a verification
+/// when it's dropped. The verified package copies (and so the Boogie files) are distinct for the mutants
+/// verified at the same time, so the guard kills only the processes of its own verification.
+pub(crate) struct SupervisionGuard {
+    groups_dir: PathBuf,
+}
+
+impl SupervisionGuard {
+    pub(crate) fn new(boogie_file: &Path) -> Self {
+        Self {
+            groups_dir: process_groups_dir(boogie_file),
+        }
+    }
+}
+
+impl Drop for SupervisionGuard {
+    fn drop(&mut self) {
+        kill_process_groups(&self.groups_dir);
+    }
+}
+
+// Internal function killing the process groups recorded in the `groups_dir` and removing the directory.
+fn kill_process_groups(groups_dir: &Path) {
+    let Ok(entries) = fs::read_dir(groups_dir) else {
+        return;
+    };
+
+    for group in entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| entry.file_name().to_str()?.parse::<u32>().ok())
+    {
+        // Groups without any process left are reported as errors, which are expected.
+        let _ = Command::new("kill")
+            .args(["-KILL", "--", &format!("-{group}")])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+    }
+    let _ = fs::remove_dir_all(groups_dir);
+}
+
+// Internal function returning the directory where the supervisor scripts record the process groups started
+// for the `boogie_file` (one file named by the group for each Boogie run, e.g. `output.bpl.process-groups/42`).
+fn process_groups_dir(boogie_file: &Path) -> PathBuf {
+    let mut dir = boogie_file.as_os_str().to_owned();
+    dir.push(".process-groups");
+    PathBuf::from(dir)
+}
+
+// Internal function writing the script running Boogie in its own process group (`set -m` enables the job
+// control in bash, so the background job gets its own group). The group is recorded next to the Boogie file (the
+// last argument, see `process_groups_dir`) and killed once Boogie exits, or when the script is interrupted.
+// The script removes its own record after the final kill, so only the groups of the killed scripts are left.
+fn write_supervisor_script(path: &Path, boogie_exe: &str) -> anyhow::Result<()> {
+    let script = format!(
+        "#!/usr/bin/env bash\n\
+         set -m\n\
+         groups=\"${{!#}}.process-groups\"\n\
+         mkdir -p \"$groups\"\n\
+         '{}' \"$@\" &\n\
+         pid=$!\n\
+         : > \"$groups/$pid\"\n\
+         trap 'kill -KILL -- -$pid 2>/dev/null' INT TERM HUP\n\
+         wait $pid\n\
+         status=$?\n\
+         kill -KILL -- -$pid 2>/dev/null\n\
+         rm -f \"$groups/$pid\"\n\
+         exit $status\n",
+        boogie_exe.replace('\'', "'\\''")
+    );
+    fs::write(path, script)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(())
+}
+
+/// The `prove_with_retries` function proves the package and re-runs the prover up to `retries` times
+/// if it fails because of an infrastructure problem or an inconclusive result.
+///
+/// The prover output is captured to classify the failure and forwarded to the `error_writer` afterwards.
+///
+/// # Arguments
+///
+/// * `backend` - The `ExecutionBackend` running the prover.
+/// * `package_path` - A `Path` to the package.
+/// * `prover_conf` - `move_prover::cli::Options` the options for the prover.
+/// * `retries` - The maximum number of retries.
+/// * `error_writer` - `&mut dyn std::io::Write` the error writer.
+///
+/// # Returns
+///
+/// * `ProverResult` - The classified result of the last proving process.
+pub(crate) fn prove_with_retries<W: WriteColor>(
+    backend: &dyn ExecutionBackend,
+    package_path: &Path,
+    prover_conf: &move_prover::cli::Options,
+    retries: usize,
+    error_writer: &mut W,
+) -> ProverResult {
+    let mut attempt = 0;
+
+    loop {
+        let mut buffer = Buffer::no_color();
+        let result = backend.prove(package_path, prover_conf, &mut buffer);
+
+        if let Err(e) = error_writer.write_all(buffer.as_slice()) {
+            warn!("Can't write the prover output: {e}");
+        }
+
+        let Err(error) = result else {
+            return ProverResult::Verified;
+        };
+
+        let output = String::from_utf8_lossy(buffer.as_slice());
+        let result = classify_failure(error, &output);
+
+        // Resource limit breaches are not retried, as they would most likely happen again.
+        if matches!(
+            result,
+            ProverResult::Failed(..) | ProverResult::ResourceLimit(_)
+        ) || attempt >= retries
+        {
+            return result;
+        }
+
+        attempt += 1;
+        warn!("Prover didn't give a conclusive answer: {result:?}. Retrying ({attempt}/{retries})");
+    }
+}
+
+/// The `prove_matrix` function verifies the package against every prover configuration and decides,
+/// using the given kill policy, whether the specification caught the mutant.
+///
+/// Configurations which failed because of infrastructure problems or inconclusive results (even
+/// after retries) don't count as failures. Proving stops as soon as the outcome is known (first
+/// success for `KillPolicy::All`, first failure for `KillPolicy::Any`).
+///
+/// # Arguments
+///
+/// * `backend` - The `ExecutionBackend` running the prover.
+/// * `package_path` - A `Path` to the package.
+/// * `prover_confs` - `&[move_prover::cli::Options]` the list of options for the prover.
+/// * `policy` - `KillPolicy` deciding when the mutant is killed.
+/// * `retries` - The maximum number of retries for each configuration.
+/// * `error_writer` - `&mut dyn std::io::Write` the error writer.
+///
+/// # Returns
+///
+/// * `Verification` - The outcome of the verification with the errors reported by the failed configurations.
+pub(crate) fn prove_matrix<W: WriteColor>(
+    backend: &dyn ExecutionBackend,
+    package_path: &Path,
+    prover_confs: &[move_prover::cli::Options],
+    policy: KillPolicy,
+    retries: usize,
+    error_writer: &mut W,
+) -> Verification {
+    let mut undecided = false;
+    let mut resource_limit = false;
+    let mut inconclusive = false;
+    let mut diagnostics: Vec<ProverDiagnostic> = vec![];
+
+    for (index, prover_conf) in prover_confs.iter().enumerate() {
+        let result = prove_with_retries(backend, package_path, prover_conf, retries, error_writer);
+        trace!("Prover configuration {index} result: {result:?}");
+
+        match (policy, result) {
+            (KillPolicy::All, ProverResult::Verified) => return MutantOutcome::Alive.into(),
+            (KillPolicy::Any, ProverResult::Failed(_, errors)) => {
+                return Verification {
+                    outcome: MutantOutcome::Killed,
+                    diagnostics: errors,
+                }
+            },
+            (KillPolicy::All, ProverResult::Failed(_, errors)) => {
+                // The same errors are usually reported by all configurations.
+                for error in errors {
+                    if !diagnostics.contains(&error) {
+                        diagnostics.push(error);
+                    }
+                }
+            },
+            (_, ProverResult::InfrastructureError(_)) => undecided = true,
+            (_, ProverResult::ResourceLimit(_)) => resource_limit = true,
+            (_, ProverResult::Inconclusive(_)) => inconclusive = true,
+            _ => {},
+        }
+    }
+
+    let outcome = match policy {
+        _ if undecided || prover_confs.is_empty() => MutantOutcome::Undecided,
+        _ if resource_limit => MutantOutcome::ResourceLimit,
+        _ if inconclusive => MutantOutcome::Inconclusive,
+        KillPolicy::All => MutantOutcome::Killed,
+        KillPolicy::Any => MutantOutcome::Alive,
+    };
+
+    if outcome == MutantOutcome::Killed {
+        Verification {
+            outcome,
+            diagnostics,
+        }
+    } else {
+        outcome.into()
+    }
+}
+
+/// The `prove_modules` function verifies the mutant in the given modules, using the `prove_matrix` function
+/// with the prover options narrowed to the modules. All modules are verified by a single prover run for each
+/// configuration. If no modules are given, the whole package is verified.
+///
+/// # Arguments
+///
+/// * `backend` - The `ExecutionBackend` running the prover.
+/// * `package_path` - A `Path` to the package.
+/// * `prover_confs` - `&[move_prover::cli::Options]` the list of options for the prover.
+/// * `modules` - `Option<&[String]>` the names of the verified modules.
+/// * `policy` - `KillPolicy` deciding when the mutant is killed.
+/// * `retries` - The maximum number of retries for each configuration.
+/// * `error_writer` - `&mut dyn std::io::Write` the error writer.
+///
+/// # Returns
+///
+/// * `Verification` - The outcome of the verification with the errors which killed the mutant.
+pub(crate) fn prove_modules<W: WriteColor>(
+    backend: &dyn ExecutionBackend,
+    package_path: &Path,
+    prover_confs: &[move_prover::cli::Options],
+    modules: Option<&[String]>,
+    policy: KillPolicy,
+    retries: usize,
+    error_writer: &mut W,
+) -> Verification {
+    let Some(modules) = modules else {
+        return prove_matrix(
+            backend,
+            package_path,
+            prover_confs,
+            policy,
+            retries,
+            error_writer,
+        );
+    };
+
+    let confs = scoped_prover_confs(prover_confs, modules);
+    let verification = prove_matrix(backend, package_path, &confs, policy, retries, error_writer);
+    trace!("Modules {modules:?} outcome: {:?}", verification.outcome);
+    verification
+}
+
+/// Classifies the prover failure using the error and the prover output.
+/// Failures where all reported errors are unknown solver results (e.g. timeouts) are inconclusive,
+/// since they don't prove anything about the mutant.
+fn classify_failure(error: anyhow::Error, output: &str) -> ProverResult {
+    let message = format!("{error:#}");
+    let contains = |pattern: &&str| message.contains(pattern) || output.contains(pattern);
+
+    if RESOURCE_LIMIT_PATTERNS.iter().any(contains) {
+        return ProverResult::ResourceLimit(error);
+    }
+
+    if INFRASTRUCTURE_ERROR_PATTERNS.iter().any(contains) {
+        return ProverResult::InfrastructureError(error);
+    }
+
+    let mut errors = output
+        .lines()
+        .filter(|line| line.starts_with("error"))
+        .peekable();
+    let only_unknown_results = errors.peek().is_some()
+        && errors.all(|line| {
+            UNKNOWN_RESULT_PATTERNS
+                .iter()
+                .any(|pattern| line.contains(pattern))
+        });
+
+    if only_unknown_results || UNKNOWN_RESULT_PATTERNS.iter().any(|p| message.contains(p)) {
+        ProverResult::Inconclusive(error)
+    } else {
+        ProverResult::Failed(error, parse_prover_output(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::LocalBackend;
+    use anyhow::anyhow;
+
+    #[test]
+    fn genuine_verification_error_is_failure() {
+        let output = "error: post-condition does not hold\n   ┌─ sources/Sum.move:8:9\n";
+        let ProverResult::Failed(_, diagnostics) =
+            classify_failure(anyhow!("exiting with verification errors"), output)
+        else {
+            panic!("verification error is not a failure");
+        };
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].condition, "post-condition does not hold");
+        assert_eq!(diagnostics[0].line, Some(8));
+    }
+
+    #[test]
+    fn boogie_crash_is_infrastructure_error() {
+        assert!(matches!(
+            classify_failure(anyhow!("Boogie error (exit status: 134): "), ""),
+            ProverResult::InfrastructureError(_)
+        ));
+    }
+
+    #[test]
+    fn exceeded_limits_are_resource_limit() {
+        let message = format!("Boogie error (exit status: 137): {RESOURCE_LIMIT_MARKER}");
+        assert!(matches!(
+            classify_failure(anyhow!(message), ""),
+            ProverResult::ResourceLimit(_)
+        ));
+        assert!(matches!(
+            classify_failure(
+                anyhow!("exiting with verification errors"),
+                "error: Prover error: out of memory\n"
+            ),
+            ProverResult::ResourceLimit(_)
+        ));
+    }
+
+    #[test]
+    fn timeouts_only_are_inconclusive() {
+        let output = "error: verification out of resources/timeout (global timeout set to 40s)\n";
+        assert!(matches!(
+            classify_failure(anyhow!("exiting with verification errors"), output),
+            ProverResult::Inconclusive(_)
+        ));
+    }
+
+    #[test]
+    fn hard_timeout_is_inconclusive() {
+        let output = "error: Boogie execution exceeded hard timeout of 40s\n";
+        assert!(matches!(
+            classify_failure(anyhow!("exiting with verification errors"), output),
+            ProverResult::Inconclusive(_)
+        ));
+    }
+
+    #[test]
+    fn timeout_with_verification_error_is_failure() {
+        let output = "error: verification out of resources/timeout (global timeout set to 40s)\nerror: abort not covered by any of the `aborts_if` clauses\n";
+        assert!(matches!(
+            classify_failure(anyhow!("exiting with verification errors"), output),
+            ProverResult::Failed(..)
+        ));
+    }
+
+    #[test]
+    fn prove_matrix_without_configurations_is_undecided() {
+        let mut buffer = Buffer::no_color();
+        let verification = prove_matrix(
+            &LocalBackend::new(&BuildConfig::default(), VerifyScope::All),
+            Path::new("."),
+            &[],
+            KillPolicy::All,
+            0,
+            &mut buffer,
+        );
+        assert_eq!(verification, MutantOutcome::Undecided.into());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn supervisor_kills_orphaned_solver_processes() {
+        let dir = tempfile::tempdir().unwrap();
+        let solver_pid = dir.path().join("solver.pid");
+        let fake_boogie = dir.path().join("fake_boogie.sh");
+        fs::write(
+            &fake_boogie,
+            format!(
+                "#!/bin/sh\nsleep 300 &\necho $! > '{}'\nexit 3\n",
+                solver_pid.display()
+            ),
+        )
+        .unwrap();
+
+        let supervisor = dir.path().join("supervisor.sh");
+        write_supervisor_script(&supervisor, "sh").unwrap();
+        let status = Command::new(&supervisor)
+            .arg(&fake_boogie)
+            .status()
+            .unwrap();
+        assert_eq!(status.code(), Some(3));
+
+        // The script removes the record of the group it killed itself.
+        let groups_dir = process_groups_dir(&fake_boogie);
+        assert_eq!(fs::read_dir(&groups_dir).unwrap().count(), 0);
+
+        kill_process_groups(&groups_dir);
+        assert!(!groups_dir.exists());
+
+        // The killed process is either gone or a zombie waiting to be reaped.
+        let pid = fs::read_to_string(solver_pid).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        let state = fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        assert!(state.is_empty() || state.contains(") Z "), "{state}");
+    }
+}
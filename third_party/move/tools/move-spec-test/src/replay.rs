@@ -0,0 +1,150 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backend::create_backend,
+    cli::CLIOptions,
+    limits::ResourceLimits,
+    overrides::ProverOverrides,
+    prover::{prove_with_retries, supervise, ProverResult},
+    verify,
+    workspace::Workspace,
+};
+use move_mutator::{archive, compiler::IgnoreRules};
+use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
+use std::{fs, path::Path};
+
+/// This function replays the verification of a single mutant, to investigate why it survived (or was killed).
+///
+/// The mutant workspace is reconstructed: the package is copied, the mutation is re-applied to the current
+/// sources and the prover is run once (without retries) with the debug logs and the Boogie artifacts kept.
+/// The workspace isn't removed afterwards, so the mutated package and the prover files can be inspected.
+///
+/// # Arguments
+///
+/// * `options` - A `cli::CLIOptions` representing the options for the spec test.
+/// * `config` - A `BuildConfig` representing the build configuration.
+/// * `package_path` - A `Path` to the package.
+/// * `mutants_dir` - An optional `Path` to the directory (or the `.tar.zst` archive) with the generated
+///   mutants. If not given, the mutants are generated again.
+/// * `mutant_id` - The ID of the mutant to replay.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_replay(
+    options: &CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+    mutants_dir: Option<&Path>,
+    mutant_id: &str,
+) -> anyhow::Result<()> {
+    let package_path = SourcePackageLayout::try_find_root(&package_path.canonicalize()?)?;
+    let workspace = Workspace::new(true)?.with_copy_ignore(IgnoreRules::new(&options.copy_ignore)?);
+
+    let (outdir_mutant, generation_root) = match mutants_dir {
+        Some(dir) if archive::is_archive(dir) => {
+            let extracted = workspace.mutants_dir();
+            archive::extract_archive(dir, &extracted)?;
+            let root = verify::check_generated_mutants(&extracted, &package_path)?;
+            (extracted, root)
+        },
+        Some(dir) => {
+            let root = verify::check_generated_mutants(dir, &package_path)?;
+            (dir.to_path_buf(), root)
+        },
+        None => (
+            crate::run_mutator(options, config, &package_path, &workspace)?,
+            None,
+        ),
+    };
+
+    let report = crate::load_mutator_report(&outdir_mutant)?;
+    let Some(elem) = report
+        .get_mutants()
+        .iter()
+        .find(|elem| elem.get_id() == mutant_id)
+    else {
+        anyhow::bail!(
+            "Mutant {mutant_id} not found in {}",
+            outdir_mutant.display()
+        );
+    };
+
+    let roots = [Some(package_path.as_path()), generation_root.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    let replay_dir = workspace.create_mutant_dir(0)?;
+    workspace.copy_package(&package_path, &replay_dir)?;
+    for (file, mutant_file, diff) in crate::changed_files(elem, &roots) {
+        let source = fs::read_to_string(package_path.join(file))?;
+        fs::write(
+            replay_dir.join(file),
+            verify::rebase_mutant(&source, diff, mutant_file)?,
+        )?;
+        println!("Mutation applied to {}:\n{diff}", file.display());
+    }
+    move_mutator::compiler::rewrite_manifest_for_mutant(&package_path, &replay_dir)?;
+
+    // Only the first prover configuration is used, as the replay is meant to show a single prover run.
+    let mut prover_conf = crate::cli::generate_prover_matrix(options)?.remove(0);
+    prover_conf.enable_debug();
+    prover_conf.backend.keep_artifacts = true;
+    prover_conf.setup_logging();
+
+    let limits = ResourceLimits::new(options);
+    limits.apply(&mut prover_conf, workspace.root(), 0)?;
+    supervise(&mut prover_conf, workspace.root(), 0)?;
+    let mutator_configuration =
+        crate::cli::resolve_mutator_configuration(options, Some(&package_path))?;
+    let prover_conf = ProverOverrides::new(&mutator_configuration)
+        .prover_confs(&[prover_conf], elem, workspace.root(), 0)?
+        .remove(0);
+    let backend = create_backend(options, config, &package_path, &limits)?;
+
+    let mut error_writer = termcolor::StandardStream::stderr(termcolor::ColorChoice::Auto);
+    let result = prove_with_retries(
+        backend.as_ref(),
+        &replay_dir,
+        &prover_conf,
+        0,
+        &mut error_writer,
+    );
+
+    println!();
+    match result {
+        ProverResult::Verified => println!("Mutant {mutant_id} survived (the prover verified it)"),
+        ProverResult::Failed(_, diagnostics) => {
+            println!("Mutant {mutant_id} was killed by the prover");
+            for diagnostic in diagnostics {
+                let location = match (&diagnostic.file, diagnostic.line) {
+                    (Some(file), Some(line)) => format!(" ({file}:{line})"),
+                    (Some(file), None) => format!(" ({file})"),
+                    _ => String::new(),
+                };
+                println!("  {}{location}", diagnostic.condition);
+            }
+        },
+        ProverResult::Inconclusive(e) => {
+            println!("Prover result for the mutant {mutant_id} is inconclusive: {e}")
+        },
+        ProverResult::InfrastructureError(e) => {
+            println!(
+                "Mutant {mutant_id} couldn't be verified because of an infrastructure error: {e}"
+            )
+        },
+        ProverResult::ResourceLimit(e) => {
+            println!("Prover exceeded the resource limits for the mutant {mutant_id}: {e}")
+        },
+    }
+    println!(
+        "Mutant workspace kept in: {} (the mutated package is in {})",
+        workspace.root().display(),
+        replay_dir.display()
+    );
+
+    Ok(())
+}
@@ -2,271 +2,996 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use serde::Serialize;
+mod annotations;
+mod backend;
+mod batch;
+mod benchmark;
+mod budget;
+pub mod cli;
+pub mod commands;
+pub mod compare;
+mod coverage;
+mod csv_report;
+pub mod diagnostics;
+mod events;
+pub mod harness;
+mod hints;
+pub mod history;
+mod interrupt;
+mod limits;
+pub mod metadata;
+mod overrides;
+mod priority;
+mod prover;
+pub mod replay;
+pub mod report;
+mod scope;
+pub mod serve;
+pub mod site;
+pub mod suggest;
+pub mod suppressions;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+pub mod triage;
+pub mod verify;
+mod watch;
+mod workspace;
+
+extern crate pretty_env_logger;
+#[macro_use]
+extern crate log;
+
+use crate::{
+    backend::create_backend,
+    batch::{BatchProver, PendingMutant},
+    benchmark::{sample_mutants, Benchmark, Benchmarks, PerformanceReport},
+    budget::TimeBudget,
+    coverage::Coverage,
+    events::{Event, EventStream},
+    hints::SpecHint,
+    history::HistoryRecord,
+    limits::ResourceLimits,
+    metadata::RunMetadata,
+    overrides::ProverOverrides,
+    priority::Priorities,
+    prover::{
+        prove_modules, prove_with_retries, supervise, MutantOutcome, ProverResult, Verification,
+    },
+    report::{MutantStatus, SurvivingMutant},
+    scope::{ModuleGroups, ScopedModules},
+    suppressions::Suppressions,
+    workspace::Workspace,
+};
+use anyhow::anyhow;
+use move_mutator::{
+    compiler::IgnoreRules,
+    configuration::Configuration,
+    diff::{render_diff, DiffStyle},
+};
+use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
 use std::{
     collections::BTreeMap,
+    fs,
+    io::IsTerminal,
     path::{Path, PathBuf},
 };
-use tabled::{builder::Builder, settings::Style};
-
-/// This struct represents a report of the specification testing.
-/// It contains the list of entries, where each entry is a file and the number of mutants tested
-/// and killed in that file (in form of a `ReportEntry` structure).
-#[derive(Debug, Serialize)]
-pub struct Report {
-    /// The list of entries in the report.
-    files: BTreeMap<PathBuf, Vec<MutantStats>>,
+
+/// This function runs the specification testing, which is a combination of the
+/// mutator tool and the prover tool
+/// It takes the CLI options and constructs appropriate options for the
+/// Move Mutator tool and Move Prover tool. Then it mutates the code storing
+/// results in a temporary directory. Then it runs the prover on the mutated
+/// code and remember the results, using them to generate the report at the end.
+///
+/// # Arguments
+///
+/// * `options` - A `cli::Options` representing the options for the spec test.
+/// * `config` - A `BuildConfig` representing the build configuration.
+/// * `package_path` - A `PathBuf` representing the path to the package.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+///
+/// # Returns
+///
+/// * `anyhow::Result<()>` - The result of the spec test.
+pub fn run_spec_test(
+    options: &cli::CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+) -> anyhow::Result<()> {
+    // We need to initialize logger using try_init() as it might be already initialized in some other tool
+    // (e.g. spec-test). If we use init() instead, we will get an abort.
+    let _ = pretty_env_logger::try_init();
+
+    // Check if package is correctly structured.
+    let package_path = SourcePackageLayout::try_find_root(&package_path.canonicalize()?)?;
+
+    if options.watch {
+        return watch::run_watch(options, config, &package_path);
+    }
+
+    // Previously generated mutants are checked against their manifest before anything else is done.
+    let generation_root = match &options.use_generated_mutants {
+        Some(mutants_dir) => verify::check_generated_mutants(mutants_dir, &package_path)?,
+        None => None,
+    };
+
+    // Setup temporary directory structure.
+    let mut workspace = Workspace::new(options.keep_temp)?
+        .with_copy_ignore(IgnoreRules::new(&options.copy_ignore)?);
+
+    let result =
+        spec_test_in_workspace(options, config, &package_path, generation_root, &workspace);
+    // The files of the failed runs are kept for debugging.
+    if result.is_err() && !workspace.is_kept() {
+        workspace.persist();
+        error!(
+            "Specification testing failed, temporary files kept in: {}",
+            workspace.root().display()
+        );
+    }
+    result
 }
 
-impl Report {
-    /// Creates a new report.
-    pub fn new() -> Self {
-        Self {
-            files: BTreeMap::new(),
+// Internal function running the specification testing of the package in the given workspace (see
+// `run_spec_test`). `generation_root` is the package the previously generated mutants were generated from
+// (if they are used).
+fn spec_test_in_workspace(
+    options: &cli::CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+    generation_root: Option<PathBuf>,
+    workspace: &Workspace,
+) -> anyhow::Result<()> {
+    let package_path = package_path.to_path_buf();
+    info!("Running specification tester with the following options: {options:?} and package path: {package_path:?}");
+
+    let events = EventStream::new(options.event_stream);
+    events.emit(&Event::RunStarted {
+        package: &package_path,
+    });
+
+    // Always create and use benchmarks.
+    // Benchmarks call only time getting functions, so it's safe to use them in any case and
+    // they are not expensive to create (won't hit the performance).
+    let mut benchmarks = Benchmarks::new();
+    benchmarks.spec_test.start();
+    let budget = options.time_budget.map(TimeBudget::new);
+
+    // Interrupted runs stop the provers and write the partial report (the workspace is removed when
+    // the function returns).
+    let _interrupt_guard = interrupt::InterruptGuard::new();
+
+    let limits = ResourceLimits::new(options);
+    let mut prover_confs = cli::generate_prover_matrix(options)?;
+    // The versions of the prover tools are taken before Boogie is wrapped by the scripts below.
+    let mut metadata = RunMetadata::start(options, &package_path, &prover_confs[0]);
+    for (index, prover_conf) in prover_confs.iter_mut().enumerate() {
+        limits.apply(prover_conf, workspace.root(), index)?;
+        supervise(prover_conf, workspace.root(), index)?;
+    }
+    let backend = create_backend(options, config, &package_path, &limits)?;
+
+    let mut error_writer = termcolor::StandardStream::stderr(termcolor::ColorChoice::Auto);
+
+    // The original code must be provable with every prover configuration.
+    benchmarks.original.start();
+    for prover_conf in &prover_confs {
+        let result = prove_with_retries(
+            backend.as_ref(),
+            &package_path,
+            prover_conf,
+            options.prover_retries,
+            &mut error_writer,
+        );
+        if interrupt::is_interrupted() {
+            return Err(anyhow!(
+                "Specification testing interrupted before any mutant was verified"
+            ));
+        }
+
+        if let ProverResult::Failed(e, _)
+        | ProverResult::InfrastructureError(e)
+        | ProverResult::ResourceLimit(e) = result
+        {
+            let mut msg =
+                format!("Original code verification failed! Prover failed with error: {e}");
+            if options.verify_scope == cli::VerifyScope::All {
+                msg.push_str("\nIf the failure comes from a dependency, use the `--verify-scope targets` option to verify only the modules of the package.");
+            }
+            error!("{msg}");
+            return Err(anyhow!(msg));
         }
     }
+    benchmarks.original.stop();
+
+    let outdir_mutant = if let Some(mutant_path) = &options.use_generated_mutants {
+        mutant_path.clone()
+    } else {
+        benchmarks.mutator.start();
+        let outdir_mutant = run_mutator(options, config, &package_path, workspace)?;
+        benchmarks.mutator.stop();
+        outdir_mutant
+    };
 
-    /// Increments the number of mutants tested for the given path by 1.
-    /// If the path is not in the report, it adds it with the number of mutants tested set to 1.
-    pub fn increment_mutants_tested(&mut self, path: &Path, module_func: &str) {
-        self.increment_stat(path, module_func, |stat| stat.tested += 1);
+    let report = load_mutator_report(&outdir_mutant)?;
+    for elem in report.get_mutants() {
+        events.emit(&Event::mutant_generated(elem));
     }
+    for failure in report.get_failures() {
+        warn!(
+            "{} couldn't be mutated and is not tested: {}",
+            failure.file.display(),
+            failure.error
+        );
+    }
+
+    // The other backends verify the whole package, so the mutants aren't scoped for them.
+    let mutant_scope = if options.remote_command.is_some()
+        || options.isolate != cli::Isolation::None
+    {
+        if options.mutant_scope != cli::MutantScope::Package {
+            warn!("Mutant scope is applied only by the built-in prover, the whole package is verified by the other backends");
+        }
+        cli::MutantScope::Package
+    } else {
+        options.mutant_scope
+    };
+    let scoped_modules = ScopedModules::new(mutant_scope, config, &package_path)?;
+    // The package model is built only if the mutants are verified in batches.
+    let module_groups = match options.batch_mutants {
+        Some(_) => ModuleGroups::new(config, &package_path)?,
+        None => ModuleGroups::default(),
+    };
 
-    /// Increments the number of mutants killed for the given path by 1.
-    /// If the path is not in the report, it adds it with the number of mutants tested set to 0 and killed
-    /// count set to 1.
-    pub fn increment_mutants_killed(&mut self, path: &Path, module_func: &str) {
-        self.increment_stat(path, module_func, |stat| stat.killed += 1);
+    // Operator weights of the mutator configuration are used to compute the weighted scores (and its
+    // minimum score to decide if the run passes).
+    let mutator_configuration = cli::resolve_mutator_configuration(options, Some(&package_path))?;
+    let overrides = ProverOverrides::new(&mutator_configuration);
+    metadata.set_mutator_configuration(&mutator_configuration);
+
+    let coverage = match &options.coverage_map {
+        Some(coverage_map) => Some(Coverage::new(coverage_map, config, &package_path)?),
+        None => None,
+    };
+    if options.skip_uncovered && coverage.is_none() {
+        warn!("No coverage map given, mutants of the uncovered lines can't be skipped");
     }
 
-    /// Returns the number of mutants tested.
-    pub fn mutants_tested(&self) -> u32 {
-        self.total_count(|v| v.tested)
+    let suppressions = match &options.suppressions {
+        Some(path) => Suppressions::load(path)?,
+        None => Suppressions::default(),
+    };
+
+    // Proving part.
+    workspace.copy_package(&package_path, &workspace.base_dir())?;
+
+    let mut spec_report = report::Report::new();
+    for (operator, candidates) in report.get_operators() {
+        spec_report.set_operator_candidates(operator, candidates);
     }
+    let mut outcomes = BTreeMap::new();
+    let mut prover_times = BTreeMap::new();
+    let mut outdated = 0;
+    let mut unobservable = 0;
+    let mut not_covered = 0;
+    let mut pending = vec![];
 
-    /// Returns the number of mutants killed.
-    pub fn mutants_killed(&self) -> u32 {
-        self.total_count(|v| v.killed)
+    // Mutants handed off from another machine refer to the package root at the time of the generation.
+    let roots = [Some(package_path.as_path()), generation_root.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+    // Mutants of the lines covered by the tests are verified first, then the most informative ones (the sorts
+    // are stable).
+    let mut mutants = report.get_mutants().iter().collect::<Vec<_>>();
+    // With the time budget, all functions get their share of the verified mutants.
+    if budget.is_some() {
+        mutants = budget::interleave_groups(mutants, |elem| {
+            (
+                elem.qualified_module_name(),
+                elem.get_function_name().to_owned(),
+            )
+        });
+    }
+    if options.prioritize || budget.is_some() {
+        Priorities::new(config, &package_path, options.history.as_deref())?.sort(&mut mutants);
     }
+    if let Some(coverage) = &coverage {
+        mutants.sort_by_key(|elem| !coverage.is_covered(original_file(elem, &roots), elem));
+    }
+
+    // In the benchmark mode, only the sampled mutants are verified.
+    let sampled = sample_mutants(
+        mutants.len(),
+        options.bench_mutants.filter(|_| options.bench),
+    );
+    let mut verification_times = vec![];
+    let mut stopped_early = false;
+    // Mutants which weren't verified, because the time budget was exhausted.
+    let mut unverified = vec![];
+
+    let mut proving_benchmarks = vec![Benchmark::new(); mutants.len()];
+    benchmarks.prover.start();
+    'mutants: for (index, (elem, benchmark)) in mutants
+        .iter()
+        .copied()
+        .zip(proving_benchmarks.iter_mut())
+        .enumerate()
+    {
+        if interrupt::is_interrupted() {
+            break;
+        }
+        if fail_fast_reached(&spec_report, options.fail_fast) {
+            stopped_early = true;
+            break;
+        }
+        if budget.as_ref().is_some_and(TimeBudget::is_exhausted) {
+            unverified = mutants[index..]
+                .iter()
+                .zip(&sampled[index..])
+                .filter(|(elem, sampled)| **sampled && is_selected(options, &suppressions, elem))
+                .map(|(elem, _)| elem.get_id().to_owned())
+                .collect();
+            break;
+        }
+
+        info!("Proving mutant {index} out of {}", mutants.len());
+
+        let mutant_file = elem.mutant_path();
+
+        if !sampled[index] {
+            trace!("Skipping mutant {mutant_file:?} as it's not sampled for the benchmark");
+            continue;
+        }
+
+        if suppressions.is_suppressed(elem.get_id()) {
+            info!("Skipping mutant {mutant_file:?} as it's suppressed");
+            continue;
+        }
 
-    /// Add a diff for a not killed mutant.
-    pub fn add_mutants_alive_diff(&mut self, path: &Path, module_func: &str, diff: &str) {
-        let entry = self
-            .files
-            .entry(path.to_path_buf())
-            .or_insert(vec![MutantStats::new(module_func)]);
+        if let Some(shard) = &options.shard {
+            if !shard.contains(elem.get_id()) {
+                trace!("Skipping mutant {mutant_file:?} as it belongs to another shard");
+                continue;
+            }
+        }
+
+        let mut qname = elem.get_module_name().to_owned();
+        qname.push_str("::");
+        qname.push_str(elem.get_function_name());
+
+        if !options.include_functions.is_empty() && !options.include_functions.contains(&qname) {
+            trace!("Skipping mutant {mutant_file:?} as its function is not included");
+            continue;
+        }
 
-        if let Some(stat) = entry.iter_mut().find(|s| s.module_func == module_func) {
-            stat.mutants_alive_diffs.push(diff.to_owned());
+        let original_file = original_file(elem, &roots);
+        // Mutants spanning multiple files replace each of the changed files.
+        let changed_files = changed_files(elem, &roots);
+
+        // Previously generated mutants may be outdated (e.g. the specs have changed since), so the mutations
+        // are re-applied to the current versions of the original files.
+        let rebased_sources = if options.use_generated_mutants.is_some() {
+            let mut rebased_sources = vec![];
+            for (file, file_mutant, diff) in &changed_files {
+                let source = fs::read_to_string(package_path.join(file))?;
+                match verify::rebase_mutant(&source, diff, file_mutant) {
+                    Ok(rebased) => rebased_sources.push(rebased),
+                    Err(e) => {
+                        warn!("Skipping outdated mutant {mutant_file:?}: {e}");
+                        outdated += 1;
+                        continue 'mutants;
+                    },
+                }
+            }
+            Some(rebased_sources)
         } else {
-            let mut new_entry = MutantStats::new(module_func);
-            new_entry.mutants_alive_diffs.push(diff.to_owned());
-            entry.push(new_entry);
+            None
+        };
+
+        let verified_modules = scoped_modules.modules_for(&elem.qualified_function_name());
+        if verified_modules.is_some_and(<[String]>::is_empty) {
+            trace!("Mutant {mutant_file:?} can't be observed by any specification");
+            unobservable += 1;
+            outcomes.insert(elem.get_id().to_owned(), MutantOutcome::Unobservable);
+
+            let mut mutant = SurvivingMutant::new(elem);
+            mutant.hints.insert(0, SpecHint::MissingSpec);
+            record_unverified_survivor(
+                &mut spec_report,
+                original_file,
+                &qname,
+                mutant,
+                MutantStatus::Unobservable,
+                mutant_weight(&mutator_configuration, elem),
+            );
+            continue;
         }
-    }
 
-    /// Save the report to a JSON file.
-    /// The file is created if it does not exist, otherwise it is overwritten.
-    pub fn save_to_json_file(&self, path: &PathBuf) -> anyhow::Result<()> {
-        let file = std::fs::File::create(path)?;
-        Ok(serde_json::to_writer_pretty(file, self)?)
-    }
-
-    /// Prints the report to stdout in a table format.
-    pub fn print_table(&self) {
-        let mut builder = Builder::new();
-        builder.push_record(["Module", "Mutants tested", "Mutants killed", "Percentage"]);
-
-        for (path, stats) in &self.files {
-            for stat in stats {
-                let percentage = if stat.tested == 0 {
-                    0.0
-                } else {
-                    f64::from(stat.killed) / f64::from(stat.tested) * 100.0
-                };
-
-                builder.push_record([
-                    format!("{}::{}", path.to_string_lossy(), stat.module_func.clone()),
-                    stat.tested.to_string(),
-                    stat.killed.to_string(),
-                    format!("{percentage:.2}%"),
-                ]);
+        if options.skip_uncovered
+            && coverage
+                .as_ref()
+                .is_some_and(|coverage| !coverage.is_covered(original_file, elem))
+        {
+            trace!("Mutant {mutant_file:?} isn't covered by any test");
+            not_covered += 1;
+            outcomes.insert(elem.get_id().to_owned(), MutantOutcome::NotCovered);
+            record_unverified_survivor(
+                &mut spec_report,
+                original_file,
+                &qname,
+                SurvivingMutant::new(elem),
+                MutantStatus::Alive,
+                mutant_weight(&mutator_configuration, elem),
+            );
+            continue;
+        }
+
+        events.emit(&Event::MutantProving {
+            id: elem.get_id(),
+            index,
+            total: mutants.len(),
+        });
+
+        // Mutants spanning multiple files or using the prover overrides are verified on their own, outside
+        // of the batches.
+        if options.batch_mutants.is_some()
+            && changed_files.len() == 1
+            && !overrides.applies_to(elem)
+        {
+            let source = match rebased_sources.and_then(|sources| sources.into_iter().next()) {
+                Some(rebased) => rebased,
+                None => fs::read_to_string(mutant_file)?,
+            };
+            pending.push(PendingMutant {
+                elem,
+                original_file: original_file.to_path_buf(),
+                qname,
+                source,
+                modules: verified_modules.map(<[String]>::to_vec),
+            });
+            continue;
+        }
+
+        let outdir_prove = workspace.create_mutant_dir(index)?;
+        workspace.copy_package(&package_path, &outdir_prove)?;
+
+        if let Some(rebased_sources) = rebased_sources {
+            for ((file, _, _), rebased) in changed_files.iter().zip(rebased_sources) {
+                fs::write(outdir_prove.join(file), rebased)?;
+            }
+        } else {
+            for (file, mutant_file, _) in &changed_files {
+                trace!(
+                    "Copying mutant file {:?} to the package directory {:?}",
+                    mutant_file,
+                    outdir_prove.join(file)
+                );
+
+                if let Err(res) = fs::copy(mutant_file, outdir_prove.join(file)) {
+                    return Err(anyhow!(
+                        "Can't copy mutant file to the package directory: {res:?}"
+                    ));
+                }
             }
         }
 
-        let table = builder.build().with(Style::modern_rounded()).to_string();
+        move_mutator::compiler::rewrite_manifest_for_mutant(&package_path, &outdir_prove)?;
+
+        let mutant_prover_confs =
+            overrides.prover_confs(&prover_confs, elem, workspace.root(), index)?;
+        benchmark.start();
+        let verification = prove_modules(
+            backend.as_ref(),
+            &outdir_prove,
+            &mutant_prover_confs,
+            verified_modules,
+            options.kill_policy,
+            options.prover_retries,
+            &mut error_writer,
+        );
+        benchmark.stop();
+        verification_times.push(benchmark.elapsed);
+        prover_times.insert(elem.get_id().to_owned(), benchmark.elapsed);
 
-        println!("{table}\n\n");
+        // The outcome of the mutant verified during the interruption is unreliable.
+        if interrupt::is_interrupted() {
+            break;
+        }
+
+        outcomes.insert(elem.get_id().to_owned(), verification.outcome);
+        if verification.outcome == MutantOutcome::Killed {
+            events.emit(&Event::MutantKilled { id: elem.get_id() });
+        }
+        record_outcome(
+            &mut spec_report,
+            original_file,
+            &qname,
+            elem,
+            verification,
+            mutant_weight(&mutator_configuration, elem),
+        );
+
+        workspace.cleanup_mutant_dir(&outdir_prove)?;
     }
 
-    // Internal function to increment the chosen stat.
-    fn increment_stat<F>(&mut self, path: &Path, module_func: &str, mut increment: F)
-    where
-        F: FnMut(&mut MutantStats),
-    {
-        let entry = self
-            .files
-            .entry(path.to_path_buf())
-            .or_insert(vec![MutantStats::new(module_func)]);
+    let budget_exhausted = budget.as_ref().is_some_and(TimeBudget::is_exhausted);
+    if budget_exhausted && !interrupt::is_interrupted() && !stopped_early {
+        unverified.extend(pending.iter().map(|mutant| mutant.elem.get_id().to_owned()));
+    }
 
-        if let Some(stat) = entry.iter_mut().find(|s| s.module_func == module_func) {
-            increment(stat);
+    if let Some(size) = options
+        .batch_mutants
+        .filter(|_| !interrupt::is_interrupted() && !stopped_early && !budget_exhausted)
+    {
+        let prover = BatchProver {
+            backend: backend.as_ref(),
+            package_path: &package_path,
+            workspace,
+            prover_confs: &prover_confs,
+            policy: options.kill_policy,
+            retries: options.prover_retries,
+            module_groups: &module_groups,
+        };
+        let batch_outcomes = prover.prove_batches(&pending, size, &mut error_writer)?;
+        // Outcomes of the batches interrupted in the middle are unreliable, so none of them is recorded.
+        let batch_outcomes = if interrupt::is_interrupted() {
+            vec![]
         } else {
-            entry.push(MutantStats::new(module_func));
-            increment(entry.last_mut().unwrap());
+            batch_outcomes
+        };
+        for (mutant, verification) in pending.iter().zip(batch_outcomes) {
+            outcomes.insert(mutant.elem.get_id().to_owned(), verification.outcome);
+            if verification.outcome == MutantOutcome::Killed {
+                events.emit(&Event::MutantKilled {
+                    id: mutant.elem.get_id(),
+                });
+            }
+            record_outcome(
+                &mut spec_report,
+                &mutant.original_file,
+                &mutant.qname,
+                mutant.elem,
+                verification,
+                mutant_weight(&mutator_configuration, mutant.elem),
+            );
         }
     }
 
-    // Internal function to count the chosen stat.
-    fn total_count<F>(&self, mut count: F) -> u32
-    where
-        F: FnMut(&MutantStats) -> u32,
+    benchmarks.prover.stop();
+    benchmarks.prover_results = proving_benchmarks;
+
+    // The report follows the order of the mutator report, whatever the order of the completed verifications
+    // (e.g. the batches are verified after the other mutants).
+    let positions = report
+        .get_mutants()
+        .iter()
+        .enumerate()
+        .map(|(position, elem)| (elem.get_id().to_owned(), position))
+        .collect();
+    spec_report.sort_by_mutant_order(&positions);
+    let mutants_verified = outcomes
+        .values()
+        .filter(|outcome| {
+            !matches!(
+                outcome,
+                MutantOutcome::Unobservable | MutantOutcome::NotCovered
+            )
+        })
+        .count();
+
+    let interrupted = interrupt::is_interrupted();
+    if interrupted {
+        warn!("Specification testing interrupted, only the completed mutants are reported");
+        spec_report.set_interrupted();
+    }
+    if stopped_early {
+        warn!("Specification testing stopped early, only the mutants verified until then are reported");
+        spec_report.set_interrupted();
+    }
+    if !unverified.is_empty() {
+        warn!(
+            "Time budget exhausted, {} selected mutants weren't verified",
+            unverified.len()
+        );
+        spec_report.set_unverified_mutants(unverified.iter().cloned());
+    }
+    metadata.finish();
+    spec_report.set_metadata(metadata);
+
+    match options.output.as_deref() {
+        Some(output) if output == Path::new(cli::GITHUB_OUTPUT) => {
+            let workdir = std::env::current_dir()?;
+            for annotation in
+                annotations::github_annotations(&spec_report, &package_path, &workdir)?
+            {
+                println!("{annotation}");
+            }
+        },
+        Some(outfile) => spec_report.save_to_file(outfile)?,
+        None => {},
+    }
+
+    if let Some(outfile) = &options.markdown_report {
+        spec_report.save_to_markdown_file(outfile)?;
+    }
+
+    if let Some(outfile) = &options.csv_report {
+        csv_report::save_csv_report(
+            outfile,
+            &package_path,
+            report
+                .get_mutants()
+                .iter()
+                .map(|elem| (elem, original_file(elem, &roots))),
+            &outcomes,
+            &prover_times,
+        )?;
+    }
+
+    if let Some(archive) = &options.archive {
+        // The report is stored next to the mutants, so the archive is self-contained.
+        spec_report.save_to_json_file(&outdir_mutant.join(report::ARCHIVED_REPORT_FILE))?;
+        move_mutator::archive::create_archive(&outdir_mutant, archive)?;
+        println!("Mutants and reports archived to {}", archive.display());
+    }
+
+    // Partial results of the interrupted runs (and of the benchmarks) would distort the score trend.
+    if let Some(history) = options
+        .history
+        .as_ref()
+        .filter(|_| !interrupted && !stopped_early && !options.bench)
     {
-        self.files
-            .values()
-            .map(|entry| entry.iter().map(&mut count).sum::<u32>())
-            .sum()
+        let record = HistoryRecord::new(&package_path, &spec_report, outcomes);
+        history::append_record(history, &record)?;
+    }
+
+    println!();
+    if outdated > 0 {
+        println!("Outdated mutants skipped: {outdated}\n");
+    }
+    if unobservable > 0 {
+        println!("Unobservable mutants (not verified, as no specification can observe them): {unobservable}\n");
+    }
+    if not_covered > 0 {
+        println!("Not covered mutants (not verified, as no test executes the mutated lines): {not_covered}\n");
+    }
+    if !unverified.is_empty() {
+        println!(
+            "Mutants not verified within the time budget: {}\n",
+            unverified.len()
+        );
+    }
+    spec_report.print_table();
+    print_reduced_surviving_mutants(&spec_report, options);
+
+    if workspace.is_kept() {
+        println!("Temporary files kept in: {}\n", workspace.root().display());
+    }
+
+    println!("{}\n", spec_report.summary_table(options.no_color));
+    println!("{}", spec_report.status_line());
+    events.emit(&Event::run_finished(&spec_report.total_summary()));
+
+    benchmarks.spec_test.stop();
+    benchmarks.display();
+
+    if options.bench {
+        let performance = PerformanceReport::new(
+            &benchmarks,
+            options.use_generated_mutants.is_none(),
+            report.get_mutants().len(),
+            mutants_verified,
+            &verification_times,
+        );
+        println!("{}", serde_json::to_string(&performance)?);
     }
 
-    /// Returns the list of entries in the report.
-    #[cfg(test)]
-    pub fn entries(&self) -> &BTreeMap<PathBuf, Vec<MutantStats>> {
-        &self.files
+    if interrupted {
+        return Err(anyhow!(
+            "Specification testing interrupted, the report contains only the completed mutants"
+        ));
     }
+
+    // Batches are verified as a whole, so the limit may be reached without stopping early.
+    if fail_fast_reached(&spec_report, options.fail_fast) {
+        return Err(anyhow!(
+            "Found {} surviving mutants, which reaches the fail-fast limit",
+            spec_report.total_summary().survived
+        ));
+    }
+
+    if let Some(min_score) = mutator_configuration
+        .mutation
+        .as_ref()
+        .and_then(|mutation| mutation.min_score)
+    {
+        let score = spec_report.total_summary().score();
+        if score < min_score {
+            return Err(anyhow!(
+                "Mutation score {score:.2}% is below the minimum score {min_score:.2}%"
+            ));
+        }
+    }
+
+    Ok(())
 }
 
-/// This struct represents an entry in the report.
-/// It contains the number of mutants tested and killed.
-#[derive(Default, Debug, Serialize)]
-pub struct MutantStats {
-    /// Module::function where mutant resides.
-    pub module_func: String,
-    /// The number of mutants tested.
-    pub tested: u32,
-    /// The number of mutants killed.
-    pub killed: u32,
-    /// The list of not killed mutants.
-    pub mutants_alive_diffs: Vec<String>,
+/// This function checks if the mutant passes the filters of the run (benchmark sampling aside): it's not
+/// suppressed, it belongs to the verified shard and its function is included.
+fn is_selected(
+    options: &cli::CLIOptions,
+    suppressions: &Suppressions,
+    elem: &move_mutator::report::MutationReport,
+) -> bool {
+    let qname = format!("{}::{}", elem.get_module_name(), elem.get_function_name());
+    !suppressions.is_suppressed(elem.get_id())
+        && options
+            .shard
+            .as_ref()
+            .map_or(true, |shard| shard.contains(elem.get_id()))
+        && (options.include_functions.is_empty() || options.include_functions.contains(&qname))
 }
 
-impl MutantStats {
-    /// Creates a new entry with the given number of mutants tested and killed.
-    pub fn new(module_func: &str) -> Self {
-        Self {
-            module_func: module_func.to_string(),
-            tested: 0,
-            killed: 0,
-            mutants_alive_diffs: vec![],
-        }
+/// This function checks if the fail-fast limit is set and the report contains at least that many surviving
+/// mutants.
+fn fail_fast_reached(spec_report: &report::Report, limit: Option<usize>) -> bool {
+    limit.is_some_and(|limit| spec_report.total_summary().survived as usize >= limit)
+}
+
+/// This function records the outcome of the verified mutant (and its weight, if it's not the default one)
+/// in the spec-test report. The verification errors which killed the mutant are recorded as well.
+fn record_outcome(
+    spec_report: &mut report::Report,
+    original_file: &Path,
+    qname: &str,
+    elem: &move_mutator::report::MutationReport,
+    verification: Verification,
+    weight: Option<f64>,
+) {
+    let Verification {
+        outcome,
+        diagnostics,
+    } = verification;
+    let mutant_file = elem.mutant_path();
+
+    let tested = !matches!(
+        outcome,
+        MutantOutcome::Undecided | MutantOutcome::Unobservable | MutantOutcome::NotCovered
+    );
+    if let Some(weight) = weight.filter(|_| tested) {
+        spec_report.set_mutant_weight(original_file, qname, elem.get_id(), weight);
+    }
+    let operators = elem.operator_names();
+    if tested {
+        spec_report.set_mutant_operators(
+            original_file,
+            qname,
+            elem.get_id(),
+            operators.iter().cloned().collect(),
+        );
+    }
+    let operators = operators.iter().map(String::as_str);
+
+    match outcome {
+        MutantOutcome::Killed => {
+            trace!("Mutant killed!");
+            spec_report.increment_mutants_tested(original_file, qname);
+            spec_report.increment_mutants_killed(original_file, qname);
+            spec_report.set_mutant_diagnostics(original_file, qname, elem.get_id(), diagnostics);
+            spec_report.set_mutant_status(
+                original_file,
+                qname,
+                elem.get_id(),
+                MutantStatus::Killed,
+            );
+            spec_report.record_operator_status(operators, MutantStatus::Killed);
+        },
+        MutantOutcome::Alive => {
+            trace!("Mutant hasn't been killed!");
+            spec_report.increment_mutants_tested(original_file, qname);
+            spec_report.add_surviving_mutant(original_file, qname, SurvivingMutant::new(elem));
+            spec_report.set_mutant_status(original_file, qname, elem.get_id(), MutantStatus::Alive);
+            spec_report.record_operator_status(operators, MutantStatus::Alive);
+        },
+        MutantOutcome::Inconclusive => {
+            trace!("Prover result for the mutant is inconclusive!");
+            spec_report.increment_mutants_tested(original_file, qname);
+            spec_report.increment_mutants_inconclusive(original_file, qname);
+            spec_report.set_mutant_status(
+                original_file,
+                qname,
+                elem.get_id(),
+                MutantStatus::Inconclusive,
+            );
+            spec_report.record_operator_status(operators, MutantStatus::Inconclusive);
+        },
+        MutantOutcome::ResourceLimit => {
+            warn!("Mutant {mutant_file:?} exceeded the prover resource limits");
+            spec_report.increment_mutants_tested(original_file, qname);
+            spec_report.increment_mutants_resource_limit(original_file, qname);
+            spec_report.set_mutant_status(
+                original_file,
+                qname,
+                elem.get_id(),
+                MutantStatus::ResourceLimit,
+            );
+            spec_report.record_operator_status(operators, MutantStatus::ResourceLimit);
+        },
+        MutantOutcome::Undecided => {
+            warn!("Mutant {mutant_file:?} skipped! Prover failed because of infrastructure errors");
+        },
+        // Unobservable and not covered mutants are reported before running the prover.
+        MutantOutcome::Unobservable | MutantOutcome::NotCovered => {},
+    }
+}
+
+/// This function records the mutant classified as surviving without running the prover in the spec-test
+/// report. Mutants which no specification can observe (the `Unobservable` status) aren't counted as tested,
+/// the others (e.g. not covered by any test) are counted as alive.
+fn record_unverified_survivor(
+    spec_report: &mut report::Report,
+    original_file: &Path,
+    qname: &str,
+    mutant: SurvivingMutant,
+    status: MutantStatus,
+    weight: Option<f64>,
+) {
+    let id = mutant.id.clone();
+    let operators = mutant.operators.clone();
+    if status == MutantStatus::Unobservable {
+        spec_report.increment_mutants_unobservable(original_file, qname);
+    } else {
+        spec_report.increment_mutants_tested(original_file, qname);
+    }
+    spec_report.add_surviving_mutant(original_file, qname, mutant);
+    spec_report.set_mutant_status(original_file, qname, &id, status);
+    spec_report.record_operator_status(operators.iter().map(String::as_str), status);
+    spec_report.set_mutant_operators(original_file, qname, &id, operators);
+    if let Some(weight) = weight {
+        spec_report.set_mutant_weight(original_file, qname, &id, weight);
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::PathBuf;
-
-    #[test]
-    fn report_starts_empty() {
-        let report = Report::new();
-        assert_eq!(report.entries().len(), 0);
-    }
-
-    #[test]
-    fn increment_mutants_tested_adds_new_module_if_not_present() {
-        let mut report = Report::new();
-        let path = PathBuf::from("path/to/file");
-        let module_name = "new_module";
-        report.increment_mutants_tested(&path, module_name);
-        let entry = report.entries().get(&path).unwrap();
-        assert!(entry.iter().any(|s| s.module_func == module_name));
-    }
-
-    #[test]
-    fn increment_mutants_killed_adds_new_module_if_not_present() {
-        let mut report = Report::new();
-        let path = PathBuf::from("path/to/file");
-        let module_name = "new_module";
-        report.increment_mutants_killed(&path, module_name);
-        let entry = report.entries().get(&path).unwrap();
-        assert!(entry.iter().any(|s| s.module_func == module_name));
-    }
-
-    #[test]
-    fn increment_mutants_tested_increases_tested_count_for_existing_module() {
-        let mut report = Report::new();
-        let path = PathBuf::from("path/to/file");
-        let module_name = "existing_module";
-        report.increment_mutants_tested(&path, module_name);
-        report.increment_mutants_tested(&path, module_name);
-        let entry = report.entries().get(&path).unwrap();
-        let stat = entry.iter().find(|s| s.module_func == module_name).unwrap();
-        assert_eq!(stat.tested, 2);
-        assert_eq!(stat.killed, 0);
-    }
-
-    #[test]
-    fn increment_mutants_killed_increases_killed_count_for_existing_module() {
-        let mut report = Report::new();
-        let path = PathBuf::from("path/to/file");
-        let module_name = "existing_module";
-        report.increment_mutants_killed(&path, module_name);
-        report.increment_mutants_killed(&path, module_name);
-        let entry = report.entries().get(&path).unwrap();
-        let stat = entry.iter().find(|s| s.module_func == module_name).unwrap();
-        assert_eq!(stat.killed, 2);
-        assert_eq!(stat.tested, 0);
-    }
-
-    #[test]
-    fn mutants_tested_returns_correct_total_tested_count() {
-        let mut report = Report::new();
-        let path1 = PathBuf::from("path/to/file1");
-        let path2 = PathBuf::from("path/to/file2");
-        let module_name = "module";
-        report.increment_mutants_tested(&path1, module_name);
-        report.increment_mutants_tested(&path2, module_name);
-        assert_eq!(report.mutants_tested(), 2);
-        assert_eq!(report.mutants_killed(), 0);
-    }
-
-    #[test]
-    fn mutants_killed_returns_correct_total_killed_count() {
-        let mut report = Report::new();
-        let path1 = PathBuf::from("path/to/file1");
-        let path2 = PathBuf::from("path/to/file2");
-        let module_name = "module";
-        report.increment_mutants_killed(&path1, module_name);
-        report.increment_mutants_killed(&path2, module_name);
-        assert_eq!(report.mutants_killed(), 2);
-        assert_eq!(report.mutants_tested(), 0);
-    }
-
-    #[test]
-    fn add_mutants_alive_diff_adds_new_module_if_not_present() {
-        let mut report = Report::new();
-        let path = PathBuf::from("path/to/file");
-        let module_name = "new_module";
-        let diff = "diff";
-        report.add_mutants_alive_diff(&path, module_name, diff);
-        let entry = report.entries().get(&path).unwrap();
-        assert!(entry
-            .iter()
-            .any(|s| s.module_func == module_name
-                && s.mutants_alive_diffs.contains(&diff.to_owned())));
-    }
-
-    #[test]
-    fn add_mutants_alive_diff_adds_diff_to_existing_module() {
-        let mut report = Report::new();
-        let path = PathBuf::from("path/to/file");
-        let module_name = "existing_module";
-        let diff1 = "diff1";
-        let diff2 = "diff2";
-        report.add_mutants_alive_diff(&path, module_name, diff1);
-        report.add_mutants_alive_diff(&path, module_name, diff2);
-        let entry = report.entries().get(&path).unwrap();
-        let stat = entry.iter().find(|s| s.module_func == module_name).unwrap();
-        assert_eq!(stat.mutants_alive_diffs, vec![diff1, diff2]);
+/// This function returns the path of the file mutated by the mutant, relative to the package directory.
+/// The prefix is stripped if the path starts with any of the given package roots (otherwise the path is
+/// taken as it is).
+fn original_file<'a>(elem: &'a move_mutator::report::MutationReport, roots: &[&Path]) -> &'a Path {
+    package_relative(elem.original_file_path(), roots)
+}
+
+/// This function returns the files changed by the mutant (a single file, unless the mutant spans multiple
+/// files) as the paths of the original files relative to the package directory (see `original_file`),
+/// the paths of the mutated files and the diffs.
+fn changed_files<'a>(
+    elem: &'a move_mutator::report::MutationReport,
+    roots: &[&Path],
+) -> Vec<(&'a Path, &'a Path, &'a str)> {
+    if elem.get_changed_files().is_empty() {
+        return vec![(
+            original_file(elem, roots),
+            elem.mutant_path().as_path(),
+            elem.get_diff(),
+        )];
     }
+
+    elem.get_changed_files()
+        .iter()
+        .map(|file| {
+            (
+                package_relative(file.original_file_path(), roots),
+                file.mutant_path().as_path(),
+                file.get_diff(),
+            )
+        })
+        .collect()
+}
+
+// Internal function stripping the first matching package root from the path.
+fn package_relative<'a>(path: &'a Path, roots: &[&Path]) -> &'a Path {
+    roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(path)
+}
+
+/// This function returns the weight of the mutant (the highest weight of its operators), unless it's the
+/// default weight.
+fn mutant_weight(
+    configuration: &Configuration,
+    elem: &move_mutator::report::MutationReport,
+) -> Option<f64> {
+    let weight = elem
+        .get_mutations()
+        .iter()
+        .map(|mutation| configuration.operator_weight(mutation.get_operator_name()))
+        .reduce(f64::max)?;
+    ((weight - 1.0).abs() > f64::EPSILON).then_some(weight)
+}
+
+/// This function prints the reduced set of surviving mutants, so similar mutants in the same location don't
+/// hide the other ones. If requested, the diff of each printed mutant is shown as well. The hotspots (functions
+/// with the most surviving mutants) are printed at the end.
+fn print_reduced_surviving_mutants(report: &report::Report, options: &cli::CLIOptions) {
+    let groups = report.reduced_surviving_mutants();
+    if groups.is_empty() {
+        return;
+    }
+
+    let style = DiffStyle {
+        word_level: options.word_diff,
+        color: !options.no_color
+            && match options.diff_color {
+                cli::DiffColor::Auto => std::io::stdout().is_terminal(),
+                cli::DiffColor::Always => true,
+                cli::DiffColor::Never => false,
+            },
+    };
+
+    println!(
+        "Surviving mutants after collapsing similar ones: {}",
+        groups.len()
+    );
+    for group in groups {
+        let mutant = group.representative;
+        print!(
+            "  {} {}::{}",
+            mutant.id,
+            group.path.display(),
+            group.module_func
+        );
+        if !mutant.operators.is_empty() {
+            print!(" ({})", mutant.operators.join(", "));
+        }
+        if !group.similar.is_empty() {
+            print!(" and {} similar", group.similar.len());
+        }
+        println!();
+        if options.show_diffs {
+            for line in render_diff(&mutant.diff, style).lines() {
+                println!("    {line}");
+            }
+        }
+    }
+
+    println!("\nSurviving mutant hotspots:");
+    for cluster in report.clusters() {
+        println!("  {}: {}", cluster.path.display(), cluster.summary());
+    }
+    println!();
+}
+
+/// This function runs the Move Mutator tool.
+fn run_mutator(
+    options: &cli::CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+    workspace: &Workspace,
+) -> anyhow::Result<PathBuf> {
+    debug!("Running the move mutator tool");
+    let mut mutator_conf = cli::create_mutator_options(options);
+
+    let outdir_mutant = if let Some(path) = cli::check_mutator_output_path(&mutator_conf) {
+        path
+    } else {
+        mutator_conf.out_mutant_dir = Some(workspace.mutants_dir());
+        mutator_conf.out_mutant_dir.clone().unwrap()
+    };
+
+    fs::create_dir_all(&outdir_mutant)?;
+    move_mutator::run_move_mutator(mutator_conf, config, package_path)?;
+
+    Ok(outdir_mutant)
+}
+
+/// This function loads the mutator report stored in the directory of the generated mutants (in any of the
+/// structured formats, the JSON report is preferred).
+fn load_mutator_report(outdir_mutant: &Path) -> anyhow::Result<move_mutator::report::Report> {
+    let report_path = move_mutator::report::Report::find_in_dir(outdir_mutant)
+        .ok_or_else(|| anyhow::anyhow!("No mutator report found in {}", outdir_mutant.display()))?;
+    Ok(move_mutator::report::Report::load_from_file(&report_path)?)
 }
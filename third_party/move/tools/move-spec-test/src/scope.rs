@@ -0,0 +1,594 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::MutantScope;
+use move_model::{
+    metadata::{CompilerVersion, LanguageVersion},
+    model::{FunId, FunctionEnv, GlobalEnv, ModuleEnv, QualifiedId, VerificationScope},
+};
+use move_package::{BuildConfig, ModelConfig};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+};
+
+/// Modules verified by the prover for the mutants of each function of the package.
+#[derive(Debug, Default)]
+pub(crate) struct ScopedModules {
+    /// The modules verified for the mutants of the given function (keyed by `address::Module::function`).
+    modules: BTreeMap<String, Vec<String>>,
+}
+
+impl ScopedModules {
+    /// Creates the scoped modules for the package.
+    ///
+    /// The package model is built only if the scope is narrower than the whole package.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package model can't be built.
+    pub(crate) fn new(
+        scope: MutantScope,
+        config: &BuildConfig,
+        package_path: &Path,
+    ) -> anyhow::Result<Self> {
+        if scope == MutantScope::Package {
+            return Ok(Self::default());
+        }
+
+        let env = package_model(config, package_path)?;
+
+        let modules = if scope == MutantScope::Observers {
+            let graph = ObserverGraph::from_env(&env);
+            graph
+                .functions
+                .keys()
+                .filter_map(|name| Some((name.clone(), graph.observing_modules(name)?)))
+                .collect()
+        } else {
+            let module_scopes = module_scopes(scope, &module_dependents(&env));
+            target_modules(&env)
+                .iter()
+                .flat_map(|module| {
+                    let module_scopes = &module_scopes;
+                    module.get_functions().map(move |fun| {
+                        let mut verified = module_scopes[&module_name(module)].clone();
+                        // Inline functions are verified as a part of the functions they're expanded into.
+                        for expansion_module in expansion_modules(&fun) {
+                            for name in module_scopes.get(&expansion_module).into_iter().flatten() {
+                                if !verified.contains(name) {
+                                    verified.push(name.clone());
+                                }
+                            }
+                        }
+                        (fun.get_full_name_with_address(), verified)
+                    })
+                })
+                .collect()
+        };
+
+        Ok(Self { modules })
+    }
+
+    /// Returns the modules verified for the mutants of the given function (e.g. `0x42::Sum::sum`).
+    /// `None` means the whole package is verified (e.g. for the scripts or if the scope isn't narrowed).
+    /// An empty list means no specification can observe the mutants of the function.
+    pub(crate) fn modules_for(&self, function_name: &str) -> Option<&[String]> {
+        self.modules.get(function_name).map(Vec::as_slice)
+    }
+}
+
+/// Groups of the modules of the package connected by the calls or the specifications, so the changes of a
+/// module can be observed by the other modules of its group. Mutants of the modules of different groups can
+/// be verified together without affecting each other.
+#[derive(Debug, Default)]
+pub(crate) struct ModuleGroups {
+    /// The group of each module (keyed by the module name).
+    groups: BTreeMap<String, usize>,
+}
+
+impl ModuleGroups {
+    /// Creates the module groups from the call and spec dependency graph of the package.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package model can't be built.
+    pub(crate) fn new(config: &BuildConfig, package_path: &Path) -> anyhow::Result<Self> {
+        let env = package_model(config, package_path)?;
+        Ok(ObserverGraph::from_env(&env).module_groups())
+    }
+
+    /// Returns the group of the given module. `None` means the module may interact with any other module
+    /// (e.g. its functions are used by the specifications of the whole package, or it's a script).
+    pub(crate) fn group_of(&self, module_name: &str) -> Option<usize> {
+        self.groups.get(module_name).copied()
+    }
+}
+
+/// Builds the model of the package (used to analyze the functions of the package, not for the verification).
+///
+/// # Errors
+///
+/// Returns an error if the package model can't be built.
+pub(crate) fn package_model(
+    config: &BuildConfig,
+    package_path: &Path,
+) -> anyhow::Result<GlobalEnv> {
+    config
+        .clone()
+        .move_model_for_package(package_path, ModelConfig {
+            all_files_as_targets: false,
+            target_filter: None,
+            compiler_version: config
+                .compiler_config
+                .compiler_version
+                .unwrap_or(CompilerVersion::V2_0),
+            language_version: config
+                .compiler_config
+                .language_version
+                .unwrap_or(LanguageVersion::V1),
+        })
+}
+
+/// Returns the entry functions of the package (keyed by `address::Module::function`).
+pub(crate) fn entry_functions(env: &GlobalEnv) -> BTreeSet<String> {
+    target_modules(env)
+        .iter()
+        .flat_map(|module| {
+            module
+                .get_functions()
+                .filter(|fun| fun.is_entry())
+                .map(|fun| fun.get_full_name_with_address())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Returns the prover options verifying only the given modules.
+pub(crate) fn scoped_prover_confs(
+    prover_confs: &[move_prover::cli::Options],
+    module_names: &[String],
+) -> Vec<move_prover::cli::Options> {
+    prover_confs
+        .iter()
+        .map(|conf| {
+            let mut conf = conf.clone();
+            conf.prover.verify_scope = VerificationScope::OnlyModules(module_names.to_vec());
+            conf
+        })
+        .collect()
+}
+
+/// A function of the package in the call and spec dependency graph.
+#[derive(Debug, Default, Clone)]
+struct ObservedFunction {
+    /// Name of the module declaring the function.
+    module: String,
+    /// Whether the prover verifies the function against a specification (its own conditions or the
+    /// invariants of the package). Inline functions are never verified on their own.
+    verified: bool,
+    /// Whether the callers of the function see only its specification instead of its code (never true for
+    /// the inline functions, which are expanded into their callers).
+    opaque: bool,
+    /// Functions calling the function in the code.
+    callers: BTreeSet<String>,
+    /// Functions calling the function in their specifications.
+    spec_callers: BTreeSet<String>,
+    /// Whether the function is used by the specifications which can't be tracked to a single function
+    /// (e.g. spec functions or invariants), so the whole package may observe it.
+    observed_by_package: bool,
+}
+
+/// Call and spec dependency graph of the functions of the package, keyed by `address::Module::function`.
+#[derive(Debug, Default)]
+struct ObserverGraph {
+    functions: BTreeMap<String, ObservedFunction>,
+}
+
+impl ObserverGraph {
+    // Internal function building the graph from the functions of the target modules.
+    fn from_env(env: &GlobalEnv) -> Self {
+        let targets = target_modules(env);
+        let name = |qid: QualifiedId<FunId>| env.get_function(qid).get_full_name_with_address();
+
+        // Global and struct invariants are checked for every function modifying the state.
+        let has_invariants = targets.iter().any(|module| {
+            !env.get_global_invariants_for_module(module.get_id())
+                .is_empty()
+                || module
+                    .get_structs()
+                    .any(|struct_env| struct_env.has_conditions())
+        });
+
+        let mut graph = Self::default();
+        for module in &targets {
+            // Move functions used in spec functions and module specs.
+            let mut observed_by_package = module
+                .get_spec()
+                .called_funs_with_callsites()
+                .into_keys()
+                .collect::<BTreeSet<_>>();
+            for (_, decl) in module.get_spec_funs() {
+                if let Some(body) = &decl.body {
+                    observed_by_package.extend(body.called_funs());
+                }
+            }
+            for struct_env in module.get_structs() {
+                observed_by_package.extend(
+                    struct_env
+                        .get_spec()
+                        .called_funs_with_callsites()
+                        .into_keys(),
+                );
+            }
+            for qid in observed_by_package {
+                graph.entry(name(qid)).observed_by_package = true;
+            }
+
+            for fun in module.get_functions() {
+                let fun_name = fun.get_full_name_with_address();
+                for callee in fun.get_spec().called_funs_with_callsites().into_keys() {
+                    graph
+                        .entry(name(callee))
+                        .spec_callers
+                        .insert(fun_name.clone());
+                }
+
+                let calling = fun.get_calling_functions();
+                let entry = graph.entry(fun_name);
+                entry.module = module_name(module);
+                entry.verified = !fun.is_inline()
+                    && !fun.is_explicitly_not_verified(&VerificationScope::All)
+                    && (fun.get_spec().has_conditions() || has_invariants);
+                entry.opaque = fun.is_opaque() && !fun.is_inline();
+                match calling {
+                    Some(callers) => entry.callers.extend(callers.into_iter().map(name)),
+                    // Unknown callers can't be tracked - the whole package is verified instead.
+                    None => entry.observed_by_package = true,
+                }
+            }
+        }
+
+        graph
+    }
+
+    // Internal function returning the graph node of the function, creating it if necessary.
+    fn entry(&mut self, name: String) -> &mut ObservedFunction {
+        self.functions.entry(name).or_default()
+    }
+
+    /// Returns the modules declaring the functions whose verification can observe the changes of the
+    /// given function: the function itself, its callers (transitively, unless the change is hidden
+    /// by an opaque function) and the functions using it in their specifications.
+    ///
+    /// Returns `None` if the observers can't be determined and the whole package must be verified.
+    fn observing_modules(&self, function: &str) -> Option<Vec<String>> {
+        let mut modules = BTreeSet::new();
+        let mut visited = BTreeSet::from([function.to_owned()]);
+        let mut queue = vec![function.to_owned()];
+
+        while let Some(current) = queue.pop() {
+            // Callers outside the package (e.g. scripts) are not verified.
+            let Some(node) = self.functions.get(&current) else {
+                continue;
+            };
+            if node.observed_by_package {
+                return None;
+            }
+            if node.verified {
+                modules.insert(node.module.clone());
+            }
+
+            let callers = if node.opaque {
+                None
+            } else {
+                Some(&node.callers)
+            };
+            for next in callers.into_iter().flatten().chain(&node.spec_callers) {
+                if visited.insert(next.clone()) {
+                    queue.push(next.clone());
+                }
+            }
+        }
+
+        Some(modules.into_iter().collect())
+    }
+
+    // Internal function grouping the modules with a dependency path between them (the calls and the uses in
+    // the specifications, in either direction). Modules with the functions observed by the whole package
+    // are left out of the groups.
+    fn module_groups(&self) -> ModuleGroups {
+        let mut neighbours = BTreeMap::<&str, BTreeSet<&str>>::new();
+        let mut observed_by_package = BTreeSet::new();
+        for node in self.functions.values() {
+            // Functions outside the target modules (e.g. of the dependencies) have no module.
+            if node.module.is_empty() {
+                continue;
+            }
+            if node.observed_by_package {
+                observed_by_package.insert(node.module.as_str());
+            }
+            neighbours.entry(&node.module).or_default();
+            for caller in node.callers.iter().chain(&node.spec_callers) {
+                let Some(caller) = self
+                    .functions
+                    .get(caller)
+                    .filter(|caller| !caller.module.is_empty())
+                else {
+                    continue;
+                };
+                neighbours
+                    .entry(&node.module)
+                    .or_default()
+                    .insert(&caller.module);
+                neighbours
+                    .entry(&caller.module)
+                    .or_default()
+                    .insert(&node.module);
+            }
+        }
+
+        let mut groups = BTreeMap::new();
+        for (group, start) in neighbours.keys().enumerate() {
+            if groups.contains_key(*start) {
+                continue;
+            }
+            groups.insert(*start, group);
+            let mut queue = vec![*start];
+            while let Some(current) = queue.pop() {
+                for next in &neighbours[current] {
+                    if !groups.contains_key(next) {
+                        groups.insert(*next, group);
+                        queue.push(*next);
+                    }
+                }
+            }
+        }
+
+        ModuleGroups {
+            groups: groups
+                .into_iter()
+                .filter(|(module, _)| !observed_by_package.contains(module))
+                .map(|(module, group)| (module.to_owned(), group))
+                .collect(),
+        }
+    }
+}
+
+// Internal function returning the modules of the functions the inline function is expanded into
+// (transitively, through the other inline functions). The result is empty for the other functions.
+fn expansion_modules(fun: &FunctionEnv) -> BTreeSet<String> {
+    let mut modules = BTreeSet::new();
+    let mut visited = BTreeSet::from([fun.get_qualified_id()]);
+    let mut queue = vec![fun.get_qualified_id()];
+    while let Some(current) = queue.pop() {
+        let current = fun.module_env.env.get_function(current);
+        if !current.is_inline() {
+            continue;
+        }
+        for caller in current.get_calling_functions().into_iter().flatten() {
+            if visited.insert(caller) {
+                let caller_env = fun.module_env.env.get_function(caller);
+                modules.insert(module_name(&caller_env.module_env));
+                queue.push(caller);
+            }
+        }
+    }
+    modules
+}
+
+// Internal function returning the target modules of the package (without scripts).
+fn target_modules(env: &GlobalEnv) -> Vec<ModuleEnv<'_>> {
+    env.get_modules()
+        .filter(|module| module.is_target() && !module.is_script_module())
+        .collect()
+}
+
+// Internal function returning the module name qualified with the address, in the format used by the
+// mutator report (`MutationReport::qualified_module_name`), so the modules with the same name are told apart.
+fn module_name(module: &ModuleEnv) -> String {
+    module.get_full_name_str()
+}
+
+// Internal function selecting the verified modules for each module from the direct dependents of modules.
+fn module_scopes(
+    scope: MutantScope,
+    dependents: &BTreeMap<String, BTreeSet<String>>,
+) -> BTreeMap<String, Vec<String>> {
+    dependents
+        .keys()
+        .map(|name| {
+            let mut verified = vec![name.clone()];
+            if scope == MutantScope::Dependents {
+                // The dependents are collected transitively, as the changed behaviour can reach them
+                // through the non-opaque functions of the intermediate modules.
+                let mut visited = BTreeSet::from([name.clone()]);
+                let mut queue = vec![name.clone()];
+                while let Some(current) = queue.pop() {
+                    for dependent in dependents.get(&current).into_iter().flatten() {
+                        if visited.insert(dependent.clone()) {
+                            verified.push(dependent.clone());
+                            queue.push(dependent.clone());
+                        }
+                    }
+                }
+            }
+            (name.clone(), verified)
+        })
+        .collect()
+}
+
+// Internal function collecting the target modules of the package using (in the code or the specs)
+// each target module.
+fn module_dependents(env: &GlobalEnv) -> BTreeMap<String, BTreeSet<String>> {
+    let targets = target_modules(env);
+
+    targets
+        .iter()
+        .map(|module| {
+            let users = module.get_using_modules(true);
+            let dependents = targets
+                .iter()
+                .filter(|other| {
+                    other.get_id() != module.get_id() && users.contains(&other.get_id())
+                })
+                .map(module_name)
+                .collect();
+            (module_name(module), dependents)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependents() -> BTreeMap<String, BTreeSet<String>> {
+        BTreeMap::from([
+            ("Base".to_owned(), BTreeSet::from(["Middle".to_owned()])),
+            ("Middle".to_owned(), BTreeSet::from(["Top".to_owned()])),
+            ("Top".to_owned(), BTreeSet::new()),
+        ])
+    }
+
+    fn function(module: &str, verified: bool, callers: &[&str]) -> ObservedFunction {
+        ObservedFunction {
+            module: module.to_owned(),
+            verified,
+            callers: callers.iter().map(|c| (*c).to_owned()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn module_scope_verifies_only_mutated_module() {
+        let scopes = module_scopes(MutantScope::Module, &dependents());
+        assert_eq!(scopes["Base"], vec!["Base".to_owned()]);
+        assert!(ScopedModules::default()
+            .modules_for("script::main")
+            .is_none());
+    }
+
+    #[test]
+    fn dependents_scope_verifies_transitive_dependents() {
+        let scopes = module_scopes(MutantScope::Dependents, &dependents());
+        assert_eq!(scopes["Base"], vec![
+            "Base".to_owned(),
+            "Middle".to_owned(),
+            "Top".to_owned()
+        ]);
+        assert_eq!(scopes["Top"], vec!["Top".to_owned()]);
+    }
+
+    #[test]
+    fn package_scope_verifies_whole_package() {
+        let scoped = ScopedModules::new(
+            MutantScope::Package,
+            &BuildConfig::default(),
+            Path::new("."),
+        )
+        .unwrap();
+        assert_eq!(scoped.modules_for("Base::f"), None);
+    }
+
+    #[test]
+    fn observers_include_callers_with_specs() {
+        let graph = ObserverGraph {
+            functions: BTreeMap::from([
+                (
+                    "Base::helper".to_owned(),
+                    function("Base", false, &["Top::api"]),
+                ),
+                ("Top::api".to_owned(), function("Top", true, &[])),
+                ("Base::unused".to_owned(), function("Base", false, &[])),
+            ]),
+        };
+
+        assert_eq!(
+            graph.observing_modules("Base::helper"),
+            Some(vec!["Top".to_owned()])
+        );
+        assert_eq!(graph.observing_modules("Base::unused"), Some(vec![]));
+    }
+
+    #[test]
+    fn opaque_functions_hide_changes_from_callers() {
+        let mut opaque = function("Base", true, &["Top::api"]);
+        opaque.opaque = true;
+        let graph = ObserverGraph {
+            functions: BTreeMap::from([
+                ("Base::opaque".to_owned(), opaque),
+                ("Top::api".to_owned(), function("Top", true, &[])),
+            ]),
+        };
+
+        assert_eq!(
+            graph.observing_modules("Base::opaque"),
+            Some(vec!["Base".to_owned()])
+        );
+    }
+
+    #[test]
+    fn spec_callers_observe_changes() {
+        let mut pure = function("Base", false, &[]);
+        pure.spec_callers.insert("Top::api".to_owned());
+        let graph = ObserverGraph {
+            functions: BTreeMap::from([
+                ("Base::pure".to_owned(), pure),
+                ("Top::api".to_owned(), function("Top", true, &[])),
+            ]),
+        };
+
+        assert_eq!(
+            graph.observing_modules("Base::pure"),
+            Some(vec!["Top".to_owned()])
+        );
+    }
+
+    #[test]
+    fn functions_used_by_package_specs_need_whole_package() {
+        let mut used = function("Base", false, &[]);
+        used.observed_by_package = true;
+        let graph = ObserverGraph {
+            functions: BTreeMap::from([("Base::used".to_owned(), used)]),
+        };
+
+        assert_eq!(graph.observing_modules("Base::used"), None);
+    }
+
+    #[test]
+    fn interacting_modules_share_group() {
+        let mut used = function("Shared", false, &[]);
+        used.observed_by_package = true;
+        let graph = ObserverGraph {
+            functions: BTreeMap::from([
+                (
+                    "Base::helper".to_owned(),
+                    function("Base", false, &["Top::api"]),
+                ),
+                ("Top::api".to_owned(), function("Top", true, &[])),
+                ("Other::f".to_owned(), function("Other", true, &[])),
+                ("Shared::used".to_owned(), used),
+            ]),
+        };
+
+        let groups = graph.module_groups();
+        assert!(groups.group_of("Base").is_some());
+        assert_eq!(groups.group_of("Base"), groups.group_of("Top"));
+        assert_ne!(groups.group_of("Base"), groups.group_of("Other"));
+        assert!(groups.group_of("Other").is_some());
+        assert_eq!(groups.group_of("Shared"), None);
+        assert_eq!(groups.group_of("script"), None);
+    }
+
+    #[test]
+    fn scoped_prover_confs_set_module_scope() {
+        let modules = ["0x42::Base".to_owned(), "0x42::Top".to_owned()];
+        let confs = scoped_prover_confs(&[move_prover::cli::Options::default()], &modules);
+        assert_eq!(
+            confs[0].prover.verify_scope,
+            VerificationScope::OnlyModules(modules.to_vec())
+        );
+    }
+}
@@ -0,0 +1,415 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{cli::CLIOptions, report::Report, tokens::tokenize};
+use move_compiler::parser::lexer::Tok;
+use move_mutator::cli::ModuleFilter;
+use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+};
+
+/// Default address the server listens on.
+pub const DEFAULT_SERVE_ADDRESS: &str = "127.0.0.1:9257";
+
+/// JSON-RPC error code of the requests which aren't valid JSON.
+const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code of the unknown methods.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code of the invalid method parameters.
+const INVALID_PARAMS: i64 = -32602;
+/// JSON-RPC error code of the failures of the tools (e.g. the package can't be compiled).
+const SERVER_ERROR: i64 = -32000;
+
+/// This function runs the long-running server exposing the specification testing over JSON-RPC 2.0, so the
+/// IDEs (e.g. a VS Code Move extension) can surface the surviving mutants inline.
+///
+/// Clients connect to the TCP address and send one JSON-RPC request per line, the responses are sent back
+/// in the same way. The supported methods are:
+/// - `generateMutants` - generates the mutants of the function under the cursor (`file` and `line` params)
+///   or of the given function (`function` param, e.g. `Sum::sum`),
+/// - `testMutant` - verifies the single mutant (`id` param) generated by the last `generateMutants` call,
+/// - `lastReport` - returns the report of the last `testMutant` call (`null` if there is none).
+///
+/// Connections are served one after another, the state (generated mutants, the last report) is shared.
+///
+/// # Arguments
+///
+/// * `options` - A `cli::CLIOptions` representing the options for the spec test.
+/// * `config` - A `BuildConfig` representing the build configuration.
+/// * `package_path` - A `Path` to the package.
+/// * `address` - The address to listen on (e.g. `127.0.0.1:9257`).
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_serve(
+    options: &CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+    address: &str,
+) -> anyhow::Result<()> {
+    let mut server = Server::new(options, config, package_path)?;
+    let listener = TcpListener::bind(address)?;
+    println!(
+        "Serving specification testing on {}",
+        listener.local_addr()?
+    );
+
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                warn!("Cannot accept connection: {e}");
+                continue;
+            },
+        };
+
+        let mut writer = stream.try_clone()?;
+        for line in BufReader::new(stream).lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let response = server.handle(&line);
+            if writeln!(writer, "{response}").is_err() {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// JSON-RPC request sent by the client.
+#[derive(Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Parameters of the `generateMutants` method.
+#[derive(Debug, Deserialize)]
+struct GenerateParams {
+    /// The source file with the cursor (absolute or relative to the package).
+    file: Option<PathBuf>,
+    /// The line of the cursor (starting from 1).
+    line: Option<usize>,
+    /// The function to mutate (e.g. `Sum::sum`), used instead of the cursor position.
+    function: Option<String>,
+}
+
+/// Parameters of the `testMutant` method.
+#[derive(Debug, Deserialize)]
+struct TestParams {
+    /// The ID of the mutant.
+    id: String,
+}
+
+/// Error of the method call, reported in the JSON-RPC response.
+#[derive(Debug)]
+struct CallError {
+    code: i64,
+    message: String,
+}
+
+impl CallError {
+    fn invalid_params(message: impl ToString) -> Self {
+        Self {
+            code: INVALID_PARAMS,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for CallError {
+    fn from(e: anyhow::Error) -> Self {
+        Self {
+            code: SERVER_ERROR,
+            message: e.to_string(),
+        }
+    }
+}
+
+/// State of the server shared by all connections.
+struct Server {
+    options: CLIOptions,
+    config: BuildConfig,
+    package_path: PathBuf,
+    /// Directory with the mutants generated by the server (removed when the server stops).
+    workdir: tempfile::TempDir,
+    /// Mutator report of the last `generateMutants` call.
+    generated: Option<move_mutator::report::Report>,
+    /// Report of the last `testMutant` call.
+    last_report: Option<Value>,
+}
+
+impl Server {
+    fn new(
+        options: &CLIOptions,
+        config: &BuildConfig,
+        package_path: &Path,
+    ) -> anyhow::Result<Self> {
+        let package_path = SourcePackageLayout::try_find_root(&package_path.canonicalize()?)?;
+
+        Ok(Self {
+            options: options.clone(),
+            config: config.clone(),
+            package_path,
+            workdir: tempfile::tempdir()?,
+            generated: None,
+            last_report: None,
+        })
+    }
+
+    // Internal function handling a single request line and returning the response line.
+    fn handle(&mut self, line: &str) -> String {
+        let request = match serde_json::from_str::<Request>(line) {
+            Ok(request) => request,
+            Err(e) => {
+                return error_response(Value::Null, PARSE_ERROR, &format!("Invalid request: {e}"))
+            },
+        };
+
+        let result = match request.method.as_str() {
+            "generateMutants" => self.generate_mutants(request.params),
+            "testMutant" => self.test_mutant(request.params),
+            "lastReport" => Ok(self.last_report.clone().unwrap_or(Value::Null)),
+            method => Err(CallError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unknown method: {method}"),
+            }),
+        };
+
+        match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": request.id, "result": result}).to_string(),
+            Err(e) => error_response(request.id, e.code, &e.message),
+        }
+    }
+
+    // Internal function generating the mutants of the selected function.
+    fn generate_mutants(&mut self, params: Value) -> Result<Value, CallError> {
+        let params =
+            serde_json::from_value::<GenerateParams>(params).map_err(CallError::invalid_params)?;
+        let qname = match (params.function, params.file, params.line) {
+            (Some(function), _, _) => function,
+            (None, Some(file), Some(line)) => {
+                let source = fs::read_to_string(self.package_path.join(file))
+                    .map_err(CallError::invalid_params)?;
+                function_at(&source, line).ok_or_else(|| {
+                    CallError::invalid_params(format!("No function found at line {line}"))
+                })?
+            },
+            _ => {
+                return Err(CallError::invalid_params(
+                    "Either `function` or `file` and `line` must be given",
+                ))
+            },
+        };
+        let Some((module, function)) = qname.split_once("::") else {
+            return Err(CallError::invalid_params(format!(
+                "Invalid function name: {qname}"
+            )));
+        };
+
+        let outdir = self.workdir.path().join("mutants");
+        if outdir.exists() {
+            fs::remove_dir_all(&outdir).map_err(anyhow::Error::from)?;
+        }
+
+        let mutator_options = move_mutator::cli::CLIOptions {
+            mutate_modules: ModuleFilter::Selected(vec![module.to_owned()]),
+            out_mutant_dir: Some(outdir.clone()),
+            ..crate::cli::create_mutator_options(&self.options)
+        };
+        move_mutator::run_move_mutator(mutator_options, &self.config, &self.package_path)?;
+
+        let mut generated = move_mutator::report::Report::new();
+        let report =
+            move_mutator::report::Report::load_from_json_file(&outdir.join("report.json"))?;
+        for elem in report.get_mutants() {
+            if elem.get_module_name() == module && elem.get_function_name() == function {
+                generated.add_entry(elem.clone());
+            }
+        }
+
+        let mutants = generated
+            .get_mutants()
+            .iter()
+            .map(|elem| {
+                json!({
+                    "id": elem.get_id(),
+                    "function": qname,
+                    "file": elem.original_file_path(),
+                    "operators": elem
+                        .get_mutations()
+                        .iter()
+                        .map(|mutation| mutation.get_operator_name())
+                        .collect::<Vec<_>>(),
+                    "diff": elem.get_diff(),
+                })
+            })
+            .collect::<Vec<_>>();
+        self.generated = Some(generated);
+        Ok(Value::Array(mutants))
+    }
+
+    // Internal function verifying the single mutant generated by the last `generateMutants` call.
+    fn test_mutant(&mut self, params: Value) -> Result<Value, CallError> {
+        let params =
+            serde_json::from_value::<TestParams>(params).map_err(CallError::invalid_params)?;
+        let elem = self
+            .generated
+            .as_ref()
+            .and_then(|report| {
+                report
+                    .get_mutants()
+                    .iter()
+                    .find(|elem| elem.get_id() == params.id)
+            })
+            .ok_or_else(|| CallError::invalid_params(format!("Unknown mutant: {}", params.id)))?;
+
+        // The mutant is verified as a previously generated mutant, so it's re-applied to the current sources
+        // (which may have been edited since the generation).
+        let mutant_dir = self.workdir.path().join("mutant");
+        fs::create_dir_all(&mutant_dir).map_err(anyhow::Error::from)?;
+        let mut single = move_mutator::report::Report::new();
+        single.add_entry(elem.clone());
+        single.save_to_json_file(&mutant_dir.join("report.json"))?;
+
+        let report_path = self.workdir.path().join("spec_test_report.json");
+        let options = CLIOptions {
+            use_generated_mutants: Some(mutant_dir),
+            output: Some(report_path.clone()),
+            ..self.options.clone()
+        };
+        crate::run_spec_test(&options, &self.config, &self.package_path)?;
+
+        let report = Report::load_from_json_file(&report_path)?;
+        let status = if report.mutants_unobservable() > 0 {
+            "unobservable"
+        } else if report.mutants_tested() == 0 {
+            "skipped"
+        } else if report.mutants_killed() > 0 {
+            "killed"
+        } else if report.mutants_inconclusive() > 0 {
+            "inconclusive"
+        } else if report.mutants_resource_limit() > 0 {
+            "resource_limit"
+        } else {
+            "alive"
+        };
+
+        let report = serde_json::to_value(&report).map_err(anyhow::Error::from)?;
+        self.last_report = Some(report.clone());
+        Ok(json!({"id": params.id, "status": status, "report": report}))
+    }
+}
+
+// Internal function creating the JSON-RPC error response.
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}}).to_string()
+}
+
+// Internal function returning the qualified name (e.g. `Sum::sum`) of the function declared last before
+// the given line (starting from 1) of the Move source, which is the function under the cursor.
+fn function_at(source: &str, line: usize) -> Option<String> {
+    let tokens = tokenize(source);
+    // Only the tokens starting before the end of the given line are considered.
+    let tokens = tokens
+        .iter()
+        .take_while(|token| source[..token.start].matches('\n').count() < line)
+        .collect::<Vec<_>>();
+    let mut module = None;
+    let mut function = None;
+
+    for (index, token) in tokens.iter().enumerate() {
+        match token.kind {
+            // Module name is the last identifier before the opening brace (e.g. `module 0x1::Sum {`).
+            Tok::Module => {
+                module = tokens[index + 1..]
+                    .iter()
+                    .take_while(|token| token.kind != Tok::LBrace)
+                    .filter(|token| token.kind == Tok::Identifier)
+                    .last()
+                    .map(|token| token.text.to_owned());
+                function = None;
+            },
+            // Spec functions (`spec fun`) aren't the functions which can be tested.
+            Tok::Fun if index == 0 || tokens[index - 1].kind != Tok::Spec => {
+                function = tokens
+                    .get(index + 1)
+                    .filter(|token| token.kind == Tok::Identifier)
+                    .map(|token| token.text.to_owned());
+            },
+            _ => {},
+        }
+    }
+
+    Some(format!("{}::{}", module?, function?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "module 0x1::Sum {\n    // fun commented()\n    public fun sum(a: u64): u64 {\n        a + 1\n    }\n\n    fun sub(a: u64): u64 {\n        a - 1\n    }\n\n    spec fun helper(): u64 {\n        1\n    }\n}\n";
+
+    #[test]
+    fn function_under_cursor_is_found() {
+        assert_eq!(function_at(SOURCE, 1), None);
+        assert_eq!(function_at(SOURCE, 2), None);
+        assert_eq!(function_at(SOURCE, 4), Some("Sum::sum".to_owned()));
+        assert_eq!(function_at(SOURCE, 8), Some("Sum::sub".to_owned()));
+        assert_eq!(function_at(SOURCE, 12), Some("Sum::sub".to_owned()));
+    }
+
+    #[test]
+    fn invalid_requests_are_reported() {
+        let package = tempfile::tempdir().unwrap();
+        fs::write(
+            package.path().join("Move.toml"),
+            "[package]\nname = \"Test\"\nversion = \"0.0.0\"\n",
+        )
+        .unwrap();
+        let mut server = Server::new(
+            &CLIOptions::default(),
+            &BuildConfig::default(),
+            package.path(),
+        )
+        .unwrap();
+
+        let response: Value = serde_json::from_str(&server.handle("not json")).unwrap();
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let response: Value =
+            serde_json::from_str(&server.handle(r#"{"jsonrpc":"2.0","id":1,"method":"unknown"}"#))
+                .unwrap();
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        let response: Value = serde_json::from_str(
+            &server.handle(r#"{"jsonrpc":"2.0","id":2,"method":"testMutant","params":{"id":"x"}}"#),
+        )
+        .unwrap();
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+
+        let response: Value = serde_json::from_str(
+            &server.handle(r#"{"jsonrpc":"2.0","id":3,"method":"lastReport"}"#),
+        )
+        .unwrap();
+        assert_eq!(response["result"], Value::Null);
+    }
+}
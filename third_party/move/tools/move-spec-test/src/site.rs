@@ -0,0 +1,414 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    diagnostics::ProverDiagnostic,
+    report::{Report, SurvivingMutant},
+};
+use std::{fmt::Write as _, fs, path::Path};
+
+/// Directory (inside the site directory) with the pages of the single mutants.
+const MUTANTS_DIR: &str = "mutants";
+
+/// Style sheet shared by all pages of the site.
+const STYLE: &str = "body{font-family:sans-serif;margin:2em auto;max-width:70em;padding:0 1em}\
+table{border-collapse:collapse;margin-bottom:2em}\
+th,td{border:1px solid #ccc;padding:.3em .6em;text-align:left}\
+th{background:#f0f0f0}pre{background:#f6f8fa;padding:1em;overflow-x:auto}\
+.added{color:#22863a}.removed{color:#b31d28}.hunk{color:#6f42c1}\
+.alive{color:#b31d28}.killed{color:#22863a}";
+
+/// Generates the static website presenting the report (e.g. to publish it with GitHub Pages).
+///
+/// The site consists of the `index.html` page with the scores of the modules, operators and functions, and
+/// the pages of the surviving mutants (with their diffs and the suggested spec hints) and of the killed
+/// mutants with the prover diagnostics (the violated conditions and their counterexamples) in the `mutants`
+/// directory.
+///
+/// # Errors
+///
+/// Returns an error if the site files can't be written.
+pub fn generate_site(report: &Report, title: &str, output_dir: &Path) -> anyhow::Result<()> {
+    let mutants_dir = output_dir.join(MUTANTS_DIR);
+    fs::create_dir_all(&mutants_dir)?;
+
+    let mut index = String::new();
+    writeln!(index, "<h1>{}</h1>", escape(title))?;
+
+    let total = report.total_summary();
+    writeln!(
+        index,
+        "<p>Mutants tested: {}, killed: {}, survived: {}, inconclusive: {}. Score: {:.2}% (weighted: {:.2}%).</p>",
+        total.generated,
+        total.killed,
+        total.survived,
+        total.timeout,
+        total.score(),
+        total.weighted_score()
+    )?;
+
+    writeln!(index, "<h2>Modules</h2>")?;
+    writeln!(
+        index,
+        "<table><tr><th>Module</th><th>Tested</th><th>Killed</th><th>Survived</th><th>Inconclusive</th><th>Score</th><th>Weighted score</th></tr>"
+    )?;
+    for summary in report.module_summaries() {
+        writeln!(
+            index,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}%</td><td>{:.2}%</td></tr>",
+            escape(&summary.module),
+            summary.generated,
+            summary.killed,
+            summary.survived,
+            summary.timeout,
+            summary.score(),
+            summary.weighted_score()
+        )?;
+    }
+    writeln!(index, "</table>")?;
+
+    if !report.operators().is_empty() {
+        writeln!(index, "<h2>Operators</h2>")?;
+        writeln!(
+            index,
+            "<table><tr><th>Operator</th><th>Sites</th><th>Generated</th><th>Skipped</th><th>Killed</th><th>Survived</th><th>Inconclusive</th><th>Score</th></tr>"
+        )?;
+        for (operator, stats) in report.operators() {
+            writeln!(
+                index,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}%</td></tr>",
+                escape(operator),
+                stats.sites,
+                stats.generated,
+                stats.skipped,
+                stats.killed,
+                stats.survived,
+                stats.inconclusive,
+                stats.score()
+            )?;
+        }
+        writeln!(index, "</table>")?;
+    }
+
+    writeln!(index, "<h2>Functions</h2>")?;
+    writeln!(
+        index,
+        "<table><tr><th>File</th><th>Function</th><th>Tested</th><th>Killed</th><th>Inconclusive</th><th>Score</th><th>Surviving mutants</th><th>Counterexamples</th></tr>"
+    )?;
+    let mut page_index = 0;
+    for (path, stats) in report.entries() {
+        for stat in stats {
+            let mut links = vec![];
+            for mutant in &stat.surviving_mutants {
+                let name = page_name(&mutant.id, page_index);
+                page_index += 1;
+
+                let page = mutant_page(&path.display().to_string(), &stat.module_func, mutant)?;
+                fs::write(
+                    mutants_dir.join(&name),
+                    page_html(&mutant_title(mutant), &page, "../"),
+                )?;
+                links.push(format!(
+                    "<a href=\"{MUTANTS_DIR}/{name}\">{}</a>",
+                    escape(&mutant_title(mutant))
+                ));
+            }
+
+            let mut killed_links = vec![];
+            for (id, diagnostics) in &stat.diagnostics {
+                let name = page_name(id, page_index);
+                page_index += 1;
+
+                let title = format!("Mutant {id}");
+                let page = killed_mutant_page(
+                    &path.display().to_string(),
+                    &stat.module_func,
+                    &title,
+                    diagnostics,
+                )?;
+                fs::write(mutants_dir.join(&name), page_html(&title, &page, "../"))?;
+                killed_links.push(format!(
+                    "<a href=\"{MUTANTS_DIR}/{name}\">{}</a>",
+                    escape(&title)
+                ));
+            }
+
+            writeln!(
+                index,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.2}%</td><td>{}</td><td>{}</td></tr>",
+                escape(&path.display().to_string()),
+                escape(&stat.module_func),
+                stat.tested,
+                stat.killed,
+                stat.inconclusive,
+                stat.kill_percentage(),
+                links.join("<br>"),
+                killed_links.join("<br>")
+            )?;
+        }
+    }
+    writeln!(index, "</table>")?;
+
+    fs::write(output_dir.join("index.html"), page_html(title, &index, ""))?;
+    fs::write(output_dir.join("style.css"), STYLE)?;
+    // GitHub Pages shouldn't process the site with Jekyll.
+    fs::write(output_dir.join(".nojekyll"), "")?;
+
+    Ok(())
+}
+
+// Internal function rendering the body of the surviving mutant page.
+fn mutant_page(path: &str, module_func: &str, mutant: &SurvivingMutant) -> anyhow::Result<String> {
+    let mut page = String::new();
+    writeln!(
+        page,
+        "<p><a href=\"../index.html\">Back to the summary</a></p>"
+    )?;
+    writeln!(page, "<h1>{}</h1>", escape(&mutant_title(mutant)))?;
+    writeln!(
+        page,
+        "<p>File: {}<br>Function: {}<br>Operators: {}<br>Status: <span class=\"alive\">alive</span></p>",
+        escape(path),
+        escape(module_func),
+        escape(&mutant.operators.join(", "))
+    )?;
+
+    if !mutant.hints.is_empty() {
+        writeln!(page, "<h2>Suggested specifications</h2><ul>")?;
+        for hint in &mutant.hints {
+            writeln!(page, "<li>{}</li>", escape(hint.message()))?;
+        }
+        writeln!(page, "</ul>")?;
+    }
+
+    writeln!(page, "<h2>Diff</h2>")?;
+    writeln!(page, "<pre>{}</pre>", diff_html(&mutant.diff))?;
+    Ok(page)
+}
+
+// Internal function rendering the body of the killed mutant page with the verification errors which
+// killed the mutant and the execution traces of their counterexamples.
+fn killed_mutant_page(
+    path: &str,
+    module_func: &str,
+    title: &str,
+    diagnostics: &[ProverDiagnostic],
+) -> anyhow::Result<String> {
+    let mut page = String::new();
+    writeln!(
+        page,
+        "<p><a href=\"../index.html\">Back to the summary</a></p>"
+    )?;
+    writeln!(page, "<h1>{}</h1>", escape(title))?;
+    writeln!(
+        page,
+        "<p>File: {}<br>Function: {}<br>Status: <span class=\"killed\">killed</span></p>",
+        escape(path),
+        escape(module_func)
+    )?;
+
+    for diagnostic in diagnostics {
+        writeln!(page, "<h2>{}</h2>", escape(&diagnostic.condition))?;
+        match (&diagnostic.file, diagnostic.line) {
+            (Some(file), Some(line)) => {
+                writeln!(page, "<p>Specification: {}:{line}</p>", escape(file))?
+            },
+            (Some(file), None) => writeln!(page, "<p>Specification: {}</p>", escape(file))?,
+            _ => {},
+        }
+
+        let trace = diagnostic.trace();
+        if trace.is_empty() {
+            writeln!(page, "<p>The prover reported no counterexample.</p>")?;
+            continue;
+        }
+
+        writeln!(page, "<h3>Counterexample</h3><ol>")?;
+        for step in trace {
+            if step.location.is_empty() {
+                writeln!(page, "<li>Initial state")?;
+            } else {
+                writeln!(page, "<li>At <code>{}</code>", escape(&step.location))?;
+            }
+            if !step.assignments.is_empty() {
+                writeln!(page, "<table><tr><th>Variable</th><th>Value</th></tr>")?;
+                for (name, value) in &step.assignments {
+                    writeln!(
+                        page,
+                        "<tr><td><code>{}</code></td><td><code>{}</code></td></tr>",
+                        escape(name),
+                        escape(value)
+                    )?;
+                }
+                writeln!(page, "</table>")?;
+            }
+            for note in &step.notes {
+                writeln!(page, "<p>{}</p>", escape(note))?;
+            }
+            writeln!(page, "</li>")?;
+        }
+        writeln!(page, "</ol>")?;
+    }
+    Ok(page)
+}
+
+// Internal function wrapping the body into the complete HTML page. The `root` is the relative path of
+// the site root.
+fn page_html(title: &str, body: &str, root: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<link rel=\"stylesheet\" href=\"{root}style.css\">\n</head>\n<body>\n{body}</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+// Internal function rendering the diff with the added and removed lines highlighted.
+fn diff_html(diff: &str) -> String {
+    let mut html = String::new();
+    for line in diff.lines() {
+        let class = if line.starts_with("+++") || line.starts_with("---") {
+            None
+        } else if line.starts_with('+') {
+            Some("added")
+        } else if line.starts_with('-') {
+            Some("removed")
+        } else if line.starts_with("@@") {
+            Some("hunk")
+        } else {
+            None
+        };
+        match class {
+            Some(class) => html.push_str(&format!(
+                "<span class=\"{class}\">{}</span>\n",
+                escape(line)
+            )),
+            None => html.push_str(&format!("{}\n", escape(line))),
+        }
+    }
+    html
+}
+
+// Internal function returning the title of the surviving mutant.
+fn mutant_title(mutant: &SurvivingMutant) -> String {
+    if mutant.id.is_empty() {
+        "Surviving mutant".to_owned()
+    } else {
+        format!("Mutant {}", mutant.id)
+    }
+}
+
+// Internal function returning the file name of the mutant page. Mutants without IDs (from the old reports)
+// are named by their index.
+fn page_name(id: &str, index: usize) -> String {
+    if !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric()) {
+        format!("{id}.html")
+    } else {
+        format!("mutant_{index}.html")
+    }
+}
+
+// Internal function escaping the text embedded in the HTML.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::MutantStatus;
+    use std::path::PathBuf;
+
+    #[test]
+    fn html_is_escaped() {
+        assert_eq!(
+            escape("<a href=\"x\">&'</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&#39;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn diff_lines_are_highlighted() {
+        let html = diff_html("--- original\n+++ modified\n@@ -1 +1 @@\n-a < b\n+a <= b\n");
+        assert!(html.starts_with("--- original\n+++ modified\n"));
+        assert!(html.contains("<span class=\"hunk\">@@ -1 +1 @@</span>"));
+        assert!(html.contains("<span class=\"removed\">-a &lt; b</span>"));
+        assert!(html.contains("<span class=\"added\">+a &lt;= b</span>"));
+    }
+
+    #[test]
+    fn site_contains_index_and_mutant_pages() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        report.increment_mutants_tested(&path, "Sum::sum");
+        report.increment_mutants_tested(&path, "Sum::sum");
+        report.increment_mutants_killed(&path, "Sum::sum");
+        report.add_surviving_mutant(&path, "Sum::sum", SurvivingMutant {
+            id: "0123abcd".to_owned(),
+            diff: "@@ -1 +1 @@\n-a\n+b\n".to_owned(),
+            operators: vec!["binary_operator_replacement".to_owned()],
+            ..Default::default()
+        });
+        report.record_operator_status(["binary_operator_replacement"], MutantStatus::Alive);
+
+        let dir = tempfile::tempdir().unwrap();
+        generate_site(&report, "Spec <test>", dir.path()).unwrap();
+
+        let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+        assert!(index.contains("<title>Spec &lt;test&gt;</title>"));
+        assert!(index.contains("<td>Sum</td>"));
+        assert!(index.contains("href=\"mutants/0123abcd.html\""));
+        assert!(index.contains("<h2>Operators</h2>"));
+        assert!(index.contains("<tr><td>binary_operator_replacement</td>"));
+        assert!(dir.path().join("style.css").exists());
+        assert!(dir.path().join(".nojekyll").exists());
+
+        let page = fs::read_to_string(dir.path().join("mutants/0123abcd.html")).unwrap();
+        assert!(page.contains("href=\"../style.css\""));
+        assert!(page.contains("binary_operator_replacement"));
+        assert!(page.contains("<span class=\"added\">+b</span>"));
+    }
+
+    #[test]
+    fn killed_mutant_page_shows_counterexample() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        report.increment_mutants_tested(&path, "Sum::sum");
+        report.increment_mutants_killed(&path, "Sum::sum");
+        report.set_mutant_diagnostics(&path, "Sum::sum", "4567cdef", vec![ProverDiagnostic {
+            condition: "post-condition does not hold".to_owned(),
+            file: Some("sources/Sum.move".to_owned()),
+            line: Some(8),
+            counterexample: vec![
+                "at sources/Sum.move:4: sum".to_owned(),
+                "a = 1".to_owned(),
+                "at sources/Sum.move:8: sum (spec)".to_owned(),
+                "result = 4".to_owned(),
+            ],
+        }]);
+
+        let dir = tempfile::tempdir().unwrap();
+        generate_site(&report, "Spec test", dir.path()).unwrap();
+
+        let index = fs::read_to_string(dir.path().join("index.html")).unwrap();
+        assert!(index.contains("href=\"mutants/4567cdef.html\""));
+
+        let page = fs::read_to_string(dir.path().join("mutants/4567cdef.html")).unwrap();
+        assert!(page.contains("<span class=\"killed\">killed</span>"));
+        assert!(page.contains("<h2>post-condition does not hold</h2>"));
+        assert!(page.contains("<p>Specification: sources/Sum.move:8</p>"));
+        assert!(page.contains("<li>At <code>sources/Sum.move:4: sum</code>"));
+        assert!(page.contains("<tr><td><code>a</code></td><td><code>1</code></td></tr>"));
+        assert!(page.contains("<tr><td><code>result</code></td><td><code>4</code></td></tr>"));
+    }
+}
@@ -0,0 +1,297 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    hints::SpecHint,
+    report::{Report, SurvivingMutant},
+};
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Default directory where the spec skeletons are written.
+pub const DEFAULT_SUGGESTIONS_DIR: &str = "suggestions";
+
+/// Kind of the spec clause suggested for the surviving mutant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Clause {
+    AbortsIf,
+    Ensures,
+    GlobalStateEnsures,
+    Emits,
+    LoopEnsures,
+}
+
+impl Clause {
+    // Internal function returning the placeholder line of the clause for the mutated expression.
+    fn placeholder(&self, expression: &str) -> String {
+        match self {
+            Clause::AbortsIf => format!("// TODO: aborts_if ...; (when does `{expression}` abort?)"),
+            Clause::Ensures => {
+                format!("// TODO: ensures result == ...; (what does `{expression}` compute?)")
+            },
+            Clause::GlobalStateEnsures => format!(
+                "// TODO: ensures global<...>(...) == ...; (how does `{expression}` change the global state?)"
+            ),
+            Clause::Emits => {
+                format!("// TODO: emits ... to ...; (which event does `{expression}` emit?)")
+            },
+            Clause::LoopEnsures => format!(
+                "// TODO: ensures ...; (what is the result of the loop at `{expression}`? Consider a loop invariant too.)"
+            ),
+        }
+    }
+
+    // Internal function mapping the spec hints of the mutant to the suggested clauses.
+    fn for_hints(hints: &[SpecHint]) -> Vec<Clause> {
+        let mut clauses = hints
+            .iter()
+            .flat_map(|hint| match hint {
+                SpecHint::MissingSpec | SpecHint::MissingBranchCoverage => {
+                    vec![Clause::AbortsIf, Clause::Ensures]
+                },
+                SpecHint::MissingAbortsIf => vec![Clause::AbortsIf],
+                SpecHint::MissingEnsures => vec![Clause::Ensures],
+                SpecHint::MissingGlobalStateEnsures => vec![Clause::GlobalStateEnsures],
+                SpecHint::MissingEmits => vec![Clause::Emits],
+                SpecHint::MissingLoopInvariant => vec![Clause::LoopEnsures],
+            })
+            .collect::<Vec<_>>();
+
+        if clauses.is_empty() {
+            clauses.push(Clause::Ensures);
+        }
+        clauses.sort();
+        clauses.dedup();
+        clauses
+    }
+}
+
+/// This function writes the skeletons of the `spec` blocks for the functions with surviving mutants from
+/// the given spec-test report (experimental). A file is written for each module (`<module>.move`, or
+/// `<address>_<module>.move` if modules of the same name are published at different addresses), with
+/// a `spec` block for each function and the TODO placeholders of the `ensures` and `aborts_if` clauses
+/// for the mutated expressions, chosen according to the spec hints of the mutants.
+///
+/// The placeholders are commented out, so the blocks can be moved into the module right away and
+/// completed one by one.
+///
+/// # Arguments
+///
+/// * `report_path` - A `Path` to the JSON report generated by the spec-test tool (or to the archive created
+///   with the `--archive` option).
+/// * `output_dir` - A `Path` to the directory where the skeletons are written (created if it does not exist).
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_suggest_specs(report_path: &Path, output_dir: &Path) -> anyhow::Result<()> {
+    let report = Report::load_from_json_or_archive(report_path)?;
+    let suggestions = suggestions(&report);
+
+    if suggestions.is_empty() {
+        println!("No surviving mutants, no spec suggestions written.");
+        return Ok(());
+    }
+
+    fs::create_dir_all(output_dir)?;
+    for (module, skeleton) in &suggestions {
+        fs::write(output_dir.join(format!("{module}.move")), skeleton)?;
+    }
+
+    println!(
+        "Spec skeletons for {} modules written to {}",
+        suggestions.len(),
+        output_dir.display()
+    );
+    Ok(())
+}
+
+// Internal function generating the spec skeletons of the modules, keyed by the name of the skeleton file
+// (the module name, prefixed by the address if modules of the same name have different addresses).
+fn suggestions(report: &Report) -> BTreeMap<String, String> {
+    type Functions<'a> = Vec<(&'a PathBuf, &'a str, &'a [SurvivingMutant])>;
+    let mut functions: BTreeMap<(&str, Option<&str>), Functions> = BTreeMap::new();
+    for (path, stats) in report.entries() {
+        for stat in stats {
+            if stat.surviving_mutants.is_empty() {
+                continue;
+            }
+
+            let (module, function) = stat
+                .module_func
+                .rsplit_once("::")
+                .unwrap_or(("", stat.module_func.as_str()));
+            let module = if module.is_empty() { "script" } else { module };
+            let address = stat
+                .surviving_mutants
+                .iter()
+                .find_map(|mutant| mutant.module_address.as_deref());
+            functions.entry((module, address)).or_default().push((
+                path,
+                function,
+                &stat.surviving_mutants,
+            ));
+        }
+    }
+
+    let mut addresses = BTreeMap::<&str, usize>::new();
+    for (module, _) in functions.keys() {
+        *addresses.entry(module).or_default() += 1;
+    }
+
+    functions
+        .iter()
+        .map(|((module, address), functions)| match address {
+            Some(address) if addresses[module] > 1 => (
+                format!("{address}_{module}"),
+                module_skeleton(&format!("{address}::{module}"), functions),
+            ),
+            _ => ((*module).to_owned(), module_skeleton(module, functions)),
+        })
+        .collect()
+}
+
+// Internal function generating the spec skeleton of a single module.
+fn module_skeleton(module: &str, functions: &[(&PathBuf, &str, &[SurvivingMutant])]) -> String {
+    let mut skeleton = format!(
+        "// Spec skeletons for the functions of the module `{module}` with surviving mutants.\n\
+         // Generated by `move-spec-test suggest-specs` (experimental): complete the TODOs and move the\n\
+         // blocks into the module.\n"
+    );
+
+    for (path, function, mutants) in functions {
+        let _ = write!(skeleton, "\n// {}\nspec {function} {{\n", path.display());
+
+        let mut placeholders = Vec::new();
+        for mutant in *mutants {
+            let _ = writeln!(
+                skeleton,
+                "    // Surviving mutant {} ({}) changes:",
+                mutant.id,
+                mutant.operators.join(", ")
+            );
+            let expressions = mutated_expressions(&mutant.diff);
+            for expression in &expressions {
+                let _ = writeln!(skeleton, "    //     {expression}");
+            }
+
+            for clause in Clause::for_hints(&mutant.hints) {
+                for expression in &expressions {
+                    let placeholder = clause.placeholder(expression);
+                    if !placeholders.contains(&placeholder) {
+                        placeholders.push(placeholder);
+                    }
+                }
+            }
+        }
+
+        for placeholder in placeholders {
+            let _ = writeln!(skeleton, "    {placeholder}");
+        }
+        skeleton.push_str("}\n");
+    }
+
+    skeleton
+}
+
+// Internal function returning the original lines changed by the mutant (taken from its diff).
+fn mutated_expressions(diff: &str) -> Vec<String> {
+    let mut expressions = diff
+        .lines()
+        .filter(|line| line.starts_with('-') && !line.starts_with("---"))
+        .map(|line| line[1..].trim().to_owned())
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    expressions.dedup();
+
+    if expressions.is_empty() {
+        expressions.push("the mutated code".to_owned());
+    }
+    expressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surviving_mutant(id: &str, diff: &str, hints: Vec<SpecHint>) -> SurvivingMutant {
+        SurvivingMutant {
+            id: id.to_owned(),
+            diff: diff.to_owned(),
+            operators: vec!["binary_operator_replacement".to_owned()],
+            hints,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn skeletons_are_generated_for_functions_with_surviving_mutants() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        report.increment_mutants_tested(&path, "Sum::sum");
+        report.add_surviving_mutant(
+            &path,
+            "Sum::sum",
+            surviving_mutant(
+                "0123abcd",
+                "--- original\n+++ modified\n-        a + b\n+        a - b\n",
+                vec![SpecHint::MissingAbortsIf, SpecHint::MissingEnsures],
+            ),
+        );
+        report.increment_mutants_tested(&path, "Sum::checked");
+        report.increment_mutants_killed(&path, "Sum::checked");
+
+        let suggestions = suggestions(&report);
+        assert_eq!(suggestions.len(), 1);
+
+        let skeleton = &suggestions["Sum"];
+        assert!(skeleton.contains("// sources/Sum.move\nspec sum {\n"));
+        assert!(skeleton
+            .contains("    // Surviving mutant 0123abcd (binary_operator_replacement) changes:\n"));
+        assert!(skeleton.contains("    //     a + b\n"));
+        assert!(skeleton.contains("    // TODO: aborts_if ...; (when does `a + b` abort?)\n"));
+        assert!(
+            skeleton.contains("    // TODO: ensures result == ...; (what does `a + b` compute?)\n")
+        );
+        assert!(!skeleton.contains("spec checked"));
+    }
+
+    #[test]
+    fn placeholders_are_not_repeated() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        for id in ["first", "second"] {
+            report.add_surviving_mutant(
+                &path,
+                "Sum::sum",
+                surviving_mutant(id, "-a + b\n+a * b\n", vec![]),
+            );
+        }
+
+        let skeleton = &suggestions(&report)["Sum"];
+        assert_eq!(skeleton.matches("// TODO: ensures").count(), 1);
+        assert_eq!(skeleton.matches("// Surviving mutant").count(), 2);
+    }
+
+    #[test]
+    fn modules_of_the_same_name_are_told_apart_by_address() {
+        let mut report = Report::new();
+        for address in ["0x1", "0x2"] {
+            let path = PathBuf::from(format!("sources/Sum_{address}.move"));
+            let mut mutant = surviving_mutant(address, "-a + b\n+a * b\n", vec![]);
+            mutant.module_address = Some(address.to_owned());
+            report.add_surviving_mutant(&path, "Sum::sum", mutant);
+        }
+
+        let suggestions = suggestions(&report);
+        assert_eq!(suggestions.keys().collect::<Vec<_>>(), vec![
+            "0x1_Sum", "0x2_Sum"
+        ]);
+        assert!(suggestions["0x2_Sum"].contains("module `0x2::Sum`"));
+    }
+}
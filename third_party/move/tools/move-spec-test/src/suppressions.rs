@@ -0,0 +1,139 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::Path};
+
+/// Default name of the file storing triage decisions.
+pub const DEFAULT_SUPPRESSIONS_FILE: &str = ".spec-test-suppressions.json";
+
+/// Triage decision taken for a surviving mutant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Decision {
+    /// The mutant reveals a gap in the specification which should be fixed.
+    NeedsSpec,
+    /// The mutant is semantically equivalent to the original code and can't be killed.
+    Equivalent,
+    /// The mutant is not interesting and should be ignored.
+    Suppress,
+}
+
+impl Decision {
+    /// Returns the human-readable name of the decision.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Decision::NeedsSpec => "needs spec",
+            Decision::Equivalent => "equivalent",
+            Decision::Suppress => "suppress",
+        }
+    }
+
+    /// Indicates if mutants marked with this decision should be skipped during testing.
+    pub fn skips_mutant(&self) -> bool {
+        matches!(self, Decision::Equivalent | Decision::Suppress)
+    }
+}
+
+/// Single entry in the suppression file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuppressionEntry {
+    /// The decision taken for the mutant.
+    pub decision: Decision,
+    /// Module::function where mutant resides (informational only).
+    #[serde(default)]
+    pub module_func: String,
+}
+
+/// Suppression file storing triage decisions, keyed by mutant ID.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Suppressions {
+    /// Decisions taken for the mutants.
+    #[serde(default)]
+    mutants: BTreeMap<String, SuppressionEntry>,
+}
+
+impl Suppressions {
+    /// Loads the suppressions from the given JSON file.
+    /// If the file doesn't exist, empty suppressions are returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or has an invalid format.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Saves the suppressions to the given JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let file = std::fs::File::create(path)?;
+        Ok(serde_json::to_writer_pretty(file, self)?)
+    }
+
+    /// Records the decision for the mutant with the given ID.
+    pub fn set(&mut self, id: &str, module_func: &str, decision: Decision) {
+        self.mutants.insert(id.to_owned(), SuppressionEntry {
+            decision,
+            module_func: module_func.to_owned(),
+        });
+    }
+
+    /// Returns the decision taken for the mutant with the given ID.
+    pub fn get(&self, id: &str) -> Option<Decision> {
+        self.mutants.get(id).map(|entry| entry.decision)
+    }
+
+    /// Indicates if the mutant with the given ID should be skipped during testing.
+    pub fn is_suppressed(&self, id: &str) -> bool {
+        self.get(id).is_some_and(|decision| decision.skips_mutant())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn load_returns_empty_suppressions_for_missing_file() {
+        let suppressions = Suppressions::load(Path::new("non_existent_suppressions.json")).unwrap();
+        assert_eq!(suppressions, Suppressions::default());
+    }
+
+    #[test]
+    fn suppressions_are_saved_and_loaded() {
+        let mut suppressions = Suppressions::default();
+        suppressions.set("0123456789abcdef", "Sum::sum", Decision::Equivalent);
+        suppressions.set("fedcba9876543210", "Sum::sum", Decision::NeedsSpec);
+
+        let path = Path::new("test_suppressions.json");
+        suppressions.save(path).unwrap();
+        let loaded = Suppressions::load(path);
+        fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded.unwrap(), suppressions);
+    }
+
+    #[test]
+    fn only_equivalent_and_suppressed_mutants_are_skipped() {
+        let mut suppressions = Suppressions::default();
+        suppressions.set("a", "", Decision::NeedsSpec);
+        suppressions.set("b", "", Decision::Equivalent);
+        suppressions.set("c", "", Decision::Suppress);
+
+        assert!(!suppressions.is_suppressed("a"));
+        assert!(suppressions.is_suppressed("b"));
+        assert!(suppressions.is_suppressed("c"));
+        assert!(!suppressions.is_suppressed("unknown"));
+    }
+}
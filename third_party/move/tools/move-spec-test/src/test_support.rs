@@ -0,0 +1,174 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Support for the black-box tests of the specification testing (enabled with the `test-support`
+//! feature): fixture Move packages and helpers running the mutator and the whole specification testing
+//! in-process, without shelling out to the tools.
+
+use crate::{cli::CLIOptions, report::Report};
+use move_package::BuildConfig;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+/// Move package created in a temporary directory, which is removed when the fixture is dropped.
+pub struct FixturePackage {
+    dir: TempDir,
+    path: PathBuf,
+}
+
+impl FixturePackage {
+    /// Creates the package with the given name and the source files (paths relative to the `sources`
+    /// directory with their content). The package has the `TestAccount` address set to `0xCAFE` and no
+    /// dependencies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package files can't be written.
+    pub fn new(name: &str, sources: &[(&str, &str)]) -> anyhow::Result<Self> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join(name);
+        fs::create_dir_all(path.join("sources"))?;
+        fs::write(
+            path.join("Move.toml"),
+            format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.0.0\"\n\n[addresses]\nTestAccount = \"0xCAFE\"\n"
+            ),
+        )?;
+        for (file, content) in sources {
+            let file = path.join("sources").join(file);
+            if let Some(parent) = file.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(file, content)?;
+        }
+
+        Ok(Self { dir, path })
+    }
+
+    /// Creates the package whose specification doesn't constrain the result, so the mutants of the
+    /// arithmetic operator survive.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package files can't be written.
+    pub fn poor_spec() -> anyhow::Result<Self> {
+        Self::new("poor_spec", &[(
+            "Sum.move",
+            "module TestAccount::Sum {\n    \
+                 fun sum(x: u128, y: u128): u128 {\n        \
+                     let sum_r = x + y;\n\n        \
+                     spec {\n            \
+                         assert sum_r >= 0;\n        \
+                     };\n\n        \
+                     sum_r\n    \
+                 }\n\
+             }\n",
+        )])
+    }
+
+    /// Creates the package whose specification determines the result, so the mutants of the arithmetic
+    /// operator are killed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package files can't be written.
+    pub fn well_specified() -> anyhow::Result<Self> {
+        Self::new("well_specified", &[(
+            "Sum.move",
+            "module TestAccount::Sum {\n    \
+                 fun sum(x: u128, y: u128): u128 {\n        \
+                     x + y\n    \
+                 }\n\n    \
+                 spec sum {\n        \
+                     ensures result == x + y;\n    \
+                 }\n\
+             }\n",
+        )])
+    }
+
+    /// Returns the root directory of the package.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the temporary directory containing the package, which may be used for the other test
+    /// files (e.g. the reports).
+    pub fn temp_dir(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+/// Checks if the prover can be run (Boogie is configured with the `BOOGIE_EXE` environment variable), so
+/// the tests verifying the mutants can be skipped on the machines without the prover.
+pub fn prover_available() -> bool {
+    !move_prover::cli::Options::default()
+        .backend
+        .boogie_exe
+        .is_empty()
+}
+
+/// Generates the mutants of the package in-process and returns the mutator report. The mutants are
+/// stored in the `mutants` directory next to the package (unless the options give another directory).
+///
+/// # Errors
+///
+/// Returns an error if the mutator fails or its report can't be read.
+pub fn generate_mutants(
+    package: &FixturePackage,
+    options: &CLIOptions,
+) -> anyhow::Result<move_mutator::report::Report> {
+    let mut mutator_conf = crate::cli::create_mutator_options(options);
+    let out_dir = crate::cli::check_mutator_output_path(&mutator_conf)
+        .unwrap_or_else(|| package.temp_dir().join("mutants"));
+    mutator_conf.out_mutant_dir = Some(out_dir.clone());
+    fs::create_dir_all(&out_dir)?;
+
+    move_mutator::run_move_mutator(mutator_conf, &BuildConfig::default(), package.path())?;
+    Ok(move_mutator::report::Report::load_from_json_file(
+        &out_dir.join(move_mutator::report::ReportFormat::Json.file_name()),
+    )?)
+}
+
+/// Runs the whole specification testing of the package in-process (generating and verifying the mutants)
+/// and returns its report. The report is also saved to the file given by the `output` option (or to the
+/// `report.json` file next to the package).
+///
+/// # Errors
+///
+/// Returns an error if the specification testing fails (e.g. the original code can't be verified).
+pub fn run_spec_test(package: &FixturePackage, options: &CLIOptions) -> anyhow::Result<Report> {
+    let mut options = options.clone();
+    let output = options
+        .output
+        .clone()
+        .unwrap_or_else(|| package.temp_dir().join("report.json"));
+    options.output = Some(output.clone());
+
+    crate::run_spec_test(&options, &BuildConfig::default(), package.path())?;
+    Report::load_from_json_file(&output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_packages_are_created() {
+        let package = FixturePackage::poor_spec().unwrap();
+        assert!(package.path().join("Move.toml").exists());
+        assert!(package.path().join("sources/Sum.move").exists());
+
+        let path = {
+            let package =
+                FixturePackage::new("nested", &[("dir/A.move", "module 0x1::A {}")]).unwrap();
+            assert!(package.path().join("sources/dir/A.move").exists());
+            package.path().to_path_buf()
+        };
+        // The package is removed with the fixture.
+        assert!(!path.exists());
+    }
+}
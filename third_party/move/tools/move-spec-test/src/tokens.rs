@@ -0,0 +1,67 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::files::FileHash;
+use move_compiler::parser::lexer::{Lexer, Tok};
+
+/// Token of the Move source, as found by the Move lexer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Token<'a> {
+    /// Kind of the token (`Tok::EOF` for the rest of the source which can't be lexed).
+    pub(crate) kind: Tok,
+    /// Text of the token.
+    pub(crate) text: &'a str,
+    /// Offset of the token in the source.
+    pub(crate) start: usize,
+}
+
+/// Splits the Move source into tokens using the Move lexer, skipping the comments and the whitespaces.
+/// If the source can't be lexed (e.g. while it's being edited), the rest of the source from the invalid
+/// token is returned as a single `Tok::EOF` token, so its changes are still noticed.
+pub(crate) fn tokenize(source: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut lexer = Lexer::new(source, FileHash::empty());
+
+    loop {
+        if lexer.advance().is_err() {
+            let rest = &source[lexer.previous_end_loc()..];
+            let text = rest.trim_start();
+            tokens.push(Token {
+                kind: Tok::EOF,
+                text: text.trim_end(),
+                start: source.len() - text.len(),
+            });
+            break;
+        }
+        if lexer.peek() == Tok::EOF {
+            break;
+        }
+        tokens.push(Token {
+            kind: lexer.peek(),
+            text: lexer.content(),
+            start: lexer.start_loc(),
+        });
+    }
+
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_are_skipped_and_strings_kept() {
+        let tokens = tokenize("fun f() /* { */ { b\"}\" == x } // }\n");
+        let texts = tokens.iter().map(|token| token.text).collect::<Vec<_>>();
+        assert_eq!(texts, ["fun", "f", "(", ")", "{", "b\"}\"", "==", "x", "}"]);
+        assert_eq!(tokens[0].kind, Tok::Fun);
+        assert_eq!(tokens[5].kind, Tok::ByteStringValue);
+        assert_eq!(tokens[4].start, 16);
+
+        let tokens = tokenize("fun f() { /* unterminated");
+        assert_eq!(tokens.last().unwrap().kind, Tok::EOF);
+        assert_eq!(tokens.last().unwrap().text, "/* unterminated");
+    }
+}
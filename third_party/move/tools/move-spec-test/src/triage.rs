@@ -0,0 +1,376 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    report::{Report, SurvivingMutant},
+    suppressions::{Decision, Suppressions},
+};
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyCode, KeyEventKind},
+    execute, queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::{
+    io::{stdout, Write},
+    path::Path,
+};
+
+/// Help line displayed at the bottom of the screen.
+const HELP: &str =
+    "[n] needs spec  [e] equivalent  [s] suppress  [←/→] prev/next  [↑/↓] scroll  [q] quit";
+
+/// This function runs the interactive triage of the surviving mutants from the given spec-test report.
+/// Decisions are persisted to the suppression file right after they are taken.
+///
+/// # Arguments
+///
+/// * `report_path` - A `Path` to the JSON report generated by the spec-test tool (or to the archive created
+///   with the `--archive` option).
+/// * `suppressions_path` - A `Path` to the suppression file (created if it does not exist).
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_triage(report_path: &Path, suppressions_path: &Path) -> anyhow::Result<()> {
+    let report = Report::load_from_json_or_archive(report_path)?;
+    let suppressions = Suppressions::load(suppressions_path)?;
+    let mut session = TriageSession::new(&report, suppressions);
+
+    if session.is_empty() {
+        println!("No surviving mutants to triage.");
+        return Ok(());
+    }
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
+
+    let result = event_loop(&mut session, suppressions_path);
+
+    // Always try to restore the terminal, even if the event loop failed.
+    let restored = execute!(stdout(), cursor::Show, LeaveAlternateScreen);
+    terminal::disable_raw_mode()?;
+    result?;
+    restored?;
+
+    println!(
+        "Triaged {} out of {} surviving mutants. Decisions saved to {}",
+        session.decided_count(),
+        session.len(),
+        suppressions_path.display()
+    );
+
+    Ok(())
+}
+
+/// Action triggered by the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Next,
+    Previous,
+    ScrollUp,
+    ScrollDown,
+    Decide(Decision),
+    Quit,
+}
+
+impl Action {
+    // Internal function mapping the pressed key to the action.
+    fn from_key(code: KeyCode) -> Option<Action> {
+        match code {
+            KeyCode::Right | KeyCode::Char('l') | KeyCode::Char(' ') => Some(Action::Next),
+            KeyCode::Left | KeyCode::Char('h') => Some(Action::Previous),
+            KeyCode::Up | KeyCode::Char('k') => Some(Action::ScrollUp),
+            KeyCode::Down | KeyCode::Char('j') => Some(Action::ScrollDown),
+            KeyCode::Char('n') => Some(Action::Decide(Decision::NeedsSpec)),
+            KeyCode::Char('e') => Some(Action::Decide(Decision::Equivalent)),
+            KeyCode::Char('s') => Some(Action::Decide(Decision::Suppress)),
+            KeyCode::Char('q') | KeyCode::Esc => Some(Action::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// State of the triage session, independent of the terminal handling.
+struct TriageSession<'a> {
+    /// Surviving mutants ranked by the spec hints priority, with the representatives of the groups of similar
+    /// mutants first.
+    mutants: Vec<(&'a Path, &'a str, &'a SurvivingMutant)>,
+    /// Index of the currently displayed mutant.
+    index: usize,
+    /// Number of the diff lines scrolled out of the screen.
+    scroll: usize,
+    /// Decisions taken so far (including the ones loaded from the suppression file).
+    suppressions: Suppressions,
+}
+
+impl<'a> TriageSession<'a> {
+    fn new(report: &'a Report, suppressions: Suppressions) -> Self {
+        let groups = report.reduced_surviving_mutants();
+        let representatives = groups
+            .iter()
+            .map(|g| (g.path, g.module_func, g.representative));
+        let similar = groups
+            .iter()
+            .flat_map(|g| g.similar.iter().map(|m| (g.path, g.module_func, *m)));
+
+        Self {
+            mutants: representatives.chain(similar).collect(),
+            index: 0,
+            scroll: 0,
+            suppressions,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.mutants.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.mutants.len()
+    }
+
+    fn decided_count(&self) -> usize {
+        self.mutants
+            .iter()
+            .filter(|(_, _, m)| self.suppressions.get(&m.id).is_some())
+            .count()
+    }
+
+    /// Applies the action. Returns `true` if the suppressions were changed.
+    fn apply(&mut self, action: Action) -> bool {
+        match action {
+            Action::Next => self.select(self.index + 1),
+            Action::Previous => self.select(self.index.saturating_sub(1)),
+            Action::ScrollUp => self.scroll = self.scroll.saturating_sub(1),
+            Action::ScrollDown => self.scroll += 1,
+            Action::Decide(decision) => {
+                let (_, module_func, mutant) = self.mutants[self.index];
+                self.suppressions.set(&mutant.id, module_func, decision);
+                self.select(self.index + 1);
+                return true;
+            },
+            Action::Quit => {},
+        }
+        false
+    }
+
+    // Internal function selecting the mutant with the given index (clamped to the valid range).
+    fn select(&mut self, index: usize) {
+        self.index = index.min(self.mutants.len().saturating_sub(1));
+        self.scroll = 0;
+    }
+
+    // Internal function preparing the lines to display for the current mutant.
+    fn render_lines(&self) -> Vec<(Color, String)> {
+        let (path, module_func, mutant) = self.mutants[self.index];
+        let decision = self
+            .suppressions
+            .get(&mutant.id)
+            .map_or("undecided", |d| d.name());
+
+        let mut lines = vec![
+            (
+                Color::Cyan,
+                format!(
+                    "Mutant {}/{} [{}] {}::{}",
+                    self.index + 1,
+                    self.mutants.len(),
+                    mutant.id,
+                    path.display(),
+                    module_func
+                ),
+            ),
+            (Color::Yellow, format!("Decision: {decision}")),
+            (
+                Color::Reset,
+                format!("Operators: {}", mutant.operators.join(", ")),
+            ),
+            (Color::Reset, "Hints:".to_owned()),
+        ];
+
+        if mutant.hints.is_empty() {
+            lines.push((Color::Reset, "  (none)".to_owned()));
+        }
+        lines.extend(
+            mutant
+                .hints
+                .iter()
+                .map(|hint| (Color::Reset, format!("  - {}", hint.message()))),
+        );
+        lines.push((Color::Reset, String::new()));
+        lines.extend(mutant.diff.lines().skip(self.scroll).map(|line| {
+            let color = match line.chars().next() {
+                Some('+') => Color::Green,
+                Some('-') => Color::Red,
+                Some('@') => Color::Cyan,
+                _ => Color::Reset,
+            };
+            (color, line.to_owned())
+        }));
+
+        lines
+    }
+}
+
+// Internal function handling the terminal events until the user quits.
+fn event_loop(session: &mut TriageSession<'_>, suppressions_path: &Path) -> anyhow::Result<()> {
+    loop {
+        draw(session)?;
+
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        let Some(action) = Action::from_key(key.code) else {
+            continue;
+        };
+
+        if action == Action::Quit {
+            return Ok(());
+        }
+
+        if session.apply(action) {
+            session.suppressions.save(suppressions_path)?;
+        }
+    }
+}
+
+// Internal function drawing the current state of the session.
+fn draw(session: &TriageSession<'_>) -> anyhow::Result<()> {
+    let mut out = stdout();
+    let (width, height) = terminal::size()?;
+    let body_height = usize::from(height.saturating_sub(1));
+
+    queue!(out, terminal::Clear(ClearType::All))?;
+    for (row, (color, line)) in session
+        .render_lines()
+        .into_iter()
+        .take(body_height)
+        .enumerate()
+    {
+        let line = line.chars().take(usize::from(width)).collect::<String>();
+        queue!(
+            out,
+            cursor::MoveTo(0, row as u16),
+            SetForegroundColor(color),
+            Print(line),
+            ResetColor
+        )?;
+    }
+    queue!(
+        out,
+        cursor::MoveTo(0, height.saturating_sub(1)),
+        Print(HELP)
+    )?;
+    out.flush()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn report_with_mutants(ids: &[&str]) -> Report {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        for id in ids {
+            report.increment_mutants_tested(&path, "Sum::sum");
+            report.add_surviving_mutant(&path, "Sum::sum", SurvivingMutant {
+                id: (*id).to_owned(),
+                diff: "-a + b\n+a - b\n".to_owned(),
+                ..Default::default()
+            });
+        }
+        report
+    }
+
+    #[test]
+    fn representatives_of_similar_mutants_come_first() {
+        let mut report = Report::new();
+        let path = PathBuf::from("sources/Sum.move");
+        for (id, location) in [("first", 1), ("similar", 1), ("other", 2)] {
+            report.add_surviving_mutant(&path, "Sum::sum", SurvivingMutant {
+                id: id.to_owned(),
+                location: Some(location),
+                ..Default::default()
+            });
+        }
+
+        let session = TriageSession::new(&report, Suppressions::default());
+        let ids = session
+            .mutants
+            .iter()
+            .map(|(_, _, m)| m.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["first", "other", "similar"]);
+    }
+
+    #[test]
+    fn decisions_are_recorded_and_next_mutant_is_selected() {
+        let report = report_with_mutants(&["first", "second"]);
+        let mut session = TriageSession::new(&report, Suppressions::default());
+
+        assert!(session.apply(Action::Decide(Decision::Equivalent)));
+        assert_eq!(session.index, 1);
+        assert!(session.apply(Action::Decide(Decision::NeedsSpec)));
+        assert_eq!(session.index, 1);
+
+        assert_eq!(
+            session.suppressions.get("first"),
+            Some(Decision::Equivalent)
+        );
+        assert_eq!(
+            session.suppressions.get("second"),
+            Some(Decision::NeedsSpec)
+        );
+        assert_eq!(session.decided_count(), 2);
+    }
+
+    #[test]
+    fn navigation_stays_in_range() {
+        let report = report_with_mutants(&["first", "second"]);
+        let mut session = TriageSession::new(&report, Suppressions::default());
+
+        assert!(!session.apply(Action::Previous));
+        assert_eq!(session.index, 0);
+        session.apply(Action::Next);
+        session.apply(Action::Next);
+        assert_eq!(session.index, 1);
+    }
+
+    #[test]
+    fn rendered_lines_contain_mutant_details() {
+        let report = report_with_mutants(&["first"]);
+        let mut suppressions = Suppressions::default();
+        suppressions.set("first", "Sum::sum", Decision::Suppress);
+        let session = TriageSession::new(&report, suppressions);
+
+        let lines = session
+            .render_lines()
+            .into_iter()
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>();
+        assert!(lines[0].contains("[first] sources/Sum.move::Sum::sum"));
+        assert_eq!(lines[1], "Decision: suppress");
+        assert!(lines.contains(&"+a - b".to_owned()));
+    }
+
+    #[test]
+    fn keys_are_mapped_to_actions() {
+        assert_eq!(
+            Action::from_key(KeyCode::Char('e')),
+            Some(Action::Decide(Decision::Equivalent))
+        );
+        assert_eq!(Action::from_key(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(Action::from_key(KeyCode::Char('x')), None);
+    }
+}
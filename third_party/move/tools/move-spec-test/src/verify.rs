@@ -0,0 +1,183 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::cli::CLIOptions;
+use move_mutator::{archive, manifest::Manifest};
+use move_package::BuildConfig;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// This function verifies previously generated mutants against the current version of the package,
+/// without generating the mutants again (e.g. after tweaking the specifications).
+///
+/// The mutants directory must contain the report generated by the mutator tool (`report.json`, or the report
+/// in the other structured format, e.g. `report.yaml`). The archive
+/// created with the `--archive` option can be given instead of the directory.
+/// Mutations are re-applied to the current sources of the package, so changes made since the
+/// generation (e.g. in the spec blocks) are taken into account.
+///
+/// # Arguments
+///
+/// * `options` - A `cli::CLIOptions` representing the options for the spec test.
+/// * `config` - A `BuildConfig` representing the build configuration.
+/// * `package_path` - A `Path` to the package.
+/// * `mutants_dir` - A `Path` to the directory (or the `.tar.zst` archive) with the generated mutants.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub fn run_verify(
+    options: &CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+    mutants_dir: &Path,
+) -> anyhow::Result<()> {
+    // The archive is extracted to the temporary directory, which is removed after the verification.
+    let extracted = if archive::is_archive(mutants_dir) {
+        let dir = tempfile::tempdir()?;
+        archive::extract_archive(mutants_dir, dir.path())?;
+        Some(dir)
+    } else {
+        None
+    };
+    let mutants_dir = extracted.as_ref().map_or(mutants_dir, |dir| dir.path());
+
+    if move_mutator::report::Report::find_in_dir(mutants_dir).is_none() {
+        anyhow::bail!(
+            "No mutator report found in {} (expected report.json, report.yaml, report.toml or report.jsonl)",
+            mutants_dir.display()
+        );
+    }
+
+    let options = CLIOptions {
+        use_generated_mutants: Some(mutants_dir.to_path_buf()),
+        ..options.clone()
+    };
+    crate::run_spec_test(&options, config, package_path)
+}
+
+/// Checks the previously generated mutants directory against its manifest.
+///
+/// Returns the root of the mutated package at the time of the generation (if known), which allows using
+/// the mutants generated on another machine. Sources changed since the generation are reported.
+///
+/// # Errors
+///
+/// Returns an error if the mutants directory doesn't match its manifest (e.g. files are missing or modified).
+pub(crate) fn check_generated_mutants(
+    mutants_dir: &Path,
+    package_path: &Path,
+) -> anyhow::Result<Option<PathBuf>> {
+    let Some(manifest) = Manifest::load(mutants_dir)? else {
+        warn!(
+            "No manifest found in {}, integrity of the mutants can't be checked",
+            mutants_dir.display()
+        );
+        return Ok(None);
+    };
+
+    manifest.verify_integrity(mutants_dir)?;
+
+    let stale = manifest.stale_sources(package_path);
+    if !stale.is_empty() {
+        println!(
+            "Sources changed since the mutants were generated (mutations will be re-applied): {}",
+            stale
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    Ok(manifest.package_root().map(Path::to_path_buf))
+}
+
+/// Re-applies the mutation described by the diff to the current source of the original file.
+///
+/// Reports without the diffs (`--report-details compact`) can't be re-applied, so the generated mutant file
+/// is used as is instead.
+///
+/// # Errors
+///
+/// Returns an error if the diff can't be parsed or doesn't apply to the source anymore (e.g. the mutated
+/// code has changed since the mutant was generated), or if the diff is missing and the mutant file can't
+/// be read.
+pub(crate) fn rebase_mutant(
+    original_source: &str,
+    diff: &str,
+    mutant_file: &Path,
+) -> anyhow::Result<String> {
+    if diff.is_empty() {
+        debug!(
+            "The report has no diff of {}, the mutant file is used as is",
+            mutant_file.display()
+        );
+        return fs::read_to_string(mutant_file).map_err(|e| {
+            anyhow::anyhow!(
+                "The report has no diff of the mutant and its file {} can't be read: {e}",
+                mutant_file.display()
+            )
+        });
+    }
+
+    let patch = diffy::Patch::from_str(diff).map_err(|e| anyhow::anyhow!("Invalid diff: {e}"))?;
+    diffy::apply(original_source, &patch)
+        .map_err(|e| anyhow::anyhow!("Mutation doesn't apply to the current source: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORIGINAL: &str = "module 0x42::Sum {\n    fun sum(a: u64, b: u64): u64 {\n        a + b\n    }\n\n    spec sum {\n        ensures result >= a;\n    }\n}\n";
+
+    #[test]
+    fn mutation_is_reapplied_to_changed_specs() {
+        let mutated = ORIGINAL.replace("a + b", "a - b");
+        let diff = diffy::create_patch(ORIGINAL, &mutated).to_string();
+
+        let changed = ORIGINAL.replace("ensures result >= a;", "ensures result == a + b;");
+        let rebased = rebase_mutant(&changed, &diff, Path::new("missing.move")).unwrap();
+        assert!(rebased.contains("a - b"));
+        assert!(rebased.contains("ensures result == a + b;"));
+    }
+
+    #[test]
+    fn mutation_of_changed_code_is_rejected() {
+        let mutated = ORIGINAL.replace("a + b", "a - b");
+        let diff = diffy::create_patch(ORIGINAL, &mutated).to_string();
+
+        let changed = ORIGINAL.replace("a + b", "b + a");
+        assert!(rebase_mutant(&changed, &diff, Path::new("missing.move")).is_err());
+    }
+
+    #[test]
+    fn mutant_file_is_used_without_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let mutant_file = dir.path().join("Sum.move");
+        let mutated = ORIGINAL.replace("a + b", "a - b");
+        fs::write(&mutant_file, &mutated).unwrap();
+
+        assert_eq!(rebase_mutant(ORIGINAL, "", &mutant_file).unwrap(), mutated);
+        assert!(rebase_mutant(ORIGINAL, "", &dir.path().join("missing.move")).is_err());
+    }
+
+    #[test]
+    fn missing_report_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let result = run_verify(
+            &CLIOptions::default(),
+            &BuildConfig::default(),
+            Path::new("."),
+            dir.path(),
+        );
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("No mutator report found"));
+    }
+}
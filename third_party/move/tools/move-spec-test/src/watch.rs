@@ -0,0 +1,486 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cli::CLIOptions,
+    tokens::{tokenize, Token},
+};
+use move_compiler::parser::lexer::Tok;
+use move_mutator::cli::ModuleFilter;
+use move_package::{source_package::layout::SourcePackageLayout, BuildConfig};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime},
+};
+
+/// Interval between checks for changes in the package sources.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Key under which the module-level items (structs, constants, module specs, etc.) are fingerprinted.
+const MODULE_ITEMS_KEY: &str = "";
+
+/// Key under which the module-level items of the specification files (the items of the
+/// `spec <address>::<module>` blocks other than the function specs) are fingerprinted.
+const MODULE_SPEC_ITEMS_KEY: &str = "spec module";
+
+/// This function runs the specification testing in the watch mode.
+///
+/// The whole package is tested first. Then the package sources are monitored and, on every change,
+/// the specification testing is re-run only for the functions affected by the change (functions whose
+/// code or spec block changed, or all functions of modules whose module-level items changed).
+/// Errors of the individual runs (e.g. when the original code can't be verified) are reported, but
+/// don't stop the watch mode.
+///
+/// # Arguments
+///
+/// * `options` - A `cli::CLIOptions` representing the options for the spec test.
+/// * `config` - A `BuildConfig` representing the build configuration.
+/// * `package_path` - A `Path` to the package.
+///
+/// # Errors
+///
+/// Errors are returned as `anyhow::Result`.
+pub(crate) fn run_watch(
+    options: &CLIOptions,
+    config: &BuildConfig,
+    package_path: &Path,
+) -> anyhow::Result<()> {
+    if options.use_generated_mutants.is_some() {
+        anyhow::bail!(
+            "The `--watch` option can't be used with the `--use-generated-mutants` option"
+        );
+    }
+
+    let options = CLIOptions {
+        watch: false,
+        ..options.clone()
+    };
+    let sources_dir = package_path.join(SourcePackageLayout::Sources.path());
+
+    let mut snapshot = Snapshot::take(&sources_dir)?;
+    report_run(crate::run_spec_test(&options, config, package_path));
+
+    loop {
+        println!(
+            "Watching {} for changes (press Ctrl-C to stop)...",
+            sources_dir.display()
+        );
+
+        let new_snapshot = loop {
+            thread::sleep(POLL_INTERVAL);
+            let new_snapshot = Snapshot::take(&sources_dir)?;
+            if new_snapshot.modified != snapshot.modified {
+                break new_snapshot;
+            }
+        };
+
+        let affected = snapshot.affected_functions(&new_snapshot);
+        snapshot = new_snapshot;
+
+        if affected.is_empty() {
+            println!("No functions affected by the change");
+            continue;
+        }
+
+        println!(
+            "Re-running specification testing for: {}",
+            affected.join(", ")
+        );
+
+        // Modules are selected by their qualified names, so the modules with the same name at other
+        // addresses aren't tested, while the functions are selected as `Module::function`.
+        let mut modules = BTreeSet::new();
+        let mut functions = vec![];
+        for (module, function) in affected.iter().filter_map(|f| f.rsplit_once("::")) {
+            let name = module.rsplit("::").next().unwrap_or(module);
+            modules.insert(module.to_owned());
+            functions.push(format!("{name}::{function}"));
+        }
+        let run_options = CLIOptions {
+            include_modules: ModuleFilter::Selected(modules.into_iter().collect()),
+            include_functions: functions,
+            ..options.clone()
+        };
+        report_run(crate::run_spec_test(&run_options, config, package_path));
+    }
+}
+
+// Internal function reporting the result of a single run in the watch mode.
+fn report_run(result: anyhow::Result<()>) {
+    if let Err(e) = result {
+        error!("Specification testing failed: {e}");
+        println!("Specification testing failed: {e}");
+    }
+}
+
+/// State of the package sources at a given moment.
+#[derive(Debug, Default)]
+struct Snapshot {
+    /// Modification times of the source files.
+    modified: BTreeMap<PathBuf, SystemTime>,
+    /// Fingerprints of the items in the source files, keyed by the address-qualified module name and
+    /// the item name.
+    fingerprints: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+impl Snapshot {
+    // Internal function taking the snapshot of all Move files in the given directory (recursively).
+    fn take(dir: &Path) -> anyhow::Result<Self> {
+        let mut snapshot = Snapshot::default();
+        let mut dirs = vec![dir.to_path_buf()];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "move") {
+                    snapshot
+                        .modified
+                        .insert(path.clone(), fs::metadata(&path)?.modified()?);
+                    for (module, items) in fingerprint_source(&fs::read_to_string(&path)?) {
+                        snapshot
+                            .fingerprints
+                            .entry(module)
+                            .or_default()
+                            .extend(items);
+                    }
+                }
+            }
+        }
+
+        Ok(snapshot)
+    }
+
+    // Internal function returning the functions (as `address::Module::function`) affected by changes between
+    // this and the newer snapshot. Removed functions are not included, as there is nothing to test.
+    fn affected_functions(&self, newer: &Snapshot) -> Vec<String> {
+        let empty = BTreeMap::new();
+        let mut affected = BTreeSet::new();
+
+        for (module, items) in &newer.fingerprints {
+            let old_items = self.fingerprints.get(module).unwrap_or(&empty);
+            let module_changed = [MODULE_ITEMS_KEY, MODULE_SPEC_ITEMS_KEY]
+                .iter()
+                .any(|key| old_items.get(*key) != items.get(*key));
+
+            for (name, fingerprint) in items {
+                // Spec blocks of functions are fingerprinted under `spec <name>` keys.
+                let Some(function) = name.strip_prefix("fun ") else {
+                    continue;
+                };
+                let spec_key = format!("spec {function}");
+
+                if module_changed
+                    || old_items.get(name) != Some(fingerprint)
+                    || old_items.get(&spec_key) != items.get(&spec_key)
+                {
+                    affected.insert(format!("{module}::{function}"));
+                }
+            }
+        }
+
+        affected.into_iter().collect()
+    }
+}
+
+// Internal function computing the fingerprints of the items in the Move source.
+// Returns a map from the address-qualified module name (e.g. `0x42::Sum`, with the address as written in
+// the source) to the fingerprints of its items: `fun <name>` for functions,
+// `spec <name>` for spec blocks of functions and `MODULE_ITEMS_KEY` for all other module-level items.
+// The specification files define the specs of the module in the `spec <address>::<module>` block, whose
+// function specs are fingerprinted as the spec blocks inside of the module and whose other items under
+// `MODULE_SPEC_ITEMS_KEY`. The items are fingerprinted by their tokens, so the changes of the comments and
+// of the whitespaces don't change the fingerprints.
+fn fingerprint_source(source: &str) -> BTreeMap<String, BTreeMap<String, String>> {
+    let tokens = tokenize(source);
+    let mut modules = BTreeMap::<String, BTreeMap<String, String>>::new();
+    // Address of the `address <address> { ... }` block the modules are declared in.
+    let mut block_address = None;
+    let mut i = 0;
+
+    while i < tokens.len() {
+        let module_items_key = match tokens[i].kind {
+            Tok::Identifier
+                if tokens[i].text == "address"
+                    && tokens.get(i + 2).is_some_and(|t| t.kind == Tok::LBrace) =>
+            {
+                block_address = Some(tokens[i + 1].text);
+                i += 3;
+                continue;
+            },
+            Tok::RBrace => {
+                block_address = None;
+                i += 1;
+                continue;
+            },
+            Tok::Module => MODULE_ITEMS_KEY,
+            Tok::Spec if tokens.get(i + 2).is_some_and(|t| t.kind == Tok::ColonColon) => {
+                MODULE_SPEC_ITEMS_KEY
+            },
+            // Other spec blocks outside of the modules (e.g. `spec module` or `spec script`) are skipped,
+            // so the keywords inside of them aren't taken for the modules.
+            Tok::Spec => {
+                i = item_end(&tokens, i);
+                continue;
+            },
+            _ => {
+                i += 1;
+                continue;
+            },
+        };
+
+        // Module name is given before the opening brace, qualified with the address unless the module is
+        // declared in the address block (e.g. `module 0x1::Sum {` or `address 0x1 { module Sum {`).
+        let Some(open) = tokens[i..]
+            .iter()
+            .position(|t| t.kind == Tok::LBrace)
+            .map(|p| p + i)
+        else {
+            break;
+        };
+        let name = tokens[i + 1..open]
+            .iter()
+            .map(|t| t.text)
+            .collect::<String>();
+        let name = match block_address {
+            Some(address) if !name.contains("::") => format!("{address}::{name}"),
+            _ => name,
+        };
+        let items = modules.entry(name).or_default();
+
+        i = open + 1;
+        let mut module_items = String::new();
+
+        // Iterate over the module-level items until the closing brace of the module.
+        while i < tokens.len() && tokens[i].kind != Tok::RBrace {
+            let end = item_end(&tokens, i);
+            let text = tokens[i..end]
+                .iter()
+                .map(|t| t.text)
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            match item_key(&tokens[i..end]) {
+                Some(key) => {
+                    items.insert(key, text);
+                },
+                None => {
+                    module_items.push_str(&text);
+                    module_items.push('\n');
+                },
+            }
+            i = end;
+        }
+
+        items.insert(module_items_key.to_owned(), module_items);
+        i += 1;
+    }
+
+    modules
+}
+
+// Internal function returning the index right after the module-level item starting at `start`.
+// Items end with a semicolon or with the closing brace of their body.
+fn item_end(tokens: &[Token], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+
+    while i < tokens.len() {
+        match tokens[i].kind {
+            Tok::LBrace => depth += 1,
+            Tok::RBrace if depth == 0 => return i,
+            Tok::RBrace => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            },
+            Tok::Semicolon if depth == 0 => return i + 1,
+            _ => {},
+        }
+        i += 1;
+    }
+
+    i
+}
+
+// Internal function returning the fingerprint key of functions and function spec blocks.
+// Returns `None` for other items (including spec functions, schemas and module spec blocks).
+fn item_key(item: &[Token]) -> Option<String> {
+    let first_keyword = item.iter().position(|t| {
+        matches!(
+            t.kind,
+            Tok::Fun | Tok::Spec | Tok::Struct | Tok::Const | Tok::Use
+        )
+    })?;
+
+    match (item[first_keyword].kind, item.get(first_keyword + 1)) {
+        (Tok::Fun, Some(name)) => Some(format!("fun {}", name.text)),
+        // Module spec blocks (`spec module`), schemas and spec functions aren't the function specs.
+        (Tok::Spec, Some(name))
+            if name.kind == Tok::Identifier
+                && name.text != "schema"
+                && item
+                    .get(first_keyword + 2)
+                    .is_some_and(|t| matches!(t.kind, Tok::LBrace | Tok::LParen)) =>
+        {
+            Some(format!("spec {}", name.text))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+        module 0x42::Sum {
+            const E: u64 = 1;
+
+            // Comment with a brace {
+            fun sum(a: u64, b: u64): u64 {
+                if (a > b) { a + b } else { b + a }
+            }
+
+            spec sum {
+                ensures result == a + b;
+            }
+
+            public fun msg(): vector<u8> {
+                b"}"
+            }
+        }
+    "#;
+
+    fn snapshot(source: &str) -> Snapshot {
+        Snapshot {
+            modified: BTreeMap::new(),
+            fingerprints: fingerprint_source(source),
+        }
+    }
+
+    #[test]
+    fn functions_and_specs_are_fingerprinted() {
+        let fingerprints = fingerprint_source(SOURCE);
+        let items = &fingerprints["0x42::Sum"];
+
+        assert!(items["fun sum"].starts_with("fun sum ( a : u64"));
+        assert!(items["spec sum"].contains("ensures result == a + b"));
+        assert!(items["fun msg"].contains("b\"}\""));
+        assert!(items[MODULE_ITEMS_KEY].contains("const E : u64 = 1 ;"));
+    }
+
+    #[test]
+    fn comment_changes_affect_no_functions() {
+        let changed = SOURCE.replace("Comment with a brace {", "Other comment");
+        assert!(snapshot(SOURCE)
+            .affected_functions(&snapshot(&changed))
+            .is_empty());
+    }
+
+    #[test]
+    fn function_and_spec_changes_affect_only_that_function() {
+        let changed = SOURCE.replace("{ b + a }", "{ a }");
+        assert_eq!(
+            snapshot(SOURCE).affected_functions(&snapshot(&changed)),
+            vec!["0x42::Sum::sum"]
+        );
+
+        let changed = SOURCE.replace("ensures result == a + b;", "ensures result >= a;");
+        assert_eq!(
+            snapshot(SOURCE).affected_functions(&snapshot(&changed)),
+            vec!["0x42::Sum::sum"]
+        );
+    }
+
+    #[test]
+    fn module_item_changes_affect_all_functions() {
+        let changed = SOURCE.replace("const E: u64 = 1;", "const E: u64 = 2;");
+        assert_eq!(
+            snapshot(SOURCE).affected_functions(&snapshot(&changed)),
+            vec!["0x42::Sum::msg", "0x42::Sum::sum"]
+        );
+    }
+
+    #[test]
+    fn spec_file_changes_affect_specified_functions() {
+        const SPEC_SOURCE: &str = r#"
+            spec 0x42::Sum {
+                spec module {
+                    pragma verify = true;
+                }
+
+                spec sum {
+                    ensures result == a + b;
+                }
+            }
+        "#;
+        let sources = |spec_source: &str| {
+            let mut fingerprints = fingerprint_source(SOURCE);
+            for (module, items) in fingerprint_source(spec_source) {
+                fingerprints.entry(module).or_default().extend(items);
+            }
+            Snapshot {
+                modified: BTreeMap::new(),
+                fingerprints,
+            }
+        };
+
+        let fingerprints = fingerprint_source(SPEC_SOURCE);
+        assert_eq!(fingerprints.keys().collect::<Vec<_>>(), ["0x42::Sum"]);
+        assert!(fingerprints["0x42::Sum"]["spec sum"].contains("ensures result == a + b"));
+
+        let changed = SPEC_SOURCE.replace("ensures result == a + b;", "ensures result >= a;");
+        assert_eq!(
+            sources(SPEC_SOURCE).affected_functions(&sources(&changed)),
+            vec!["0x42::Sum::sum"]
+        );
+
+        let changed = SPEC_SOURCE.replace("pragma verify = true;", "pragma verify = false;");
+        assert_eq!(
+            sources(SPEC_SOURCE).affected_functions(&sources(&changed)),
+            vec!["0x42::Sum::msg", "0x42::Sum::sum"]
+        );
+    }
+
+    #[test]
+    fn new_functions_are_affected() {
+        let changed = SOURCE.replace(
+            "spec sum {",
+            "fun new_fun(): u64 { 1 }\n            spec sum {",
+        );
+        assert_eq!(
+            snapshot(SOURCE).affected_functions(&snapshot(&changed)),
+            vec!["0x42::Sum::new_fun"]
+        );
+    }
+
+    #[test]
+    fn modules_are_qualified_with_addresses() {
+        const OTHER_SOURCE: &str = r#"
+            address 0x43 {
+                module Sum {
+                    fun sum(): u64 { 1 }
+                }
+            }
+        "#;
+        let fingerprints = fingerprint_source(&format!("{SOURCE}{OTHER_SOURCE}"));
+        assert_eq!(fingerprints.keys().collect::<Vec<_>>(), [
+            "0x42::Sum",
+            "0x43::Sum"
+        ]);
+
+        let changed = OTHER_SOURCE.replace("{ 1 }", "{ 2 }");
+        assert_eq!(
+            snapshot(&format!("{SOURCE}{OTHER_SOURCE}"))
+                .affected_functions(&snapshot(&format!("{SOURCE}{changed}"))),
+            vec!["0x43::Sum::sum"]
+        );
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_mutator::compiler::IgnoreRules;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tempfile::TempDir;
+
+/// Prefix of the per-run scratch directory created in the system temporary directory.
+const WORKSPACE_PREFIX: &str = "move-spec-test-";
+
+/// Scratch directory structure used by a single specification testing run.
+///
+/// Each run gets its own uniquely named root directory, so multiple runs can be executed in parallel.
+/// Inside the root directory there are:
+/// - `base` - copy of the original package,
+/// - `mutants` - mutants generated by the mutator tool,
+/// - `prove/mutant_<index>` - per-mutant copies of the package used for proving.
+///
+/// The whole structure is removed when the workspace is dropped, unless it was created with the `keep`
+/// flag set or it was persisted (as the workspaces of the failed runs are). In such case, all the files
+/// are preserved for debugging purposes.
+pub(crate) struct Workspace {
+    /// Root of the workspace.
+    root: PathBuf,
+    /// Handle removing the root directory on drop (`None` if the files should be kept).
+    dir: Option<TempDir>,
+    /// Indicates if the files should be preserved after the run.
+    keep: bool,
+    /// Rules deciding which entries of the package are not copied into the workspace.
+    copy_ignore: IgnoreRules,
+}
+
+impl Workspace {
+    /// Creates a new workspace in the system temporary directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directories can't be created.
+    pub(crate) fn new(keep: bool) -> anyhow::Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix(WORKSPACE_PREFIX)
+            .tempdir()?;
+
+        let (root, dir) = if keep {
+            (dir.into_path(), None)
+        } else {
+            (dir.path().to_path_buf(), Some(dir))
+        };
+
+        let workspace = Self {
+            root,
+            dir,
+            keep,
+            copy_ignore: IgnoreRules::default(),
+        };
+
+        fs::create_dir_all(workspace.base_dir())?;
+        fs::create_dir_all(workspace.prove_dir())?;
+
+        debug!("Created workspace in {}", workspace.root.display());
+
+        Ok(workspace)
+    }
+
+    /// Sets the rules deciding which entries of the package are not copied into the workspace.
+    #[must_use]
+    pub(crate) fn with_copy_ignore(mut self, copy_ignore: IgnoreRules) -> Self {
+        self.copy_ignore = copy_ignore;
+        self
+    }
+
+    /// Copies the package into the given directory of the workspace, without the ignored entries.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the package can't be copied.
+    pub(crate) fn copy_package(&self, package_path: &Path, dir: &Path) -> anyhow::Result<()> {
+        move_mutator::compiler::copy_package(package_path, dir, &self.copy_ignore)
+    }
+
+    /// Returns the root directory of the workspace.
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the directory with the copy of the original package.
+    pub(crate) fn base_dir(&self) -> PathBuf {
+        self.root.join("base")
+    }
+
+    /// Returns the directory where the mutator tool stores the generated mutants.
+    pub(crate) fn mutants_dir(&self) -> PathBuf {
+        self.root.join("mutants")
+    }
+
+    /// Indicates if the workspace files are preserved after the run.
+    pub(crate) fn is_kept(&self) -> bool {
+        self.keep
+    }
+
+    /// Preserves the workspace files after the run (e.g. when the run fails, to debug it).
+    pub(crate) fn persist(&mut self) {
+        if let Some(dir) = self.dir.take() {
+            self.root = dir.into_path();
+        }
+        self.keep = true;
+    }
+
+    /// Creates a fresh scratch directory for the mutant with the given index.
+    /// If the directory already exists, it's removed first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be created.
+    pub(crate) fn create_mutant_dir(&self, index: usize) -> anyhow::Result<PathBuf> {
+        let dir = self.prove_dir().join(format!("mutant_{index}"));
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    /// Removes the scratch directory of a single mutant (unless the files should be kept).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory can't be removed.
+    pub(crate) fn cleanup_mutant_dir(&self, dir: &Path) -> anyhow::Result<()> {
+        if !self.keep && dir.exists() {
+            fs::remove_dir_all(dir)?;
+        }
+        Ok(())
+    }
+
+    // Internal function returning the directory with the per-mutant scratch directories.
+    fn prove_dir(&self) -> PathBuf {
+        self.root.join("prove")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workspace_is_removed_on_drop() {
+        let workspace = Workspace::new(false).unwrap();
+        let root = workspace.root().to_path_buf();
+        assert!(workspace.base_dir().exists());
+        drop(workspace);
+        assert!(!root.exists());
+    }
+
+    #[test]
+    fn workspace_is_kept_when_requested() {
+        let workspace = Workspace::new(true).unwrap();
+        let root = workspace.root().to_path_buf();
+        assert!(workspace.is_kept());
+        drop(workspace);
+        assert!(root.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn persisted_workspace_is_kept() {
+        let mut workspace = Workspace::new(false).unwrap();
+        let root = workspace.root().to_path_buf();
+        workspace.persist();
+        assert!(workspace.is_kept());
+        assert_eq!(workspace.root(), root);
+        drop(workspace);
+        assert!(root.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn workspaces_have_unique_roots() {
+        let first = Workspace::new(false).unwrap();
+        let second = Workspace::new(false).unwrap();
+        assert_ne!(first.root(), second.root());
+    }
+
+    #[test]
+    fn mutant_dirs_are_created_and_cleaned_up() {
+        let workspace = Workspace::new(false).unwrap();
+        let first = workspace.create_mutant_dir(0).unwrap();
+        let second = workspace.create_mutant_dir(1).unwrap();
+        assert_ne!(first, second);
+        assert!(first.exists());
+
+        workspace.cleanup_mutant_dir(&first).unwrap();
+        assert!(!first.exists());
+        assert!(second.exists());
+    }
+
+    #[test]
+    fn mutant_dirs_are_kept_when_requested() {
+        let workspace = Workspace::new(true).unwrap();
+        let dir = workspace.create_mutant_dir(0).unwrap();
+        workspace.cleanup_mutant_dir(&dir).unwrap();
+        assert!(dir.exists());
+        fs::remove_dir_all(workspace.root()).unwrap();
+    }
+
+    #[test]
+    fn package_copies_skip_ignored_entries() {
+        let package = tempfile::tempdir().unwrap();
+        for dir in ["sources", "build", "docs"] {
+            fs::create_dir(package.path().join(dir)).unwrap();
+        }
+        fs::write(package.path().join("sources/m.move"), "").unwrap();
+        fs::write(package.path().join("build/artifact"), "").unwrap();
+        fs::write(package.path().join("docs/m.md"), "").unwrap();
+
+        let workspace = Workspace::new(false)
+            .unwrap()
+            .with_copy_ignore(IgnoreRules::new(&["docs".to_owned()]).unwrap());
+        let dir = workspace.create_mutant_dir(0).unwrap();
+        workspace.copy_package(package.path(), &dir).unwrap();
+
+        assert!(dir.join("sources/m.move").exists());
+        assert!(!dir.join("build").exists());
+        assert!(!dir.join("docs").exists());
+    }
+}
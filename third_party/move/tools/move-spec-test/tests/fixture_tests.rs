@@ -0,0 +1,65 @@
+// Copyright © Eiger
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_spec_test::{
+    cli::CLIOptions,
+    harness::SpecTest,
+    test_support::{generate_mutants, prover_available, run_spec_test, FixturePackage},
+};
+
+#[test]
+fn fixture_mutants_are_generated() {
+    let package = FixturePackage::poor_spec().unwrap();
+    let report = generate_mutants(&package, &CLIOptions::default()).unwrap();
+
+    let mutants = report.get_mutants();
+    assert!(!mutants.is_empty());
+    assert!(mutants.iter().all(|m| m.get_module_name() == "Sum"));
+}
+
+#[test]
+fn poor_specification_lets_mutants_survive() {
+    if !prover_available() {
+        eprintln!("Skipped, the prover isn't configured (BOOGIE_EXE)");
+        return;
+    }
+
+    let package = FixturePackage::poor_spec().unwrap();
+    let report = run_spec_test(&package, &CLIOptions::default()).unwrap();
+    assert!(report.mutants_tested() > 0);
+    assert!(report.mutants_killed() < report.mutants_tested());
+    assert!(!report.ranked_surviving_mutants().is_empty());
+}
+
+#[test]
+fn precise_specification_kills_mutants() {
+    if !prover_available() {
+        eprintln!("Skipped, the prover isn't configured (BOOGIE_EXE)");
+        return;
+    }
+
+    let package = FixturePackage::well_specified().unwrap();
+    let report = run_spec_test(&package, &CLIOptions::default()).unwrap();
+    assert!(report.mutants_killed() > 0);
+    assert!(report.metadata().is_some());
+}
+
+#[test]
+fn harness_enforces_the_minimum_score() {
+    if !prover_available() {
+        eprintln!("Skipped, the prover isn't configured (BOOGIE_EXE)");
+        return;
+    }
+
+    let package = FixturePackage::well_specified().unwrap();
+    let report = SpecTest::new(package.path()).with_min_score(50.0).run();
+    assert!(report.score() >= 50.0);
+
+    let package = FixturePackage::poor_spec().unwrap();
+    let err = SpecTest::new(package.path())
+        .with_min_score(100.0)
+        .try_run()
+        .unwrap_err();
+    assert!(err.to_string().contains("surviving mutant"));
+}